@@ -0,0 +1,284 @@
+//! Benchmarks for the request hot path
+//!
+//! Run with `cargo bench`; compare against a saved baseline with
+//! `cargo bench -- --save-baseline before` / `--baseline before`.
+
+use async_trait::async_trait;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use futures::future::BoxFuture;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::tools::{CallToolResult, TextContent, ToolHandler};
+use mcp_server_rust::transport::StdioTransport;
+use mcp_server_rust::utils::Snapshot;
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::RwLock;
+
+struct EchoTool;
+
+#[async_trait]
+impl ToolHandler for EchoTool {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![TextContent::new(arguments.to_string())]))
+    }
+}
+
+fn server() -> McpServer {
+    McpServer::new(ServerConfig::new("BenchServer", "0.0.0"))
+}
+
+/// Raw request line -> parse -> dispatch -> serialized response line
+fn round_trip(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let server = server();
+    let requests = [
+        ("ping", r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#),
+        ("tools_list", r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#),
+        (
+            "tools_call",
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"greet","arguments":{"name":"Ada"}}}"#,
+        ),
+        (
+            "resources_read",
+            r#"{"jsonrpc":"2.0","id":1,"method":"resources/read","params":{"uri":"config://app"}}"#,
+        ),
+    ];
+
+    let mut group = c.benchmark_group("round_trip");
+    for (name, line) in requests {
+        group.bench_function(BenchmarkId::new("value", name), |b| {
+            b.to_async(&runtime).iter(|| async {
+                let message = StdioTransport::parse_line(line).unwrap().unwrap();
+                let response = server.handle_request(message).await.unwrap().unwrap();
+                serde_json::to_string(&response).unwrap()
+            })
+        });
+        group.bench_function(BenchmarkId::new("raw", name), |b| {
+            b.to_async(&runtime).iter(|| async {
+                let response = server.handle_message(line).await.unwrap().unwrap();
+                serde_json::to_string(&response).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// tools/list against registries of increasing size
+fn tools_list(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" });
+
+    let mut group = c.benchmark_group("tools_list");
+    for size in [10, 100, 1000] {
+        let server = server();
+        runtime.block_on(async {
+            for i in 0..size {
+                server
+                    .register_tool(format!("echo-{}", i), Arc::new(EchoTool))
+                    .await
+                    .unwrap();
+            }
+        });
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.to_async(&runtime)
+                .iter(|| async { server.handle_request(request.clone()).await.unwrap() })
+        });
+    }
+    group.finish();
+}
+
+type Registry = HashMap<String, Arc<dyn ToolHandler>>;
+
+const READERS: usize = 8;
+const LOOKUPS: usize = 1000;
+
+fn multi_thread_runtime() -> Runtime {
+    Builder::new_multi_thread().worker_threads(4).enable_all().build().unwrap()
+}
+
+/// Registers and unregisters a tool every 50µs until `stop` is set
+fn churn<F>(runtime: &Runtime, stop: Arc<AtomicBool>, write: F)
+where
+    F: Fn(bool) -> BoxFuture<'static, ()> + Send + 'static,
+{
+    runtime.spawn(async move {
+        let mut insert = true;
+        while !stop.load(Ordering::Relaxed) {
+            write(insert).await;
+            insert = !insert;
+            tokio::time::sleep(Duration::from_micros(50)).await;
+        }
+    });
+}
+
+/// Tool lookups from concurrent readers while a writer keeps registering:
+/// the registries' copy-on-write snapshots against the `RwLock` they
+/// replaced
+fn registry_contention(c: &mut Criterion) {
+    let runtime = multi_thread_runtime();
+    let tools: Registry = (0..100)
+        .map(|i| (format!("echo-{}", i), Arc::new(EchoTool) as Arc<dyn ToolHandler>))
+        .collect();
+    let snapshot = Arc::new(Snapshot::new(tools.clone()));
+    let locked = Arc::new(RwLock::new(tools));
+
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let snapshot = snapshot.clone();
+        let locked = locked.clone();
+        churn(&runtime, stop.clone(), move |insert| {
+            let snapshot = snapshot.clone();
+            let locked = locked.clone();
+            Box::pin(async move {
+                if insert {
+                    snapshot.update(|tools| tools.insert("churn".to_string(), Arc::new(EchoTool)));
+                    locked.write().await.insert("churn".to_string(), Arc::new(EchoTool));
+                } else {
+                    snapshot.update(|tools| tools.remove("churn"));
+                    locked.write().await.remove("churn");
+                }
+            })
+        });
+    }
+
+    let mut group = c.benchmark_group("registry_contention");
+    group.throughput(Throughput::Elements((READERS * LOOKUPS) as u64));
+    group.bench_function("snapshot", |b| {
+        b.to_async(&runtime).iter(|| {
+            let readers = (0..READERS).map(|reader| {
+                let snapshot = snapshot.clone();
+                tokio::spawn(async move {
+                    for i in 0..LOOKUPS {
+                        let name = format!("echo-{}", (reader + i) % 100);
+                        assert!(snapshot.load().get(&name).is_some());
+                    }
+                })
+            });
+            async move {
+                futures::future::join_all(readers).await;
+            }
+        })
+    });
+    group.bench_function("rwlock", |b| {
+        b.to_async(&runtime).iter(|| {
+            let readers = (0..READERS).map(|reader| {
+                let locked = locked.clone();
+                tokio::spawn(async move {
+                    for i in 0..LOOKUPS {
+                        let name = format!("echo-{}", (reader + i) % 100);
+                        assert!(locked.read().await.get(&name).is_some());
+                    }
+                })
+            });
+            async move {
+                futures::future::join_all(readers).await;
+            }
+        })
+    });
+    group.finish();
+    stop.store(true, Ordering::Relaxed);
+}
+
+/// Concurrent tools/call and tools/list through the server while tools are
+/// registered and unregistered
+fn concurrent_dispatch(c: &mut Criterion) {
+    let runtime = multi_thread_runtime();
+    let server = Arc::new(server());
+    runtime.block_on(async {
+        for i in 0..100 {
+            server.register_tool(format!("echo-{}", i), Arc::new(EchoTool)).await.unwrap();
+        }
+    });
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let server = server.clone();
+        churn(&runtime, stop.clone(), move |insert| {
+            let server = server.clone();
+            Box::pin(async move {
+                if insert {
+                    server.register_tool("churn".to_string(), Arc::new(EchoTool)).await.unwrap();
+                } else {
+                    server.unregister_tool("churn").await;
+                }
+            })
+        });
+    }
+
+    let mut group = c.benchmark_group("concurrent_dispatch");
+    for (name, line) in [
+        (
+            "tools_call",
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"echo-7","arguments":{}}}"#,
+        ),
+        ("tools_list", r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#),
+    ] {
+        group.throughput(Throughput::Elements((READERS * 100) as u64));
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter(|| {
+                let clients = (0..READERS).map(|client| {
+                    let server = server.clone();
+                    // Each client is its own session, so request ids don't collide
+                    tokio::spawn(mcp_server_rust::session::with_session(
+                        Some(format!("bench-{}", client)),
+                        async move {
+                            for _ in 0..100 {
+                                server.handle_message(line).await.unwrap().unwrap();
+                            }
+                        },
+                    ))
+                });
+                async move {
+                    futures::future::join_all(clients).await;
+                }
+            })
+        });
+    }
+    group.finish();
+    stop.store(true, Ordering::Relaxed);
+}
+
+/// Newline-delimited framing throughput of the stdio listen loop
+fn stdio_framing(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let server = Arc::new(server());
+    let messages = 1000;
+    let input: Vec<u8> = (0..messages)
+        .map(|i| format!("{{\"jsonrpc\":\"2.0\",\"id\":{},\"method\":\"ping\"}}\n", i))
+        .collect::<String>()
+        .into_bytes();
+
+    let mut group = c.benchmark_group("stdio_framing");
+    group.throughput(Throughput::Elements(messages));
+    group.bench_function("ping_x1000", |b| {
+        b.to_async(&runtime).iter(|| {
+            let server = server.clone();
+            let input = input.clone();
+            async move {
+                let handler = move |message: Value| -> BoxFuture<'static, Result<Option<Value>>> {
+                    let server = server.clone();
+                    Box::pin(async move { server.handle_request(message).await })
+                };
+                let mut transport =
+                    StdioTransport::from_io(std::io::Cursor::new(input), tokio::io::sink());
+                transport.listen(handler).await.unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    round_trip,
+    tools_list,
+    registry_contention,
+    concurrent_dispatch,
+    stdio_framing
+);
+criterion_main!(benches);
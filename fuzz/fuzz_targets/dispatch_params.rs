@@ -0,0 +1,51 @@
+//! Wraps arbitrary JSON params in a valid envelope for a known method, so
+//! the fuzzer spends its time inside the method handlers instead of being
+//! rejected by envelope validation
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use tokio::runtime::Runtime;
+
+const METHODS: &[&str] = &[
+    "initialize",
+    "ping",
+    "tools/list",
+    "tools/call",
+    "resources/list",
+    "resources/read",
+    "prompts/list",
+    "prompts/get",
+    "notifications/initialized",
+];
+
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| Runtime::new().expect("tokio runtime"))
+}
+
+fn server() -> &'static McpServer {
+    static SERVER: OnceLock<McpServer> = OnceLock::new();
+    SERVER.get_or_init(|| McpServer::new(ServerConfig::new("FuzzServer", "0.0.0")))
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((selector, params)) = data.split_first() else {
+        return;
+    };
+    let Ok(params) = serde_json::from_slice::<Value>(params) else {
+        return;
+    };
+
+    let method = METHODS[*selector as usize % METHODS.len()];
+    let message = json!({
+        "jsonrpc": "2.0",
+        "id": *selector,
+        "method": method,
+        "params": params
+    });
+    let _ = runtime().block_on(server().handle_request(message));
+});
@@ -0,0 +1,28 @@
+//! Feeds arbitrary input to `McpServer::handle_message` and, when it is valid
+//! JSON, to `McpServer::handle_request`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mcp_server_rust::{McpServer, ServerConfig};
+use std::sync::OnceLock;
+use tokio::runtime::Runtime;
+
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| Runtime::new().expect("tokio runtime"))
+}
+
+fn server() -> &'static McpServer {
+    static SERVER: OnceLock<McpServer> = OnceLock::new();
+    SERVER.get_or_init(|| McpServer::new(ServerConfig::new("FuzzServer", "0.0.0")))
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(raw) = std::str::from_utf8(data) {
+        let _ = runtime().block_on(server().handle_message(raw));
+    }
+    if let Ok(message) = serde_json::from_slice(data) {
+        let _ = runtime().block_on(server().handle_request(message));
+    }
+});
@@ -0,0 +1,35 @@
+//! Runs the stdio listen loop over arbitrary input bytes; the loop must
+//! neither panic nor fail to finish once the input is exhausted
+
+#![no_main]
+
+use futures::future::BoxFuture;
+use libfuzzer_sys::fuzz_target;
+use mcp_server_rust::transport::StdioTransport;
+use mcp_server_rust::{McpServer, ServerConfig};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| Runtime::new().expect("tokio runtime"))
+}
+
+fuzz_target!(|data: &[u8]| {
+    for line in String::from_utf8_lossy(data).lines() {
+        let _ = StdioTransport::parse_line(line);
+    }
+
+    let server = Arc::new(McpServer::new(ServerConfig::new("FuzzServer", "0.0.0")));
+    let handler = move |message: serde_json::Value| -> BoxFuture<'static, mcp_server_rust::Result<Option<serde_json::Value>>> {
+        let server = server.clone();
+        Box::pin(async move { server.handle_request(message).await })
+    };
+
+    let mut transport = StdioTransport::from_io(std::io::Cursor::new(data.to_vec()), tokio::io::sink());
+    let finished = runtime().block_on(async {
+        tokio::time::timeout(Duration::from_secs(5), transport.listen(handler)).await
+    });
+    assert!(finished.is_ok(), "listen loop did not finish on exhausted input");
+});
@@ -0,0 +1,54 @@
+/*
+ * C API for embedding the MCP server (see src/ffi.rs)
+ *
+ * Build the library with:
+ *   cargo rustc --release --lib --crate-type cdylib
+ */
+#ifndef MCP_SERVER_H
+#define MCP_SERVER_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct McpServer McpServer;
+
+/*
+ * Called with the tool arguments as JSON. Returns the tool's text output, or
+ * NULL to report a failure. The string is copied before the next call into
+ * the host, so it may point into a reused buffer.
+ */
+typedef const char *(*McpToolCallback)(void *user_data, const char *arguments_json);
+
+/* Returns NULL on invalid arguments. Free with mcp_server_free. */
+McpServer *mcp_server_new(const char *name, const char *version);
+
+void mcp_server_free(McpServer *server);
+
+/*
+ * Returns 0 on success, -1 on invalid arguments. input_schema_json may be
+ * NULL. user_data must stay valid for the lifetime of the server.
+ */
+int mcp_server_register_tool(McpServer *server,
+                             const char *name,
+                             const char *description,
+                             const char *input_schema_json,
+                             McpToolCallback callback,
+                             void *user_data);
+
+/*
+ * Handles one JSON-RPC message and returns the response, or NULL when there
+ * is none (notifications). Free the result with mcp_string_free.
+ */
+char *mcp_server_handle_message(McpServer *server, const char *message);
+
+/* Next pending server notification, or NULL. Free with mcp_string_free. */
+char *mcp_server_poll_notification(McpServer *server);
+
+void mcp_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MCP_SERVER_H */
@@ -0,0 +1,156 @@
+//! Attribute macros for `mcp-server-rust`
+//!
+//! Use them through the main crate, e.g. `mcp_server_rust::mcp_tool`; the
+//! generated code refers to it by that name.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::spanned::Spanned;
+use syn::{Attribute, Expr, FnArg, Ident, ItemFn, Lit, LitStr, Meta, Pat, meta};
+
+/// Turns a function into a tool handler
+///
+/// Each parameter becomes a property of the tool's input, so its type must
+/// implement `Deserialize` and `JsonSchema`; `#[serde(...)]` and
+/// `#[schemars(...)]` attributes on a parameter apply to that property. The
+/// function may be async and returns `Result<T>` (or `T`) for any `T`
+/// implementing `IntoCallToolResult`.
+///
+/// Generates a unit struct named after the function in PascalCase with a
+/// `Tool` suffix (`fn word_count` gives `WordCountTool`) implementing
+/// `ToolHandler`. The tool is named after the function with dashes for
+/// underscores and described by its doc comment, unless `name = "..."` or
+/// `description = "..."` say otherwise.
+#[proc_macro_attribute]
+pub fn mcp_tool(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let function = syn::parse_macro_input!(item as ItemFn);
+    match expand(attr.into(), function) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(attr: proc_macro2::TokenStream, mut function: ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let mut name: Option<LitStr> = None;
+    let mut description: Option<LitStr> = None;
+    meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("description") {
+            description = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `name` or `description`"))
+        }
+    })
+    .parse2(attr)?;
+
+    let ident = function.sig.ident.clone();
+    let name = name.unwrap_or_else(|| LitStr::new(&ident.to_string().replace('_', "-"), ident.span()));
+    let description = match description {
+        Some(description) => description,
+        None => LitStr::new(&doc_comment(&function.attrs), Span::call_site()),
+    };
+
+    let mut fields = Vec::new();
+    let mut field_names = Vec::new();
+    for input in function.sig.inputs.iter_mut() {
+        let FnArg::Typed(input) = input else {
+            return Err(syn::Error::new(input.span(), "#[mcp_tool] functions cannot take `self`"));
+        };
+        let Pat::Ident(pattern) = input.pat.as_ref() else {
+            return Err(syn::Error::new(input.pat.span(), "#[mcp_tool] parameters must be plain names"));
+        };
+        let field = pattern.ident.clone();
+        let ty = &input.ty;
+        // The attributes describe the input property, not the parameter
+        let attrs = std::mem::take(&mut input.attrs);
+        fields.push(quote! { #(#attrs)* #field: #ty });
+        field_names.push(field);
+    }
+
+    let krate = quote! { ::mcp_server_rust };
+    let private = quote! { #krate::__private };
+    let vis = &function.vis;
+    let handler = format_ident!("{}Tool", pascal_case(&ident));
+    let arguments = format_ident!("__{}Arguments", pascal_case(&ident));
+    let serde_path = LitStr::new("::mcp_server_rust::__private::serde", Span::call_site());
+    let schemars_path = LitStr::new("::mcp_server_rust::__private::schemars", Span::call_site());
+    let await_call = function.sig.asyncness.map(|_| quote! { .await });
+    let docs = function.attrs.iter().filter(|a| a.path().is_ident("doc"));
+
+    Ok(quote! {
+        #function
+
+        #[doc(hidden)]
+        #[derive(#private::serde::Deserialize, #private::schemars::JsonSchema)]
+        #[serde(crate = #serde_path)]
+        #[schemars(crate = #schemars_path)]
+        struct #arguments {
+            #(#fields,)*
+        }
+
+        #(#docs)*
+        #[derive(Clone, Copy, Debug, Default)]
+        #vis struct #handler;
+
+        #[#private::async_trait::async_trait]
+        impl #krate::tools::ToolHandler for #handler {
+            async fn call(
+                &self,
+                _context: &#krate::context::RequestContext,
+                arguments: #private::serde_json::Value,
+            ) -> #krate::Result<#krate::tools::CallToolResult> {
+                let arguments: #arguments = #krate::tools::typed::parse_arguments(arguments)?;
+                let result = #ident(#(arguments.#field_names),*) #await_call;
+                #krate::tools::typed::IntoCallToolResult::into_call_tool_result(result)
+            }
+
+            fn definition(&self) -> ::core::option::Option<#krate::tools::Tool> {
+                ::core::option::Option::Some(#krate::tools::Tool {
+                    name: ::std::string::String::from(#name),
+                    description: ::std::string::String::from(#description),
+                    input_schema: #krate::tools::typed::input_schema::<#arguments>(),
+                    annotations: ::core::option::Option::None,
+                    output_schema: ::core::option::Option::None,
+                })
+            }
+        }
+    })
+}
+
+/// The `///` lines, trimmed and joined
+fn doc_comment(attrs: &[Attribute]) -> String {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(pair) if pair.path.is_ident("doc") => match &pair.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(line) => Some(line.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+fn pascal_case(ident: &Ident) -> String {
+    ident
+        .to_string()
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
\ No newline at end of file
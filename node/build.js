@@ -0,0 +1,22 @@
+'use strict';
+
+// Builds the addon and copies it to where index.js loads it from
+const { execFileSync } = require('child_process');
+const fs = require('fs');
+const path = require('path');
+
+const release = process.argv.includes('--release');
+execFileSync('cargo', release ? ['build', '--release'] : ['build'], {
+  cwd: __dirname,
+  stdio: 'inherit',
+});
+
+const library = {
+  win32: 'mcp_server_node.dll',
+  darwin: 'libmcp_server_node.dylib',
+}[process.platform] || 'libmcp_server_node.so';
+const profile = release ? 'release' : 'debug';
+fs.copyFileSync(
+  path.join(__dirname, 'target', profile, library),
+  path.join(__dirname, 'mcp-server-node.node')
+);
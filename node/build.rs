@@ -0,0 +1 @@
+fn main() { napi_build::setup(); }
@@ -0,0 +1,30 @@
+export interface ToolDefinition {
+  name: string;
+  description?: string;
+  inputSchema?: object;
+  annotations?: object;
+}
+
+export interface TextContent {
+  type: 'text';
+  text: string;
+}
+
+export interface CallToolResult {
+  content: TextContent[];
+  isError?: boolean;
+}
+
+export type ToolHandler = (
+  args: any
+) => string | CallToolResult | Promise<string | CallToolResult>;
+
+export class McpServer {
+  constructor(name: string, version: string);
+  registerTool(definition: ToolDefinition, handler: ToolHandler): void;
+  unregisterTool(name: string): Promise<boolean>;
+  /** Resolves to the JSON-RPC response, or null for notifications */
+  handleMessage(message: string | object): Promise<object | null>;
+  /** Serves newline-delimited JSON-RPC on stdin/stdout until stdin closes */
+  serveStdio(): Promise<void>;
+}
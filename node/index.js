@@ -0,0 +1,46 @@
+'use strict';
+
+const { NativeServer } = require('./mcp-server-node.node');
+
+/**
+ * An MCP server whose tools are implemented in JavaScript.
+ *
+ * A tool handler receives the call arguments and returns (or resolves to)
+ * a string, used as the text output, or an object shaped like a
+ * CallToolResult. A thrown error becomes an error result.
+ */
+class McpServer {
+  constructor(name, version) {
+    this.native = new NativeServer(name, version);
+  }
+
+  registerTool(definition, handler) {
+    const tool = {
+      name: definition.name,
+      description: definition.description || '',
+      inputSchema: definition.inputSchema || { type: 'object' },
+    };
+    if (definition.annotations) {
+      tool.annotations = definition.annotations;
+    }
+    this.native.registerTool(JSON.stringify(tool), async (argumentsJson) =>
+      JSON.stringify(await handler(JSON.parse(argumentsJson)))
+    );
+  }
+
+  unregisterTool(name) {
+    return this.native.unregisterTool(name);
+  }
+
+  async handleMessage(message) {
+    const raw = typeof message === 'string' ? message : JSON.stringify(message);
+    const response = await this.native.handleMessage(raw);
+    return response === null ? null : JSON.parse(response);
+  }
+
+  serveStdio() {
+    return this.native.serveStdio();
+  }
+}
+
+module.exports = { McpServer };
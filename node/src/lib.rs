@@ -0,0 +1,110 @@
+//! Node.js bindings for mcp-server-rust
+//!
+//! Wraps [`mcp_server_rust::McpServer`] in an N-API class so JavaScript and
+//! TypeScript code can register tool handlers while parsing, dispatch and
+//! the stdio transport stay in Rust. `index.js` adds the JSON plumbing
+//! around the raw class exported here.
+
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::transport::StdioTransport;
+use mcp_server_rust::{Error, ServerConfig};
+use napi::bindgen_prelude::{Promise, block_on};
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction};
+use napi::Env;
+use napi_derive::napi;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Called with the tool arguments as JSON; resolves to the handler's return
+/// value as JSON
+type JsHandler = ThreadsafeFunction<String, ErrorStrategy::Fatal>;
+
+struct JsTool {
+    tool: Tool,
+    handler: JsHandler,
+}
+
+#[async_trait]
+impl ToolHandler for JsTool {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> mcp_server_rust::Result<CallToolResult> {
+        let output = match self.handler.call_async::<Promise<String>>(arguments.to_string()).await {
+            Ok(promise) => promise.await,
+            Err(e) => Err(e),
+        };
+        let output = match output {
+            Ok(output) => output,
+            Err(e) => return Ok(CallToolResult::error(e.reason)),
+        };
+        match serde_json::from_str(&output)? {
+            Value::String(text) => Ok(CallToolResult::success(vec![TextContent::new(text)])),
+            result => serde_json::from_value(result).map_err(|e| {
+                Error::ToolError(format!("{} returned an invalid result: {}", self.tool.name, e))
+            }),
+        }
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(self.tool.clone())
+    }
+}
+
+fn to_napi(e: Error) -> napi::Error {
+    napi::Error::from_reason(e.to_string())
+}
+
+/// An MCP server whose tools may be implemented in JavaScript
+#[napi(js_name = "NativeServer")]
+pub struct NativeServer {
+    server: Arc<mcp_server_rust::McpServer>,
+}
+
+#[napi]
+impl NativeServer {
+    #[napi(constructor)]
+    pub fn new(name: String, version: String) -> Self {
+        Self {
+            server: Arc::new(mcp_server_rust::McpServer::new(ServerConfig::new(name, version))),
+        }
+    }
+
+    /// Registers `handler` as the tool `definition_json` describes
+    #[napi]
+    pub fn register_tool(
+        &self,
+        env: Env,
+        definition_json: String,
+        mut handler: JsHandler,
+    ) -> napi::Result<()> {
+        let tool: Tool = serde_json::from_str(&definition_json)
+            .map_err(|e| napi::Error::from_reason(format!("Invalid tool definition: {}", e)))?;
+        // Registered handlers must not keep the process alive on their own
+        handler.unref(&env)?;
+        let name = tool.name.clone();
+        let tool = JsTool { tool, handler };
+        // Registration only awaits an uncontended lock, so it is fine to
+        // finish it on the JavaScript thread
+        block_on(self.server.register_tool(name, Arc::new(tool))).map_err(to_napi)
+    }
+
+    /// Removes a tool; returns whether it was registered
+    #[napi]
+    pub async fn unregister_tool(&self, name: String) -> bool {
+        self.server.unregister_tool(&name).await
+    }
+
+    /// Handles one JSON-RPC message; resolves to the JSON response, or null
+    /// for notifications
+    #[napi]
+    pub async fn handle_message(&self, message: String) -> napi::Result<Option<String>> {
+        let response = self.server.handle_message(&message).await.map_err(to_napi)?;
+        Ok(response.map(|response| response.to_string()))
+    }
+
+    /// Serves newline-delimited JSON-RPC on stdin/stdout until stdin closes
+    #[napi]
+    pub async fn serve_stdio(&self) -> napi::Result<()> {
+        StdioTransport::new().serve(&self.server).await.map_err(to_napi)
+    }
+}
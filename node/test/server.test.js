@@ -0,0 +1,55 @@
+'use strict';
+
+const assert = require('node:assert');
+const test = require('node:test');
+const { McpServer } = require('..');
+
+function callTool(server, name, args) {
+  return server.handleMessage({
+    jsonrpc: '2.0',
+    id: 1,
+    method: 'tools/call',
+    params: { name, arguments: args },
+  });
+}
+
+test('JavaScript tools are listed and called', async () => {
+  const server = new McpServer('node-test', '0.1.0');
+  server.registerTool(
+    { name: 'shout', description: 'Uppercases its input' },
+    ({ text }) => text.toUpperCase()
+  );
+  server.registerTool({ name: 'slow-add' }, async ({ a, b }) => ({
+    content: [{ type: 'text', text: String(a + b) }],
+  }));
+  server.registerTool({ name: 'fail' }, () => {
+    throw new Error('boom');
+  });
+
+  const list = await server.handleMessage({ jsonrpc: '2.0', id: 1, method: 'tools/list' });
+  const shout = list.result.tools.find((tool) => tool.name === 'shout');
+  assert.strictEqual(shout.description, 'Uppercases its input');
+
+  let response = await callTool(server, 'shout', { text: 'hi' });
+  assert.strictEqual(response.result.content[0].text, 'HI');
+
+  response = await callTool(server, 'slow-add', { a: 2, b: 3 });
+  assert.strictEqual(response.result.content[0].text, '5');
+
+  response = await callTool(server, 'fail', {});
+  assert.strictEqual(response.result.isError, true);
+  assert.match(response.result.content[0].text, /boom/);
+
+  assert.strictEqual(await server.unregisterTool('shout'), true);
+  response = await callTool(server, 'shout', { text: 'hi' });
+  assert.ok(response.error);
+});
+
+test('notifications have no response', async () => {
+  const server = new McpServer('node-test', '0.1.0');
+  const response = await server.handleMessage({
+    jsonrpc: '2.0',
+    method: 'notifications/initialized',
+  });
+  assert.strictEqual(response, null);
+});
@@ -0,0 +1,163 @@
+//! Role-based access control
+//!
+//! An [`AccessPolicy`] maps identities to roles, and each role lists the
+//! tools, resource URIs and prompts it may use. Transports establish the
+//! caller's identity (the HTTP transport from its bearer-token layer) and
+//! run the request inside [`with_identity`]. The server then checks every
+//! call against the policy and leaves what a caller may not use out of
+//! listings. Without a policy, everything is allowed.
+
+use crate::utils::Error;
+use crate::utils::error_codes::ACCESS_DENIED;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT_IDENTITY: Option<String>;
+}
+
+/// Who may use what
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessPolicy {
+    /// Roles by name
+    pub roles: HashMap<String, Role>,
+    /// Role names granted to each identity
+    pub identities: HashMap<String, Vec<String>>,
+    /// Role names for callers without an identity, or with one the policy
+    /// doesn't list
+    pub anonymous: Vec<String>,
+}
+
+/// Patterns naming what a role may use; `*` matches any run of characters
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Role {
+    pub tools: Vec<String>,
+    /// Resource URIs, e.g. `file:///shared/*`
+    pub resources: Vec<String>,
+    pub prompts: Vec<String>,
+}
+
+/// The kind of thing being accessed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    Tool,
+    Resource,
+    Prompt,
+}
+
+impl Target {
+    fn as_str(self) -> &'static str {
+        match self {
+            Target::Tool => "tool",
+            Target::Resource => "resource",
+            Target::Prompt => "prompt",
+        }
+    }
+}
+
+impl AccessPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_role(mut self, name: impl Into<String>, role: Role) -> Self {
+        self.roles.insert(name.into(), role);
+        self
+    }
+
+    /// Grants `identity` the named roles
+    pub fn with_identity(mut self, identity: impl Into<String>, roles: &[&str]) -> Self {
+        self.identities
+            .insert(identity.into(), roles.iter().map(|r| r.to_string()).collect());
+        self
+    }
+
+    /// Grants the named roles to callers without a known identity
+    pub fn with_anonymous(mut self, roles: &[&str]) -> Self {
+        self.anonymous = roles.iter().map(|r| r.to_string()).collect();
+        self
+    }
+
+    /// Whether `identity` may use the `target` called `name`
+    pub fn allows(&self, identity: Option<&str>, target: Target, name: &str) -> bool {
+        let roles = identity
+            .and_then(|identity| self.identities.get(identity))
+            .unwrap_or(&self.anonymous);
+        roles
+            .iter()
+            .filter_map(|role| self.roles.get(role))
+            .any(|role| {
+                let patterns = match target {
+                    Target::Tool => &role.tools,
+                    Target::Resource => &role.resources,
+                    Target::Prompt => &role.prompts,
+                };
+                patterns.iter().any(|pattern| matches(pattern, name))
+            })
+    }
+}
+
+impl Role {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tools(mut self, patterns: &[&str]) -> Self {
+        self.tools = patterns.iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    pub fn with_resources(mut self, patterns: &[&str]) -> Self {
+        self.resources = patterns.iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    pub fn with_prompts(mut self, patterns: &[&str]) -> Self {
+        self.prompts = patterns.iter().map(|p| p.to_string()).collect();
+        self
+    }
+}
+
+/// The error returned when the caller may not use `name`
+pub fn denied(identity: Option<&str>, target: Target, name: &str) -> Error {
+    Error::Application {
+        code: ACCESS_DENIED.code(),
+        message: format!("Access to {} {} denied", target.as_str(), name),
+        data: Some(json!({ "identity": identity, "target": target.as_str(), "name": name })),
+    }
+}
+
+/// Identity of the caller of the current request, if a transport set one
+pub fn current_identity() -> Option<String> {
+    CURRENT_IDENTITY.try_with(Clone::clone).ok().flatten()
+}
+
+/// Runs `future` on behalf of `identity`
+pub fn with_identity<F: Future>(identity: Option<String>, future: F) -> impl Future<Output = F::Output> {
+    CURRENT_IDENTITY.scope(identity, future)
+}
+
+/// Glob match where `*` stands for any run of characters
+fn matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*`: the whole name must match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
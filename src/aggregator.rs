@@ -0,0 +1,388 @@
+//! Gateway that fronts several downstream MCP servers as one
+//!
+//! Each downstream server is spawned as a child process and spoken to over
+//! stdio with an [`McpClient`]. Its tools and prompts are exposed under
+//! `{namespace}{separator}{name}`; resource URIs are kept as-is and routed to
+//! the downstream that listed them. Notifications sent by downstream servers
+//! are forwarded to the upstream client after the response they arrived with.
+
+use crate::client::McpClient;
+use crate::protocol::{RequestEnvelope, ValueRequest};
+use crate::transport::{StdioTransport, Transport};
+use crate::utils::i18n::{self, MessageKey};
+use crate::utils::{Error, Logger, Result};
+use futures::future::{Either, select};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::BufReader;
+use tokio::process::{Child, Command};
+use tokio::sync::{Mutex, RwLock, mpsc};
+
+/// Maximum number of pages followed when listing a downstream server
+const MAX_PAGES: usize = 100;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AggregatorConfig {
+    pub name: String,
+    pub version: String,
+    /// Joins a downstream namespace and a tool or prompt name
+    #[serde(default = "default_separator")]
+    pub separator: String,
+    pub servers: Vec<DownstreamConfig>,
+}
+
+fn default_separator() -> String {
+    "__".to_string()
+}
+
+impl AggregatorConfig {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref())?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Checks the configuration without starting any server; returns the
+    /// problems found, empty if none
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.servers.is_empty() {
+            problems.push("No downstream servers are configured".to_string());
+        }
+        let mut namespaces = std::collections::HashSet::new();
+        for server in &self.servers {
+            if server.namespace.contains(&self.separator) {
+                problems.push(format!(
+                    "Namespace {} contains the separator {}",
+                    server.namespace, self.separator
+                ));
+            }
+            if !namespaces.insert(server.namespace.as_str()) {
+                problems.push(format!("Duplicate namespace: {}", server.namespace));
+            }
+        }
+        problems
+    }
+}
+
+/// A downstream server started as a child process speaking MCP over stdio
+#[derive(Clone, Debug, Deserialize)]
+pub struct DownstreamConfig {
+    pub namespace: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+struct Downstream {
+    namespace: String,
+    client: Mutex<McpClient<StdioTransport>>,
+    capabilities: Value,
+    _child: Child,
+}
+
+/// Merges the catalogs of several downstream MCP servers and forwards calls
+pub struct Aggregator {
+    config: AggregatorConfig,
+    logger: Logger,
+    downstreams: Vec<Downstream>,
+    resource_routes: RwLock<HashMap<String, usize>>,
+    notifications: Mutex<mpsc::UnboundedReceiver<Value>>,
+}
+
+impl Aggregator {
+    /// Spawns and initializes every configured downstream server
+    pub async fn connect(config: AggregatorConfig) -> Result<Self> {
+        let logger = Logger::new("Aggregator");
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let mut downstreams = Vec::with_capacity(config.servers.len());
+        for server in &config.servers {
+            if server.namespace.contains(&config.separator) {
+                return Err(Error::ValidationError(format!(
+                    "Namespace {} contains the separator {}",
+                    server.namespace, config.separator
+                )));
+            }
+
+            logger.info(&format!("Starting downstream server {}", server.namespace));
+            let mut child = Command::new(&server.command)
+                .args(&server.args)
+                .envs(&server.env)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::inherit())
+                .kill_on_drop(true)
+                .spawn()?;
+            let stdin = child.stdin.take().ok_or_else(|| {
+                Error::InternalError(format!("No stdin for {}", server.namespace))
+            })?;
+            let stdout = child.stdout.take().ok_or_else(|| {
+                Error::InternalError(format!("No stdout for {}", server.namespace))
+            })?;
+
+            let sender = sender.clone();
+            let mut client =
+                McpClient::new(StdioTransport::from_io(BufReader::new(stdout), stdin))
+                    .with_client_info(config.name.clone(), config.version.clone())
+                    .on_notification(move |method, params| {
+                        let _ = sender.send(json!({
+                            "jsonrpc": "2.0",
+                            "method": method,
+                            "params": params
+                        }));
+                    });
+            client.initialize().await?;
+            let capabilities = client.server_capabilities().cloned().unwrap_or(json!({}));
+
+            downstreams.push(Downstream {
+                namespace: server.namespace.clone(),
+                client: Mutex::new(client),
+                capabilities,
+                _child: child,
+            });
+        }
+
+        Ok(Self {
+            config,
+            logger,
+            downstreams,
+            resource_routes: RwLock::new(HashMap::new()),
+            notifications: Mutex::new(receiver),
+        })
+    }
+
+    /// Serves upstream requests on `transport` until it closes
+    pub async fn serve(&self, transport: &mut StdioTransport) -> Result<()> {
+        self.logger.info("Starting gateway on stdio");
+
+        // Downstream notifications go out as they arrive rather than after
+        // the next upstream request
+        let sender = transport.sender();
+        let forward = Box::pin(async {
+            let mut notifications = self.notifications.lock().await;
+            while let Some(notification) = notifications.recv().await {
+                if sender.send(notification).await.is_err() {
+                    break;
+                }
+            }
+        });
+        let serve = Box::pin(async {
+            while let Some(message) = transport.receive().await? {
+                if let Some(response) = self.handle_request(message).await? {
+                    transport.send(response).await?;
+                }
+            }
+            Ok(())
+        });
+
+        match select(serve, forward).await {
+            Either::Left((result, _)) => result,
+            Either::Right(((), serve)) => serve.await,
+        }
+    }
+
+    pub async fn handle_request(&self, message: Value) -> Result<Option<Value>> {
+        let envelope = match ValueRequest::from_value(message) {
+            Ok(envelope) => envelope,
+            Err(e) => return Ok(Some(self.respond(Value::Null, Err(e)))),
+        };
+        if let Err(e) = envelope.validate() {
+            return Ok(Some(self.respond(envelope.id.unwrap_or(Value::Null), Err(e))));
+        }
+
+        let RequestEnvelope { id, method, params, .. } = envelope;
+        let method = method.unwrap_or_default();
+        let result = self.dispatch(&method, params.unwrap_or(json!({}))).await;
+
+        match id {
+            Some(id) => Ok(Some(self.respond(id, result))),
+            None => Ok(None),
+        }
+    }
+
+    async fn dispatch(&self, method: &str, params: Value) -> Result<Value> {
+        match method {
+            "initialize" => Ok(self.initialize_result()),
+            "initialized" | "notifications/initialized" | "ping" => Ok(json!({})),
+            "tools/list" => self.list_namespaced("tools", "tools/list").await,
+            "tools/call" => self.call_namespaced("tools/call", MessageKey::ToolNotFound, params).await,
+            "prompts/list" => self.list_namespaced("prompts", "prompts/list").await,
+            "prompts/get" => {
+                self.call_namespaced("prompts/get", MessageKey::PromptNotFound, params)
+                    .await
+            }
+            "resources/list" => self.list_resources().await,
+            "resources/read" => self.read_resource(params).await,
+            _ if method.starts_with("notifications/") => Ok(json!({})),
+            _ => Err(Error::MethodNotFound(method.to_string())),
+        }
+    }
+
+    fn initialize_result(&self) -> Value {
+        let mut capabilities = serde_json::Map::new();
+        for capability in ["tools", "resources", "prompts"] {
+            if self
+                .downstreams
+                .iter()
+                .any(|d| d.capabilities.get(capability).is_some())
+            {
+                capabilities.insert(capability.to_string(), json!({}));
+            }
+        }
+
+        json!({
+            "protocolVersion": crate::PROTOCOL_VERSION,
+            "capabilities": capabilities,
+            "serverInfo": {
+                "name": self.config.name,
+                "version": self.config.version
+            }
+        })
+    }
+
+    /// Lists `capability` on every downstream that advertises it, prefixing
+    /// each item's name with the downstream's namespace
+    async fn list_namespaced(&self, capability: &str, method: &str) -> Result<Value> {
+        let mut merged = Vec::new();
+        for downstream in self.supporting(capability) {
+            for mut item in self.list_all(downstream, method, capability).await? {
+                if let Some(name) = item.get("name").and_then(Value::as_str) {
+                    item["name"] = json!(self.qualify(&downstream.namespace, name));
+                }
+                merged.push(item);
+            }
+        }
+        Ok(json!({ capability: merged }))
+    }
+
+    async fn call_namespaced(&self, method: &str, not_found: MessageKey, mut params: Value) -> Result<Value> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::InvalidParams(format!("Missing name for {}", method)))?;
+        let (downstream, local_name) = self
+            .resolve(name)
+            .ok_or_else(|| Error::method_not_found(not_found, &[name]))?;
+
+        params["name"] = json!(local_name);
+        downstream.client.lock().await.request(method, params).await
+    }
+
+    async fn list_resources(&self) -> Result<Value> {
+        let mut merged = Vec::new();
+        let mut routes = HashMap::new();
+        for (index, downstream) in self.downstreams.iter().enumerate() {
+            if downstream.capabilities.get("resources").is_none() {
+                continue;
+            }
+            for resource in self.list_all(downstream, "resources/list", "resources").await? {
+                let Some(uri) = resource.get("uri").and_then(Value::as_str) else {
+                    continue;
+                };
+                if routes.contains_key(uri) {
+                    self.logger.warn(&format!(
+                        "Resource {} from {} shadowed by an earlier server",
+                        uri, downstream.namespace
+                    ));
+                    continue;
+                }
+                routes.insert(uri.to_string(), index);
+                merged.push(resource);
+            }
+        }
+
+        *self.resource_routes.write().await = routes;
+        Ok(json!({ "resources": merged }))
+    }
+
+    async fn read_resource(&self, params: Value) -> Result<Value> {
+        let uri = params
+            .get("uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::invalid_params(MessageKey::MissingResourceUri, &[]))?;
+
+        let mut route = self.resource_routes.read().await.get(uri).copied();
+        if route.is_none() {
+            // The client may read a URI it learned elsewhere; refresh once
+            self.list_resources().await?;
+            route = self.resource_routes.read().await.get(uri).copied();
+        }
+        let downstream = route
+            .map(|index| &self.downstreams[index])
+            .ok_or_else(|| Error::resource_error(MessageKey::ResourceNotFound, &[uri]))?;
+
+        downstream
+            .client
+            .lock()
+            .await
+            .request("resources/read", params)
+            .await
+    }
+
+    async fn list_all(&self, downstream: &Downstream, method: &str, field: &str) -> Result<Vec<Value>> {
+        let mut client = downstream.client.lock().await;
+        let mut items = Vec::new();
+        let mut params = json!({});
+
+        for _ in 0..MAX_PAGES {
+            let mut result = client.request(method, params).await?;
+            if let Some(Value::Array(page)) = result.get_mut(field).map(Value::take) {
+                items.extend(page);
+            }
+            match result.get("nextCursor").and_then(Value::as_str) {
+                Some(cursor) => params = json!({ "cursor": cursor }),
+                None => return Ok(items),
+            }
+        }
+
+        self.logger.warn(&format!(
+            "{} on {} did not finish within {} pages",
+            method, downstream.namespace, MAX_PAGES
+        ));
+        Ok(items)
+    }
+
+    fn supporting<'a>(&'a self, capability: &'a str) -> impl Iterator<Item = &'a Downstream> {
+        self.downstreams
+            .iter()
+            .filter(move |d| d.capabilities.get(capability).is_some())
+    }
+
+    fn qualify(&self, namespace: &str, name: &str) -> String {
+        format!("{}{}{}", namespace, self.config.separator, name)
+    }
+
+    fn resolve<'a>(&self, name: &'a str) -> Option<(&Downstream, &'a str)> {
+        let (namespace, local_name) = name.split_once(&self.config.separator)?;
+        let downstream = self.downstreams.iter().find(|d| d.namespace == namespace)?;
+        Some((downstream, local_name))
+    }
+
+    fn respond(&self, id: Value, result: Result<Value>) -> Value {
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => {
+                let error = match e {
+                    // Downstream errors are passed through unchanged
+                    Error::Rpc { code, message, data } => {
+                        let mut error = json!({ "code": code, "message": message });
+                        if let Some(data) = data {
+                            error["data"] = data;
+                        }
+                        error
+                    }
+                    e => {
+                        let (message, _) = i18n::localize_error(i18n::DEFAULT_LOCALE, &e);
+                        json!({ "code": e.code(), "message": message })
+                    }
+                };
+                json!({ "jsonrpc": "2.0", "id": id, "error": error })
+            }
+        }
+    }
+}
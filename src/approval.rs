@@ -0,0 +1,122 @@
+//! Human-in-the-loop approval of destructive tool calls
+//!
+//! When an [`ApprovalPolicy`] is installed with
+//! [`McpServer::with_approval_policy`], every call of a tool annotated
+//! `destructiveHint: true` is reviewed before it runs. A denied call
+//! returns an error result instead of executing. Either way the decision is
+//! recorded in the [audit log](crate::audit).
+//!
+//! [`McpServer::with_approval_policy`]: crate::server::McpServer::with_approval_policy
+
+use crate::elicitation::ElicitationRequest;
+use crate::server::McpServer;
+use crate::utils::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+
+/// A tool call awaiting approval
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ApprovalRequest {
+    pub tool: String,
+    pub arguments: Value,
+    pub session: Option<String>,
+    pub identity: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Approval {
+    Approved,
+    Denied { reason: String },
+}
+
+impl Approval {
+    pub fn denied(reason: impl Into<String>) -> Self {
+        Approval::Denied { reason: reason.into() }
+    }
+}
+
+/// Decides whether a destructive tool call may go ahead
+///
+/// An error counts as a denial, so an unreachable approver never lets a
+/// call through.
+#[async_trait]
+pub trait ApprovalPolicy: Send + Sync {
+    async fn review(&self, server: &McpServer, request: &ApprovalRequest) -> Result<Approval>;
+}
+
+/// Asks the user through the client with `elicitation/create`
+///
+/// The client must have declared the `elicitation` capability. Over stdio
+/// the client's answer can only be read once the call has returned, so use
+/// this with a transport that serves requests concurrently, such as HTTP.
+pub struct ElicitationApproval;
+
+#[async_trait]
+impl ApprovalPolicy for ElicitationApproval {
+    async fn review(&self, server: &McpServer, request: &ApprovalRequest) -> Result<Approval> {
+        let prompt = ElicitationRequest::new(format!(
+            "Allow the tool {} to run with arguments {}?",
+            request.tool, request.arguments
+        ))
+        .with_boolean("approve", "Approve", true);
+        let answer = server.client_link().elicit(&prompt).await?;
+        Ok(if answer.accepted() && answer.boolean("approve") != Some(false) {
+            Approval::Approved
+        } else {
+            Approval::denied(format!("User did not approve ({})", answer.action.as_str()))
+        })
+    }
+}
+
+/// Asks an external service: the request is POSTed to `url` as JSON, and
+/// the service answers `{"approved": bool, "reason": "..."}`
+#[cfg(feature = "native")]
+pub struct WebhookApproval {
+    url: String,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "native")]
+impl WebhookApproval {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+#[derive(serde::Deserialize)]
+struct WebhookAnswer {
+    approved: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+#[cfg(feature = "native")]
+#[async_trait]
+impl ApprovalPolicy for WebhookApproval {
+    async fn review(&self, _server: &McpServer, request: &ApprovalRequest) -> Result<Approval> {
+        let fail = |e: reqwest::Error| {
+            crate::utils::Error::InternalError(format!("Approval webhook failed: {}", e))
+        };
+        let answer: WebhookAnswer = self
+            .client
+            .post(&self.url)
+            .json(request)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(fail)?
+            .json()
+            .await
+            .map_err(fail)?;
+        Ok(if answer.approved {
+            Approval::Approved
+        } else {
+            Approval::denied(answer.reason.unwrap_or_else(|| "Denied by approval webhook".to_string()))
+        })
+    }
+}
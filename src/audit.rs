@@ -0,0 +1,111 @@
+//! Record of security-relevant decisions
+//!
+//! Entries are kept in the server's state store under [`AUDIT_NAMESPACE`],
+//! so they persist (and are encrypted) along with the rest of its state,
+//! and are mirrored to the log.
+
+use crate::state::StateStore;
+use crate::utils::{Logger, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// State store namespace holding the audit entries
+pub const AUDIT_NAMESPACE: &str = "audit";
+
+/// One recorded decision
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Milliseconds since the Unix epoch
+    pub at_ms: u64,
+    /// What was decided on, e.g. `tools/call`
+    pub action: String,
+    /// The tool, resource or prompt involved
+    pub target: String,
+    pub session: Option<String>,
+    pub identity: Option<String>,
+    /// e.g. `approved` or `denied`
+    pub outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl AuditEntry {
+    /// An entry stamped with the current time
+    pub fn new(action: impl Into<String>, target: impl Into<String>, outcome: impl Into<String>) -> Self {
+        let at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        Self {
+            at_ms,
+            action: action.into(),
+            target: target.into(),
+            session: None,
+            identity: None,
+            outcome: outcome.into(),
+            reason: None,
+        }
+    }
+
+    pub fn with_session(mut self, session: Option<String>) -> Self {
+        self.session = session;
+        self
+    }
+
+    pub fn with_identity(mut self, identity: Option<String>) -> Self {
+        self.identity = identity;
+        self
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+/// Appends entries to the state store
+pub struct AuditLog {
+    store: Arc<dyn StateStore>,
+    logger: Logger,
+    next_seq: AtomicU64,
+}
+
+impl AuditLog {
+    pub fn new(store: Arc<dyn StateStore>) -> Self {
+        Self {
+            store,
+            logger: Logger::new("Audit"),
+            next_seq: AtomicU64::new(0),
+        }
+    }
+
+    pub async fn record(&self, entry: &AuditEntry) -> Result<()> {
+        self.logger.info(&format!(
+            "{} {} {} (session {:?}, identity {:?}){}",
+            entry.action,
+            entry.target,
+            entry.outcome,
+            entry.session,
+            entry.identity,
+            entry.reason.as_ref().map(|r| format!(": {}", r)).unwrap_or_default()
+        ));
+        // Zero-padded so keys sort by time, then by order of recording; the
+        // uuid keeps keys from different runs apart
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        let key = format!("{:020}-{:010}-{}", entry.at_ms, seq, uuid::Uuid::new_v4());
+        self.store
+            .put(AUDIT_NAMESPACE, &key, serde_json::to_vec(entry)?)
+            .await
+    }
+
+    /// All recorded entries, oldest first
+    pub async fn entries(&self) -> Result<Vec<AuditEntry>> {
+        let mut entries = self.store.list(AUDIT_NAMESPACE).await?;
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+            .into_iter()
+            .map(|(_, bytes)| Ok(serde_json::from_slice(&bytes)?))
+            .collect()
+    }
+}
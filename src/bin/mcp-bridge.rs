@@ -0,0 +1,64 @@
+//! Bridges stdio MCP hosts and servers to Streamable HTTP and back
+
+use clap::{Parser, Subcommand};
+use mcp_server_rust::{
+    bridge::{HttpToStdio, StdioToHttp},
+    transport::StdioTransport,
+    utils::logger::init_logger,
+};
+use std::net::SocketAddr;
+use tokio::io::BufReader;
+use tracing::error;
+
+#[derive(Parser)]
+#[command(version, about = "Bridge MCP sessions between stdio and HTTP")]
+struct Args {
+    #[command(subcommand)]
+    mode: Mode,
+}
+
+#[derive(Subcommand)]
+enum Mode {
+    /// Forward a stdio session from a desktop host to a remote HTTP server
+    ToHttp {
+        /// Streamable HTTP endpoint, e.g. https://example.com/mcp
+        url: String,
+    },
+    /// Expose a local stdio server over HTTP at POST /mcp
+    ToStdio {
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: SocketAddr,
+        /// Server command and its arguments
+        #[arg(required = true, trailing_var_arg = true)]
+        command: Vec<String>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    init_logger();
+
+    let result = match Args::parse().mode {
+        Mode::ToHttp { url } => {
+            let input = StdioTransport::from_io(
+                BufReader::new(tokio::io::stdin()),
+                tokio::io::sink(),
+            );
+            let output = StdioTransport::from_io(
+                BufReader::new(tokio::io::empty()),
+                tokio::io::stdout(),
+            );
+            StdioToHttp::new(url).run(input, output).await
+        }
+        Mode::ToStdio { listen, command } => {
+            let bridge = HttpToStdio::spawn(&command[0], &command[1..]).await?;
+            bridge.serve(listen).await
+        }
+    };
+
+    if let Err(e) = result {
+        error!("Bridge error: {}", e);
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Serves the operations of an OpenAPI document as MCP tools
+
+use clap::Parser;
+use mcp_server_rust::{
+    McpServer, ServerConfig,
+    openapi::{AuthConfig, OpenApiSpec},
+    transport::{StdioTransport, http},
+    utils::logger::init_logger,
+};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{error, info};
+
+#[derive(Parser)]
+#[command(version, about = "Expose an OpenAPI 3 document (JSON or YAML) as MCP tools")]
+struct Args {
+    /// OpenAPI document to serve
+    spec: PathBuf,
+
+    /// JSON auth config, e.g. {"type": "bearer", "token": {"env": "API_TOKEN"}}
+    #[arg(long)]
+    auth: Option<PathBuf>,
+
+    /// Overrides the first `servers` entry of the document
+    #[arg(long)]
+    base_url: Option<String>,
+
+    /// Serve JSON-RPC over HTTP on this address instead of stdio
+    #[arg(long)]
+    http: Option<SocketAddr>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    init_logger();
+    let args = Args::parse();
+
+    let spec = OpenApiSpec::from_file(&args.spec)?;
+    let auth = match &args.auth {
+        Some(path) => AuthConfig::from_file(path)?,
+        None => AuthConfig::None,
+    };
+    let base_url = args
+        .base_url
+        .as_deref()
+        .or(spec.base_url())
+        .ok_or_else(|| anyhow::anyhow!("The document has no servers entry; pass --base-url"))?
+        .to_string();
+
+    let server = McpServer::new(ServerConfig::new("OpenApiBridge", env!("CARGO_PKG_VERSION")));
+    for tool in spec.tools(&base_url, auth)? {
+        server.register_tool(tool.name().to_string(), Arc::new(tool)).await?;
+    }
+    info!("Forwarding tool calls to {}", base_url);
+
+    let result = match args.http {
+        Some(addr) => http::serve(Arc::new(server), addr).await,
+        None => StdioTransport::new().serve(&server).await,
+    };
+    if let Err(e) = result {
+        error!("Transport error: {}", e);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,17 @@
+//! The built-in server for WASI runtimes
+//!
+//! ```bash
+//! cargo build --bin mcp-server-wasi --no-default-features --features rt-tokio --target wasm32-wasip1
+//! wasmtime target/wasm32-wasip1/debug/mcp-server-wasi.wasm
+//! ```
+
+use mcp_server_rust::{McpServer, ServerConfig, transport::WasiTransport, utils::logger::init_logger};
+
+fn main() -> anyhow::Result<()> {
+    init_logger();
+
+    let server = McpServer::new(ServerConfig::new("RustMcpServer", "1.0.0"));
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build()?;
+    runtime.block_on(WasiTransport::new().serve(&server))?;
+    Ok(())
+}
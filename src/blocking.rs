@@ -0,0 +1,131 @@
+//! Synchronous facade over [`McpServer`]
+//!
+//! For sync applications that want to embed a server without adopting
+//! async: [`BlockingServer`] owns a current-thread runtime and blocks on it
+//! for every call.
+//!
+//! ```no_run
+//! use mcp_server_rust::blocking::BlockingServer;
+//! use mcp_server_rust::tools::{CallToolResult, TextContent, Tool};
+//! use mcp_server_rust::ServerConfig;
+//! use serde_json::json;
+//!
+//! # fn main() -> mcp_server_rust::Result<()> {
+//! let server = BlockingServer::new(ServerConfig::new("sync-app", "0.1.0"))?;
+//! let tool = Tool {
+//!     name: "shout".to_string(),
+//!     description: "Uppercases its input".to_string(),
+//!     input_schema: json!({ "type": "object" }),
+//!     annotations: None,
+//!     output_schema: None,
+//! };
+//! server.register_tool_fn(tool, |args| {
+//!     let text = args["text"].as_str().unwrap_or_default().to_uppercase();
+//!     Ok(CallToolResult::success(vec![TextContent::new(text)]))
+//! })?;
+//! server.serve_stdio()
+//! # }
+//! ```
+//!
+//! Methods block the calling thread, so they must not be called from
+//! inside an async runtime.
+
+use crate::context::RequestContext;
+use crate::prompts::PromptHandler;
+use crate::resources::ResourceHandler;
+use crate::tools::{CallToolResult, Tool, ToolHandler};
+use crate::transport::WasiTransport;
+use crate::utils::Result;
+use crate::{McpServer, ServerConfig};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// An [`McpServer`] driven by its own runtime
+pub struct BlockingServer {
+    server: Arc<McpServer>,
+    runtime: Runtime,
+}
+
+impl BlockingServer {
+    pub fn new(config: ServerConfig) -> Result<Self> {
+        Self::from_server(McpServer::new(config))
+    }
+
+    /// Wraps an already configured server
+    pub fn from_server(server: McpServer) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            server: Arc::new(server),
+            runtime,
+        })
+    }
+
+    /// The wrapped server, e.g. to hand to async code elsewhere
+    pub fn server(&self) -> &Arc<McpServer> {
+        &self.server
+    }
+
+    /// Runs `future` to completion on the server's runtime
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    pub fn register_tool(&self, name: String, handler: Arc<dyn ToolHandler>) -> Result<()> {
+        self.block_on(self.server.register_tool(name, handler))
+    }
+
+    /// Registers a plain function as the tool `tool` describes
+    pub fn register_tool_fn<F>(&self, tool: Tool, f: F) -> Result<()>
+    where
+        F: Fn(Value) -> Result<CallToolResult> + Send + Sync + 'static,
+    {
+        let name = tool.name.clone();
+        self.register_tool(name, Arc::new(FnTool { tool, f }))
+    }
+
+    pub fn register_resource(&self, name: String, handler: Arc<dyn ResourceHandler>) -> Result<()> {
+        self.block_on(self.server.register_resource(name, handler))
+    }
+
+    pub fn register_prompt(&self, name: String, handler: Arc<dyn PromptHandler>) -> Result<()> {
+        self.block_on(self.server.register_prompt(name, handler))
+    }
+
+    pub fn handle_request(&self, message: Value) -> Result<Option<Value>> {
+        self.block_on(self.server.handle_request(message))
+    }
+
+    pub fn handle_message(&self, raw: &str) -> Result<Option<Value>> {
+        self.block_on(self.server.handle_message(raw))
+    }
+
+    /// Serves newline-delimited JSON-RPC on stdin/stdout until stdin closes
+    pub fn serve_stdio(&self) -> Result<()> {
+        self.block_on(WasiTransport::new().serve(&self.server))
+    }
+}
+
+/// [`ToolHandler`] calling a synchronous function
+struct FnTool<F> {
+    tool: Tool,
+    f: F,
+}
+
+#[async_trait]
+impl<F> ToolHandler for FnTool<F>
+where
+    F: Fn(Value) -> Result<CallToolResult> + Send + Sync + 'static,
+{
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        (self.f)(arguments)
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(self.tool.clone())
+    }
+}
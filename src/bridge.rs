@@ -0,0 +1,423 @@
+//! Bridges between stdio and HTTP MCP endpoints
+//!
+//! [`StdioToHttp`] lets a desktop host that only speaks stdio use a remote
+//! Streamable HTTP server: every message read from stdin is POSTed to the
+//! endpoint, and JSON or SSE replies are written to stdout. The
+//! `Mcp-Session-Id` issued by the server is echoed on later requests; if the
+//! server forgets the session (404), the bridge replays the host's initialize
+//! handshake and retries.
+//!
+//! [`HttpToStdio`] goes the other way: it spawns a stdio server and exposes
+//! it at `POST /mcp`, matching responses to requests by id.
+
+use crate::transport::http::SESSION_HEADER;
+use crate::transport::{StdioTransport, Transport};
+use crate::utils::{Error, Logger, Result, RetryPolicy};
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::value::RawValue;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::BufReader;
+use tokio::process::{Child, Command};
+use tokio::sync::{Mutex, mpsc, oneshot};
+
+#[derive(Default)]
+struct Session {
+    id: Option<String>,
+    /// The host's initialize request, replayed when the session expires
+    initialize: Option<Value>,
+}
+
+/// Forwards a stdio session to a remote Streamable HTTP endpoint
+pub struct StdioToHttp {
+    endpoint: String,
+    http: reqwest::Client,
+    retry_policy: RetryPolicy,
+    session: Mutex<Session>,
+    /// Serializes re-initialization when several requests see the expiry
+    reinitializing: Mutex<()>,
+    logger: Logger,
+}
+
+impl StdioToHttp {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            http: reqwest::Client::new(),
+            retry_policy: RetryPolicy::default(),
+            session: Mutex::new(Session::default()),
+            reinitializing: Mutex::new(()),
+            logger: Logger::new("StdioToHttp"),
+        }
+    }
+
+    /// Backoff used when the endpoint cannot be reached
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Reads messages from `input` until it closes and writes every reply to
+    /// `output`; the session is ended with a DELETE afterwards
+    pub async fn run(self, mut input: StdioTransport, mut output: StdioTransport) -> Result<()> {
+        let bridge = Arc::new(self);
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Box<RawValue>>();
+
+        let writer = tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                output.write_message(&message).await?;
+            }
+            Ok::<_, Error>(())
+        });
+
+        let mut in_flight = Vec::new();
+        while let Some(message) = input.receive().await? {
+            // The handshake establishes the session, so nothing may overtake it
+            if message.get("method").and_then(Value::as_str) == Some("initialize") {
+                bridge.forward(message, &sender).await;
+                continue;
+            }
+
+            // Everything else is forwarded concurrently so a long-running
+            // call does not hold up the ones behind it
+            let bridge = bridge.clone();
+            let sender = sender.clone();
+            in_flight.push(tokio::spawn(async move {
+                bridge.forward(message, &sender).await;
+            }));
+            in_flight.retain(|task| !task.is_finished());
+        }
+
+        for task in in_flight {
+            let _ = task.await;
+        }
+        drop(sender);
+        writer
+            .await
+            .map_err(|e| Error::AsyncError(e.to_string()))??;
+
+        bridge.end_session().await;
+        Ok(())
+    }
+
+    async fn forward(&self, message: Value, output: &mpsc::UnboundedSender<Box<RawValue>>) {
+        if message.get("method").and_then(Value::as_str) == Some("initialize") {
+            self.session.lock().await.initialize = Some(message.clone());
+        }
+
+        let id = message.get("id").cloned();
+        let result = match self.post_with_session(&message).await {
+            Ok(replies) => replies.into_iter().try_for_each(|reply| {
+                output
+                    .send(reply)
+                    .map_err(|_| Error::AsyncError("Output closed".to_string()))
+            }),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            self.logger.error(&format!("Forwarding failed: {}", e));
+            let is_request = message.get("method").is_some();
+            if let (Some(id), true) = (id, is_request) {
+                let error = json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32603, "message": format!("Bridge error: {}", e) }
+                });
+                if let Ok(error) = serde_json::value::to_raw_value(&error) {
+                    let _ = output.send(error);
+                }
+            }
+        }
+    }
+
+    /// POSTs `message`, re-establishing the session once if it has expired
+    async fn post_with_session(&self, message: &Value) -> Result<Vec<Box<RawValue>>> {
+        let session_id = self.session.lock().await.id.clone();
+        match self.post(message, session_id.as_deref()).await? {
+            Reply::Messages(replies) => Ok(replies),
+            Reply::SessionExpired => {
+                self.logger.warn("Session expired, re-initializing");
+                self.reinitialize(session_id.as_deref()).await?;
+                let session_id = self.session.lock().await.id.clone();
+                match self.post(message, session_id.as_deref()).await? {
+                    Reply::Messages(replies) => Ok(replies),
+                    Reply::SessionExpired => Err(Error::InternalError(
+                        "Session expired again after re-initializing".to_string(),
+                    )),
+                }
+            }
+        }
+    }
+
+    async fn reinitialize(&self, expired: Option<&str>) -> Result<()> {
+        let _reinitializing = self.reinitializing.lock().await;
+        let initialize = {
+            let mut session = self.session.lock().await;
+            if session.id.as_deref() != expired {
+                // Another request already established a new session
+                return Ok(());
+            }
+            session.id = None;
+            session.initialize.clone().ok_or_else(|| {
+                Error::InternalError("No initialize request to replay".to_string())
+            })?
+        };
+
+        // The replayed handshake's replies are not forwarded to the host
+        self.post(&initialize, None).await?;
+        let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        let session_id = self.session.lock().await.id.clone();
+        self.post(&initialized, session_id.as_deref()).await?;
+        Ok(())
+    }
+
+    async fn post(&self, message: &Value, session_id: Option<&str>) -> Result<Reply> {
+        let response = self
+            .retry_policy
+            .retry(|| async {
+                let mut request = self
+                    .http
+                    .post(&self.endpoint)
+                    .header(header::ACCEPT, "application/json, text/event-stream")
+                    .json(message);
+                if let Some(session_id) = session_id {
+                    request = request.header(SESSION_HEADER, session_id);
+                }
+                request.send().await.map_err(http_error)
+            })
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND && session_id.is_some() {
+            return Ok(Reply::SessionExpired);
+        }
+        if let Some(new_id) = response
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            let mut session = self.session.lock().await;
+            if session.id.as_deref() != Some(new_id) {
+                self.logger.info(&format!("Session established: {}", new_id));
+                session.id = Some(new_id.to_string());
+            }
+        }
+
+        let status = response.status();
+        if status == StatusCode::ACCEPTED || status == StatusCode::NO_CONTENT {
+            return Ok(Reply::Messages(Vec::new()));
+        }
+        let is_event_stream = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        if !status.is_success() && !is_event_stream {
+            let body = response.text().await.unwrap_or_default();
+            // JSON-RPC error bodies are passed through; anything else fails
+            if serde_json::from_str::<Value>(&body).is_ok_and(|v| v.get("jsonrpc").is_some()) {
+                return Ok(Reply::Messages(vec![RawValue::from_string(body)?]));
+            }
+            return Err(Error::InternalError(format!("HTTP {}: {}", status, body)));
+        }
+
+        let body = response.text().await.map_err(http_error)?;
+        let payloads = if is_event_stream {
+            parse_sse(&body)
+        } else {
+            vec![body]
+        };
+
+        payloads
+            .into_iter()
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| RawValue::from_string(p).map_err(Error::from))
+            .collect::<Result<Vec<_>>>()
+            .map(Reply::Messages)
+    }
+
+    async fn end_session(&self) {
+        let Some(session_id) = self.session.lock().await.id.clone() else {
+            return;
+        };
+        let result = self
+            .http
+            .delete(&self.endpoint)
+            .header(SESSION_HEADER, &session_id)
+            .send()
+            .await;
+        if let Err(e) = result {
+            self.logger.warn(&format!("Failed to end session {}: {}", session_id, e));
+        }
+    }
+}
+
+enum Reply {
+    Messages(Vec<Box<RawValue>>),
+    SessionExpired,
+}
+
+/// Connection failures map to retryable IO errors so `RetryPolicy::retry`
+/// backs off on them
+fn http_error(e: reqwest::Error) -> Error {
+    let kind = if e.is_timeout() {
+        ErrorKind::TimedOut
+    } else if e.is_connect() {
+        ErrorKind::ConnectionRefused
+    } else {
+        ErrorKind::Other
+    };
+    Error::Io(std::io::Error::new(kind, e))
+}
+
+/// Returns the data of each event in a `text/event-stream` body
+fn parse_sse(body: &str) -> Vec<String> {
+    let mut events = Vec::new();
+    let mut data: Option<String> = None;
+
+    for line in body.lines() {
+        if line.is_empty() {
+            events.extend(data.take());
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("data:") {
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match &mut data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => data = Some(value.to_string()),
+            }
+        }
+    }
+    events.extend(data);
+    events
+}
+
+/// Serves a stdio MCP server child process over HTTP
+pub struct HttpToStdio {
+    child_input: Mutex<StdioTransport>,
+    pending: Arc<Mutex<HashMap<String, oneshot::Sender<Value>>>>,
+    session_id: String,
+    logger: Logger,
+    _child: Child,
+}
+
+impl HttpToStdio {
+    pub async fn spawn(command: &str, args: &[String]) -> Result<Arc<Self>> {
+        let logger = Logger::new("HttpToStdio");
+        let mut child = Command::new(command)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| Error::InternalError("No stdin for child".to_string()))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| Error::InternalError("No stdout for child".to_string()))?;
+
+        let pending: Arc<Mutex<HashMap<String, oneshot::Sender<Value>>>> = Arc::default();
+        let mut child_output = StdioTransport::from_io(BufReader::new(stdout), tokio::io::sink());
+        let reader_pending = pending.clone();
+        let reader_logger = logger.clone();
+        tokio::spawn(async move {
+            while let Ok(Some(message)) = child_output.receive().await {
+                let waiter = match (message.get("id"), message.get("method")) {
+                    (Some(id), None) => reader_pending.lock().await.remove(&id.to_string()),
+                    _ => None,
+                };
+                match waiter {
+                    Some(waiter) => {
+                        let _ = waiter.send(message);
+                    }
+                    None => reader_logger.debug(&format!(
+                        "Dropping unsolicited message from child: {}",
+                        message
+                    )),
+                }
+            }
+            reader_logger.warn("Child closed its output");
+            // Waiting requests see their sender dropped
+            reader_pending.lock().await.clear();
+        });
+
+        Ok(Arc::new(Self {
+            child_input: Mutex::new(StdioTransport::from_io(
+                BufReader::new(tokio::io::empty()),
+                stdin,
+            )),
+            pending,
+            session_id: uuid::Uuid::new_v4().to_string(),
+            logger,
+            _child: child,
+        }))
+    }
+
+    pub fn router(self: &Arc<Self>) -> Router {
+        Router::new()
+            .route("/mcp", post(handle_post))
+            .with_state(self.clone())
+    }
+
+    pub async fn serve(self: &Arc<Self>, addr: SocketAddr) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        self.logger
+            .info(&format!("Bridging http://{}/mcp to stdio", listener.local_addr()?));
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+}
+
+async fn handle_post(
+    State(bridge): State<Arc<HttpToStdio>>,
+    headers: HeaderMap,
+    Json(message): Json<Value>,
+) -> Response {
+    let session = headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok());
+    if session.is_some_and(|s| s != bridge.session_id) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let is_initialize = message.get("method").and_then(Value::as_str) == Some("initialize");
+    let waiter = match (message.get("id"), message.get("method")) {
+        (Some(id), Some(_)) => {
+            let (sender, receiver) = oneshot::channel();
+            bridge.pending.lock().await.insert(id.to_string(), sender);
+            Some(receiver)
+        }
+        _ => None,
+    };
+
+    if let Err(e) = bridge.child_input.lock().await.send(message).await {
+        bridge.logger.error(&format!("Writing to child failed: {}", e));
+        return StatusCode::BAD_GATEWAY.into_response();
+    }
+
+    let Some(waiter) = waiter else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+    match waiter.await {
+        Ok(response) if is_initialize => (
+            [(SESSION_HEADER, bridge.session_id.clone())],
+            Json(response),
+        )
+            .into_response(),
+        Ok(response) => Json(response).into_response(),
+        Err(_) => StatusCode::BAD_GATEWAY.into_response(),
+    }
+}
@@ -0,0 +1,234 @@
+//! Assembling a server in one expression
+//!
+//! [`McpServerBuilder`] gathers the configuration and the handlers a server
+//! starts with, then [`build`](McpServerBuilder::build) creates the server
+//! and registers everything in one go:
+//!
+//! ```ignore
+//! let server = McpServer::builder("Notes", "1.0.0")
+//!     .instructions("Search notes before creating new ones")
+//!     .tool(Arc::new(SearchNotes))
+//!     .resource("notes://", Arc::new(NotesResource))
+//!     .build()
+//!     .await?;
+//! ```
+
+use crate::access::AccessPolicy;
+use crate::approval::ApprovalPolicy;
+use crate::context::Extensions;
+use crate::middleware::Middleware;
+use crate::prompts::PromptHandler;
+use crate::resources::ResourceHandler;
+use crate::server::{FeatureFlags, Icon, McpServer, ServerConfig};
+use crate::state::StateStore;
+use crate::tools::ToolHandler;
+use crate::tools::search::EmbeddingBackend;
+use crate::utils::{Clock, Error, RequestLimits, Result};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Configuration and handlers for a server yet to be built
+pub struct McpServerBuilder {
+    config: ServerConfig,
+    tools: Vec<Arc<dyn ToolHandler>>,
+    resources: Vec<(String, Arc<dyn ResourceHandler>)>,
+    prompts: Vec<Arc<dyn PromptHandler>>,
+    state_store: Option<Arc<dyn StateStore>>,
+    clock: Option<Arc<dyn Clock>>,
+    approval: Option<Arc<dyn ApprovalPolicy>>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    extensions: Extensions,
+    embeddings: Option<Arc<dyn EmbeddingBackend>>,
+}
+
+impl McpServerBuilder {
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self::from_config(ServerConfig::new(name, version))
+    }
+
+    /// Starts from an existing configuration, e.g. one loaded from a file
+    pub fn from_config(config: ServerConfig) -> Self {
+        Self {
+            config,
+            tools: Vec::new(),
+            resources: Vec::new(),
+            prompts: Vec::new(),
+            state_store: None,
+            clock: None,
+            approval: None,
+            middleware: Vec::new(),
+            extensions: Extensions::new(),
+            embeddings: None,
+        }
+    }
+
+    /// Registers a tool under the name in its definition
+    pub fn tool(mut self, handler: Arc<dyn ToolHandler>) -> Self {
+        self.tools.push(handler);
+        self
+    }
+
+    /// Registers a resource handler for URIs starting with `prefix`
+    pub fn resource(mut self, prefix: impl Into<String>, handler: Arc<dyn ResourceHandler>) -> Self {
+        self.resources.push((prefix.into(), handler));
+        self
+    }
+
+    /// Registers a prompt under the name in its definition
+    pub fn prompt(mut self, handler: Arc<dyn PromptHandler>) -> Self {
+        self.prompts.push(handler);
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.config = self.config.with_title(title);
+        self
+    }
+
+    pub fn website_url(mut self, url: impl Into<String>) -> Self {
+        self.config = self.config.with_website_url(url);
+        self
+    }
+
+    pub fn icon(mut self, icon: Icon) -> Self {
+        self.config = self.config.with_icon(icon);
+        self
+    }
+
+    /// See [`ServerConfig::with_instructions`]
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.config = self.config.with_instructions(instructions);
+        self
+    }
+
+    /// See [`ServerConfig::with_capabilities`]
+    pub fn capabilities(mut self, capabilities: Value) -> Self {
+        self.config = self.config.with_capabilities(capabilities);
+        self
+    }
+
+    /// See [`ServerConfig::with_experimental`]
+    pub fn experimental(mut self, name: impl Into<String>, capability: Value) -> Self {
+        self.config = self.config.with_experimental(name, capability);
+        self
+    }
+
+    /// See [`ServerConfig::resource_subscriptions`]
+    pub fn resource_subscriptions(mut self, resource_subscriptions: bool) -> Self {
+        self.config = self.config.with_resource_subscriptions(resource_subscriptions);
+        self
+    }
+
+    pub fn features(mut self, features: FeatureFlags) -> Self {
+        self.config = self.config.with_features(features);
+        self
+    }
+
+    pub fn limits(mut self, limits: RequestLimits) -> Self {
+        self.config = self.config.with_limits(limits);
+        self
+    }
+
+    /// See [`ServerConfig::with_page_size`]
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.config = self.config.with_page_size(page_size);
+        self
+    }
+
+    /// See [`ServerConfig::list_changed`]
+    pub fn list_changed(mut self, list_changed: bool) -> Self {
+        self.config = self.config.with_list_changed(list_changed);
+        self
+    }
+
+    /// See [`ServerConfig::strict_lifecycle`]
+    pub fn strict_lifecycle(mut self, strict_lifecycle: bool) -> Self {
+        self.config = self.config.with_strict_lifecycle(strict_lifecycle);
+        self
+    }
+
+    pub fn access_policy(mut self, access: AccessPolicy) -> Self {
+        self.config = self.config.with_access_policy(access);
+        self
+    }
+
+    pub fn state_store(mut self, state_store: Arc<dyn StateStore>) -> Self {
+        self.state_store = Some(state_store);
+        self
+    }
+
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    pub fn approval_policy(mut self, policy: Arc<dyn ApprovalPolicy>) -> Self {
+        self.approval = Some(policy);
+        self
+    }
+
+    /// Runs `middleware` around every method, after any added before it
+    pub fn middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Shares `state` with every handler; see [`McpServer::with_state`]
+    pub fn state<T: Send + Sync + 'static>(mut self, state: Arc<T>) -> Self {
+        self.extensions.insert(state);
+        self
+    }
+
+    pub fn embedding_backend(mut self, backend: Arc<dyn EmbeddingBackend>) -> Self {
+        self.embeddings = Some(backend);
+        self
+    }
+
+    /// Creates the server and registers the handlers; fails if a tool or
+    /// prompt has no definition to take its name from
+    pub async fn build(self) -> Result<McpServer> {
+        let mut server = McpServer::new(self.config);
+        if let Some(state_store) = self.state_store {
+            server = server.with_state_store(state_store);
+        }
+        if let Some(clock) = self.clock {
+            server = server.with_clock(clock);
+        }
+        if let Some(policy) = self.approval {
+            server = server.with_approval_policy(policy);
+        }
+        if !self.extensions.is_empty() {
+            server = server.with_extensions(self.extensions);
+        }
+        for middleware in self.middleware {
+            server = server.with_middleware(middleware);
+        }
+        if let Some(backend) = self.embeddings {
+            server = server.with_embedding_backend(backend);
+        }
+
+        for handler in self.tools {
+            let definition = handler.definition().ok_or_else(|| {
+                Error::ValidationError("Tool handlers added to a builder need a definition".to_string())
+            })?;
+            server.register_tool(definition.name, handler).await?;
+        }
+        for (prefix, handler) in self.resources {
+            server.register_resource(prefix, handler).await?;
+        }
+        for handler in self.prompts {
+            let definition = handler.definition().ok_or_else(|| {
+                Error::ValidationError("Prompt handlers added to a builder need a definition".to_string())
+            })?;
+            server.register_prompt(definition.name, handler).await?;
+        }
+        Ok(server)
+    }
+}
+
+impl McpServer {
+    /// Starts a [`McpServerBuilder`]
+    pub fn builder(name: impl Into<String>, version: impl Into<String>) -> McpServerBuilder {
+        McpServerBuilder::new(name, version)
+    }
+}
\ No newline at end of file
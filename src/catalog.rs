@@ -0,0 +1,203 @@
+//! A server's catalog: what it is and the tools, resources and prompts it
+//! offers
+//!
+//! [`Catalog::fetch`] collects it from any server an [`McpClient`] is
+//! connected to, e.g. to dump another server's catalog for inspection, and
+//! [`Catalog::from_server`] from a server in this process. Catalogs
+//! serialize to JSON for offline tool manifests or render as Markdown with
+//! [`Catalog::to_markdown`] for documentation.
+
+use crate::McpServer;
+use crate::client::McpClient;
+use crate::prompts::Prompt;
+use crate::testing::McpTestClient;
+use crate::tools::Tool;
+use crate::transport::Transport;
+use crate::utils::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Write;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Catalog {
+    pub server_info: Value,
+    pub capabilities: Value,
+    pub tools: Vec<Tool>,
+    pub resources: Vec<Value>,
+    pub prompts: Vec<Prompt>,
+}
+
+impl Catalog {
+    /// Initializes the session if needed and lists everything the server
+    /// advertises a capability for
+    pub async fn fetch<T: Transport>(client: &mut McpClient<T>) -> Result<Self> {
+        if client.initialize_result().is_none() {
+            client.initialize().await?;
+        }
+        let initialize_result = client.initialize_result().cloned().unwrap_or_default();
+        let capabilities = &initialize_result["capabilities"];
+
+        let tools = if capabilities.get("tools").is_some() {
+            client.list_tools().await?
+        } else {
+            Vec::new()
+        };
+        let resources = if capabilities.get("resources").is_some() {
+            client.list_resources().await?
+        } else {
+            Vec::new()
+        };
+        let prompts = if capabilities.get("prompts").is_some() {
+            client.list_prompts().await?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self::from_parts(&initialize_result, tools, resources, prompts))
+    }
+
+    /// Collects the catalog of a server in this process
+    pub async fn from_server(server: Arc<McpServer>) -> Result<Self> {
+        let client = McpTestClient::from_arc(server);
+        let initialize_result = client.initialize().await?;
+        let capabilities = &initialize_result["capabilities"];
+
+        let tools = if capabilities.get("tools").is_some() {
+            client.list_tools().await?
+        } else {
+            Vec::new()
+        };
+        let resources = if capabilities.get("resources").is_some() {
+            client.list_resources().await?
+        } else {
+            Vec::new()
+        };
+        let prompts = if capabilities.get("prompts").is_some() {
+            client.list_prompts().await?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self::from_parts(&initialize_result, tools, resources, prompts))
+    }
+
+    fn from_parts(
+        initialize_result: &Value,
+        tools: Vec<Tool>,
+        resources: Vec<Value>,
+        prompts: Vec<Prompt>,
+    ) -> Self {
+        Self {
+            server_info: initialize_result["serverInfo"].clone(),
+            capabilities: initialize_result["capabilities"].clone(),
+            tools,
+            resources,
+            prompts,
+        }
+    }
+
+    /// Renders the catalog as a Markdown document
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let name = self.server_info["name"].as_str().unwrap_or("MCP server");
+        match self.server_info["version"].as_str() {
+            Some(version) => writeln!(out, "# {} {}", name, version),
+            None => writeln!(out, "# {}", name),
+        }
+        .ok();
+
+        if !self.tools.is_empty() {
+            out.push_str("\n## Tools\n");
+            for tool in &self.tools {
+                write!(out, "\n### `{}`\n\n", tool.name).ok();
+                if !tool.description.is_empty() {
+                    write!(out, "{}\n\n", tool.description).ok();
+                }
+                let properties = tool.input_schema["properties"].as_object();
+                if let Some(properties) = properties.filter(|p| !p.is_empty()) {
+                    let required = tool.input_schema["required"].as_array();
+                    out.push_str("| Argument | Type | Required | Description |\n");
+                    out.push_str("| --- | --- | --- | --- |\n");
+                    for (name, schema) in properties {
+                        let is_required = required
+                            .is_some_and(|r| r.iter().any(|n| n.as_str() == Some(name)));
+                        writeln!(
+                            out,
+                            "| `{}` | {} | {} | {} |",
+                            name,
+                            schema["type"].as_str().unwrap_or("any"),
+                            if is_required { "yes" } else { "no" },
+                            table_cell(schema["description"].as_str().unwrap_or_default())
+                        )
+                        .ok();
+                    }
+                    out.push('\n');
+                }
+                if let Some(annotations) = tool.annotations.as_ref().and_then(Value::as_object) {
+                    out.push_str("Annotations:");
+                    for (key, value) in annotations {
+                        write!(out, " `{}: {}`", key, value).ok();
+                    }
+                    out.push_str("\n\n");
+                }
+                write!(
+                    out,
+                    "<details><summary>Input schema</summary>\n\n```json\n{}\n```\n\n</details>\n",
+                    serde_json::to_string_pretty(&tool.input_schema).unwrap_or_default()
+                )
+                .ok();
+            }
+        }
+
+        if !self.resources.is_empty() {
+            out.push_str("\n## Resources\n\n");
+            out.push_str("| URI | Name | Type | Description |\n");
+            out.push_str("| --- | --- | --- | --- |\n");
+            for resource in &self.resources {
+                writeln!(
+                    out,
+                    "| `{}` | {} | {} | {} |",
+                    resource["uri"].as_str().unwrap_or_default(),
+                    table_cell(resource["name"].as_str().unwrap_or_default()),
+                    resource["mimeType"].as_str().unwrap_or_default(),
+                    table_cell(resource["description"].as_str().unwrap_or_default())
+                )
+                .ok();
+            }
+        }
+
+        if !self.prompts.is_empty() {
+            out.push_str("\n## Prompts\n");
+            for prompt in &self.prompts {
+                write!(out, "\n### `{}`\n\n", prompt.name).ok();
+                if !prompt.description.is_empty() {
+                    write!(out, "{}\n\n", prompt.description).ok();
+                }
+                let arguments = prompt.arguments.as_deref().unwrap_or_default();
+                if !arguments.is_empty() {
+                    out.push_str("| Argument | Required | Description |\n");
+                    out.push_str("| --- | --- | --- |\n");
+                    for argument in arguments {
+                        writeln!(
+                            out,
+                            "| `{}` | {} | {} |",
+                            argument.name,
+                            if argument.required == Some(true) { "yes" } else { "no" },
+                            table_cell(&argument.description)
+                        )
+                        .ok();
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Keeps text from breaking out of a Markdown table cell
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
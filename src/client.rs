@@ -0,0 +1,252 @@
+//! MCP client
+//!
+//! [`McpClient`] speaks the client side of the protocol over any
+//! [`Transport`], e.g. to drive a server in a child process over stdio:
+//!
+//! ```no_run
+//! # async fn example() -> mcp_server_rust::Result<()> {
+//! use mcp_server_rust::client::McpClient;
+//! use mcp_server_rust::transport::StdioTransport;
+//! use serde_json::json;
+//!
+//! let mut client = McpClient::new(StdioTransport::new())
+//!     .on_notification(|method, params| eprintln!("{}: {}", method, params));
+//! client.initialize().await?;
+//! let result = client.call_tool("greet", json!({ "name": "Ada" })).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::prompts::{GetPromptResult, Prompt};
+use crate::protocol::{self, Implementation, InitializeParams};
+use crate::resources::ResourceReadResult;
+use crate::tools::{CallToolResult, Tool};
+use crate::transport::{AUTH_TOKEN_KEY, Transport};
+use crate::utils::{Error, Logger, Result};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+
+/// Callback for notifications the server sends while the client waits
+pub type NotificationHandler = Box<dyn Fn(&str, Value) + Send + Sync>;
+
+/// Client side of an MCP session over a [`Transport`]
+///
+/// Requests are answered in order: while waiting for a response the client
+/// passes notifications to the handler and rejects server-initiated
+/// requests with -32601.
+pub struct McpClient<T: Transport> {
+    logger: Logger,
+    transport: T,
+    next_id: u64,
+    client_name: String,
+    client_version: String,
+    on_notification: Option<NotificationHandler>,
+    initialize_result: Option<Value>,
+    auth_token: Option<String>,
+}
+
+impl<T: Transport> McpClient<T> {
+    pub fn new(transport: T) -> Self {
+        Self {
+            logger: Logger::new("McpClient"),
+            transport,
+            next_id: 1,
+            client_name: env!("CARGO_PKG_NAME").to_string(),
+            client_version: env!("CARGO_PKG_VERSION").to_string(),
+            on_notification: None,
+            initialize_result: None,
+            auth_token: None,
+        }
+    }
+
+    /// Name and version sent as `clientInfo` during initialization
+    pub fn with_client_info(mut self, name: impl Into<String>, version: impl Into<String>) -> Self {
+        self.client_name = name.into();
+        self.client_version = version.into();
+        self
+    }
+
+    /// Presents `token` in `initialize` to a server started with a shared
+    /// secret
+    pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    pub fn on_notification<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&str, Value) + Send + Sync + 'static,
+    {
+        self.on_notification = Some(Box::new(handler));
+        self
+    }
+
+    /// The server's initialize result, once [`McpClient::initialize`] succeeded
+    pub fn initialize_result(&self) -> Option<&Value> {
+        self.initialize_result.as_ref()
+    }
+
+    /// The capabilities the server advertised during initialization
+    pub fn server_capabilities(&self) -> Option<&Value> {
+        self.initialize_result.as_ref()?.get("capabilities")
+    }
+
+    /// Sends a request and waits for its `result`, or the JSON-RPC error as
+    /// `Error::Rpc`
+    pub async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.transport
+            .send(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": method,
+                "params": params
+            }))
+            .await?;
+
+        loop {
+            let message = self.transport.receive().await?.ok_or_else(|| {
+                Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!("Connection closed while waiting for {}", method),
+                ))
+            })?;
+
+            if message.get("method").is_some() {
+                self.handle_server_message(message).await?;
+                continue;
+            }
+
+            if message.get("id").and_then(Value::as_u64) == Some(id) {
+                return protocol::response_result(message);
+            }
+
+            self.logger
+                .warn(&format!("Ignoring response with unexpected id: {}", message));
+        }
+    }
+
+    /// Sends a notification
+    pub async fn notify(&mut self, method: &str, params: Value) -> Result<()> {
+        self.transport
+            .send(json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": params
+            }))
+            .await
+    }
+
+    /// Performs the initialize handshake and returns the initialize result
+    pub async fn initialize(&mut self) -> Result<Value> {
+        let mut params = serde_json::to_value(InitializeParams {
+            protocol_version: Some(crate::PROTOCOL_VERSION.to_string()),
+            client_info: Some(Implementation::new(&self.client_name, &self.client_version)),
+            ..InitializeParams::default()
+        })?;
+        if let Some(token) = &self.auth_token {
+            params["_meta"] = json!({ AUTH_TOKEN_KEY: token });
+        }
+        let result = self.request("initialize", params).await?;
+        self.notify("notifications/initialized", json!({})).await?;
+        self.initialize_result = Some(result.clone());
+        Ok(result)
+    }
+
+    pub async fn ping(&mut self) -> Result<()> {
+        self.request("ping", json!({})).await.map(|_| ())
+    }
+
+    pub async fn list_tools(&mut self) -> Result<Vec<Tool>> {
+        self.list("tools/list", "tools").await
+    }
+
+    pub async fn call_tool(&mut self, name: &str, arguments: Value) -> Result<CallToolResult> {
+        let result = self
+            .request(
+                "tools/call",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    pub async fn list_resources(&mut self) -> Result<Vec<Value>> {
+        self.list("resources/list", "resources").await
+    }
+
+    pub async fn read_resource(&mut self, uri: &str) -> Result<ResourceReadResult> {
+        let result = self
+            .request("resources/read", json!({ "uri": uri }))
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    pub async fn list_prompts(&mut self) -> Result<Vec<Prompt>> {
+        self.list("prompts/list", "prompts").await
+    }
+
+    pub async fn get_prompt(&mut self, name: &str, arguments: Value) -> Result<GetPromptResult> {
+        let result = self
+            .request(
+                "prompts/get",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    pub async fn close(mut self) -> Result<()> {
+        self.transport.close().await
+    }
+
+    async fn list<R: DeserializeOwned>(&mut self, method: &str, field: &str) -> Result<Vec<R>> {
+        let mut result = self.request(method, json!({})).await?;
+        let items = result
+            .get_mut(field)
+            .map(Value::take)
+            .ok_or_else(|| Error::InternalError(format!("{} result has no '{}'", method, field)))?;
+        Ok(serde_json::from_value(items)?)
+    }
+
+    async fn handle_server_message(&mut self, mut message: Value) -> Result<()> {
+        let method = message
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        match message.get_mut("id").map(Value::take) {
+            // Server-initiated requests are not supported yet
+            Some(id) => {
+                self.logger
+                    .warn(&format!("Rejecting server request: {}", method));
+                self.transport
+                    .send(json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {
+                            "code": -32601,
+                            "message": format!("Method not found: {}", method)
+                        }
+                    }))
+                    .await
+            }
+            None => {
+                let params = message
+                    .get_mut("params")
+                    .map(Value::take)
+                    .unwrap_or(Value::Null);
+                match &self.on_notification {
+                    Some(handler) => handler(&method, params),
+                    None => self
+                        .logger
+                        .debug(&format!("Unhandled notification: {}", method)),
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Serving clients that speak an older protocol revision
+//!
+//! The server produces results in the latest revision's shape. When a
+//! client negotiated an older one, [`downgrade`] rewrites each result into
+//! something that client understands: fields it doesn't know are dropped
+//! and content types it can't render become text.
+
+use crate::utils::error_codes::INVALID_PARAMS;
+use crate::utils::{Error, Result};
+use serde_json::{Value, json};
+
+/// The revision the server speaks natively
+pub const LATEST: &str = "2025-06-18";
+
+/// Revisions the server can serve, newest first
+pub const SUPPORTED: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Added `structuredContent`, `outputSchema`, `title` and resource links
+const STRUCTURED: &str = "2025-06-18";
+/// Added tool annotations, audio content and the `completions` capability
+const ANNOTATED: &str = "2025-03-26";
+
+/// The revision to use with a client that asked for `requested`: the same
+/// one, or the latest when the client didn't say
+///
+/// A revision the server doesn't support is refused with invalid params,
+/// listing the supported revisions in `data` so the client can retry with
+/// one of them.
+pub fn negotiate(requested: Option<&str>) -> Result<&'static str> {
+    let Some(requested) = requested else {
+        return Ok(LATEST);
+    };
+    SUPPORTED
+        .iter()
+        .find(|v| **v == requested)
+        .copied()
+        .ok_or_else(|| Error::Application {
+            code: INVALID_PARAMS.code(),
+            message: "Unsupported protocol version".to_string(),
+            data: Some(json!({ "supported": SUPPORTED, "requested": requested })),
+        })
+}
+
+/// Whether results for `version` need rewriting
+pub fn needs_downgrade(version: &str) -> bool {
+    // Revisions are dates, so they order as strings
+    version < LATEST
+}
+
+/// Rewrites the result of `method` for a client on `version`
+pub fn downgrade(version: &str, method: &str, result: &mut Value) {
+    let before_structured = version < STRUCTURED;
+    let before_annotated = version < ANNOTATED;
+
+    match method {
+        "initialize" => {
+            if before_structured {
+                remove(result.get_mut("serverInfo"), "title");
+            }
+            if before_annotated {
+                remove(result.get_mut("capabilities"), "completions");
+            }
+        }
+        "tools/list" => {
+            for tool in items(result, "tools") {
+                if before_structured {
+                    remove(Some(&mut *tool), "title");
+                    remove(Some(&mut *tool), "outputSchema");
+                }
+                if before_annotated {
+                    remove(Some(tool), "annotations");
+                }
+            }
+        }
+        "tools/call" => {
+            if before_structured {
+                remove(Some(&mut *result), "structuredContent");
+            }
+            for content in items(result, "content") {
+                downgrade_content(version, content);
+            }
+        }
+        "resources/list" | "resources/templates/list" if before_structured => {
+            for key in ["resources", "resourceTemplates"] {
+                for resource in items(result, key) {
+                    remove(Some(resource), "title");
+                }
+            }
+        }
+        "prompts/list" if before_structured => {
+            for prompt in items(result, "prompts") {
+                remove(Some(&mut *prompt), "title");
+                for argument in items(prompt, "arguments") {
+                    remove(Some(argument), "title");
+                }
+            }
+        }
+        "prompts/get" => {
+            for message in items(result, "messages") {
+                if let Some(content) = message.get_mut("content") {
+                    downgrade_content(version, content);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Turns content types the client doesn't know into text
+fn downgrade_content(version: &str, content: &mut Value) {
+    let text = match content.get("type").and_then(Value::as_str) {
+        Some("resource_link") if version < STRUCTURED => {
+            let uri = content.get("uri").and_then(Value::as_str).unwrap_or_default();
+            match content.get("name").and_then(Value::as_str) {
+                Some(name) => format!("{} ({})", name, uri),
+                None => uri.to_string(),
+            }
+        }
+        Some("audio") if version < ANNOTATED => {
+            let mime_type = content.get("mimeType").and_then(Value::as_str).unwrap_or("audio");
+            format!("[{} audio omitted]", mime_type)
+        }
+        _ => return,
+    };
+    *content = json!({ "type": "text", "text": text });
+}
+
+fn items<'a>(value: &'a mut Value, key: &str) -> impl Iterator<Item = &'a mut Value> {
+    value
+        .get_mut(key)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+}
+
+fn remove(value: Option<&mut Value>, key: &str) {
+    if let Some(object) = value.and_then(Value::as_object_mut) {
+        object.remove(key);
+    }
+}
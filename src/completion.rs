@@ -0,0 +1,116 @@
+//! Argument autocompletion (`completion/complete`)
+//!
+//! A client completing a prompt argument or a resource template variable
+//! sends a reference (`ref/prompt` with a name, `ref/resource` with a URI
+//! template), the argument's name and what was typed so far. The server
+//! routes it to the prompt or resource handler it refers to; handlers that
+//! implement [`Completable`] and return it from `completable` supply the
+//! suggestions, everyone else gets none.
+
+use crate::utils::{Error, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Most values sent in one completion, as set by the protocol
+pub const MAX_COMPLETION_VALUES: usize = 100;
+
+/// Suggests values for arguments
+#[async_trait]
+pub trait Completable: Send + Sync {
+    /// Values for `request.argument` starting from `request.value`
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion>;
+}
+
+/// What a `completion/complete` request refers to
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Reference {
+    #[serde(rename = "ref/prompt")]
+    Prompt { name: String },
+    #[serde(rename = "ref/resource")]
+    Resource { uri: String },
+}
+
+/// The params of `completion/complete`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompletionRequest {
+    #[serde(rename = "ref")]
+    pub reference: Reference,
+    pub argument: CompletionArgument,
+    /// Arguments the client already resolved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<CompletionContext>,
+}
+
+impl CompletionRequest {
+    pub fn from_params(params: Option<Value>) -> Result<Self> {
+        let params = params.ok_or_else(|| Error::InvalidParams("Missing params".to_string()))?;
+        serde_json::from_value(params).map_err(|e| Error::InvalidParams(e.to_string()))
+    }
+
+    /// The value already resolved for another argument, if the client sent it
+    pub fn context_argument(&self, name: &str) -> Option<&str> {
+        self.context.as_ref()?.arguments.get(name)?.as_str()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompletionArgument {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CompletionContext {
+    #[serde(default)]
+    pub arguments: Map<String, Value>,
+}
+
+/// Suggestions for one argument
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Completion {
+    pub values: Vec<String>,
+    /// Matches in all, when more exist than were sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
+}
+
+impl Completion {
+    /// `values`, cut to [`MAX_COMPLETION_VALUES`] with `total` and
+    /// `hasMore` telling what was left out
+    pub fn new(values: Vec<String>) -> Self {
+        let total = values.len();
+        if total <= MAX_COMPLETION_VALUES {
+            return Self {
+                values,
+                total: None,
+                has_more: None,
+            };
+        }
+        Self {
+            values: values.into_iter().take(MAX_COMPLETION_VALUES).collect(),
+            total: Some(total),
+            has_more: Some(true),
+        }
+    }
+
+    /// The `candidates` starting with `prefix`, ignoring case
+    pub fn matching<I, S>(candidates: I, prefix: &str) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let prefix = prefix.to_lowercase();
+        Self::new(
+            candidates
+                .into_iter()
+                .map(Into::into)
+                .filter(|candidate: &String| candidate.to_lowercase().starts_with(&prefix))
+                .collect(),
+        )
+    }
+}
\ No newline at end of file
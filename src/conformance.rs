@@ -0,0 +1,378 @@
+//! MCP protocol conformance checks
+//!
+//! [`run`] exercises a server with a battery of checks derived from the MCP
+//! and JSON-RPC 2.0 specifications and returns a [`ConformanceReport`].
+//! Each check is tagged with the strength of the spec requirement it covers;
+//! a server is compliant when every [`Requirement::Must`] check passes.
+//!
+//! Checks that depend on lifecycle state use a fresh server from the
+//! factory, so the factory should build the server exactly as it is
+//! deployed (same config and registrations).
+
+use crate::McpServer;
+use crate::testing::McpTestClient;
+use crate::utils::Error;
+use serde_json::{Value, json};
+use std::fmt;
+
+/// Maximum number of pages followed when checking pagination
+const MAX_PAGES: usize = 100;
+
+/// Strength of the spec requirement a check covers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Requirement {
+    Must,
+    Should,
+}
+
+#[derive(Clone, Debug)]
+pub struct CheckResult {
+    pub name: String,
+    pub requirement: Requirement,
+    pub passed: bool,
+    pub detail: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ConformanceReport {
+    pub results: Vec<CheckResult>,
+}
+
+impl ConformanceReport {
+    /// Whether every MUST-level check passed
+    pub fn is_compliant(&self) -> bool {
+        self.results
+            .iter()
+            .all(|r| r.passed || r.requirement == Requirement::Should)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.results.iter().filter(|r| !r.passed)
+    }
+
+    fn record(
+        &mut self,
+        name: impl Into<String>,
+        requirement: Requirement,
+        outcome: Result<(), String>,
+    ) {
+        self.results.push(CheckResult {
+            name: name.into(),
+            requirement,
+            passed: outcome.is_ok(),
+            detail: outcome.err(),
+        });
+    }
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            let status = if result.passed { "PASS" } else { "FAIL" };
+            write!(f, "[{}] {:?} {}", status, result.requirement, result.name)?;
+            if let Some(detail) = &result.detail {
+                write!(f, ": {}", detail)?;
+            }
+            writeln!(f)?;
+        }
+        let failed = self.failures().count();
+        write!(f, "{} checks, {} failed", self.results.len(), failed)
+    }
+}
+
+/// Runs every check against servers produced by `server_factory`
+pub async fn run<F>(server_factory: F) -> ConformanceReport
+where
+    F: Fn() -> McpServer,
+{
+    let mut report = ConformanceReport::default();
+
+    // Checks against a server that has not been initialized
+    let client = McpTestClient::new(server_factory());
+    report.record(
+        "ping is answered before initialization",
+        Requirement::Must,
+        client.ping().await.map_err(|e| e.to_string()),
+    );
+    report.record(
+        "requests before initialization are rejected",
+        Requirement::Should,
+        expect_error(client.request("tools/list", json!({})).await, None),
+    );
+
+    // Checks against an initialized server
+    let client = McpTestClient::new(server_factory());
+    let initialize = client.initialize().await;
+    let capabilities = initialize
+        .as_ref()
+        .ok()
+        .and_then(|r| r.get("capabilities"))
+        .cloned()
+        .unwrap_or(Value::Null);
+    report.record(
+        "initialize result has protocolVersion, capabilities and serverInfo",
+        Requirement::Must,
+        check_initialize(initialize),
+    );
+
+    check_envelope(&client, &mut report).await;
+    check_capability_gating(&client, &capabilities, &mut report).await;
+    check_pagination(&client, &capabilities, &mut report).await;
+    check_error_codes(&client, &capabilities, &mut report).await;
+
+    report
+}
+
+fn check_initialize(result: crate::Result<Value>) -> Result<(), String> {
+    let result = result.map_err(|e| e.to_string())?;
+    if !result.get("protocolVersion").is_some_and(Value::is_string) {
+        return Err("missing protocolVersion".to_string());
+    }
+    if !result.get("capabilities").is_some_and(Value::is_object) {
+        return Err("missing capabilities object".to_string());
+    }
+    let info = result.get("serverInfo").ok_or("missing serverInfo")?;
+    if !info.get("name").is_some_and(Value::is_string)
+        || !info.get("version").is_some_and(Value::is_string)
+    {
+        return Err("serverInfo needs name and version".to_string());
+    }
+    Ok(())
+}
+
+async fn check_envelope(client: &McpTestClient, report: &mut ConformanceReport) {
+    let server = client.server();
+
+    let response = server
+        .handle_request(json!({ "jsonrpc": "2.0", "id": "string-id", "method": "ping" }))
+        .await;
+    report.record(
+        "response echoes a string request id",
+        Requirement::Must,
+        match response {
+            Ok(Some(r)) if r.get("id") == Some(&json!("string-id")) => Ok(()),
+            other => Err(format!("got {:?}", other)),
+        },
+    );
+
+    let response = server
+        .handle_request(json!({ "jsonrpc": "2.0", "method": "notifications/unknown" }))
+        .await;
+    report.record(
+        "notifications are never answered",
+        Requirement::Must,
+        match response {
+            Ok(None) => Ok(()),
+            other => Err(format!("got {:?}", other)),
+        },
+    );
+
+    let response = server
+        .handle_request(json!({ "jsonrpc": "1.0", "id": 7, "method": "ping" }))
+        .await;
+    report.record(
+        "invalid jsonrpc version is answered with -32600",
+        Requirement::Must,
+        expect_error_response(response, -32600),
+    );
+
+    let response = server
+        .handle_request(json!({ "jsonrpc": "2.0", "id": 8 }))
+        .await;
+    report.record(
+        "request without method is answered with -32600",
+        Requirement::Must,
+        expect_error_response(response, -32600),
+    );
+
+    report.record(
+        "unknown methods return -32601",
+        Requirement::Must,
+        expect_error(
+            client.request("does/not/exist", json!({})).await,
+            Some(-32601),
+        ),
+    );
+}
+
+async fn check_capability_gating(
+    client: &McpTestClient,
+    capabilities: &Value,
+    report: &mut ConformanceReport,
+) {
+    for (capability, method) in [
+        ("tools", "tools/list"),
+        ("resources", "resources/list"),
+        ("prompts", "prompts/list"),
+    ] {
+        let result = client.request(method, json!({})).await;
+        let outcome = match (capabilities.get(capability).is_some(), result) {
+            (true, Ok(_)) | (false, Err(_)) => Ok(()),
+            (true, Err(e)) => Err(format!(
+                "{} advertised but {} failed: {}",
+                capability, method, e
+            )),
+            (false, Ok(_)) => Err(format!(
+                "{} not advertised but {} succeeded",
+                capability, method
+            )),
+        };
+        report.record(
+            format!(
+                "{} matches the advertised {} capability",
+                method, capability
+            ),
+            Requirement::Must,
+            outcome,
+        );
+    }
+}
+
+async fn check_pagination(
+    client: &McpTestClient,
+    capabilities: &Value,
+    report: &mut ConformanceReport,
+) {
+    for (capability, method) in [
+        ("tools", "tools/list"),
+        ("resources", "resources/list"),
+        ("prompts", "prompts/list"),
+    ] {
+        if capabilities.get(capability).is_none() {
+            continue;
+        }
+
+        report.record(
+            format!(
+                "{}: nextCursor is an opaque string and pagination terminates",
+                method
+            ),
+            Requirement::Must,
+            follow_pages(client, method, capability).await,
+        );
+        report.record(
+            format!("{}: invalid cursors are rejected with -32602", method),
+            Requirement::Should,
+            expect_error(
+                client
+                    .request(method, json!({ "cursor": "not-a-valid-cursor" }))
+                    .await,
+                Some(-32602),
+            ),
+        );
+    }
+}
+
+async fn follow_pages(client: &McpTestClient, method: &str, field: &str) -> Result<(), String> {
+    let mut params = json!({});
+    for _ in 0..MAX_PAGES {
+        let result = client
+            .request(method, params)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !result.get(field).is_some_and(Value::is_array) {
+            return Err(format!("{} result has no '{}' array", method, field));
+        }
+        match result.get("nextCursor") {
+            None | Some(Value::Null) => return Ok(()),
+            Some(Value::String(cursor)) => params = json!({ "cursor": cursor }),
+            Some(other) => return Err(format!("nextCursor is not a string: {}", other)),
+        }
+    }
+    Err(format!(
+        "{} did not finish within {} pages",
+        method, MAX_PAGES
+    ))
+}
+
+async fn check_error_codes(
+    client: &McpTestClient,
+    capabilities: &Value,
+    report: &mut ConformanceReport,
+) {
+    if capabilities.get("tools").is_some() {
+        report.record(
+            "tools/call without a name returns -32602",
+            Requirement::Must,
+            expect_error(client.request("tools/call", json!({})).await, Some(-32602)),
+        );
+        report.record(
+            "tools/call for an unknown tool returns -32602",
+            Requirement::Should,
+            expect_error(
+                client
+                    .request("tools/call", json!({ "name": "conformance-unknown-tool" }))
+                    .await,
+                Some(-32602),
+            ),
+        );
+    }
+
+    if capabilities.get("resources").is_some() {
+        report.record(
+            "resources/read for an unknown resource returns -32002",
+            Requirement::Should,
+            expect_error(
+                client
+                    .request("resources/read", json!({ "uri": "conformance://unknown" }))
+                    .await,
+                Some(-32002),
+            ),
+        );
+    }
+
+    if capabilities.get("prompts").is_some() {
+        report.record(
+            "prompts/get for an unknown prompt returns -32602",
+            Requirement::Should,
+            expect_error(
+                client
+                    .request(
+                        "prompts/get",
+                        json!({ "name": "conformance-unknown-prompt" }),
+                    )
+                    .await,
+                Some(-32602),
+            ),
+        );
+    }
+}
+
+fn expect_error(result: crate::Result<Value>, code: Option<i64>) -> Result<(), String> {
+    match (result, code) {
+        (Err(Error::Rpc { code: actual, .. }), Some(expected)) if actual != expected => {
+            Err(format!("expected error {} but got {}", expected, actual))
+        }
+        (Err(Error::Rpc { .. }), _) => Ok(()),
+        (Err(e), _) => Err(format!("expected a JSON-RPC error response but got {}", e)),
+        (Ok(result), _) => Err(format!("expected an error but got {}", abbreviate(&result))),
+    }
+}
+
+fn expect_error_response(response: crate::Result<Option<Value>>, code: i64) -> Result<(), String> {
+    match response {
+        Ok(Some(response)) => {
+            let actual = response.pointer("/error/code").and_then(Value::as_i64);
+            if actual == Some(code) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "expected error {} but got {}",
+                    code,
+                    abbreviate(&response)
+                ))
+            }
+        }
+        Ok(None) => Err("no response".to_string()),
+        Err(e) => Err(format!("handler failed instead of responding: {}", e)),
+    }
+}
+
+fn abbreviate(value: &Value) -> String {
+    const MAX_CHARS: usize = 120;
+    let text = value.to_string();
+    match text.char_indices().nth(MAX_CHARS) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text,
+    }
+}
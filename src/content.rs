@@ -0,0 +1,141 @@
+//! Content blocks shared by tool results and prompt messages
+
+use crate::resources::{Resource, ResourceDefinition};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+/// A block of a tool result or prompt message
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Content {
+    Text(TextContent),
+    Image(ImageContent),
+    Audio(AudioContent),
+    Resource(EmbeddedResource),
+    /// A resource the client can fetch with `resources/read`, for results
+    /// too large to inline
+    ResourceLink(ResourceDefinition),
+}
+
+impl Content {
+    pub fn text(text: impl Into<String>) -> Self {
+        Content::Text(TextContent::new(text))
+    }
+
+    /// An image from base64-encoded `data`
+    pub fn image(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Content::Image(ImageContent::new(data, mime_type))
+    }
+
+    /// A clip from base64-encoded `data`
+    pub fn audio(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Content::Audio(AudioContent::new(data, mime_type))
+    }
+
+    /// The contents of `resource`, such as a file a tool read
+    pub fn resource(resource: Resource) -> Self {
+        Content::Resource(EmbeddedResource { resource })
+    }
+
+    /// A link to the resource `definition` describes
+    pub fn resource_link(definition: ResourceDefinition) -> Self {
+        Content::ResourceLink(definition)
+    }
+
+    /// The text of a text block
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Content::Text(content) => Some(&content.text),
+            _ => None,
+        }
+    }
+}
+
+/// Plain text
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TextContent {
+    pub text: String,
+}
+
+impl TextContent {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+}
+
+impl From<TextContent> for Content {
+    fn from(content: TextContent) -> Self {
+        Content::Text(content)
+    }
+}
+
+/// An image, such as a chart or screenshot
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ImageContent {
+    /// Base64-encoded image data
+    pub data: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+}
+
+impl ImageContent {
+    pub fn new(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Self {
+            data: data.into(),
+            mime_type: mime_type.into(),
+        }
+    }
+
+    /// Encodes raw image `bytes`
+    pub fn from_bytes(bytes: &[u8], mime_type: impl Into<String>) -> Self {
+        Self::new(STANDARD.encode(bytes), mime_type)
+    }
+}
+
+impl From<ImageContent> for Content {
+    fn from(content: ImageContent) -> Self {
+        Content::Image(content)
+    }
+}
+
+/// An audio clip, such as synthesized speech
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct AudioContent {
+    /// Base64-encoded audio data
+    pub data: String,
+    #[serde(rename = "mimeType")]
+    pub mime_type: String,
+}
+
+impl AudioContent {
+    pub fn new(data: impl Into<String>, mime_type: impl Into<String>) -> Self {
+        Self {
+            data: data.into(),
+            mime_type: mime_type.into(),
+        }
+    }
+
+    /// Encodes raw audio `bytes`
+    pub fn from_bytes(bytes: &[u8], mime_type: impl Into<String>) -> Self {
+        Self::new(STANDARD.encode(bytes), mime_type)
+    }
+}
+
+impl From<AudioContent> for Content {
+    fn from(content: AudioContent) -> Self {
+        Content::Audio(content)
+    }
+}
+
+/// A resource's contents, embedded so the client can treat them as the
+/// resource at their URI rather than as loose text
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct EmbeddedResource {
+    pub resource: Resource,
+}
+
+impl From<EmbeddedResource> for Content {
+    fn from(content: EmbeddedResource) -> Self {
+        Content::Resource(content)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,214 @@
+//! What a handler knows about the request it serves
+//!
+//! Every call of a tool, resource or prompt handler gets a
+//! [`RequestContext`]: the request's id, the client's `clientInfo` from
+//! `initialize`, the `progressToken` it sent, a token that is cancelled with
+//! the request, a [`NotificationSender`] addressed to the client, and the
+//! server's [`Clock`], so timeouts and caches can be driven by a
+//! [`MockClock`](crate::utils::MockClock) in tests. The context is cheap to
+//! clone, so a handler can hand it to work that outlives
+//! the call.
+//!
+//! State the server shares among handlers, such as a database pool or an
+//! HTTP client, is registered once with
+//! [`McpServer::with_state`](crate::McpServer::with_state) and read back by
+//! type with [`RequestContext::state`]:
+//!
+//! ```ignore
+//! async fn call(&self, context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+//!     let db = context.state::<DbPool>().expect("registered with with_state");
+//!     let files = db.pending_files().await?;
+//!     for (done, file) in files.iter().enumerate() {
+//!         if context.is_cancelled() {
+//!             return Ok(CallToolResult::error("Indexing was cancelled"));
+//!         }
+//!         index(file).await?;
+//!         if let Some(progress) = context.progress() {
+//!             progress.report((done + 1) as f64, Some(files.len() as f64), None);
+//!         }
+//!     }
+//!     ...
+//! }
+//! ```
+
+use crate::notifications::NotificationSender;
+use crate::progress::ProgressReporter;
+use crate::utils::{Clock, SystemClock};
+use serde_json::Value;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+tokio::task_local! {
+    static CURRENT_CONTEXT: RequestContext;
+}
+
+/// Values shared by every handler, one per type
+#[derive(Clone, Default)]
+pub struct Extensions {
+    values: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `value`, replacing the one of the same type, if any
+    pub fn insert<T: Send + Sync + 'static>(&mut self, value: Arc<T>) {
+        self.values.insert(TypeId::of::<T>(), value);
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.values.get(&TypeId::of::<T>())?.clone().downcast().ok()
+    }
+
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// The request a handler is called for
+#[derive(Clone)]
+pub struct RequestContext {
+    request_id: Option<Value>,
+    client_info: Option<Value>,
+    progress_token: Option<Value>,
+    cancellation: CancellationToken,
+    notifications: NotificationSender,
+    extensions: Arc<Extensions>,
+    clock: Arc<dyn Clock>,
+}
+
+impl RequestContext {
+    /// Context of a request that has no id, client info or progress token
+    /// yet and is never cancelled, e.g. to call a handler from a test
+    pub fn new(notifications: NotificationSender) -> Self {
+        Self {
+            request_id: None,
+            client_info: None,
+            progress_token: None,
+            cancellation: CancellationToken::new(),
+            notifications,
+            extensions: Arc::default(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_request_id(mut self, id: Option<Value>) -> Self {
+        self.request_id = id;
+        self
+    }
+
+    pub fn with_client_info(mut self, client_info: Option<Value>) -> Self {
+        self.client_info = client_info;
+        self
+    }
+
+    pub fn with_progress_token(mut self, token: Option<Value>) -> Self {
+        self.progress_token = token;
+        self
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    pub fn with_extensions(mut self, extensions: Arc<Extensions>) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Id of the request; `None` for notifications
+    pub fn request_id(&self) -> Option<&Value> {
+        self.request_id.as_ref()
+    }
+
+    /// Session the request belongs to, if any
+    pub fn session(&self) -> Option<&str> {
+        self.notifications.session()
+    }
+
+    /// `clientInfo` the client sent with `initialize`
+    pub fn client_info(&self) -> Option<&Value> {
+        self.client_info.as_ref()
+    }
+
+    /// Name from the client's `clientInfo`
+    pub fn client_name(&self) -> Option<&str> {
+        self.client_info.as_ref()?.get("name")?.as_str()
+    }
+
+    /// The `progressToken` from the request's `_meta`
+    pub fn progress_token(&self) -> Option<&Value> {
+        self.progress_token.as_ref()
+    }
+
+    /// Reporter for the request's progress; `None` when the client did not
+    /// ask for progress
+    pub fn progress(&self) -> Option<ProgressReporter> {
+        let token = self.progress_token.clone()?;
+        Some(ProgressReporter::new(token, self.notifications.clone()))
+    }
+
+    /// Token cancelled when the client cancels the request or the server
+    /// shuts down before answering it
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Resolves once the request is cancelled
+    pub async fn cancelled(&self) {
+        self.cancellation.cancelled().await
+    }
+
+    /// Sender for notifications to the client of the request
+    pub fn notifications(&self) -> &NotificationSender {
+        &self.notifications
+    }
+
+    /// The server's shared state of type `T`, if it was registered
+    pub fn state<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.extensions.get()
+    }
+
+    /// Every value the server shares with its handlers
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// The server's clock, for timeouts and expiry that tests can control
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+}
+
+/// Context of the request being handled, if any
+pub fn current() -> Option<RequestContext> {
+    CURRENT_CONTEXT.try_with(Clone::clone).ok()
+}
+
+/// Runs `future` with `context` as the current request's
+pub fn with_context<F: Future>(context: RequestContext, future: F) -> impl Future<Output = F::Output> {
+    CURRENT_CONTEXT.scope(context, future)
+}
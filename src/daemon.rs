@@ -0,0 +1,122 @@
+//! Running network transports as a background service
+//!
+//! [`detach`] forks the process into the background, and [`PidFile`] records
+//! its pid for service managers and init scripts. Both must run before the
+//! tokio runtime starts: forking a process with runtime threads leaves the
+//! child without them.
+
+use crate::utils::{Error, Result};
+use std::path::{Path, PathBuf};
+
+/// A pid file, removed again when dropped
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Writes the current pid to `path`
+    ///
+    /// Fails when the file already names a running process, so two
+    /// instances cannot share a pid file; a stale file is replaced.
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Some(pid) = read_pid(&path)
+            && is_running(pid)
+        {
+            return Err(Error::ValidationError(format!(
+                "{} names running process {}",
+                path.display(),
+                pid
+            )));
+        }
+        std::fs::write(&path, format!("{}\n", std::process::id()))?;
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // Only remove the file if it is still ours
+        if read_pid(&self.path) == Some(std::process::id()) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 only checks that the process exists; EPERM means it does but
+    // belongs to someone else
+    let alive = unsafe { libc::kill(pid, 0) } == 0;
+    alive || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+    false
+}
+
+/// Detaches from the terminal: forks into a new session whose stdin is
+/// `/dev/null` and whose stdout and stderr go to `log_file` (appended to)
+/// or `/dev/null`
+///
+/// Only the detached process returns; the original process exits. The
+/// working directory is kept so relative paths in the configuration still
+/// resolve.
+#[cfg(unix)]
+pub fn detach(log_file: Option<&Path>) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    // Open the targets before forking so errors reach the terminal
+    let null = std::fs::OpenOptions::new().read(true).write(true).open("/dev/null")?;
+    let log = match log_file {
+        Some(path) => std::fs::OpenOptions::new().create(true).append(true).open(path)?,
+        None => null.try_clone()?,
+    };
+
+    fork_and_exit_parent()?;
+    if unsafe { libc::setsid() } < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    // Fork again so the daemon is not a session leader and can never
+    // reacquire a controlling terminal
+    fork_and_exit_parent()?;
+
+    for (file, fd) in [
+        (&null, libc::STDIN_FILENO),
+        (&log, libc::STDOUT_FILENO),
+        (&log, libc::STDERR_FILENO),
+    ] {
+        if unsafe { libc::dup2(file.as_raw_fd(), fd) } < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn fork_and_exit_parent() -> Result<()> {
+    match unsafe { libc::fork() } {
+        -1 => Err(std::io::Error::last_os_error().into()),
+        0 => Ok(()),
+        _ => std::process::exit(0),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn detach(_log_file: Option<&Path>) -> Result<()> {
+    Err(Error::ValidationError(
+        "Daemon mode is only supported on Unix".to_string(),
+    ))
+}
@@ -0,0 +1,202 @@
+//! Asking the user for input mid-call (`elicitation/create`)
+//!
+//! A tool missing something only the user can supply, such as a
+//! confirmation or an API key, sends an [`ElicitationRequest`] to the
+//! client of the request it handles with [`elicit`]. The requested schema
+//! is the flat object of strings, numbers, booleans and enums the spec
+//! allows, built one field at a time:
+//!
+//! ```ignore
+//! let request = ElicitationRequest::new("Which account should be billed?")
+//!     .with_choice("account", "Account", ["personal", "team"], true)
+//!     .with_boolean("remember", "Remember this choice", false);
+//! let answer = elicitation::elicit(&request).await?;
+//! match answer.text("account") {
+//!     Some(account) if answer.accepted() => bill(account).await,
+//!     _ => Ok(CallToolResult::error("Billing cancelled")),
+//! }
+//! ```
+
+use crate::server::ClientLink;
+use crate::tools::schema;
+use crate::utils::{Error, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+/// The params of `elicitation/create`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ElicitationRequest {
+    pub message: String,
+    properties: Map<String, Value>,
+    required: Vec<String>,
+}
+
+impl ElicitationRequest {
+    /// A request showing `message` to the user; add the fields to fill in
+    /// with the `with_*` methods
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds a field with a schema of its own, e.g. a string with a `format`
+    pub fn with_field(mut self, name: impl Into<String>, schema: Value, required: bool) -> Self {
+        let name = name.into();
+        if required && !self.required.contains(&name) {
+            self.required.push(name.clone());
+        }
+        self.properties.insert(name, schema);
+        self
+    }
+
+    pub fn with_text(self, name: impl Into<String>, title: &str, required: bool) -> Self {
+        self.with_field(name, json!({ "type": "string", "title": title }), required)
+    }
+
+    pub fn with_number(self, name: impl Into<String>, title: &str, required: bool) -> Self {
+        self.with_field(name, json!({ "type": "number", "title": title }), required)
+    }
+
+    pub fn with_boolean(self, name: impl Into<String>, title: &str, required: bool) -> Self {
+        self.with_field(name, json!({ "type": "boolean", "title": title }), required)
+    }
+
+    /// Adds a string field limited to `options`
+    pub fn with_choice<I, S>(self, name: impl Into<String>, title: &str, options: I, required: bool) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let options: Vec<String> = options.into_iter().map(Into::into).collect();
+        self.with_field(name, json!({ "type": "string", "title": title, "enum": options }), required)
+    }
+
+    /// The `requestedSchema` sent to the client
+    pub fn requested_schema(&self) -> Value {
+        let mut schema = json!({ "type": "object", "properties": self.properties });
+        if !self.required.is_empty() {
+            schema["required"] = json!(self.required);
+        }
+        schema
+    }
+
+    /// Checks the request against the spec's constraints: a message, and
+    /// fields of primitive types only
+    pub fn validate(&self) -> Result<()> {
+        if self.message.trim().is_empty() {
+            return Err(Error::ValidationError(
+                "An elicitation request needs a message".to_string(),
+            ));
+        }
+        for (name, field) in &self.properties {
+            if !matches!(field["type"].as_str(), Some("string" | "number" | "integer" | "boolean")) {
+                return Err(Error::ValidationError(format!(
+                    "Elicitation field {} must be a string, number, integer or boolean",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The validated request as `elicitation/create` params
+    pub fn params(&self) -> Result<Value> {
+        self.validate()?;
+        Ok(json!({ "message": self.message, "requestedSchema": self.requested_schema() }))
+    }
+}
+
+/// What the user did with the request
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitationAction {
+    /// Submitted the form
+    Accept,
+    /// Explicitly said no
+    Decline,
+    /// Dismissed the request without choosing
+    Cancel,
+}
+
+impl ElicitationAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ElicitationAction::Accept => "accept",
+            ElicitationAction::Decline => "decline",
+            ElicitationAction::Cancel => "cancel",
+        }
+    }
+}
+
+/// The client's answer to `elicitation/create`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ElicitationResponse {
+    pub action: ElicitationAction,
+    /// What the user entered; only sent with `accept`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<Map<String, Value>>,
+}
+
+impl ElicitationResponse {
+    pub fn accepted(&self) -> bool {
+        self.action == ElicitationAction::Accept
+    }
+
+    /// The value the user entered for `name`
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.content.as_ref()?.get(name)
+    }
+
+    pub fn text(&self, name: &str) -> Option<&str> {
+        self.get(name)?.as_str()
+    }
+
+    pub fn boolean(&self, name: &str) -> Option<bool> {
+        self.get(name)?.as_bool()
+    }
+
+    pub fn number(&self, name: &str) -> Option<f64> {
+        self.get(name)?.as_f64()
+    }
+
+    /// The content as `T`; `None` unless the user accepted
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        match &self.content {
+            Some(content) if self.accepted() => Ok(Some(serde_json::from_value(Value::Object(content.clone()))?)),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl ClientLink {
+    /// Asks the user for the fields of `request`
+    ///
+    /// Fails if the client didn't declare the `elicitation` capability, the
+    /// request is invalid, the client doesn't answer in time, or accepted
+    /// content doesn't match the requested schema.
+    pub async fn elicit(&self, request: &ElicitationRequest) -> Result<ElicitationResponse> {
+        let result = self.request("elicitation/create", request.params()?).await?;
+        let response: ElicitationResponse = serde_json::from_value(result)
+            .map_err(|e| Error::InternalError(format!("Invalid elicitation result: {}", e)))?;
+        if let Some(content) = response.content.as_ref().filter(|_| response.accepted()) {
+            let violations = schema::violations(&request.requested_schema(), &Value::Object(content.clone()))?;
+            if !violations.is_empty() {
+                return Err(Error::InvalidParams(format!(
+                    "Elicitation content doesn't match the requested schema: {}",
+                    violations.join("; ")
+                )));
+            }
+        }
+        Ok(response)
+    }
+}
+
+/// [`ClientLink::elicit`] on the client of the request being handled
+pub async fn elicit(request: &ElicitationRequest) -> Result<ElicitationResponse> {
+    let link = ClientLink::current()
+        .ok_or_else(|| Error::InternalError("Elicitation needs a request being handled".to_string()))?;
+    link.elicit(request).await
+}
\ No newline at end of file
@@ -0,0 +1,233 @@
+//! C ABI for embedding the server in C and C++ hosts
+//!
+//! The declarations live in `include/mcp_server.h`. Build a shared or
+//! static library with:
+//!
+//! ```bash
+//! cargo rustc --release --lib --crate-type cdylib   # or staticlib
+//! ```
+//!
+//! A server is driven entirely by the host: it feeds each incoming JSON-RPC
+//! message to [`mcp_server_handle_message`], writes out the returned
+//! response, and drains server notifications with
+//! [`mcp_server_poll_notification`]. Tools call back into the host on the
+//! thread that called `mcp_server_handle_message`.
+
+use crate::context::RequestContext;
+use crate::blocking::BlockingServer;
+use crate::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use crate::utils::{Error, Result};
+use crate::ServerConfig;
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::ffi::{CStr, CString, c_char, c_int, c_void};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Called with the tool arguments as JSON; returns the tool's text output,
+/// or NULL to report a failure. The library copies the string before the
+/// next call into the host, so it may point into a reused buffer.
+pub type McpToolCallback =
+    Option<unsafe extern "C" fn(user_data: *mut c_void, arguments_json: *const c_char) -> *const c_char>;
+
+/// Opaque server handle
+pub struct McpFfiServer {
+    server: BlockingServer,
+    notifications: std::sync::Mutex<broadcast::Receiver<Value>>,
+}
+
+struct CallbackTool {
+    tool: Tool,
+    callback: unsafe extern "C" fn(*mut c_void, *const c_char) -> *const c_char,
+    user_data: *mut c_void,
+}
+
+// The host owns `user_data` and promises it may be used from the thread
+// that drives the server
+unsafe impl Send for CallbackTool {}
+unsafe impl Sync for CallbackTool {}
+
+#[async_trait]
+impl ToolHandler for CallbackTool {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        let arguments = CString::new(arguments.to_string())
+            .map_err(|e| Error::InvalidParams(e.to_string()))?;
+        let output = unsafe { (self.callback)(self.user_data, arguments.as_ptr()) };
+        if output.is_null() {
+            return Ok(CallToolResult::error(format!("{} failed", self.tool.name)));
+        }
+        let text = unsafe { CStr::from_ptr(output) }.to_string_lossy().into_owned();
+        Ok(CallToolResult::success(vec![TextContent::new(text)]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(self.tool.clone())
+    }
+}
+
+unsafe fn str_arg<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    unsafe { CStr::from_ptr(ptr) }.to_str().ok()
+}
+
+fn into_c_string(value: &Value) -> *mut c_char {
+    CString::new(value.to_string()).map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Creates a server; returns NULL if an argument is not valid UTF-8 or the
+/// runtime cannot start. Free it with [`mcp_server_free`].
+///
+/// # Safety
+///
+/// `name` and `version` must be NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mcp_server_new(
+    name: *const c_char,
+    version: *const c_char,
+) -> *mut McpFfiServer {
+    let (Some(name), Some(version)) = (unsafe { str_arg(name) }, unsafe { str_arg(version) }) else {
+        return std::ptr::null_mut();
+    };
+    let Ok(server) = BlockingServer::new(ServerConfig::new(name, version)) else {
+        return std::ptr::null_mut();
+    };
+    let notifications = std::sync::Mutex::new(server.server().subscribe_notifications());
+    Box::into_raw(Box::new(McpFfiServer {
+        server,
+        notifications,
+    }))
+}
+
+/// Frees a server created by [`mcp_server_new`]
+///
+/// # Safety
+///
+/// `server` must come from [`mcp_server_new`] and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mcp_server_free(server: *mut McpFfiServer) {
+    if !server.is_null() {
+        drop(unsafe { Box::from_raw(server) });
+    }
+}
+
+/// Registers a tool implemented by `callback`; returns 0 on success and -1
+/// if an argument is invalid (`input_schema_json` may be NULL for an
+/// object schema without properties)
+///
+/// # Safety
+///
+/// `server` must be a live server, the strings NUL-terminated, and
+/// `user_data` valid for as long as the server exists.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mcp_server_register_tool(
+    server: *mut McpFfiServer,
+    name: *const c_char,
+    description: *const c_char,
+    input_schema_json: *const c_char,
+    callback: McpToolCallback,
+    user_data: *mut c_void,
+) -> c_int {
+    let Some(server) = (unsafe { server.as_ref() }) else {
+        return -1;
+    };
+    let (Some(name), Some(callback)) = (unsafe { str_arg(name) }, callback) else {
+        return -1;
+    };
+    let description = unsafe { str_arg(description) }.unwrap_or_default();
+    let input_schema = match unsafe { str_arg(input_schema_json) } {
+        Some(schema) => match serde_json::from_str(schema) {
+            Ok(schema) => schema,
+            Err(_) => return -1,
+        },
+        None => json!({ "type": "object" }),
+    };
+
+    let tool = CallbackTool {
+        tool: Tool {
+            name: name.to_string(),
+            description: description.to_string(),
+            input_schema,
+            annotations: None,
+            output_schema: None,
+        },
+        callback,
+        user_data,
+    };
+    match server.server.register_tool(name.to_string(), Arc::new(tool)) {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+/// Handles one JSON-RPC message and returns the response to send back, or
+/// NULL when there is none (notifications). Malformed JSON is answered
+/// with a -32700 error response. Free the result with [`mcp_string_free`].
+///
+/// # Safety
+///
+/// `server` must be a live server and `message` NUL-terminated.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mcp_server_handle_message(
+    server: *mut McpFfiServer,
+    message: *const c_char,
+) -> *mut c_char {
+    let Some(server) = (unsafe { server.as_ref() }) else {
+        return std::ptr::null_mut();
+    };
+    let response = match unsafe { str_arg(message) } {
+        Some(message) => server.server.handle_message(message),
+        None => Err(Error::ParseError("Message is not valid UTF-8".to_string())),
+    };
+    match response {
+        Ok(Some(response)) => into_c_string(&response),
+        Ok(None) => std::ptr::null_mut(),
+        Err(Error::Json(e)) => into_c_string(&json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32700, "message": format!("Parse error: {}", e) }
+        })),
+        Err(e) => into_c_string(&json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": e.code(), "message": e.to_string() }
+        })),
+    }
+}
+
+/// Returns the next notification the server sent, or NULL when none is
+/// pending. Free the result with [`mcp_string_free`].
+///
+/// # Safety
+///
+/// `server` must be a live server.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mcp_server_poll_notification(server: *mut McpFfiServer) -> *mut c_char {
+    let Some(server) = (unsafe { server.as_ref() }) else {
+        return std::ptr::null_mut();
+    };
+    let mut notifications = server
+        .notifications
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    loop {
+        match notifications.try_recv() {
+            Ok(notification) => return into_c_string(&notification),
+            Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+            Err(_) => return std::ptr::null_mut(),
+        }
+    }
+}
+
+/// Frees a string returned by this library
+///
+/// # Safety
+///
+/// `string` must come from this library and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn mcp_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
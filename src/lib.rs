@@ -2,15 +2,83 @@
 //!
 //! This library provides a framework for building MCP servers that expose
 //! tools, resources, and prompts to LLM applications.
+//!
+//! The `native` feature (on by default) adds the stdio and HTTP transports,
+//! gateways, and file and process access. Without it the core (dispatch,
+//! registries and protocol types) builds for `wasm32-wasip1`; serve it
+//! there with [`transport::WasiTransport`]. The core runs on tokio,
+//! async-std or smol; see [`runtime`].
 
 pub mod server;
+pub mod builder;
+pub mod client;
+pub mod catalog;
+pub mod content;
+#[cfg(feature = "native")]
+pub mod aggregator;
+#[cfg(feature = "native")]
+pub mod openapi;
+#[cfg(feature = "native")]
+pub mod bridge;
+#[cfg(feature = "native")]
+pub mod plugins;
+#[cfg(feature = "native")]
+pub mod manifest;
+#[cfg(feature = "native")]
+pub mod repl;
+#[cfg(feature = "native")]
+pub mod load_test;
+pub mod scaffold;
+#[cfg(feature = "native")]
+pub mod daemon;
+#[cfg(all(unix, feature = "native"))]
+pub mod systemd;
+pub mod protocol;
+pub mod compat;
+pub mod access;
+pub mod approval;
+pub mod audit;
+pub mod middleware;
+pub mod sampling;
+pub mod elicitation;
+pub mod roots;
+pub mod spec;
+pub mod runtime;
+#[cfg(feature = "rt-tokio")]
+pub mod blocking;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod session;
+pub mod context;
+pub mod lifecycle;
+pub mod notifications;
+pub mod progress;
+pub mod logging;
+pub mod completion;
 pub mod tools;
 pub mod resources;
 pub mod prompts;
+pub mod state;
 pub mod utils;
 pub mod transport;
+pub mod testing;
+pub mod conformance;
 
-pub use server::{McpServer, ServerConfig};
+pub use builder::McpServerBuilder;
+pub use mcp_server_macros::mcp_tool;
+pub use server::{FeatureFlags, McpServer, ServerConfig};
 pub use utils::error::{Error, Result};
 
-pub const PROTOCOL_VERSION: &str = "2024-11-05";
\ No newline at end of file
+// Paths used by code that `#[mcp_tool]` generates in other crates
+#[doc(hidden)]
+pub mod __private {
+    pub use async_trait;
+    pub use schemars;
+    pub use serde;
+    pub use serde_json;
+}
+
+/// Protocol revision the server and client speak natively
+pub const PROTOCOL_VERSION: &str = compat::LATEST;
\ No newline at end of file
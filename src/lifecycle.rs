@@ -0,0 +1,130 @@
+//! The connection lifecycle: initialization, operation, shutdown
+//!
+//! Each client goes through the phases of [`Phase`]: `initialize` starts
+//! the handshake, `notifications/initialized` completes it, and
+//! [`McpServer::begin_shutdown`] ends operation for everyone. The phases
+//! are tracked per session, and for the client outside sessions.
+//!
+//! With [`ServerConfig::strict_lifecycle`] the server also enforces them,
+//! answering anything but `initialize` and `ping` before the handshake
+//! with [`SERVER_NOT_INITIALIZED`]. Once shutdown has begun, requests other
+//! than `ping` get [`SERVER_SHUTTING_DOWN`] whether or not the lifecycle is
+//! strict.
+//!
+//! [`McpServer::begin_shutdown`]: crate::server::McpServer::begin_shutdown
+//! [`ServerConfig::strict_lifecycle`]: crate::server::ServerConfig::strict_lifecycle
+
+use crate::utils::error_codes::{SERVER_NOT_INITIALIZED, SERVER_SHUTTING_DOWN};
+use crate::utils::Error;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Notify;
+
+/// Where a client is in the lifecycle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Nothing but `initialize` and `ping` should arrive yet
+    AwaitingInitialize,
+    /// `initialize` was answered; waiting for `notifications/initialized`
+    Initializing,
+    /// The handshake is complete
+    Operating,
+    /// The server is shutting down and takes no new requests
+    ShuttingDown,
+}
+
+/// Lifecycle phase of every client, keyed like the rest of the per-client
+/// state by session (`None` outside sessions)
+pub(crate) struct Lifecycle {
+    phases: Mutex<HashMap<Option<String>, Phase>>,
+    shutting_down: AtomicBool,
+    shutdown_begun: Notify,
+}
+
+impl Lifecycle {
+    pub(crate) fn new() -> Self {
+        Self {
+            phases: Mutex::new(HashMap::new()),
+            shutting_down: AtomicBool::new(false),
+            shutdown_begun: Notify::new(),
+        }
+    }
+
+    pub(crate) fn phase(&self, session: &Option<String>) -> Phase {
+        if self.shutting_down.load(Ordering::Relaxed) {
+            return Phase::ShuttingDown;
+        }
+        self.lock().get(session).copied().unwrap_or(Phase::AwaitingInitialize)
+    }
+
+    /// Moves `session` to `phase`
+    pub(crate) fn enter(&self, session: Option<String>, phase: Phase) {
+        self.lock().insert(session, phase);
+    }
+
+    /// Moves `session` to `phase` unless its phase is already known, e.g.
+    /// for a session restored from the state store
+    #[cfg(feature = "native")]
+    pub(crate) fn restore(&self, session: String, phase: Phase) {
+        self.lock().entry(Some(session)).or_insert(phase);
+    }
+
+    pub(crate) fn forget(&self, session: &str) {
+        self.lock().remove(&Some(session.to_string()));
+    }
+
+    pub(crate) fn begin_shutdown(&self) {
+        self.shutting_down.store(true, Ordering::Relaxed);
+        self.shutdown_begun.notify_waiters();
+    }
+
+    /// Resolves once shutdown has begun
+    pub(crate) async fn shutting_down(&self) {
+        loop {
+            let begun = self.shutdown_begun.notified();
+            if self.shutting_down.load(Ordering::Relaxed) {
+                return;
+            }
+            begun.await;
+        }
+    }
+
+    /// Whether `session` may send `method` now; `strict` enforces the
+    /// handshake, while shutdown is always enforced
+    pub(crate) fn admit(&self, session: &Option<String>, method: &str, strict: bool) -> Result<(), Error> {
+        if method == "ping" {
+            return Ok(());
+        }
+        let admitted = match self.phase(session) {
+            Phase::ShuttingDown => {
+                return Err(Error::Application {
+                    code: SERVER_SHUTTING_DOWN.code(),
+                    message: format!("Server is shutting down; {} was not handled", method),
+                    data: None,
+                });
+            }
+            _ if !strict => true,
+            // A client may start over, and every new HTTP client initializes
+            // outside a session
+            _ if method == "initialize" => true,
+            Phase::AwaitingInitialize => false,
+            // Only notifications, `notifications/initialized` among them,
+            // until the handshake completes
+            Phase::Initializing => method.starts_with("notifications/") || method == "initialized",
+            Phase::Operating => true,
+        };
+        if admitted {
+            return Ok(());
+        }
+        Err(Error::Application {
+            code: SERVER_NOT_INITIALIZED.code(),
+            message: format!("Server not initialized; {} was sent before the handshake completed", method),
+            data: None,
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Option<String>, Phase>> {
+        self.phases.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,335 @@
+//! Load testing with synthetic client sessions
+//!
+//! [`LoadTest`] opens a number of concurrent sessions against a target,
+//! each sending a fixed number of requests drawn from a weighted
+//! [`MixEntry`] list, and reports throughput and latency percentiles. The
+//! target is either a server in this process or a command that is started
+//! once per session and spoken to over stdio.
+//!
+//! A mix is a JSON array:
+//!
+//! ```json
+//! [
+//!   { "method": "tools/list" },
+//!   { "method": "tools/call", "params": { "name": "greet", "arguments": { "name": "Ada" } }, "weight": 3 }
+//! ]
+//! ```
+
+use crate::McpServer;
+use crate::client::McpClient;
+use crate::testing::McpTestClient;
+use crate::transport::StdioTransport;
+use crate::utils::{Error, Result};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::BufReader;
+use tokio::process::{Child, Command};
+
+/// One kind of request in a mix
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MixEntry {
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    /// Relative frequency of this request
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl MixEntry {
+    pub fn new(method: impl Into<String>, params: Value) -> Self {
+        Self {
+            method: method.into(),
+            params,
+            weight: 1,
+        }
+    }
+
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Loads a JSON array of entries
+    pub fn load(path: impl AsRef<Path>) -> Result<Vec<Self>> {
+        let content = std::fs::read_to_string(path.as_ref())?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Requests any server answers: pings and catalog listings
+    pub fn default_mix() -> Vec<Self> {
+        vec![
+            Self::new("ping", json!({})).with_weight(2),
+            Self::new("tools/list", json!({})),
+        ]
+    }
+}
+
+/// What the sessions connect to
+#[derive(Clone)]
+pub enum Target {
+    /// A server in this process
+    InProcess(Arc<McpServer>),
+    /// A stdio server started once per session
+    Command { command: String, args: Vec<String> },
+}
+
+/// A load test run; see the module docs
+pub struct LoadTest {
+    target: Target,
+    sessions: usize,
+    requests_per_session: usize,
+    mix: Vec<MixEntry>,
+}
+
+impl LoadTest {
+    pub fn new(target: Target) -> Self {
+        Self {
+            target,
+            sessions: 10,
+            requests_per_session: 100,
+            mix: MixEntry::default_mix(),
+        }
+    }
+
+    /// Number of concurrent sessions (default 10)
+    pub fn with_sessions(mut self, sessions: usize) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
+    /// Requests each session sends after initializing (default 100)
+    pub fn with_requests_per_session(mut self, requests: usize) -> Self {
+        self.requests_per_session = requests;
+        self
+    }
+
+    pub fn with_mix(mut self, mix: Vec<MixEntry>) -> Self {
+        self.mix = mix;
+        self
+    }
+
+    /// Runs every session to completion and aggregates their timings
+    pub async fn run(&self) -> Result<LoadTestReport> {
+        // Spread each entry by its weight; sessions cycle through the
+        // result from different offsets so they don't move in lockstep
+        let schedule: Arc<Vec<MixEntry>> = Arc::new(
+            self.mix
+                .iter()
+                .flat_map(|entry| std::iter::repeat_n(entry.clone(), entry.weight as usize))
+                .collect(),
+        );
+        if schedule.is_empty() {
+            return Err(Error::ValidationError("The request mix is empty".to_string()));
+        }
+
+        let mut handles = Vec::with_capacity(self.sessions);
+        for session in 0..self.sessions {
+            let target = self.target.clone();
+            let schedule = schedule.clone();
+            let requests = self.requests_per_session;
+            handles.push(tokio::spawn(async move {
+                run_session(target, &schedule, session, requests).await
+            }));
+        }
+
+        // Throughput covers the time requests were in flight, not process
+        // startup and initialization
+        let mut samples = Vec::new();
+        let mut window: Option<(Instant, Instant)> = None;
+        for handle in handles {
+            let (session_samples, started, finished) =
+                handle.await.map_err(|e| Error::AsyncError(e.to_string()))??;
+            samples.extend(session_samples);
+            window = Some(match window {
+                Some((first, last)) => (first.min(started), last.max(finished)),
+                None => (started, finished),
+            });
+        }
+        let elapsed = window.map_or(Duration::ZERO, |(first, last)| last - first);
+        Ok(LoadTestReport::new(samples, elapsed))
+    }
+}
+
+/// Client side of one synthetic session
+enum Session {
+    InProcess(McpTestClient),
+    Command {
+        client: Box<McpClient<StdioTransport>>,
+        _child: Child,
+    },
+}
+
+impl Session {
+    async fn connect(target: Target) -> Result<Self> {
+        match target {
+            Target::InProcess(server) => {
+                let client = McpTestClient::from_arc(server);
+                client.initialize().await?;
+                Ok(Self::InProcess(client))
+            }
+            Target::Command { command, args } => {
+                let mut child = Command::new(&command)
+                    .args(&args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .kill_on_drop(true)
+                    .spawn()?;
+                let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+                    return Err(Error::InternalError(format!("No stdio for {}", command)));
+                };
+                let mut client = McpClient::new(StdioTransport::from_io(BufReader::new(stdout), stdin))
+                    .with_client_info("mcp-load-test", env!("CARGO_PKG_VERSION"));
+                client.initialize().await?;
+                Ok(Self::Command {
+                    client: Box::new(client),
+                    _child: child,
+                })
+            }
+        }
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        match self {
+            Self::InProcess(client) => client.request(method, params).await,
+            Self::Command { client, .. } => client.request(method, params).await,
+        }
+    }
+}
+
+struct Sample {
+    method: String,
+    latency: Duration,
+    ok: bool,
+}
+
+async fn run_session(
+    target: Target,
+    schedule: &[MixEntry],
+    session: usize,
+    requests: usize,
+) -> Result<(Vec<Sample>, Instant, Instant)> {
+    let mut client = Session::connect(target).await?;
+    let mut samples = Vec::with_capacity(requests);
+    let session_started = Instant::now();
+    for i in 0..requests {
+        let entry = &schedule[(session + i) % schedule.len()];
+        let started = Instant::now();
+        let result = client.request(&entry.method, entry.params.clone()).await;
+        samples.push(Sample {
+            method: entry.method.clone(),
+            latency: started.elapsed(),
+            ok: result.is_ok(),
+        });
+        // A closed connection fails every later request too
+        if let Err(Error::Io(e)) = result {
+            return Err(Error::Io(e));
+        }
+    }
+    Ok((samples, session_started, Instant::now()))
+}
+
+/// Latency distribution of a set of requests
+#[derive(Clone, Debug)]
+pub struct LatencyStats {
+    pub count: usize,
+    pub errors: usize,
+    pub min: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    fn new(samples: &[&Sample]) -> Self {
+        let mut latencies: Vec<Duration> = samples.iter().map(|s| s.latency).collect();
+        latencies.sort();
+        let percentile = |p: usize| {
+            if latencies.is_empty() {
+                Duration::ZERO
+            } else {
+                latencies[((latencies.len() - 1) * p) / 100]
+            }
+        };
+        Self {
+            count: samples.len(),
+            errors: samples.iter().filter(|s| !s.ok).count(),
+            min: percentile(0),
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: percentile(100),
+        }
+    }
+}
+
+/// Results of a [`LoadTest`]
+#[derive(Clone, Debug)]
+pub struct LoadTestReport {
+    pub elapsed: Duration,
+    pub overall: LatencyStats,
+    pub by_method: BTreeMap<String, LatencyStats>,
+}
+
+impl LoadTestReport {
+    fn new(samples: Vec<Sample>, elapsed: Duration) -> Self {
+        let mut grouped: BTreeMap<String, Vec<&Sample>> = BTreeMap::new();
+        for sample in &samples {
+            grouped.entry(sample.method.clone()).or_default().push(sample);
+        }
+        Self {
+            elapsed,
+            overall: LatencyStats::new(&samples.iter().collect::<Vec<_>>()),
+            by_method: grouped
+                .into_iter()
+                .map(|(method, samples)| (method, LatencyStats::new(&samples)))
+                .collect(),
+        }
+    }
+
+    /// Requests completed per second
+    pub fn throughput(&self) -> f64 {
+        self.overall.count as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+impl fmt::Display for LoadTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} requests in {:.2?} ({:.0} req/s), {} errors",
+            self.overall.count,
+            self.elapsed,
+            self.throughput(),
+            self.overall.errors
+        )?;
+        writeln!(
+            f,
+            "{:<24} {:>8} {:>7} {:>10} {:>10} {:>10} {:>10}",
+            "method", "count", "errors", "p50", "p90", "p99", "max"
+        )?;
+        let all = "all".to_string();
+        let rows = self.by_method.iter().chain([(&all, &self.overall)]);
+        for (method, stats) in rows {
+            writeln!(
+                f,
+                "{:<24} {:>8} {:>7} {:>10.2?} {:>10.2?} {:>10.2?} {:>10.2?}",
+                method, stats.count, stats.errors, stats.p50, stats.p90, stats.p99, stats.max
+            )?;
+        }
+        Ok(())
+    }
+}
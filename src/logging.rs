@@ -0,0 +1,110 @@
+//! Log messages sent to the client
+//!
+//! Separate from the server's own `tracing` output on stderr: a
+//! [`ClientLogger`] sends `notifications/message` to the client of the
+//! request being handled, dropping messages below the level that client
+//! asked for with `logging/setLevel` ([`DEFAULT_LOG_LEVEL`] until it does).
+
+use crate::notifications::{self, NotificationSender};
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Level sent to clients that never called `logging/setLevel`
+pub const DEFAULT_LOG_LEVEL: LogLevel = LogLevel::Info;
+
+/// Syslog severities, as used by MCP, from least to most severe
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+impl LogLevel {
+    /// Parses the `level` of `logging/setLevel`
+    pub fn parse(level: &str) -> Result<Self> {
+        serde_json::from_value(json!(level))
+            .map_err(|_| Error::InvalidParams(format!("Unknown log level: {}", level)))
+    }
+}
+
+impl From<tracing::Level> for LogLevel {
+    fn from(level: tracing::Level) -> Self {
+        match level {
+            tracing::Level::TRACE | tracing::Level::DEBUG => LogLevel::Debug,
+            tracing::Level::INFO => LogLevel::Info,
+            tracing::Level::WARN => LogLevel::Warning,
+            tracing::Level::ERROR => LogLevel::Error,
+        }
+    }
+}
+
+/// Minimum level each session asked for; `None` is the client outside
+/// sessions
+pub(crate) type LogLevels = Arc<Mutex<HashMap<Option<String>, LogLevel>>>;
+
+/// Sends log messages to one client
+#[derive(Clone)]
+pub struct ClientLogger {
+    name: Option<String>,
+    sender: NotificationSender,
+}
+
+impl ClientLogger {
+    /// Logger for the client of the request being handled, reported as
+    /// `name`; `None` outside a request
+    pub fn current(name: impl Into<String>) -> Option<Self> {
+        Some(Self::new(notifications::current()?).with_name(name))
+    }
+
+    pub fn new(sender: NotificationSender) -> Self {
+        Self { name: None, sender }
+    }
+
+    /// Sets the `logger` field of the messages
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sends `data` at `level`; returns whether the client got it
+    pub fn log(&self, level: LogLevel, data: Value) -> bool {
+        if level < self.sender.log_level() {
+            return false;
+        }
+        let mut params = json!({ "level": level, "data": data });
+        if let Some(name) = &self.name {
+            params["logger"] = json!(name);
+        }
+        self.sender.notify("notifications/message", params)
+    }
+
+    pub fn debug(&self, message: &str) -> bool {
+        self.log(LogLevel::Debug, json!(message))
+    }
+
+    pub fn info(&self, message: &str) -> bool {
+        self.log(LogLevel::Info, json!(message))
+    }
+
+    pub fn notice(&self, message: &str) -> bool {
+        self.log(LogLevel::Notice, json!(message))
+    }
+
+    pub fn warning(&self, message: &str) -> bool {
+        self.log(LogLevel::Warning, json!(message))
+    }
+
+    pub fn error(&self, message: &str) -> bool {
+        self.log(LogLevel::Error, json!(message))
+    }
+}
\ No newline at end of file
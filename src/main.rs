@@ -5,42 +5,463 @@
 use mcp_server_rust::{
     McpServer, ServerConfig,
     utils::logger::init_logger,
-    transport::StdioTransport,
+    transport::{StdioTransport, TcpTransport, http, rest},
+    state::{EncryptedStateStore, EncryptionKey, SledStateStore, StateStore},
+    openapi::Secret,
+    aggregator::{Aggregator, AggregatorConfig},
+    catalog::Catalog,
+    client::McpClient,
+    plugins::PluginWatcher,
+    manifest::Manifest,
+    repl::Repl,
+    load_test::{LoadTest, MixEntry, Target},
 };
-use tracing::error;
-use futures::future::BoxFuture;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use mcp_server_rust::daemon::{self, PidFile};
+#[cfg(unix)]
+use mcp_server_rust::systemd::{self, ActivatedListener};
+use mcp_server_rust::scaffold::{Scaffold, ScaffoldKind};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// How long requests in flight get to finish once a shutdown signal arrives
+/// (`MCP_SHUTDOWN_GRACE_MS` overrides it)
+const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+#[derive(Parser)]
+#[command(version, about = "Model Context Protocol server and toolbox", args_conflicts_with_subcommands = true)]
+struct Cli {
+    /// Serves when omitted
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    serve: ServeArgs,
+}
+
+#[derive(Args)]
+struct ServeArgs {
+    /// Serve JSON-RPC over HTTP on this address instead of stdio
+    #[arg(long)]
+    http: Option<SocketAddr>,
+
+    /// Serve newline-delimited JSON-RPC over TCP on this address instead of
+    /// stdio, one session per connection
+    #[arg(long, conflicts_with = "http")]
+    tcp: Option<SocketAddr>,
+
+    /// Detach and run in the background; needs a network transport
+    #[arg(long)]
+    daemon: bool,
+
+    /// Write the server's pid here, removing it on exit
+    #[arg(long)]
+    pid_file: Option<PathBuf>,
+
+    /// Append logs here instead of stderr when running as a daemon
+    #[arg(long, requires = "daemon")]
+    log_file: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serve the configured server (the default)
+    Serve(ServeArgs),
+    /// Start another MCP server over stdio and print its catalog as JSON
+    Inspect {
+        /// Command starting the server
+        command: String,
+        /// Arguments for the command
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Check a manifest or gateway configuration without starting it
+    Validate {
+        path: PathBuf,
+        #[arg(long, value_enum, default_value_t = ConfigKind::Manifest)]
+        kind: ConfigKind,
+    },
+    /// Generate the module, test and registration lines for a new handler
+    Generate {
+        #[arg(value_enum)]
+        kind: ScaffoldKind,
+        /// Name clients will use, e.g. word-count
+        name: String,
+        /// Crate root to write into
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+        /// Overwrite existing files
+        #[arg(long)]
+        force: bool,
+    },
+    /// Explore the server's tools, resources and prompts from a terminal
+    Repl,
+    /// Print the server's tools, resources and prompts with their schemas
+    ExportCatalog {
+        #[arg(long, value_enum, default_value_t = CatalogFormat::Json)]
+        format: CatalogFormat,
+        /// Write here instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Drive a server with synthetic client sessions and report latency
+    Bench {
+        /// Concurrent sessions
+        #[arg(long, default_value_t = 10)]
+        sessions: usize,
+        /// Requests per session
+        #[arg(long, default_value_t = 100)]
+        requests: usize,
+        /// JSON file with the request mix; pings and tools/list by default
+        #[arg(long)]
+        mix: Option<PathBuf>,
+        /// Stdio server to start per session, after `--`; this server
+        /// in-process when omitted
+        #[arg(last = true)]
+        target: Vec<String>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CatalogFormat {
+    Json,
+    Markdown,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ConfigKind {
+    /// A server manifest (MCP_MANIFEST)
+    Manifest,
+    /// A gateway configuration (MCP_GATEWAY_CONFIG)
+    Gateway,
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command.unwrap_or(Command::Serve(cli.serve)) {
+        Command::Serve(args) => serve(args),
+        Command::Inspect { command, args } => {
+            init_logger();
+            tokio::runtime::Runtime::new()?.block_on(inspect(&command, &args))
+        }
+        Command::Validate { path, kind } => validate(&path, kind),
+        Command::Generate { kind, name, root, force } => {
+            let scaffold = Scaffold::new(kind, &name)?;
+            for path in scaffold.write(&root, force)? {
+                println!("Created {}", path.display());
+            }
+            println!("\nRegister it with:\n\n{}", scaffold.registration_snippet());
+            Ok(())
+        }
+        Command::Repl => {
+            init_logger();
+            tokio::runtime::Runtime::new()?.block_on(repl())
+        }
+        Command::Bench { sessions, requests, mix, target } => {
+            init_logger();
+            let load_test = |target| -> anyhow::Result<LoadTest> {
+                let mut load_test = LoadTest::new(target)
+                    .with_sessions(sessions)
+                    .with_requests_per_session(requests);
+                if let Some(mix) = &mix {
+                    load_test = load_test.with_mix(MixEntry::load(mix)?);
+                }
+                Ok(load_test)
+            };
+            tokio::runtime::Runtime::new()?.block_on(async {
+                let target = match target.split_first() {
+                    Some((command, args)) => Target::Command {
+                        command: command.clone(),
+                        args: args.to_vec(),
+                    },
+                    None => Target::InProcess(configured_server().await?),
+                };
+                print!("{}", load_test(target)?.run().await?);
+                Ok(())
+            })
+        }
+        Command::ExportCatalog { format, output } => {
+            init_logger();
+            tokio::runtime::Runtime::new()?.block_on(export_catalog(format, output.as_deref()))
+        }
+    }
+}
+
+fn serve(args: ServeArgs) -> anyhow::Result<()> {
+    // Detaching forks, so it has to happen before the runtime starts
+    if args.daemon {
+        if serves_stdio(&args)? {
+            anyhow::bail!("--daemon needs a network transport: pass --http or --tcp, or use a manifest without stdio");
+        }
+        daemon::detach(args.log_file.as_deref())?;
+    }
+    let _pid_file = args.pid_file.as_ref().map(PidFile::create).transpose()?;
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
     // Initialize logging to stderr
     init_logger();
 
+    let runtime = tokio::runtime::Runtime::new()?;
+    let result = runtime.block_on(run(args));
+    // Don't wait on a stdin read still blocking a runtime thread
+    runtime.shutdown_background();
+    result
+}
+
+/// Whether the configured server talks to its client over stdio
+fn serves_stdio(args: &ServeArgs) -> anyhow::Result<bool> {
+    if std::env::var_os("MCP_GATEWAY_CONFIG").is_some() {
+        return Ok(true);
+    }
+    if let Ok(path) = std::env::var("MCP_MANIFEST") {
+        return Ok(Manifest::from_file(&path)?.transports.stdio);
+    }
+    Ok(args.http.is_none() && args.tcp.is_none())
+}
+
+async fn run(args: ServeArgs) -> anyhow::Result<()> {
+    // Front the downstream servers in MCP_GATEWAY_CONFIG instead of serving
+    // the built-in catalog
+    if let Ok(path) = std::env::var("MCP_GATEWAY_CONFIG") {
+        let aggregator = Aggregator::connect(AggregatorConfig::from_file(&path)?).await?;
+        let mut transport = StdioTransport::new();
+        if let Err(e) = aggregator.serve(&mut transport).await {
+            error!("Gateway error: {}", e);
+            anyhow::bail!(e);
+        }
+        return Ok(());
+    }
+
+    // Serve the server declared by MCP_MANIFEST
+    if let Ok(path) = std::env::var("MCP_MANIFEST") {
+        if let Err(e) = Manifest::from_file(&path)?.serve().await {
+            error!("Server error: {}", e);
+            anyhow::bail!(e);
+        }
+        return Ok(());
+    }
+
+    let server = build_server().await?;
+
+    // On SIGINT or SIGTERM, stop taking requests and give those in flight
+    // time to finish; the transport returns once they are answered
+    {
+        let server = server.clone();
+        let grace = std::env::var("MCP_SHUTDOWN_GRACE_MS")
+            .ok()
+            .and_then(|ms| ms.parse().ok())
+            .map_or(DEFAULT_SHUTDOWN_GRACE, Duration::from_millis);
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            info!("Shutting down, waiting up to {:?} for requests in flight", grace);
+            tokio::select! {
+                abandoned = server.shutdown(grace) => {
+                    for request in abandoned {
+                        warn!("Cancelled {} (id {}) at shutdown", request.method, request.id);
+                    }
+                }
+                // A second signal doesn't wait
+                _ = shutdown_signal() => std::process::exit(130),
+            }
+        });
+    }
+
+    // Optionally expose tools and resources as plain HTTP endpoints too
+    if let Ok(addr) = std::env::var("MCP_REST_ADDR") {
+        let addr = addr.parse()?;
+        let server = server.clone();
+        tokio::spawn(async move {
+            if let Err(e) = rest::serve(server, addr).await {
+                error!("REST gateway error: {}", e);
+            }
+        });
+    }
+
+    // Under systemd socket activation, serve HTTP on the inherited socket
+    #[cfg(unix)]
+    let activated = systemd::listen_fds()?.into_iter().next();
+    #[cfg(not(unix))]
+    let activated: Option<std::convert::Infallible> = None;
+
+    let result = match (activated, args.http, args.tcp) {
+        #[cfg(unix)]
+        (Some(ActivatedListener::Tcp(listener)), _, _) => {
+            notify_ready();
+            http::serve_listener(server, listener).await
+        }
+        #[cfg(unix)]
+        (Some(ActivatedListener::Unix(listener)), _, _) => {
+            notify_ready();
+            http::serve_listener(server, listener).await
+        }
+        (None, Some(addr), _) => {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            notify_ready();
+            http::serve_listener(server, listener).await
+        }
+        (None, None, Some(addr)) => {
+            let transport = TcpTransport::bind(addr).await?;
+            notify_ready();
+            transport.serve(server).await
+        }
+        (None, None, None) => {
+            notify_ready();
+            let mut transport = StdioTransport::new();
+            // Only serve a host that knows the secret it started us with
+            if let Ok(secret) = std::env::var("MCP_STDIO_TOKEN") {
+                transport = transport.with_shared_secret(secret);
+            }
+            if let Some(max) = std::env::var("MCP_STDIO_CONCURRENCY").ok().and_then(|max| max.parse().ok()) {
+                transport = transport.with_max_concurrency(max);
+            }
+            transport.serve_shared(server).await
+        }
+    };
+    #[cfg(unix)]
+    let _ = systemd::notify_stopping();
+    if let Err(e) = result {
+        error!("Transport error: {}", e);
+        anyhow::bail!(e);
+    }
+
+    Ok(())
+}
+
+/// Builds the server the environment describes: the built-in catalog plus
+/// any plugins and scripts
+async fn build_server() -> anyhow::Result<Arc<McpServer>> {
     // Create server configuration
-    let config = ServerConfig::new("RustMcpServer", "1.0.0");
+    let mut config = ServerConfig::new("RustMcpServer", "1.0.0");
+
+    // Disable subsystems listed in MCP_DISABLED_FEATURES (e.g. "prompts,resources")
+    if let Ok(disabled) = std::env::var("MCP_DISABLED_FEATURES") {
+        for name in disabled.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            if !config.features.disable(name) {
+                warn!("Unknown feature in MCP_DISABLED_FEATURES: {}", name);
+            }
+        }
+    }
 
     // Create MCP server instance
-    let server = McpServer::new(config);
-
-    // Create stdio transport
-    let mut transport = StdioTransport::new();
-
-    // Create a handler closure that processes JSON-RPC messages
-    let server_arc = std::sync::Arc::new(server);
-    let handler = {
-        let server = server_arc.clone();
-        move |message: serde_json::Value| -> BoxFuture<'static, mcp_server_rust::utils::Result<Option<serde_json::Value>>> {
-            let server = server.clone();
-            Box::pin(async move {
-                server.handle_request(message).await
-            })
+    let mut server = McpServer::new(config);
+
+    // Use a durable state store when a state directory is configured
+    if let Ok(state_dir) = std::env::var("MCP_STATE_DIR") {
+        let mut store: Arc<dyn StateStore> = Arc::new(SledStateStore::open(&state_dir)?);
+        // Encrypt stored values when a key is provided
+        if std::env::var_os("MCP_STATE_KEY").is_some() {
+            let key = EncryptionKey::from_secret(&Secret::Env { env: "MCP_STATE_KEY".to_string() })?;
+            store = Arc::new(EncryptedStateStore::new(store, &key));
         }
-    };
+        server = server.with_state_store(store);
+    }
 
-    // Start listening on stdio
-    if let Err(e) = transport.listen(handler).await {
-        error!("Transport error: {}", e);
-        std::process::exit(1);
+    let server = Arc::new(server);
+
+    // Load script plugins and pick up changes to them while running
+    if let Ok(plugin_dir) = std::env::var("MCP_PLUGIN_DIR") {
+        PluginWatcher::new(plugin_dir).spawn(server.clone()).await?;
     }
 
+    // Register the tools and prompts a Python script declares
+    #[cfg(feature = "python")]
+    if let Ok(script) = std::env::var("MCP_PYTHON_SCRIPT") {
+        mcp_server_rust::python::load_script(&server, script).await?;
+    }
+
+    Ok(server)
+}
+
+/// The server a plain run serves, for commands working on it in-process
+async fn configured_server() -> anyhow::Result<Arc<McpServer>> {
+    if std::env::var_os("MCP_GATEWAY_CONFIG").is_some() {
+        anyhow::bail!("Gateway mode has no in-process server; use inspect instead");
+    }
+    match std::env::var("MCP_MANIFEST") {
+        Ok(path) => Ok(Arc::new(Manifest::from_file(&path)?.build().await?)),
+        Err(_) => build_server().await,
+    }
+}
+
+async fn repl() -> anyhow::Result<()> {
+    Repl::new(configured_server().await?).run_stdio().await?;
+    Ok(())
+}
+
+async fn export_catalog(format: CatalogFormat, output: Option<&Path>) -> anyhow::Result<()> {
+    let catalog = Catalog::from_server(configured_server().await?).await?;
+    let contents = match format {
+        CatalogFormat::Json => serde_json::to_string_pretty(&catalog)? + "\n",
+        CatalogFormat::Markdown => catalog.to_markdown(),
+    };
+    match output {
+        Some(path) => std::fs::write(path, contents)?,
+        None => print!("{}", contents),
+    }
+    Ok(())
+}
+
+/// Prints the catalog of the server `command` starts
+async fn inspect(command: &str, args: &[String]) -> anyhow::Result<()> {
+    let mut child = tokio::process::Command::new(command)
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        anyhow::bail!("Failed to connect to {}", command);
+    };
+    let mut client = McpClient::new(StdioTransport::from_io(tokio::io::BufReader::new(stdout), stdin));
+    let catalog = Catalog::fetch(&mut client).await?;
+    println!("{}", serde_json::to_string_pretty(&catalog)?);
     Ok(())
-}
\ No newline at end of file
+}
+
+fn validate(path: &Path, kind: ConfigKind) -> anyhow::Result<()> {
+    let problems = match kind {
+        ConfigKind::Manifest => Manifest::from_file(path)?.validate(),
+        ConfigKind::Gateway => AggregatorConfig::from_file(path)?.validate(),
+    };
+    if problems.is_empty() {
+        println!("{} is valid", path.display());
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    anyhow::bail!("{} has {} problem(s)", path.display(), problems.len());
+}
+
+/// Resolves on SIGINT (Ctrl-C), or SIGTERM on Unix
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                return;
+            }
+            Err(e) => warn!("Failed to listen for SIGTERM: {}", e),
+        }
+    }
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        warn!("Failed to listen for Ctrl-C: {}", e);
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Reports readiness to systemd when running as a `Type=notify` unit
+fn notify_ready() {
+    #[cfg(unix)]
+    if let Err(e) = systemd::notify_ready() {
+        warn!("Failed to notify systemd: {}", e);
+    }
+}
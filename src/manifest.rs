@@ -0,0 +1,469 @@
+//! Whole servers described by a single YAML or TOML manifest
+//!
+//! ```yaml
+//! server:
+//!   name: docs-server
+//!   version: 1.0.0
+//! transports:
+//!   stdio: true
+//!   http: 127.0.0.1:8080
+//! roots:
+//!   - uriPrefix: file:///docs/
+//!     path: ./docs
+//! openapi:
+//!   - spec: petstore.yaml
+//!     auth: { type: bearer, token: { env: PETSTORE_TOKEN } }
+//! tools:
+//!   - name: word-count
+//!     command: wc
+//!     args: [-w]
+//! prompts:
+//!   - name: summarize
+//!     arguments: [{ name: topic, description: What to summarize, required: true }]
+//!     template: Summarize what the docs say about {{topic}}.
+//! resources:
+//!   - uri: docs://readme
+//!     path: ./README.md
+//! ```
+//!
+//! Relative paths are resolved against the manifest's directory. Tools are
+//! [`ScriptTool`]s, so they receive their arguments as JSON on stdin.
+
+use crate::context::RequestContext;
+use crate::McpServer;
+use crate::openapi::{AuthConfig, OpenApiSpec};
+use crate::plugins::{ScriptTool, ScriptToolConfig};
+use crate::prompts::{Content, GetPromptResult, Message, Prompt, PromptArgument, PromptHandler};
+use crate::resources::file_resource::FileResource;
+use crate::resources::{Resource, ResourceDefinition, ResourceHandler, ResourceReadResult};
+use crate::server::ServerConfig;
+use crate::transport::{StdioTransport, http, rest};
+use crate::utils::{Error, Logger, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A server definition; see the module docs for the format
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Manifest {
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub transports: TransportsConfig,
+    #[serde(default)]
+    pub roots: Vec<RootConfig>,
+    #[serde(default)]
+    pub openapi: Vec<OpenApiConfig>,
+    #[serde(default)]
+    pub tools: Vec<ScriptToolConfig>,
+    #[serde(default)]
+    pub prompts: Vec<PromptConfig>,
+    #[serde(default)]
+    pub resources: Vec<ResourceConfig>,
+    /// Directory relative paths are resolved against
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TransportsConfig {
+    #[serde(default = "default_stdio")]
+    pub stdio: bool,
+    /// Serves JSON-RPC over HTTP on this address
+    #[serde(default)]
+    pub http: Option<SocketAddr>,
+    /// Serves the REST facade on this address
+    #[serde(default)]
+    pub rest: Option<SocketAddr>,
+}
+
+fn default_stdio() -> bool {
+    true
+}
+
+impl Default for TransportsConfig {
+    fn default() -> Self {
+        Self {
+            stdio: true,
+            http: None,
+            rest: None,
+        }
+    }
+}
+
+/// A directory served as file resources under `uri_prefix`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RootConfig {
+    pub uri_prefix: String,
+    pub path: PathBuf,
+    /// Only serve files inside the roots the client granted
+    #[serde(default)]
+    pub client_roots: bool,
+}
+
+/// An OpenAPI document whose operations become tools
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OpenApiConfig {
+    pub spec: PathBuf,
+    /// Overrides the document's first `servers` entry
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub auth: AuthConfig,
+}
+
+/// A prompt rendered from a template with `{{argument}}` placeholders
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PromptConfig {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+    pub template: String,
+}
+
+/// A fixed resource, given inline as `text` or read from `path`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ResourceConfig {
+    pub uri: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_mime_type")]
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+fn default_mime_type() -> String {
+    "text/plain".to_string()
+}
+
+impl Manifest {
+    /// Loads a `.toml` manifest, or YAML (and so JSON) for any other extension
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let mut manifest = if path.extension().is_some_and(|ext| ext == "toml") {
+            Self::from_toml(&content)?
+        } else {
+            Self::from_yaml(&content)?
+        };
+        manifest.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(manifest)
+    }
+
+    pub fn from_yaml(content: &str) -> Result<Self> {
+        serde_yaml::from_str(content)
+            .map_err(|e| Error::ParseError(format!("Invalid manifest: {}", e)))
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|e| Error::ParseError(format!("Invalid manifest: {}", e)))
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        self.base_dir.join(path)
+    }
+
+    /// Creates the server with everything the manifest declares registered
+    pub async fn build(&self) -> Result<McpServer> {
+        let server = McpServer::new(self.server.clone());
+
+        for root in &self.roots {
+            let handler = FileResource::new(self.resolve(&root.path))
+                .with_uri_prefix(root.uri_prefix.clone())
+                .with_client_roots(root.client_roots)
+                .with_memory_guard(server.memory_guard());
+            server.register_resource(root.uri_prefix.clone(), Arc::new(handler)).await?;
+        }
+
+        for api in &self.openapi {
+            let spec = OpenApiSpec::from_file(self.resolve(&api.spec))?;
+            let base_url = api
+                .base_url
+                .as_deref()
+                .or(spec.base_url())
+                .ok_or_else(|| {
+                    Error::ValidationError(format!(
+                        "{} has no servers entry; set baseUrl",
+                        api.spec.display()
+                    ))
+                })?
+                .to_string();
+            for tool in spec.tools(&base_url, api.auth.clone())? {
+                server.register_tool(tool.name().to_string(), Arc::new(tool)).await?;
+            }
+        }
+
+        for tool in &self.tools {
+            let handler = ScriptTool::new(tool.clone(), &self.base_dir);
+            server.register_tool(tool.name.clone(), Arc::new(handler)).await?;
+        }
+
+        for prompt in &self.prompts {
+            let handler = TemplatePrompt { config: prompt.clone() };
+            server.register_prompt(prompt.name.clone(), Arc::new(handler)).await?;
+        }
+
+        for resource in &self.resources {
+            let handler = StaticResource {
+                config: resource.clone(),
+                path: resource.path.as_deref().map(|p| self.resolve(p)),
+            };
+            if resource.text.is_none() && handler.path.is_none() {
+                return Err(Error::ValidationError(format!(
+                    "Resource {} needs text or a path",
+                    resource.uri
+                )));
+            }
+            server.register_resource(resource.uri.clone(), Arc::new(handler)).await?;
+        }
+
+        Ok(server)
+    }
+
+    /// Checks the manifest without starting anything: referenced files
+    /// exist, names are unique and templates only use declared arguments.
+    /// Returns the problems found, empty if none.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let transports = &self.transports;
+        if !transports.stdio && transports.http.is_none() && transports.rest.is_none() {
+            problems.push("No transport is enabled".to_string());
+        }
+
+        for root in &self.roots {
+            if !self.resolve(&root.path).is_dir() {
+                problems.push(format!(
+                    "Root {} is not a directory: {}",
+                    root.uri_prefix,
+                    root.path.display()
+                ));
+            }
+        }
+
+        let mut tool_names = Vec::new();
+        for api in &self.openapi {
+            let spec = match OpenApiSpec::from_file(self.resolve(&api.spec)) {
+                Ok(spec) => spec,
+                Err(e) => {
+                    problems.push(format!("{}: {}", api.spec.display(), e));
+                    continue;
+                }
+            };
+            let Some(base_url) = api.base_url.as_deref().or(spec.base_url()) else {
+                problems.push(format!("{} has no servers entry; set baseUrl", api.spec.display()));
+                continue;
+            };
+            match spec.tools(base_url, api.auth.clone()) {
+                Ok(tools) => tool_names.extend(tools.iter().map(|t| t.name().to_string())),
+                Err(e) => problems.push(format!("{}: {}", api.spec.display(), e)),
+            }
+        }
+
+        for tool in &self.tools {
+            tool_names.push(tool.name.clone());
+            let command = ScriptTool::new(tool.clone(), &self.base_dir).command().to_path_buf();
+            // Bare names are looked up on PATH when the tool runs
+            if command.components().count() > 1 && !command.is_file() {
+                problems.push(format!(
+                    "Command of tool {} does not exist: {}",
+                    tool.name,
+                    command.display()
+                ));
+            }
+        }
+        problems.extend(duplicates("tool", &tool_names));
+
+        let prompt_names: Vec<_> = self.prompts.iter().map(|p| p.name.clone()).collect();
+        problems.extend(duplicates("prompt", &prompt_names));
+        let placeholder = regex::Regex::new(r"\{\{(\w+)\}\}").expect("valid placeholder pattern");
+        for prompt in &self.prompts {
+            for captures in placeholder.captures_iter(&prompt.template) {
+                let name = &captures[1];
+                if !prompt.arguments.iter().any(|a| a.name == name) {
+                    problems.push(format!(
+                        "Prompt {} uses undeclared argument {}",
+                        prompt.name, name
+                    ));
+                }
+            }
+        }
+
+        let uris: Vec<_> = self.resources.iter().map(|r| r.uri.clone()).collect();
+        problems.extend(duplicates("resource", &uris));
+        for resource in &self.resources {
+            match &resource.path {
+                Some(path) if !self.resolve(path).is_file() => problems.push(format!(
+                    "File of resource {} does not exist: {}",
+                    resource.uri,
+                    path.display()
+                )),
+                None if resource.text.is_none() => {
+                    problems.push(format!("Resource {} needs text or a path", resource.uri))
+                }
+                _ => {}
+            }
+        }
+
+        problems
+    }
+
+    /// Builds the server and serves it on the configured transports until
+    /// stdio closes or, without stdio, until the HTTP listener fails
+    pub async fn serve(&self) -> Result<()> {
+        let logger = Logger::new("Manifest");
+        let server = Arc::new(self.build().await?);
+        let transports = &self.transports;
+
+        if let Some(addr) = transports.rest {
+            let server = server.clone();
+            tokio::spawn(async move {
+                if let Err(e) = rest::serve(server, addr).await {
+                    Logger::new("Manifest").error(&format!("REST gateway error: {}", e));
+                }
+            });
+        }
+
+        match (transports.stdio, transports.http) {
+            (true, http_addr) => {
+                if let Some(addr) = http_addr {
+                    let server = server.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = http::serve(server, addr).await {
+                            Logger::new("Manifest").error(&format!("HTTP transport error: {}", e));
+                        }
+                    });
+                }
+                StdioTransport::new().serve_shared(server).await
+            }
+            (false, Some(addr)) => http::serve(server, addr).await,
+            (false, None) => {
+                logger.error("No transport enabled");
+                Err(Error::ValidationError(
+                    "The manifest enables no transport".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+/// Prompt from a manifest `prompts` entry
+pub struct TemplatePrompt {
+    config: PromptConfig,
+}
+
+impl TemplatePrompt {
+    pub fn prompt_definition(&self) -> Prompt {
+        Prompt {
+            name: self.config.name.clone(),
+            description: self.config.description.clone(),
+            arguments: Some(self.config.arguments.clone()).filter(|a| !a.is_empty()),
+        }
+    }
+}
+
+#[async_trait]
+impl PromptHandler for TemplatePrompt {
+    async fn get(&self, _context: &RequestContext, arguments: Option<Value>) -> Result<GetPromptResult> {
+        let mut text = self.config.template.clone();
+        for argument in &self.config.arguments {
+            let value = arguments
+                .as_ref()
+                .and_then(|a| a.get(&argument.name))
+                .and_then(Value::as_str);
+            if value.is_none() && argument.required == Some(true) {
+                return Err(Error::InvalidParams(format!(
+                    "Missing required argument: {}",
+                    argument.name
+                )));
+            }
+            text = text.replace(&format!("{{{{{}}}}}", argument.name), value.unwrap_or(""));
+        }
+
+        Ok(GetPromptResult {
+            description: Some(self.config.description.clone()).filter(|d| !d.is_empty()),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: Content::text(text),
+            }],
+            meta: None,
+        })
+    }
+
+    fn definition(&self) -> Option<Prompt> {
+        Some(self.prompt_definition())
+    }
+}
+
+/// Resource from a manifest `resources` entry
+pub struct StaticResource {
+    config: ResourceConfig,
+    path: Option<PathBuf>,
+}
+
+impl StaticResource {
+    pub fn resource_definition(&self) -> ResourceDefinition {
+        ResourceDefinition {
+            uri: self.config.uri.clone(),
+            name: self.config.name.clone().unwrap_or_else(|| self.config.uri.clone()),
+            description: self.config.description.clone(),
+            mime_type: Some(self.config.mime_type.clone()),
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceHandler for StaticResource {
+    async fn read(&self, _context: &RequestContext, uri: &str) -> Result<ResourceReadResult> {
+        if uri != self.config.uri {
+            return Err(Error::ResourceError(format!("Resource not found: {}", uri)));
+        }
+        let text = match (&self.config.text, &self.path) {
+            (Some(text), _) => text.clone(),
+            (None, Some(path)) => tokio::fs::read_to_string(path).await.map_err(|e| {
+                Error::ResourceError(format!("Failed to read {}: {}", path.display(), e))
+            })?,
+            (None, None) => String::new(),
+        };
+        Ok(ResourceReadResult {
+            contents: vec![Resource {
+                uri: uri.to_string(),
+                mime_type: self.config.mime_type.clone(),
+                size: Some(text.len() as u64),
+                text: Some(text),
+                blob: None,
+            }],
+            meta: None,
+        })
+    }
+
+    async fn list(&self) -> Result<Vec<ResourceDefinition>> {
+        Ok(vec![self.resource_definition()])
+    }
+}
+
+/// Reports each name that occurs more than once
+fn duplicates(kind: &str, names: &[String]) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    let mut reported = std::collections::HashSet::new();
+    names
+        .iter()
+        .filter(|name| !seen.insert(name.as_str()) && reported.insert(name.as_str()))
+        .map(|name| format!("Duplicate {} name: {}", kind, name))
+        .collect()
+}
@@ -0,0 +1,207 @@
+//! Hooks run around every method the server handles
+//!
+//! A [`Middleware`] added with [`McpServer::with_middleware`] sees each
+//! request and notification before it is dispatched, and its outcome
+//! afterwards, without touching the dispatch code. That is the place for
+//! cross-cutting concerns such as authentication, logging, rate limiting or
+//! metrics. Middleware runs in the order it was added on the way in and in
+//! reverse order on the way out:
+//!
+//! ```ignore
+//! let timing = Arc::new(TimingMiddleware::new());
+//! let server = McpServer::new(config)
+//!     .with_middleware(Arc::new(LoggingMiddleware::new()))
+//!     .with_middleware(timing.clone());
+//! // ...
+//! let slowest = timing.stats().into_iter().max_by_key(|(_, stats)| stats.max);
+//! ```
+//!
+//! Hooks run within the request's scope, so the session, identity and
+//! [`ClientLink`](crate::server::ClientLink) of the request are available.
+//!
+//! [`McpServer::with_middleware`]: crate::server::McpServer::with_middleware
+
+use crate::utils::{Error, Logger, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use serde_json::value::RawValue;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The request a hook runs for
+#[derive(Clone, Debug)]
+pub struct RequestInfo {
+    pub method: String,
+    /// `None` for notifications
+    pub id: Option<Value>,
+    pub session: Option<String>,
+    pub identity: Option<String>,
+    /// When the pipeline started on the request
+    pub started: Instant,
+}
+
+impl RequestInfo {
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+/// Hooks around the handling of a method; every hook defaults to doing
+/// nothing
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Runs before the method is dispatched and may rewrite its params. An
+    /// error rejects the request: it is answered with that error and later
+    /// middleware and the handler don't run.
+    async fn before_request(&self, _request: &RequestInfo, _params: &mut Option<Value>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Runs after the method succeeded; an error replaces the result
+    async fn after_response(&self, _request: &RequestInfo, _result: &RawValue) -> Result<()> {
+        Ok(())
+    }
+
+    /// Runs when the request failed, whether in a handler or in a
+    /// middleware's hook
+    async fn on_error(&self, _request: &RequestInfo, _error: &Error) {}
+}
+
+/// Logs each method with its outcome and duration
+pub struct LoggingMiddleware {
+    logger: Logger,
+}
+
+impl LoggingMiddleware {
+    pub fn new() -> Self {
+        Self {
+            logger: Logger::new("Middleware"),
+        }
+    }
+}
+
+impl Default for LoggingMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Middleware for LoggingMiddleware {
+    async fn before_request(&self, request: &RequestInfo, _params: &mut Option<Value>) -> Result<()> {
+        self.logger.debug(&format!("{} {} started", request.method, describe(request)));
+        Ok(())
+    }
+
+    async fn after_response(&self, request: &RequestInfo, _result: &RawValue) -> Result<()> {
+        self.logger.info(&format!(
+            "{} {} succeeded in {:?}",
+            request.method,
+            describe(request),
+            request.started.elapsed()
+        ));
+        Ok(())
+    }
+
+    async fn on_error(&self, request: &RequestInfo, error: &Error) {
+        self.logger.warn(&format!(
+            "{} {} failed in {:?}: {}",
+            request.method,
+            describe(request),
+            request.started.elapsed(),
+            error
+        ));
+    }
+}
+
+fn describe(request: &RequestInfo) -> String {
+    let id = request.id.as_ref().map_or("(notification)".to_string(), |id| format!("#{}", id));
+    match &request.session {
+        Some(session) => format!("{} in session {}", id, session),
+        None => id,
+    }
+}
+
+/// Durations of one method's requests
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MethodStats {
+    pub calls: u64,
+    pub errors: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl MethodStats {
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.calls) {
+            Ok(calls) if calls > 0 => self.total / calls,
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+/// Measures how long each method takes, for metrics
+pub struct TimingMiddleware {
+    stats: Mutex<HashMap<String, MethodStats>>,
+    slow: Option<Duration>,
+    logger: Logger,
+}
+
+impl TimingMiddleware {
+    pub fn new() -> Self {
+        Self {
+            stats: Mutex::new(HashMap::new()),
+            slow: None,
+            logger: Logger::new("Middleware"),
+        }
+    }
+
+    /// Also logs a warning for each request taking longer than `threshold`
+    pub fn with_slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow = Some(threshold);
+        self
+    }
+
+    /// Stats per method since the server started
+    pub fn stats(&self) -> HashMap<String, MethodStats> {
+        self.lock().clone()
+    }
+
+    fn record(&self, request: &RequestInfo, failed: bool) {
+        let elapsed = request.started.elapsed();
+        {
+            let mut stats = self.lock();
+            let stats = stats.entry(request.method.clone()).or_default();
+            stats.calls += 1;
+            stats.errors += u64::from(failed);
+            stats.total += elapsed;
+            stats.max = stats.max.max(elapsed);
+        }
+        if self.slow.is_some_and(|slow| elapsed > slow) {
+            self.logger.warn(&format!("Slow {}: took {:?}", request.method, elapsed));
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, MethodStats>> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for TimingMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Middleware for TimingMiddleware {
+    async fn after_response(&self, request: &RequestInfo, _result: &RawValue) -> Result<()> {
+        self.record(request, false);
+        Ok(())
+    }
+
+    async fn on_error(&self, request: &RequestInfo, _error: &Error) {
+        self.record(request, true);
+    }
+}
\ No newline at end of file
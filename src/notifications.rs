@@ -0,0 +1,126 @@
+//! Notifications pushed by handlers
+//!
+//! While a request is handled, [`current`] returns a [`NotificationSender`]
+//! addressed to the client that sent it. The sender is detached from the
+//! server, so a handler can keep a clone and notify the client after the
+//! request has completed, e.g. with `notifications/resources/updated` once a
+//! watched file changes:
+//!
+//! ```ignore
+//! let notifier = notifications::current().expect("called while handling a request");
+//! tokio::spawn(async move {
+//!     wait_for_change().await;
+//!     notifier.resource_updated("file:///notes.txt");
+//! });
+//! ```
+
+use crate::logging::{DEFAULT_LOG_LEVEL, LogLevel, LogLevels};
+use crate::utils::Logger;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
+
+tokio::task_local! {
+    static CURRENT_SENDER: NotificationSender;
+}
+
+pub(crate) type SessionChannels = Arc<Mutex<HashMap<String, mpsc::UnboundedSender<Value>>>>;
+
+/// Whether the client agreed to receive a method; see
+/// [`McpServer::may_send`](crate::McpServer::may_send)
+pub(crate) type Gate = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Sends notifications to one client
+///
+/// Within a session they go down that session's channel (Streamable HTTP,
+/// TCP); otherwise to every transport forwarding
+/// [`McpServer::notify`](crate::McpServer::notify)'s notifications (stdio).
+/// Notifications the client wasn't told to expect are dropped.
+#[derive(Clone)]
+pub struct NotificationSender {
+    logger: Logger,
+    session: Option<String>,
+    notifications: broadcast::Sender<Value>,
+    session_channels: SessionChannels,
+    gate: Gate,
+    log_levels: LogLevels,
+}
+
+impl NotificationSender {
+    pub(crate) fn new(
+        session: Option<String>,
+        notifications: broadcast::Sender<Value>,
+        session_channels: SessionChannels,
+        gate: Gate,
+        log_levels: LogLevels,
+    ) -> Self {
+        Self {
+            logger: Logger::new("NotificationSender"),
+            session,
+            notifications,
+            session_channels,
+            gate,
+            log_levels,
+        }
+    }
+
+    /// Session the notifications go to, if any
+    pub fn session(&self) -> Option<&str> {
+        self.session.as_deref()
+    }
+
+    /// Least severe log message the client wants to receive
+    pub fn log_level(&self) -> LogLevel {
+        let levels = self.log_levels.lock().unwrap_or_else(|e| e.into_inner());
+        levels.get(&self.session).copied().unwrap_or(DEFAULT_LOG_LEVEL)
+    }
+
+    /// Sends a JSON-RPC notification; returns whether anything could carry
+    /// it to the client
+    pub fn notify(&self, method: &str, params: Value) -> bool {
+        if !(self.gate)(method) {
+            self.logger.debug(&format!("Suppressing {}: not negotiated with the client", method));
+            return false;
+        }
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        });
+        match &self.session {
+            Some(session) => send_on(&self.session_channels, session, notification),
+            None => self.notifications.send(notification).is_ok(),
+        }
+    }
+
+    /// Tells the client that the resource at `uri` changed
+    pub fn resource_updated(&self, uri: &str) -> bool {
+        self.notify("notifications/resources/updated", json!({ "uri": uri }))
+    }
+}
+
+/// Sends a message down session `id`'s channel, dropping the channel if its
+/// receiver is gone; returns whether it was sent
+pub(crate) fn send_on(channels: &SessionChannels, id: &str, message: Value) -> bool {
+    let mut channels = channels.lock().unwrap_or_else(|e| e.into_inner());
+    match channels.get(id).map(|tx| tx.send(message)) {
+        Some(Ok(())) => true,
+        Some(Err(_)) => {
+            channels.remove(id);
+            false
+        }
+        None => false,
+    }
+}
+
+/// Sender for the client of the request being handled
+pub fn current() -> Option<NotificationSender> {
+    CURRENT_SENDER.try_with(Clone::clone).ok()
+}
+
+/// Runs `future` with `sender` as the current request's
+pub fn with_sender<F: Future>(sender: NotificationSender, future: F) -> impl Future<Output = F::Output> {
+    CURRENT_SENDER.scope(sender, future)
+}
\ No newline at end of file
@@ -0,0 +1,433 @@
+//! Tools generated from an OpenAPI 3 document
+//!
+//! Every operation becomes a tool named after its `operationId` (or
+//! `{method}_{path}` when it has none). Path, query and header parameters
+//! become top-level arguments; a JSON request body is passed as `body`.
+
+use crate::context::RequestContext;
+use crate::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use crate::utils::{Error, Logger, Result, deadline};
+use async_trait::async_trait;
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Maximum depth followed when resolving `$ref` chains
+const MAX_REF_DEPTH: usize = 16;
+
+const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+/// A secret given inline or read from an environment variable
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Secret {
+    Literal(String),
+    Env { env: String },
+}
+
+impl Secret {
+    pub fn resolve(&self) -> Result<String> {
+        match self {
+            Secret::Literal(value) => Ok(value.clone()),
+            Secret::Env { env } => std::env::var(env)
+                .map_err(|_| Error::ValidationError(format!("Environment variable {} is not set", env))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyLocation {
+    #[default]
+    Header,
+    Query,
+}
+
+/// Credentials attached to every outgoing request
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuthConfig {
+    #[default]
+    None,
+    Bearer {
+        token: Secret,
+    },
+    ApiKey {
+        name: String,
+        value: Secret,
+        #[serde(default)]
+        location: ApiKeyLocation,
+    },
+    Basic {
+        username: String,
+        password: Option<Secret>,
+    },
+}
+
+impl AuthConfig {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref())?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn apply(&self, request: reqwest::RequestBuilder) -> Result<reqwest::RequestBuilder> {
+        Ok(match self {
+            AuthConfig::None => request,
+            AuthConfig::Bearer { token } => request.bearer_auth(token.resolve()?),
+            AuthConfig::ApiKey { name, value, location: ApiKeyLocation::Header } => {
+                request.header(name.as_str(), value.resolve()?)
+            }
+            AuthConfig::ApiKey { name, value, location: ApiKeyLocation::Query } => {
+                request.query(&[(name.as_str(), value.resolve()?)])
+            }
+            AuthConfig::Basic { username, password } => {
+                let password = password.as_ref().map(Secret::resolve).transpose()?;
+                request.basic_auth(username, password)
+            }
+        })
+    }
+}
+
+/// A parsed OpenAPI document
+pub struct OpenApiSpec {
+    document: Value,
+}
+
+impl OpenApiSpec {
+    /// Loads a JSON or YAML document
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref())?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let document: Value = match serde_json::from_str(content) {
+            Ok(document) => document,
+            Err(_) => serde_yaml::from_str(content)
+                .map_err(|e| Error::ParseError(format!("Invalid OpenAPI document: {}", e)))?,
+        };
+        if !document.get("openapi").is_some_and(Value::is_string) {
+            return Err(Error::ParseError(
+                "Not an OpenAPI 3 document (missing 'openapi' version)".to_string(),
+            ));
+        }
+        Ok(Self { document })
+    }
+
+    /// The first `servers` entry, if any
+    pub fn base_url(&self) -> Option<&str> {
+        self.document.pointer("/servers/0/url").and_then(Value::as_str)
+    }
+
+    /// Builds one tool per operation, calling `base_url` with `auth`
+    pub fn tools(&self, base_url: &str, auth: AuthConfig) -> Result<Vec<OpenApiTool>> {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| Error::InternalError(format!("HTTP client: {}", e)))?;
+        let context = Arc::new(CallContext {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            auth,
+        });
+
+        let mut tools = Vec::new();
+        let Some(paths) = self.document.get("paths").and_then(Value::as_object) else {
+            return Ok(tools);
+        };
+
+        for (path, item) in paths {
+            let item = self.resolve(item);
+            let shared_params = item.get("parameters").and_then(Value::as_array);
+
+            for method in METHODS {
+                let Some(operation) = item.get(method) else {
+                    continue;
+                };
+                let operation = self.resolve(operation);
+                tools.push(self.operation_tool(path, method, operation, shared_params, &context)?);
+            }
+        }
+
+        Ok(tools)
+    }
+
+    fn operation_tool(
+        &self,
+        path: &str,
+        method: &str,
+        operation: &Value,
+        shared_params: Option<&Vec<Value>>,
+        context: &Arc<CallContext>,
+    ) -> Result<OpenApiTool> {
+        let name = operation
+            .get("operationId")
+            .and_then(Value::as_str)
+            .map(sanitize_name)
+            .unwrap_or_else(|| sanitize_name(&format!("{}_{}", method, path)));
+        let description = operation
+            .get("summary")
+            .or_else(|| operation.get("description"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        let mut parameters = Vec::new();
+
+        // Operation-level parameters override path-level ones of the same name
+        let operation_params = operation.get("parameters").and_then(Value::as_array);
+        let all_params = shared_params
+            .into_iter()
+            .flatten()
+            .chain(operation_params.into_iter().flatten());
+        for param in all_params {
+            let param = self.resolve(param);
+            let (Some(param_name), Some(location)) = (
+                param.get("name").and_then(Value::as_str),
+                param.get("in").and_then(Value::as_str).and_then(ParamLocation::parse),
+            ) else {
+                continue;
+            };
+
+            let mut schema = param
+                .get("schema")
+                .map(|s| self.inline_refs(s, 0))
+                .unwrap_or(json!({ "type": "string" }));
+            if let Some(description) = param.get("description") {
+                schema["description"] = description.clone();
+            }
+            properties.insert(param_name.to_string(), schema);
+
+            let is_required = location == ParamLocation::Path
+                || param.get("required").and_then(Value::as_bool).unwrap_or(false);
+            parameters.retain(|p: &Parameter| p.name != param_name);
+            required.retain(|r| r != param_name);
+            if is_required {
+                required.push(param_name.to_string());
+            }
+            parameters.push(Parameter {
+                name: param_name.to_string(),
+                location,
+            });
+        }
+
+        let body_schema = operation
+            .get("requestBody")
+            .map(|body| self.resolve(body))
+            .and_then(|body| {
+                let schema = body.pointer("/content/application~1json/schema")?;
+                Some((self.inline_refs(schema, 0), body.get("required").and_then(Value::as_bool)))
+            });
+        let has_body = body_schema.is_some();
+        if let Some((schema, body_required)) = body_schema {
+            properties.insert("body".to_string(), schema);
+            if body_required.unwrap_or(false) {
+                required.push("body".to_string());
+            }
+        }
+
+        Ok(OpenApiTool {
+            definition: Tool {
+                name,
+                description,
+                input_schema: json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required
+                }),
+                annotations: Some(json!({
+                    "readOnlyHint": method == "get"
+                })),
+                output_schema: None,
+            },
+            method: method.to_ascii_uppercase().parse::<Method>().map_err(|e| Error::InternalError(e.to_string()))?,
+            path: path.to_string(),
+            parameters,
+            has_body,
+            context: context.clone(),
+            logger: Logger::new("OpenApiTool"),
+        })
+    }
+
+    /// Follows a local `$ref` (`#/...`) to its target
+    fn resolve<'a>(&'a self, mut value: &'a Value) -> &'a Value {
+        for _ in 0..MAX_REF_DEPTH {
+            let Some(target) = value
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|r| r.strip_prefix('#'))
+                .and_then(|pointer| self.document.pointer(pointer))
+            else {
+                break;
+            };
+            value = target;
+        }
+        value
+    }
+
+    /// Copies `schema` with local `$ref`s replaced by their targets, so the
+    /// tool's input schema is self-contained
+    fn inline_refs(&self, schema: &Value, depth: usize) -> Value {
+        if depth > MAX_REF_DEPTH {
+            // Recursive schemas are cut off rather than expanded forever
+            return json!({});
+        }
+        match self.resolve(schema) {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.inline_refs(v, depth + 1)))
+                    .collect(),
+            ),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|v| self.inline_refs(v, depth + 1)).collect())
+            }
+            other => other.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ParamLocation {
+    Path,
+    Query,
+    Header,
+}
+
+impl ParamLocation {
+    fn parse(location: &str) -> Option<Self> {
+        match location {
+            "path" => Some(Self::Path),
+            "query" => Some(Self::Query),
+            "header" => Some(Self::Header),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Parameter {
+    name: String,
+    location: ParamLocation,
+}
+
+struct CallContext {
+    client: reqwest::Client,
+    base_url: String,
+    auth: AuthConfig,
+}
+
+/// A tool that performs one OpenAPI operation
+pub struct OpenApiTool {
+    definition: Tool,
+    method: Method,
+    path: String,
+    parameters: Vec<Parameter>,
+    has_body: bool,
+    context: Arc<CallContext>,
+    logger: Logger,
+}
+
+impl OpenApiTool {
+    pub fn name(&self) -> &str {
+        &self.definition.name
+    }
+}
+
+#[async_trait]
+impl ToolHandler for OpenApiTool {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        let mut path = self.path.clone();
+        let mut query = Vec::new();
+        let mut headers = Vec::new();
+
+        for param in &self.parameters {
+            let Some(value) = arguments.get(&param.name).filter(|v| !v.is_null()) else {
+                if param.location == ParamLocation::Path {
+                    return Err(Error::InvalidParams(format!(
+                        "Missing path parameter: {}",
+                        param.name
+                    )));
+                }
+                continue;
+            };
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            match param.location {
+                ParamLocation::Path => {
+                    path = path.replace(&format!("{{{}}}", param.name), &encode_path_segment(&value));
+                }
+                ParamLocation::Query => query.push((param.name.as_str(), value)),
+                ParamLocation::Header => headers.push((param.name.as_str(), value)),
+            }
+        }
+
+        let url = format!("{}{}", self.context.base_url, path);
+        self.logger.debug(&format!("{} {}", self.method, url));
+
+        let mut request = self.context.client.request(self.method.clone(), &url).query(&query);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        if self.has_body
+            && let Some(body) = arguments.get("body")
+        {
+            request = request.json(body);
+        }
+        let mut request = self.context.auth.apply(request)?;
+        // Don't outlive the caller's deadline
+        if let Some(remaining) = deadline::remaining() {
+            if remaining.is_zero() {
+                return Err(Error::ToolError(format!("Deadline passed before calling {}", url)));
+            }
+            request = request.timeout(remaining);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            Error::ToolError(format!("{} {} failed: {}", self.method, url, e))
+        })?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| Error::ToolError(format!("Reading response from {}: {}", url, e)))?;
+
+        if status.is_success() {
+            Ok(CallToolResult::success(vec![TextContent::new(text)]))
+        } else {
+            Ok(CallToolResult::error(format!("HTTP {}: {}", status, text)))
+        }
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(self.definition.clone())
+    }
+}
+
+/// Maps a name onto the characters MCP tool names allow
+fn sanitize_name(name: &str) -> String {
+    let sanitized: String = name
+        .trim_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+        .collect();
+    sanitized.chars().take(64).collect()
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters
+fn encode_path_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
@@ -0,0 +1,293 @@
+//! Script plugins loaded from a directory and reloaded while the server runs
+//!
+//! Each `*.json` file in the plugin directory describes one tool backed by an
+//! executable:
+//!
+//! ```json
+//! {
+//!   "name": "word-count",
+//!   "description": "Count the words in a text",
+//!   "inputSchema": { "type": "object", "properties": { "text": { "type": "string" } } },
+//!   "command": "./word-count.sh"
+//! }
+//! ```
+//!
+//! The command runs once per call with the tool arguments as JSON on stdin.
+//! Its stdout becomes the text result; a non-zero exit becomes an error
+//! result carrying stderr. Commands with a path component (`./tool.sh`)
+//! are resolved against the plugin directory; bare names are looked up on
+//! `PATH`.
+//!
+//! [`PluginWatcher`] polls the directory, re-registers plugins whose manifest
+//! or script changed and unregisters removed ones; the server then sends
+//! `notifications/tools/list_changed` so clients refetch the tool list
+//! without reconnecting.
+
+use crate::context::RequestContext;
+use crate::McpServer;
+use crate::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use crate::utils::{Error, Logger, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::task::JoinHandle;
+
+/// How long a script may run before the call fails
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often [`PluginWatcher::spawn`] rescans the directory
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A tool implemented by an external command
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptToolConfig {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_input_schema")]
+    pub input_schema: Value,
+    pub command: PathBuf,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+fn default_input_schema() -> Value {
+    json!({ "type": "object" })
+}
+
+/// [`ToolHandler`] running a [`ScriptToolConfig`]'s command per call
+pub struct ScriptTool {
+    config: ScriptToolConfig,
+    command: PathBuf,
+    logger: Logger,
+}
+
+impl ScriptTool {
+    /// A tool whose relative command is resolved against `base_dir`
+    pub fn new(config: ScriptToolConfig, base_dir: &Path) -> Self {
+        let command = if config.command.components().count() > 1 && config.command.is_relative() {
+            base_dir.join(&config.command)
+        } else {
+            config.command.clone()
+        };
+        Self {
+            config,
+            command,
+            logger: Logger::new("ScriptTool"),
+        }
+    }
+
+    /// Loads a plugin manifest
+    pub async fn load(manifest: &Path) -> Result<Self> {
+        let config: ScriptToolConfig = serde_json::from_slice(&tokio::fs::read(manifest).await?)?;
+        let base_dir = manifest.parent().unwrap_or(Path::new("."));
+        Ok(Self::new(config, base_dir))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// The resolved command, watched for changes alongside the manifest
+    pub fn command(&self) -> &Path {
+        &self.command
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ScriptTool {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        self.logger.debug(&format!("Running {}", self.command.display()));
+
+        let mut child = Command::new(&self.command)
+            .args(&self.config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                Error::ToolError(format!("Failed to start {}: {}", self.command.display(), e))
+            })?;
+
+        // Scripts that ignore their arguments may exit before reading them
+        if let Some(mut stdin) = child.stdin.take()
+            && let Err(e) = stdin.write_all(&serde_json::to_vec(&arguments)?).await
+            && e.kind() != std::io::ErrorKind::BrokenPipe
+        {
+            return Err(e.into());
+        }
+
+        let timeout = self
+            .config
+            .timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TIMEOUT);
+        let output = tokio::time::timeout(timeout, child.wait_with_output())
+            .await
+            .map_err(|_| {
+                Error::ToolError(format!("{} timed out after {:?}", self.config.name, timeout))
+            })??;
+
+        if output.status.success() {
+            let text = String::from_utf8_lossy(&output.stdout).into_owned();
+            Ok(CallToolResult::success(vec![TextContent::new(text)]))
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Ok(CallToolResult::error(format!(
+                "{} failed ({}): {}",
+                self.config.name,
+                output.status,
+                stderr.trim()
+            )))
+        }
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: self.config.name.clone(),
+            description: self.config.description.clone(),
+            input_schema: self.config.input_schema.clone(),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+/// Modification stamps of the files a plugin is built from
+type Stamp = Vec<(PathBuf, Option<SystemTime>, u64)>;
+
+struct LoadedPlugin {
+    stamp: Stamp,
+    /// `None` when the manifest failed to load
+    name: Option<String>,
+}
+
+/// Keeps the tools in a plugin directory registered on a server
+pub struct PluginWatcher {
+    dir: PathBuf,
+    interval: Duration,
+    loaded: HashMap<PathBuf, LoadedPlugin>,
+    logger: Logger,
+}
+
+impl PluginWatcher {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            interval: DEFAULT_POLL_INTERVAL,
+            loaded: HashMap::new(),
+            logger: Logger::new("PluginWatcher"),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Rescans the directory and applies what changed since the last scan
+    ///
+    /// Returns whether the server's tool set changed, in which case the
+    /// server has sent `notifications/tools/list_changed`.
+    pub async fn reload(&mut self, server: &McpServer) -> Result<bool> {
+        let mut manifests = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                manifests.push(path);
+            }
+        }
+
+        let mut changed = false;
+
+        let removed: Vec<PathBuf> = self
+            .loaded
+            .keys()
+            .filter(|path| !manifests.contains(path))
+            .cloned()
+            .collect();
+        for path in removed {
+            if let Some(LoadedPlugin { name: Some(name), .. }) = self.loaded.remove(&path) {
+                self.logger.info(&format!("Plugin removed: {}", name));
+                changed |= server.unregister_tool(&name).await;
+            }
+        }
+
+        for path in manifests {
+            let previous = self.loaded.get(&path);
+            let command = previous
+                .and_then(|p| p.stamp.get(1))
+                .map(|(command, _, _)| command.clone());
+            if let Some(previous) = previous
+                && previous.stamp == stamp(&path, command.as_deref()).await
+            {
+                continue;
+            }
+
+            let old_name = previous.and_then(|p| p.name.clone());
+            let (name, stamp) = match ScriptTool::load(&path).await {
+                Ok(tool) => {
+                    let name = tool.name().to_string();
+                    let stamp = stamp(&path, Some(tool.command())).await;
+                    self.logger.info(&format!("Loading plugin: {}", name));
+                    server.register_tool(name.clone(), Arc::new(tool)).await?;
+                    changed = true;
+                    (Some(name), stamp)
+                }
+                Err(e) => {
+                    self.logger
+                        .warn(&format!("Skipping plugin {}: {}", path.display(), e));
+                    (None, stamp(&path, None).await)
+                }
+            };
+            if let Some(old_name) = old_name.filter(|old| Some(old) != name.as_ref()) {
+                changed |= server.unregister_tool(&old_name).await;
+            }
+            self.loaded.insert(path, LoadedPlugin { stamp, name });
+        }
+
+        Ok(changed)
+    }
+
+    /// Loads the directory once, then keeps reloading it in the background
+    ///
+    /// Scan errors after the first load are logged and retried on the next
+    /// tick, so a half-written plugin never stops the watcher.
+    pub async fn spawn(mut self, server: Arc<McpServer>) -> Result<JoinHandle<()>> {
+        server.enable_tools_list_changed();
+        self.reload(&server).await?;
+        let clock = server.clock();
+        Ok(tokio::spawn(async move {
+            loop {
+                clock.sleep(self.interval).await;
+                if let Err(e) = self.reload(&server).await {
+                    self.logger.warn(&format!("Plugin reload failed: {}", e));
+                }
+            }
+        }))
+    }
+}
+
+async fn stamp(manifest: &Path, command: Option<&Path>) -> Stamp {
+    let mut stamp = Vec::new();
+    for path in std::iter::once(manifest).chain(command) {
+        let (modified, len) = match tokio::fs::metadata(path).await {
+            Ok(meta) => (meta.modified().ok(), meta.len()),
+            Err(_) => (None, 0),
+        };
+        stamp.push((path.to_path_buf(), modified, len));
+    }
+    stamp
+}
@@ -0,0 +1,72 @@
+//! Progress of long-running requests
+//!
+//! A client that wants to follow a request sends `_meta.progressToken` with
+//! it. While that request is handled, [`ProgressReporter::current`] returns
+//! a reporter sending `notifications/progress` for that token:
+//!
+//! ```ignore
+//! if let Some(progress) = ProgressReporter::current() {
+//!     progress.report(indexed as f64, Some(total as f64), Some("Indexing files"));
+//! }
+//! ```
+
+use crate::notifications::{self, NotificationSender};
+use crate::utils::meta;
+use serde_json::{Map, Value, json};
+use std::sync::{Arc, Mutex};
+
+/// Sends `notifications/progress` for one request's `progressToken`
+#[derive(Clone)]
+pub struct ProgressReporter {
+    token: Value,
+    sender: NotificationSender,
+    // Progress must increase with each notification
+    last: Arc<Mutex<Option<f64>>>,
+}
+
+impl ProgressReporter {
+    /// Reporter for the request being handled; `None` outside a request or
+    /// when the client did not ask for progress
+    pub fn current() -> Option<Self> {
+        let token = meta::current()?.progress_token?;
+        Some(Self::new(token, notifications::current()?))
+    }
+
+    pub fn new(token: Value, sender: NotificationSender) -> Self {
+        Self {
+            token,
+            sender,
+            last: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// The client's `progressToken`
+    pub fn token(&self) -> &Value {
+        &self.token
+    }
+
+    /// Reports `progress` out of `total`, if known; returns whether the
+    /// notification was sent
+    ///
+    /// Progress that doesn't exceed what was last reported is dropped, as
+    /// clients may rely on it increasing.
+    pub fn report(&self, progress: f64, total: Option<f64>, message: Option<&str>) -> bool {
+        {
+            let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+            if last.is_some_and(|last| progress <= last) {
+                return false;
+            }
+            *last = Some(progress);
+        }
+        let mut params = Map::new();
+        params.insert("progressToken".to_string(), self.token.clone());
+        params.insert("progress".to_string(), json!(progress));
+        if let Some(total) = total {
+            params.insert("total".to_string(), json!(total));
+        }
+        if let Some(message) = message {
+            params.insert("message".to_string(), json!(message));
+        }
+        self.sender.notify("notifications/progress", Value::Object(params))
+    }
+}
\ No newline at end of file
@@ -1,5 +1,8 @@
-use super::{GetPromptResult, Message, MessageContent, Prompt, PromptArgument, PromptHandler};
+use super::{Content, GetPromptResult, Message, Prompt, PromptArgument, PromptHandler};
+use crate::context::RequestContext;
+use crate::completion::{Completable, Completion, CompletionRequest};
 use crate::utils::{Error, Logger, Result};
+use crate::utils::i18n::MessageKey;
 use async_trait::async_trait;
 use serde_json::Value;
 
@@ -42,14 +45,14 @@ impl Default for CodeReviewPrompt {
 
 #[async_trait]
 impl PromptHandler for CodeReviewPrompt {
-    async fn get(&self, arguments: Option<Value>) -> Result<GetPromptResult> {
+    async fn get(&self, _context: &RequestContext, arguments: Option<Value>) -> Result<GetPromptResult> {
         let args =
-            arguments.ok_or_else(|| Error::InvalidParams("Missing arguments".to_string()))?;
+            arguments.ok_or_else(|| Error::invalid_params(MessageKey::MissingArguments, &[]))?;
 
         let code = args
             .get("code")
             .and_then(|v| v.as_str())
-            .ok_or_else(|| Error::InvalidParams("Missing 'code' argument".to_string()))?;
+            .ok_or_else(|| Error::invalid_params(MessageKey::MissingArgument, &["code"]))?;
 
         let focus = args
             .get("focus")
@@ -71,8 +74,30 @@ impl PromptHandler for CodeReviewPrompt {
             description: Some(format!("Requesting {} review for code snippet", focus)),
             messages: vec![Message {
                 role: "user".to_string(),
-                content: vec![MessageContent::new(prompt_text)],
+                content: Content::text(prompt_text),
             }],
+            meta: None,
+        })
+    }
+
+    fn definition(&self) -> Option<Prompt> {
+        Some(Self::prompt_definition())
+    }
+
+    fn completable(&self) -> Option<&dyn Completable> {
+        Some(self)
+    }
+}
+
+/// Areas a review can focus on
+const FOCUS_AREAS: [&str; 4] = ["general", "performance", "security", "style"];
+
+#[async_trait]
+impl Completable for CodeReviewPrompt {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion> {
+        Ok(match request.argument.name.as_str() {
+            "focus" => Completion::matching(FOCUS_AREAS, &request.argument.value),
+            _ => Completion::default(),
         })
     }
 }
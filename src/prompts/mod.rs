@@ -2,28 +2,16 @@ pub mod code_review_prompt;
 
 use serde_json::Value;
 use async_trait::async_trait;
+use crate::completion::Completable;
+use crate::context::RequestContext;
 use crate::utils::Result;
 
+pub use crate::content::Content;
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Message {
     pub role: String,
-    pub content: Vec<MessageContent>,
-}
-
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct MessageContent {
-    #[serde(rename = "type")]
-    pub content_type: String,
-    pub text: String,
-}
-
-impl MessageContent {
-    pub fn new(text: impl Into<String>) -> Self {
-        Self {
-            content_type: "text".to_string(),
-            text: text.into(),
-        }
-    }
+    pub content: Content,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -31,6 +19,9 @@ pub struct GetPromptResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub messages: Vec<Message>,
+    /// Protocol and vendor metadata about the result
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -49,7 +40,19 @@ pub struct PromptArgument {
     pub required: Option<bool>,
 }
 
+/// Builds a prompt; like a [`ToolHandler`](crate::tools::ToolHandler), one
+/// registered instance serves every request and may hold state
 #[async_trait]
 pub trait PromptHandler: Send + Sync {
-    async fn get(&self, arguments: Option<Value>) -> Result<GetPromptResult>;
+    async fn get(&self, context: &RequestContext, arguments: Option<Value>) -> Result<GetPromptResult>;
+
+    /// Definition advertised in `prompts/list` when the handler is registered
+    fn definition(&self) -> Option<Prompt> {
+        None
+    }
+
+    /// Suggests argument values for `completion/complete`
+    fn completable(&self) -> Option<&dyn Completable> {
+        None
+    }
 }
\ No newline at end of file
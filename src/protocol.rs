@@ -0,0 +1,240 @@
+//! JSON-RPC 2.0 envelope types, and in [`messages`] the MCP methods' params
+//! and results
+
+pub mod messages;
+
+pub use messages::{
+    CallToolParams, CancelledParams, FromParams, GetPromptParams, Implementation, InitializeParams,
+    InitializeResult, PaginatedParams, ReadResourceParams, SearchToolsParams, SetLevelParams,
+    SubscribeParams,
+};
+
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use serde_json::value::RawValue;
+use std::borrow::Cow;
+
+/// The JSON-RPC fields of an incoming message, parsed in a single pass
+///
+/// Fields are lenient so that a structurally invalid request still yields
+/// its `id` for the error reply; call [`RequestEnvelope::validate`] before
+/// dispatching. `P` is the params representation: `&RawValue` when parsing
+/// from a raw frame (params are left unparsed until a handler needs them),
+/// or `Value` when converting an already-parsed message.
+#[derive(Debug, Deserialize)]
+pub struct RequestEnvelope<'a, P> {
+    #[serde(borrow)]
+    pub jsonrpc: Option<Cow<'a, str>>,
+    /// `None` for notifications; `Some(Value::Null)` for an explicit null id
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub id: Option<Value>,
+    #[serde(borrow)]
+    pub method: Option<Cow<'a, str>>,
+    pub params: Option<P>,
+}
+
+/// Envelope borrowed from a raw frame, with unparsed params
+pub type RawRequest<'a> = RequestEnvelope<'a, &'a RawValue>;
+
+/// Envelope taken apart from an owned `Value`
+pub type ValueRequest = RequestEnvelope<'static, Value>;
+
+impl<'a> RawRequest<'a> {
+    /// Parses a frame; malformed JSON fails with `Error::Json`, well-formed
+    /// JSON that is not a request object with `Error::InvalidRequest`
+    pub fn parse(raw: &'a str) -> Result<Self> {
+        serde_json::from_str(raw).map_err(|e| {
+            if e.is_data() {
+                Error::InvalidRequest(e.to_string())
+            } else {
+                Error::Json(e)
+            }
+        })
+    }
+
+    /// Parses the params into an owned value for the handler
+    pub fn params_value(&self) -> Result<Option<Value>> {
+        self.params
+            .map(|raw| serde_json::from_str(raw.get()))
+            .transpose()
+            .map_err(Error::from)
+    }
+}
+
+impl ValueRequest {
+    /// Moves the envelope fields out of an already-parsed message
+    pub fn from_value(message: Value) -> Result<Self> {
+        Self::deserialize(message).map_err(|e| Error::InvalidRequest(e.to_string()))
+    }
+}
+
+impl<P: Params> RequestEnvelope<'_, P> {
+    /// Checks the envelope against JSON-RPC 2.0 and returns the method name
+    pub fn validate(&self) -> Result<&str> {
+        match self.jsonrpc.as_deref() {
+            Some("2.0") => {}
+            Some(_) => return Err(Error::InvalidRequest("Invalid jsonrpc version".to_string())),
+            None => return Err(Error::InvalidRequest("Missing jsonrpc field".to_string())),
+        }
+
+        if let Some(id) = &self.id
+            && !(id.is_string() || id.is_number() || id.is_null())
+        {
+            return Err(Error::InvalidRequest(
+                "id must be a string, number or null".to_string(),
+            ));
+        }
+
+        if let Some(params) = &self.params
+            && !params.is_structured()
+        {
+            return Err(Error::InvalidRequest(
+                "params must be an object or array".to_string(),
+            ));
+        }
+
+        self.method
+            .as_deref()
+            .ok_or_else(|| Error::InvalidRequest("Missing method".to_string()))
+    }
+
+    /// Whether the message is a notification (carries no id)
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+/// An outgoing JSON-RPC response
+///
+/// The result is kept as pre-serialized JSON so a large payload goes from the
+/// handler's typed result to the transport's write buffer without an
+/// intermediate `Value` tree.
+#[derive(Debug, Serialize)]
+pub struct Response {
+    pub jsonrpc: &'static str,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Box<RawValue>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<Value>,
+}
+
+impl Response {
+    pub fn success(id: Value, result: Box<RawValue>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn error(id: Value, error: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+
+    /// Answers a message that isn't valid JSON with -32700; its id is
+    /// unknowable, so it's null
+    pub fn parse_error(e: &dyn std::fmt::Display) -> Self {
+        Self::error(
+            Value::Null,
+            serde_json::json!({ "code": -32700, "message": format!("Parse error: {}", e) }),
+        )
+    }
+
+    /// Answers a frame whose handling failed outside the request handler,
+    /// if the frame carries an id to answer
+    pub fn for_failed_frame(raw: &str, e: &Error) -> Option<Self> {
+        let id = RawRequest::parse(raw).ok()?.id?;
+        Some(Self::error(
+            id,
+            serde_json::json!({ "code": e.code(), "message": e.to_string() }),
+        ))
+    }
+
+    /// Converts the response into a `Value`, parsing the raw result
+    pub fn into_value(self) -> Result<Value> {
+        Ok(serde_json::to_value(&self)?)
+    }
+}
+
+/// What a frame is answered with: one response, or the responses to a
+/// JSON-RPC batch in one array
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Reply {
+    Single(Response),
+    Batch(Vec<Response>),
+}
+
+impl Reply {
+    /// The response to a message that wasn't batched
+    pub fn single(&self) -> Option<&Response> {
+        match self {
+            Reply::Single(response) => Some(response),
+            Reply::Batch(_) => None,
+        }
+    }
+
+    /// Converts the reply into a `Value`: an object, or an array for a batch
+    pub fn into_value(self) -> Result<Value> {
+        Ok(serde_json::to_value(&self)?)
+    }
+}
+
+/// Whether a frame is a JSON-RPC batch, i.e. an array
+pub fn is_batch(raw: &str) -> bool {
+    raw.trim_start().starts_with('[')
+}
+
+/// Extracts the `result` of a response, or its JSON-RPC error as `Error::Rpc`
+pub fn response_result(mut response: Value) -> Result<Value> {
+    if let Some(error) = response.get_mut("error") {
+        return Err(Error::Rpc {
+            code: error.get("code").and_then(Value::as_i64).unwrap_or(-32603),
+            message: error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            data: error.get_mut("data").map(Value::take),
+        });
+    }
+
+    response
+        .get_mut("result")
+        .map(Value::take)
+        .ok_or_else(|| Error::InternalError("Response has neither result nor error".to_string()))
+}
+
+/// Params representations accepted by [`RequestEnvelope`]
+pub trait Params {
+    /// Whether the params are an object or array, as JSON-RPC requires
+    fn is_structured(&self) -> bool;
+}
+
+impl Params for Value {
+    fn is_structured(&self) -> bool {
+        self.is_object() || self.is_array()
+    }
+}
+
+impl Params for &RawValue {
+    fn is_structured(&self) -> bool {
+        matches!(self.get().trim_start().as_bytes().first(), Some(b'{' | b'['))
+    }
+}
+
+/// Maps a present field to `Some`, even when its value is `null`
+fn deserialize_present<'de, D>(deserializer: D) -> std::result::Result<Option<Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Value::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,200 @@
+//! Params and results of the MCP methods
+//!
+//! The server parses each request's params into one of these before
+//! dispatching it, and builds its results from them, so a malformed request
+//! is refused with `-32602` naming the field at fault. The types serialize
+//! to the wire format, which lets a client build its requests from them too.
+
+use crate::logging::LogLevel;
+use crate::resources::ByteRange;
+use crate::server::Icon;
+use crate::utils::i18n::MessageKey;
+use crate::utils::{Error, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Params of one method, read from a request's `params`
+pub trait FromParams: DeserializeOwned {
+    /// Required fields, with the message a request lacking one is refused
+    /// with; other problems are reported as serde describes them
+    const REQUIRED: &'static [(&'static str, MessageKey)] = &[];
+
+    /// Parses params the method can't do without
+    fn from_params(params: Option<Value>) -> Result<Self> {
+        let params = params.ok_or_else(|| Error::invalid_params(MessageKey::MissingParams, &[]))?;
+        for (field, key) in Self::REQUIRED {
+            if params.get(field).is_none_or(Value::is_null) {
+                return Err(Error::invalid_params(*key, &[]));
+            }
+        }
+        serde_json::from_value(params).map_err(|e| Error::InvalidParams(e.to_string()))
+    }
+
+    /// Parses params the method may be sent without
+    fn from_optional_params(params: Option<Value>) -> Result<Self>
+    where
+        Self: Default,
+    {
+        match params {
+            Some(params) => Self::from_params(Some(params)),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// Name and version of a client or server, as in `clientInfo` and
+/// `serverInfo`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Implementation {
+    pub name: String,
+    /// Empty when a client leaves it out, which some do
+    #[serde(default)]
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub icons: Vec<Icon>,
+}
+
+impl Implementation {
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Params of `initialize`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeParams {
+    /// Latest revision the client speaks; the server's latest when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    #[serde(default = "empty_object")]
+    pub capabilities: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_info: Option<Implementation>,
+    /// Language for error messages, e.g. `es-MX`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+impl Default for InitializeParams {
+    fn default() -> Self {
+        Self {
+            protocol_version: None,
+            capabilities: empty_object(),
+            client_info: None,
+            locale: None,
+        }
+    }
+}
+
+impl FromParams for InitializeParams {}
+
+/// Result of `initialize`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeResult {
+    pub protocol_version: String,
+    pub capabilities: Map<String, Value>,
+    pub server_info: Implementation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+}
+
+/// Params of the `*/list` methods
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PaginatedParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+impl FromParams for PaginatedParams {}
+
+/// Params of `tools/call`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CallToolParams {
+    pub name: String,
+    #[serde(default = "empty_object")]
+    pub arguments: Value,
+}
+
+impl FromParams for CallToolParams {
+    const REQUIRED: &'static [(&'static str, MessageKey)] = &[("name", MessageKey::MissingToolName)];
+}
+
+/// Params of `x-tools/search`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SearchToolsParams {
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+impl FromParams for SearchToolsParams {}
+
+/// Params of `resources/read`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReadResourceParams {
+    pub uri: String,
+    /// Window of the resource's bytes to read, for handlers that support it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<ByteRange>,
+}
+
+impl FromParams for ReadResourceParams {
+    const REQUIRED: &'static [(&'static str, MessageKey)] = &[("uri", MessageKey::MissingResourceUri)];
+}
+
+/// Params of `resources/subscribe` and `resources/unsubscribe`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SubscribeParams {
+    pub uri: String,
+}
+
+impl FromParams for SubscribeParams {
+    const REQUIRED: &'static [(&'static str, MessageKey)] = &[("uri", MessageKey::MissingResourceUri)];
+}
+
+/// Params of `prompts/get`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetPromptParams {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Value>,
+}
+
+impl FromParams for GetPromptParams {
+    const REQUIRED: &'static [(&'static str, MessageKey)] = &[("name", MessageKey::MissingPromptName)];
+}
+
+/// Params of `logging/setLevel`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetLevelParams {
+    pub level: LogLevel,
+}
+
+impl FromParams for SetLevelParams {}
+
+/// Params of `notifications/cancelled`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelledParams {
+    pub request_id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl FromParams for CancelledParams {}
+
+fn empty_object() -> Value {
+    Value::Object(Map::new())
+}
@@ -0,0 +1,225 @@
+//! Tools and prompts written in Python
+//!
+//! [`load_script`] runs a Python file in an embedded interpreter and
+//! registers the handlers it declares with the `mcp` module's decorators:
+//!
+//! ```python
+//! import mcp
+//!
+//! @mcp.tool("word-count", "Count the words in a text",
+//!           {"type": "object", "properties": {"text": {"type": "string"}}})
+//! def word_count(args):
+//!     return str(len(args["text"].split()))
+//!
+//! @mcp.prompt("haiku", "Ask for a haiku",
+//!             [{"name": "topic", "description": "What to write about", "required": True}])
+//! def haiku(args):
+//!     return f"Write a haiku about {args['topic']}."
+//! ```
+//!
+//! Handlers receive the arguments as a dict. A tool returns a string (its
+//! text output) or a dict shaped like a `CallToolResult`; a prompt returns a
+//! string (a single user message) or a dict shaped like a
+//! `GetPromptResult`. An exception in a tool becomes an error result.
+//! Handlers run on tokio's blocking pool while holding the GIL.
+
+use crate::McpServer;
+use crate::context::RequestContext;
+use crate::prompts::{Content, GetPromptResult, Message, Prompt, PromptArgument, PromptHandler};
+use crate::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use crate::utils::{Error, Logger, Result};
+use async_trait::async_trait;
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use serde_json::Value;
+use std::ffi::CString;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Source of the `mcp` module scripts import
+const MCP_MODULE: &std::ffi::CStr = cr#"
+_tools = []
+_prompts = []
+
+def tool(name, description="", input_schema=None):
+    def register(fn):
+        _tools.append((name, description, input_schema or {"type": "object"}, fn))
+        return fn
+    return register
+
+def prompt(name, description="", arguments=None):
+    def register(fn):
+        _prompts.append((name, description, arguments or [], fn))
+        return fn
+    return register
+"#;
+
+/// Runs the script at `path` and registers the tools and prompts it
+/// declares; returns how many handlers were registered
+pub async fn load_script(server: &McpServer, path: impl AsRef<Path>) -> Result<usize> {
+    let path = path.as_ref();
+    let code = CString::new(std::fs::read_to_string(path)?)
+        .map_err(|e| Error::ParseError(format!("{}: {}", path.display(), e)))?;
+    let file_name =
+        CString::new(path.display().to_string()).map_err(|e| Error::ParseError(e.to_string()))?;
+
+    let (tools, prompts) = Python::with_gil(|py| -> PyResult<_> {
+        let module = PyModule::from_code(py, MCP_MODULE, c"mcp.py", c"mcp")?;
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("mcp", &module)?;
+        PyModule::from_code(py, &code, &file_name, c"__mcp_script__")?;
+
+        let mut tools = Vec::new();
+        for entry in module.getattr("_tools")?.try_iter()? {
+            let entry = entry?;
+            let entry = entry.downcast::<PyTuple>()?;
+            tools.push(PythonTool {
+                tool: Tool {
+                    name: entry.get_item(0)?.extract()?,
+                    description: entry.get_item(1)?.extract()?,
+                    input_schema: to_json(&entry.get_item(2)?)?,
+                    annotations: None,
+                    output_schema: None,
+                },
+                function: entry.get_item(3)?.unbind(),
+            });
+        }
+        let mut prompts = Vec::new();
+        for entry in module.getattr("_prompts")?.try_iter()? {
+            let entry = entry?;
+            let entry = entry.downcast::<PyTuple>()?;
+            prompts.push(PythonPrompt {
+                name: entry.get_item(0)?.extract()?,
+                description: entry.get_item(1)?.extract()?,
+                arguments: serde_json::from_value(to_json(&entry.get_item(2)?)?).map_err(|e| {
+                    pyo3::exceptions::PyValueError::new_err(format!(
+                        "Invalid prompt arguments: {}",
+                        e
+                    ))
+                })?,
+                function: entry.get_item(3)?.unbind(),
+            });
+        }
+        Ok((tools, prompts))
+    })
+    .map_err(|e| python_error(path, e))?;
+
+    let logger = Logger::new("Python");
+    let count = tools.len() + prompts.len();
+    for tool in tools {
+        logger.info(&format!("Loaded Python tool: {}", tool.tool.name));
+        server
+            .register_tool(tool.tool.name.clone(), Arc::new(tool))
+            .await?;
+    }
+    for prompt in prompts {
+        logger.info(&format!("Loaded Python prompt: {}", prompt.name));
+        server
+            .register_prompt(prompt.name.clone(), Arc::new(prompt))
+            .await?;
+    }
+    Ok(count)
+}
+
+fn python_error(path: &Path, e: PyErr) -> Error {
+    Error::InternalError(format!("{}: {}", path.display(), e))
+}
+
+fn to_json(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let json: String = value
+        .py()
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&json).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+}
+
+fn from_json<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    py.import("json")?
+        .call_method1("loads", (value.to_string(),))
+}
+
+/// Calls `function` with `arguments` on the blocking pool
+async fn call(function: &Py<PyAny>, arguments: Value) -> Result<PyResult<Value>> {
+    let function = Python::with_gil(|py| function.clone_ref(py));
+    tokio::task::spawn_blocking(move || {
+        Python::with_gil(|py| {
+            let arguments = from_json(py, &arguments)?;
+            to_json(&function.call1(py, (arguments,))?.into_bound(py))
+        })
+    })
+    .await
+    .map_err(|e| Error::AsyncError(e.to_string()))
+}
+
+struct PythonTool {
+    tool: Tool,
+    function: Py<PyAny>,
+}
+
+#[async_trait]
+impl ToolHandler for PythonTool {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        match call(&self.function, arguments).await? {
+            Ok(Value::String(text)) => Ok(CallToolResult::success(vec![TextContent::new(text)])),
+            Ok(result) => serde_json::from_value(result).map_err(|e| {
+                Error::ToolError(format!(
+                    "{} returned an invalid result: {}",
+                    self.tool.name, e
+                ))
+            }),
+            Err(e) => Ok(CallToolResult::error(e.to_string())),
+        }
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(self.tool.clone())
+    }
+}
+
+struct PythonPrompt {
+    name: String,
+    description: String,
+    arguments: Vec<PromptArgument>,
+    function: Py<PyAny>,
+}
+
+#[async_trait]
+impl PromptHandler for PythonPrompt {
+    async fn get(&self, _context: &RequestContext, arguments: Option<Value>) -> Result<GetPromptResult> {
+        let arguments = arguments.unwrap_or_else(|| serde_json::json!({}));
+        if let Some(missing) = self
+            .arguments
+            .iter()
+            .find(|a| a.required == Some(true) && arguments.get(&a.name).is_none())
+        {
+            return Err(Error::InvalidParams(format!(
+                "Missing required argument: {}",
+                missing.name
+            )));
+        }
+        match call(&self.function, arguments).await? {
+            Ok(Value::String(text)) => Ok(GetPromptResult {
+                description: Some(self.description.clone()).filter(|d| !d.is_empty()),
+                messages: vec![Message {
+                    role: "user".to_string(),
+                    content: Content::text(text),
+                }],
+                meta: None,
+            }),
+            Ok(result) => serde_json::from_value(result).map_err(|e| {
+                Error::InternalError(format!("{} returned an invalid prompt: {}", self.name, e))
+            }),
+            Err(e) => Err(Error::InvalidParams(e.to_string())),
+        }
+    }
+
+    fn definition(&self) -> Option<Prompt> {
+        Some(Prompt {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            arguments: Some(self.arguments.clone()).filter(|a| !a.is_empty()),
+        })
+    }
+}
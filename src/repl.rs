@@ -0,0 +1,238 @@
+//! Interactive shell over a server's catalog
+//!
+//! [`Repl`] lets a developer list and call tools, read resources and render
+//! prompts from a terminal, without an MCP client. Commands go through the
+//! same JSON-RPC dispatch a client would use:
+//!
+//! ```text
+//! mcp> tools
+//! greet - Greet a person by name
+//! mcp> call greet name=Ada
+//! Hello, Ada! Welcome to MCP.
+//! mcp> prompt review-code {"code": "fn main() {}", "language": "rust"}
+//! ```
+//!
+//! Arguments are either a JSON object or `key=value` pairs, where values
+//! that parse as JSON (numbers, booleans, ...) are used as such.
+
+use crate::McpServer;
+use crate::testing::McpTestClient;
+use crate::tools::Content;
+use crate::utils::{Error, Result};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+const PROMPT: &str = "mcp> ";
+
+const HELP: &str = "\
+Commands:
+  tools                      List tools
+  resources                  List resources
+  prompts                    List prompts
+  call <tool> [arguments]    Call a tool
+  read <uri>                 Read a resource
+  prompt <name> [arguments]  Render a prompt
+  help                       Show this help
+  quit                       Leave the shell
+Arguments are a JSON object or key=value pairs.";
+
+/// Interactive shell driving a server in-process
+pub struct Repl {
+    client: McpTestClient,
+}
+
+impl Repl {
+    pub fn new(server: Arc<McpServer>) -> Self {
+        Self {
+            client: McpTestClient::from_arc(server),
+        }
+    }
+
+    /// Reads commands from stdin until `quit` or EOF
+    pub async fn run_stdio(&self) -> Result<()> {
+        let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+        self.run(stdin, tokio::io::stdout()).await
+    }
+
+    /// Reads commands from `reader` and writes results to `writer`
+    pub async fn run<R, W>(&self, reader: R, mut writer: W) -> Result<()>
+    where
+        R: AsyncBufRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        self.client.initialize().await?;
+        writer
+            .write_all(b"Type 'help' for a list of commands.\n")
+            .await?;
+
+        let mut lines = reader.lines();
+        loop {
+            writer.write_all(PROMPT.as_bytes()).await?;
+            writer.flush().await?;
+            let Some(line) = lines.next_line().await? else {
+                writer.write_all(b"\n").await?;
+                break;
+            };
+            let output = match self.execute(&line).await {
+                Ok(Some(output)) => output,
+                Ok(None) => break,
+                Err(e) => format!("error: {}", e),
+            };
+            if !output.is_empty() {
+                writer.write_all(output.as_bytes()).await?;
+                writer.write_all(b"\n").await?;
+            }
+        }
+        writer.flush().await?;
+        Ok(())
+    }
+
+    /// Runs one command line and returns what to print, or `None` to quit
+    pub async fn execute(&self, line: &str) -> Result<Option<String>> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        let output = match command {
+            "" => String::new(),
+            "help" | "?" => HELP.to_string(),
+            "quit" | "exit" => return Ok(None),
+            "tools" => self
+                .client
+                .list_tools()
+                .await?
+                .iter()
+                .map(|tool| listing(&tool.name, &tool.description))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "resources" => self
+                .client
+                .list_resources()
+                .await?
+                .iter()
+                .map(|resource| {
+                    listing(
+                        resource["uri"].as_str().unwrap_or_default(),
+                        resource["name"].as_str().unwrap_or_default(),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "prompts" => self
+                .client
+                .list_prompts()
+                .await?
+                .iter()
+                .map(|prompt| listing(&prompt.name, &prompt.description))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "call" => {
+                let (name, arguments) = name_and_arguments(rest)?;
+                let result = self.client.call_tool(name, arguments).await?;
+                let text = result
+                    .content
+                    .iter()
+                    .map(render)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                if result.is_error == Some(true) {
+                    format!("error: {}", text)
+                } else {
+                    text
+                }
+            }
+            "read" => {
+                if rest.is_empty() {
+                    return Err(Error::InvalidParams("Usage: read <uri>".to_string()));
+                }
+                self.client
+                    .read_resource(rest)
+                    .await?
+                    .contents
+                    .iter()
+                    .map(|content| match (&content.text, &content.blob) {
+                        (Some(text), _) => text.clone(),
+                        (None, Some(blob)) => {
+                            format!("<{} bytes of base64 {}>", blob.len(), content.mime_type)
+                        }
+                        (None, None) => String::new(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            "prompt" => {
+                let (name, arguments) = name_and_arguments(rest)?;
+                self.client
+                    .get_prompt(name, arguments)
+                    .await?
+                    .messages
+                    .iter()
+                    .map(|message| format!("[{}]\n{}", message.role, render(&message.content)))
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            }
+            other => {
+                return Err(Error::InvalidParams(format!(
+                    "Unknown command '{}'; type 'help' for a list",
+                    other
+                )));
+            }
+        };
+        Ok(Some(output))
+    }
+}
+
+fn listing(name: &str, description: &str) -> String {
+    if description.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} - {}", name, description)
+    }
+}
+
+/// Text as is, media as a placeholder
+fn render(content: &Content) -> String {
+    match content {
+        Content::Text(content) => content.text.clone(),
+        Content::Image(image) => {
+            format!("<{} bytes of base64 {}>", image.data.len(), image.mime_type)
+        }
+        Content::Audio(audio) => {
+            format!("<{} bytes of base64 {}>", audio.data.len(), audio.mime_type)
+        }
+        Content::Resource(embedded) => {
+            let resource = &embedded.resource;
+            match &resource.text {
+                Some(text) => format!("[{}]\n{}", resource.uri, text),
+                None => format!("<{} ({})>", resource.uri, resource.mime_type),
+            }
+        }
+        Content::ResourceLink(link) => format!("-> {} ({})", link.name, link.uri),
+    }
+}
+
+/// Splits `<name> [arguments]`
+fn name_and_arguments(input: &str) -> Result<(&str, Value)> {
+    let (name, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    if name.is_empty() {
+        return Err(Error::InvalidParams("Missing name".to_string()));
+    }
+    Ok((name, parse_arguments(rest.trim())?))
+}
+
+/// Parses a JSON object or `key=value` pairs
+pub fn parse_arguments(input: &str) -> Result<Value> {
+    if input.starts_with('{') {
+        return Ok(serde_json::from_str(input)?);
+    }
+    let mut arguments = Map::new();
+    for pair in input.split_whitespace() {
+        let (key, value) = pair.split_once('=').ok_or_else(|| {
+            Error::InvalidParams(format!("Expected key=value, got '{}'", pair))
+        })?;
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+        arguments.insert(key.to_string(), value);
+    }
+    Ok(Value::Object(arguments))
+}
@@ -1,6 +1,7 @@
-use super::{Resource, ResourceReadResult, ResourceHandler};
+use super::{Resource, ResourceDefinition, ResourceReadResult, ResourceHandler};
 use serde_json::{json};
 use async_trait::async_trait;
+use crate::context::RequestContext;
 use crate::utils::{Result, Logger};
 
 pub struct ConfigResource {
@@ -23,7 +24,7 @@ impl Default for ConfigResource {
 
 #[async_trait]
 impl ResourceHandler for ConfigResource {
-    async fn read(&self, uri: &str) -> Result<ResourceReadResult> {
+    async fn read(&self, _context: &RequestContext, uri: &str) -> Result<ResourceReadResult> {
         self.logger.debug_with_context("Reading config resource", uri);
 
         let config_data = json!({
@@ -47,6 +48,16 @@ impl ResourceHandler for ConfigResource {
                 blob: None,
                 size: None,
             }],
+            meta: None,
         })
     }
+
+    async fn list(&self) -> Result<Vec<ResourceDefinition>> {
+        Ok(vec![ResourceDefinition {
+            uri: "config://app".to_string(),
+            name: "Application Configuration".to_string(),
+            description: Some("Current application configuration".to_string()),
+            mime_type: Some("application/json".to_string()),
+        }])
+    }
 }
\ No newline at end of file
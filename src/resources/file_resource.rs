@@ -1,12 +1,32 @@
-use super::{Resource, ResourceReadResult, ResourceHandler};
+use super::{ByteRange, Resource, ResourceDefinition, ResourceReadResult, ResourceHandler};
 use async_trait::async_trait;
-use crate::utils::{Result, Error, Logger};
-use std::path::{PathBuf};
+use crate::context::RequestContext;
+use crate::utils::{Result, Error, Logger, MemoryGuard, MemoryReservation};
+use crate::utils::blob::{self, DEFAULT_MAX_BLOB_BYTES};
+use crate::roots;
+use memmap2::MmapOptions;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::fs;
 
+/// URI prefix served when none is configured
+pub const DEFAULT_URI_PREFIX: &str = "file:///data/";
+
+/// Files at least this large are memory-mapped instead of read into a buffer
+pub const DEFAULT_MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// Largest window a single ranged read returns
+pub const DEFAULT_MAX_WINDOW_BYTES: u64 = 8 * 1024 * 1024;
+
 pub struct FileResource {
     logger: Logger,
     base_dir: PathBuf,
+    uri_prefix: String,
+    max_blob_bytes: u64,
+    mmap_threshold: u64,
+    max_window_bytes: u64,
+    memory: Option<Arc<MemoryGuard>>,
+    client_roots: bool,
 }
 
 impl FileResource {
@@ -14,9 +34,76 @@ impl FileResource {
         Self {
             logger: Logger::new("FileResource"),
             base_dir,
+            uri_prefix: DEFAULT_URI_PREFIX.to_string(),
+            max_blob_bytes: DEFAULT_MAX_BLOB_BYTES,
+            mmap_threshold: DEFAULT_MMAP_THRESHOLD,
+            max_window_bytes: DEFAULT_MAX_WINDOW_BYTES,
+            memory: None,
+            client_roots: false,
+        }
+    }
+
+    /// Counts encoded blobs against the server's memory ceiling, refusing
+    /// reads that would cross it
+    pub fn with_memory_guard(mut self, memory: Arc<MemoryGuard>) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Serves the directory under `prefix` instead of `file:///data/`
+    pub fn with_uri_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.uri_prefix = prefix.into();
+        self
+    }
+
+    /// Caps the size of binary files returned as base64 blobs
+    pub fn with_max_blob_bytes(mut self, max_blob_bytes: u64) -> Self {
+        self.max_blob_bytes = max_blob_bytes;
+        self
+    }
+
+    /// Memory-maps files of at least `threshold` bytes instead of reading
+    /// them through a buffer
+    pub fn with_mmap_threshold(mut self, threshold: u64) -> Self {
+        self.mmap_threshold = threshold;
+        self
+    }
+
+    /// Caps the window returned by a ranged read; larger requests are
+    /// shortened and the client reads on from where the window ended
+    pub fn with_max_window_bytes(mut self, max_window_bytes: u64) -> Self {
+        self.max_window_bytes = max_window_bytes;
+        self
+    }
+
+    /// Also keeps reads and listings inside the roots the requesting client
+    /// granted, once they are known; clients without roots see the whole
+    /// base directory
+    pub fn with_client_roots(mut self, client_roots: bool) -> Self {
+        self.client_roots = client_roots;
+        self
+    }
+
+    fn mime_type(filename: &str) -> &'static str {
+        if filename.ends_with(".txt") {
+            "text/plain"
+        } else if filename.ends_with(".json") {
+            "application/json"
+        } else if filename.ends_with(".png") {
+            "image/png"
+        } else if filename.ends_with(".jpg") || filename.ends_with(".jpeg") {
+            "image/jpeg"
+        } else if filename.ends_with(".pdf") {
+            "application/pdf"
+        } else {
+            "application/octet-stream"
         }
     }
 
+    fn is_text(mime_type: &str) -> bool {
+        mime_type.starts_with("text/") || mime_type == "application/json"
+    }
+
     fn validate_path(&self, filename: &str) -> Result<PathBuf> {
         let requested_path = self.base_dir.join(filename);
         let resolved_base = std::fs::canonicalize(&self.base_dir)
@@ -29,47 +116,189 @@ impl FileResource {
                 "Access denied: Path traversal attempt".to_string(),
             ));
         }
+        if !self.granted(&resolved_requested) {
+            return Err(Error::ResourceError(
+                "Access denied: outside the client's roots".to_string(),
+            ));
+        }
 
         Ok(resolved_requested)
     }
+
+    /// Whether the client's roots, if enforced and known, cover `path`
+    fn granted(&self, path: &Path) -> bool {
+        if !self.client_roots {
+            return true;
+        }
+        match roots::cached() {
+            Some(roots) => roots.iter().any(|root| root.contains(path)),
+            None => true,
+        }
+    }
+
+    /// Holds memory for a blob of `raw_len` bytes while it is encoded
+    fn reserve(&self, raw_len: u64) -> Result<Option<MemoryReservation>> {
+        match &self.memory {
+            Some(memory) => Ok(Some(memory.try_reserve(blob::encoded_len(raw_len) as u64)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Maps `len` bytes of the file at `path` starting at `offset` and hands
+/// them to `f` on a blocking thread, since touching mapped pages may block
+/// on disk
+async fn with_mapped<T, F>(path: PathBuf, offset: u64, len: u64, f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&[u8]) -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || {
+        if len == 0 {
+            return f(&[]);
+        }
+        let file = std::fs::File::open(&path)?;
+        // SAFETY: the map is read-only and dropped before returning. If
+        // another process truncates the file meanwhile, touching the lost
+        // pages faults; that's the price of serving large files without
+        // copying them.
+        let map = unsafe { MmapOptions::new().offset(offset).len(len as usize).map(&file)? };
+        f(&map)
+    })
+    .await
+    .map_err(|e| Error::AsyncError(e.to_string()))?
 }
 
 #[async_trait]
 impl ResourceHandler for FileResource {
-    async fn read(&self, uri: &str) -> Result<ResourceReadResult> {
-        // Parse URI: file:///data/{filename}
+    async fn read(&self, _context: &RequestContext, uri: &str) -> Result<ResourceReadResult> {
+        // Parse URI: {prefix}{filename}
         let filename = uri
-            .strip_prefix("file:///data/")
+            .strip_prefix(self.uri_prefix.as_str())
             .ok_or_else(|| Error::ResourceError(format!("Invalid URI: {}", uri)))?;
 
         self.logger.debug_with_context("Reading file resource", filename);
 
         let validated_path = self.validate_path(filename)?;
 
-        match fs::read_to_string(&validated_path).await {
-            Ok(content) => {
-                let mime_type = if filename.ends_with(".txt") {
-                    "text/plain"
-                } else if filename.ends_with(".json") {
-                    "application/json"
-                } else {
-                    "application/octet-stream"
-                };
-
-                Ok(ResourceReadResult {
-                    contents: vec![Resource {
-                        uri: uri.to_string(),
-                        mime_type: mime_type.to_string(),
-                        text: Some(content),
-                        blob: None,
-                        size: None,
-                    }],
+        let mime_type = Self::mime_type(filename);
+        // The size decides how the file is read and what is reserved for
+        // it, so it is known before any of the file is
+        let size = fs::metadata(&validated_path).await?.len();
+        let mapped = size >= self.mmap_threshold;
+
+        let read = if Self::is_text(mime_type) {
+            if mapped {
+                with_mapped(validated_path, 0, size, |bytes| {
+                    let text = std::str::from_utf8(bytes).map_err(|e| {
+                        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+                    })?;
+                    Ok((Some(text.to_owned()), None))
+                })
+                .await
+            } else {
+                fs::read_to_string(&validated_path)
+                    .await
+                    .map(|content| (Some(content), None))
+                    .map_err(Error::Io)
+            }
+        } else {
+            let _reservation = self.reserve(size)?;
+            if mapped {
+                let max_blob_bytes = self.max_blob_bytes;
+                with_mapped(validated_path, 0, size, move |bytes| {
+                    Ok((None, Some(blob::encode_bytes(bytes, max_blob_bytes)?)))
                 })
+                .await
+            } else {
+                blob::encode_file(&validated_path, self.max_blob_bytes)
+                    .await
+                    .map(|encoded| (None, Some(encoded)))
             }
+        };
+
+        match read {
+            Ok((text, blob)) => Ok(ResourceReadResult {
+                contents: vec![Resource {
+                    uri: uri.to_string(),
+                    mime_type: mime_type.to_string(),
+                    text,
+                    blob,
+                    size: Some(size),
+                }],
+                meta: None,
+            }),
             Err(e) => {
                 self.logger.error_with_context("File read error", &e.to_string());
-                Err(Error::ResourceError(format!("Failed to read file: {}", e)))
+                Err(e)
+            }
+        }
+    }
+
+    async fn read_range(&self, _context: &RequestContext, uri: &str, range: ByteRange) -> Result<ResourceReadResult> {
+        let filename = uri
+            .strip_prefix(self.uri_prefix.as_str())
+            .ok_or_else(|| Error::ResourceError(format!("Invalid URI: {}", uri)))?;
+        let validated_path = self.validate_path(filename)?;
+        let mime_type = Self::mime_type(filename);
+
+        let size = fs::metadata(&validated_path).await?.len();
+        if range.offset > size {
+            return Err(Error::ResourceError(format!(
+                "Range starts at {} past the end of {} ({} bytes)",
+                range.offset, uri, size
+            )));
+        }
+        let len = range.length.min(size - range.offset).min(self.max_window_bytes);
+        let _reservation = self.reserve(len)?;
+
+        // A text window that splits a multi-byte character is returned as
+        // a blob rather than altered
+        let is_text = Self::is_text(mime_type);
+        let (text, blob) = with_mapped(validated_path, range.offset, len, move |bytes| {
+            Ok(match std::str::from_utf8(bytes) {
+                Ok(text) if is_text => (Some(text.to_owned()), None),
+                _ => (None, Some(blob::encode_bytes(bytes, u64::MAX)?)),
+            })
+        })
+        .await?;
+
+        Ok(ResourceReadResult {
+            contents: vec![Resource {
+                uri: uri.to_string(),
+                mime_type: mime_type.to_string(),
+                text,
+                blob,
+                size: Some(size),
+            }],
+            meta: None,
+        })
+    }
+
+    /// The files directly under the base directory; deeper files can still
+    /// be read by URI
+    async fn list(&self) -> Result<Vec<ResourceDefinition>> {
+        let mut entries = fs::read_dir(&self.base_dir).await?;
+        let mut listed = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_file() {
+                continue;
+            }
+            let Some(filename) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let path = std::fs::canonicalize(entry.path()).unwrap_or_else(|_| entry.path());
+            if !self.granted(&path) {
+                continue;
             }
+            listed.push(ResourceDefinition {
+                uri: format!("{}{}", self.uri_prefix, filename),
+                mime_type: Some(Self::mime_type(&filename).to_string()),
+                name: filename,
+                description: None,
+            });
         }
+        listed.sort_by(|a, b| a.uri.cmp(&b.uri));
+        Ok(listed)
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,12 @@
 pub mod config_resource;
+#[cfg(feature = "native")]
 pub mod file_resource;
 
 use async_trait::async_trait;
-use crate::utils::Result;
+use serde_json::Value;
+use crate::completion::Completable;
+use crate::context::RequestContext;
+use crate::utils::{Error, Result};
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Resource {
@@ -17,12 +21,59 @@ pub struct Resource {
     pub size: Option<u64>,
 }
 
+/// A resource as advertised in `resources/list`
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ResourceDefinition {
+    pub uri: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "mimeType", default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ResourceReadResult {
     pub contents: Vec<Resource>,
+    /// Protocol and vendor metadata about the result
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+/// A window of a resource's bytes, requested with `resources/read`'s
+/// `range` parameter
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ByteRange {
+    pub offset: u64,
+    pub length: u64,
 }
 
+/// Serves the resources under a URI prefix; like a
+/// [`ToolHandler`](crate::tools::ToolHandler), one registered instance
+/// serves every read and may hold state
 #[async_trait]
 pub trait ResourceHandler: Send + Sync {
-    async fn read(&self, uri: &str) -> Result<ResourceReadResult>;
+    async fn read(&self, context: &RequestContext, uri: &str) -> Result<ResourceReadResult>;
+
+    /// Reads part of the resource; handlers may serve less than requested
+    async fn read_range(
+        &self,
+        _context: &RequestContext,
+        uri: &str,
+        _range: ByteRange,
+    ) -> Result<ResourceReadResult> {
+        Err(Error::ResourceError(format!("Ranged reads are not supported for {}", uri)))
+    }
+
+    /// Resources advertised in `resources/list`; a handler serving URIs it
+    /// can't enumerate lists none and is still read through its prefix
+    async fn list(&self) -> Result<Vec<ResourceDefinition>> {
+        Ok(Vec::new())
+    }
+
+    /// Suggests values for the variables of URI templates under this
+    /// handler's prefix
+    fn completable(&self) -> Option<&dyn Completable> {
+        None
+    }
 }
\ No newline at end of file
@@ -0,0 +1,156 @@
+//! The client's roots: the directories and files it lets the server work in
+//!
+//! Roots are fetched with `roots/list` the first time they are needed and
+//! cached per session. When the client sends
+//! `notifications/roots/list_changed` the cache is dropped and refilled, and
+//! the new list is published to [`McpServer::subscribe_roots`] so
+//! components that scope paths to the workspace can follow it. Clients
+//! that declared `roots` are also asked once they send
+//! `notifications/initialized`, so the roots are usually cached before the
+//! first tool call.
+//!
+//! Handlers reach the roots of the client they serve with [`list_roots`],
+//! or [`cached`] where waiting on the client is not an option;
+//! [`FileResource::with_client_roots`] uses the latter to stay inside them.
+//!
+//! [`McpServer::subscribe_roots`]: crate::server::McpServer::subscribe_roots
+//! [`FileResource::with_client_roots`]: crate::resources::file_resource::FileResource::with_client_roots
+
+use crate::server::ClientLink;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// A directory or file the client exposes, as a `file://` URI
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Root {
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Root {
+    /// The local path of a `file://` root
+    pub fn path(&self) -> Option<PathBuf> {
+        let path = self.uri.strip_prefix("file://")?;
+        // Skip the authority, normally empty or `localhost`
+        let path = &path[path.find('/')?..];
+        Some(PathBuf::from(percent_decode(path)?))
+    }
+
+    /// Whether `path` is the root or lies below it
+    pub fn contains(&self, path: &Path) -> bool {
+        self.path().is_some_and(|root| {
+            let root = std::fs::canonicalize(&root).unwrap_or(root);
+            path.starts_with(root)
+        })
+    }
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Published whenever a session's roots have been re-read
+#[derive(Clone, Debug, PartialEq)]
+pub struct RootsChanged {
+    /// `None` outside sessions (stdio)
+    pub session: Option<String>,
+    pub roots: Vec<Root>,
+}
+
+/// Root changes buffered per subscriber before it lags
+const CHANGE_BUFFER: usize = 16;
+
+/// Roots last read from each session's client
+pub struct RootsCache {
+    cached: Mutex<HashMap<Option<String>, Vec<Root>>>,
+    changes: broadcast::Sender<RootsChanged>,
+}
+
+impl RootsCache {
+    pub fn new() -> Self {
+        Self {
+            cached: Mutex::new(HashMap::new()),
+            changes: broadcast::channel(CHANGE_BUFFER).0,
+        }
+    }
+
+    pub fn get(&self, session: &Option<String>) -> Option<Vec<Root>> {
+        self.lock().get(session).cloned()
+    }
+
+    /// Caches `roots` without announcing them, e.g. after a first read
+    pub fn store(&self, session: Option<String>, roots: Vec<Root>) {
+        self.lock().insert(session, roots);
+    }
+
+    /// Caches `roots` and tells subscribers the session's roots changed
+    pub fn replace(&self, session: Option<String>, roots: Vec<Root>) {
+        self.store(session.clone(), roots.clone());
+        let _ = self.changes.send(RootsChanged { session, roots });
+    }
+
+    pub fn invalidate(&self, session: &Option<String>) {
+        self.lock().remove(session);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RootsChanged> {
+        self.changes.subscribe()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Option<String>, Vec<Root>>> {
+        self.cached.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for RootsCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the roots out of a `roots/list` result
+pub fn parse_roots(mut result: Value) -> Result<Vec<Root>> {
+    let roots = result
+        .get_mut("roots")
+        .map(Value::take)
+        .ok_or_else(|| Error::InvalidParams("roots/list result has no roots".to_string()))?;
+    Ok(serde_json::from_value(roots)?)
+}
+
+
+/// Roots of the client of the request being handled, asking it with
+/// `roots/list` unless they are cached
+///
+/// Over TCP and in-memory connections the client's reply is only read
+/// after the current message has been handled; rely on [`cached`] there
+/// instead. Stdio reads it while the request is being handled.
+pub async fn list_roots() -> Result<Vec<Root>> {
+    ClientLink::current()
+        .ok_or_else(|| Error::InternalError("Roots need a request being handled".to_string()))?
+        .roots()
+        .await
+}
+
+/// Roots the client of the request being handled last reported; `None`
+/// outside a request or before they were read
+pub fn cached() -> Option<Vec<Root>> {
+    ClientLink::current()?.cached_roots()
+}
\ No newline at end of file
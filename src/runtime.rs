@@ -0,0 +1,44 @@
+//! The async runtime touchpoints of the core
+//!
+//! The server core only needs an executor to spawn detached tasks and a
+//! timer; everything else it uses from tokio (locks, channels, semaphores
+//! and task-locals) works on any executor. Pick the runtime with one of the
+//! `rt-tokio` (part of the default `native` feature), `rt-async-std` or
+//! `rt-smol` features. If several are enabled, tokio wins, then async-std.
+
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(not(any(feature = "rt-tokio", feature = "rt-async-std", feature = "rt-smol")))]
+compile_error!("enable one of the rt-tokio, rt-async-std or rt-smol features");
+
+/// Runs `future` to completion in the background
+pub fn spawn<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    #[cfg(feature = "rt-tokio")]
+    {
+        tokio::spawn(future);
+    }
+    #[cfg(all(feature = "rt-async-std", not(feature = "rt-tokio")))]
+    {
+        async_std::task::spawn(future);
+    }
+    #[cfg(all(feature = "rt-smol", not(any(feature = "rt-tokio", feature = "rt-async-std"))))]
+    {
+        smol::spawn(future).detach();
+    }
+}
+
+/// Waits for `duration` on the runtime's timer
+pub async fn sleep(duration: Duration) {
+    #[cfg(feature = "rt-tokio")]
+    tokio::time::sleep(duration).await;
+    #[cfg(all(feature = "rt-async-std", not(feature = "rt-tokio")))]
+    async_std::task::sleep(duration).await;
+    #[cfg(all(feature = "rt-smol", not(any(feature = "rt-tokio", feature = "rt-async-std"))))]
+    {
+        smol::Timer::after(duration).await;
+    }
+}
@@ -0,0 +1,300 @@
+//! Typed `sampling/createMessage` requests
+//!
+//! A server asks the client's LLM for a completion with a
+//! [`CreateMessageRequest`]. The builder covers the whole request as the
+//! spec defines it, so tools don't assemble the JSON by hand. Handlers send
+//! it to the client of the request they handle with [`create_message`], or
+//! through any [`ClientLink`] with [`ClientLink::create_message`].
+//!
+//! ```
+//! use mcp_server_rust::sampling::{CreateMessageRequest, IncludeContext, ModelPreferences};
+//!
+//! let request = CreateMessageRequest::new(200)
+//!     .with_user_message("Summarize the attached log")
+//!     .with_system_prompt("You are a terse SRE assistant.")
+//!     .with_include_context(IncludeContext::ThisServer)
+//!     .with_model_preferences(
+//!         ModelPreferences::new()
+//!             .with_hint("claude-3-haiku")
+//!             .with_speed_priority(0.8)
+//!             .with_intelligence_priority(0.3),
+//!     );
+//! let params = request.params().unwrap();
+//! assert_eq!(params["modelPreferences"]["hints"][0]["name"], "claude-3-haiku");
+//! ```
+
+use crate::server::ClientLink;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Which model the client should pick, as hints and relative priorities
+///
+/// Priorities range from 0 to 1; clients weigh them against each other
+/// and treat hints as substrings of model names, in order of preference.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPreferences {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<ModelHint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_priority: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_priority: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intelligence_priority: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelHint {
+    pub name: String,
+}
+
+/// Which MCP servers' context the client should add to the prompt
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IncludeContext {
+    None,
+    ThisServer,
+    AllServers,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+/// Content of a sampling message
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SamplingContent {
+    Text {
+        text: String,
+    },
+    Image {
+        /// Base64-encoded image
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    Audio {
+        /// Base64-encoded audio
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SamplingMessage {
+    pub role: Role,
+    pub content: SamplingContent,
+}
+
+/// Params of a `sampling/createMessage` request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageRequest {
+    pub messages: Vec<SamplingMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_preferences: Option<ModelPreferences>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_context: Option<IncludeContext>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    pub max_tokens: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    /// Provider-specific parameters passed through by the client
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+impl ModelPreferences {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a preferred model name, after those already added
+    pub fn with_hint(mut self, name: impl Into<String>) -> Self {
+        self.hints.push(ModelHint { name: name.into() });
+        self
+    }
+
+    pub fn with_cost_priority(mut self, priority: f64) -> Self {
+        self.cost_priority = Some(priority);
+        self
+    }
+
+    pub fn with_speed_priority(mut self, priority: f64) -> Self {
+        self.speed_priority = Some(priority);
+        self
+    }
+
+    pub fn with_intelligence_priority(mut self, priority: f64) -> Self {
+        self.intelligence_priority = Some(priority);
+        self
+    }
+
+    /// Rejects priorities outside 0..=1
+    pub fn validate(&self) -> Result<()> {
+        let priorities = [
+            ("costPriority", self.cost_priority),
+            ("speedPriority", self.speed_priority),
+            ("intelligencePriority", self.intelligence_priority),
+        ];
+        for (name, priority) in priorities {
+            if let Some(priority) = priority
+                && !(0.0..=1.0).contains(&priority)
+            {
+                return Err(Error::ValidationError(format!(
+                    "{} must be between 0 and 1, got {}",
+                    name, priority
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The client's answer to `sampling/createMessage`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageResult {
+    pub role: Role,
+    pub content: SamplingContent,
+    /// Model that produced the message
+    pub model: String,
+    /// Why sampling stopped, e.g. `endTurn` or `maxTokens`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+}
+
+impl CreateMessageResult {
+    /// The message's text, if it is text
+    pub fn text(&self) -> Option<&str> {
+        match &self.content {
+            SamplingContent::Text { text } => Some(text),
+            _ => None,
+        }
+    }
+}
+
+impl SamplingMessage {
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: SamplingContent::Text { text: text.into() },
+        }
+    }
+
+    pub fn assistant(text: impl Into<String>) -> Self {
+        Self {
+            role: Role::Assistant,
+            content: SamplingContent::Text { text: text.into() },
+        }
+    }
+}
+
+impl CreateMessageRequest {
+    /// A request for at most `max_tokens` tokens; add messages with the
+    /// `with_*` methods
+    pub fn new(max_tokens: u32) -> Self {
+        Self {
+            messages: Vec::new(),
+            model_preferences: None,
+            system_prompt: None,
+            include_context: None,
+            temperature: None,
+            max_tokens,
+            stop_sequences: Vec::new(),
+            metadata: None,
+        }
+    }
+
+    pub fn with_message(mut self, message: SamplingMessage) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    pub fn with_user_message(self, text: impl Into<String>) -> Self {
+        self.with_message(SamplingMessage::user(text))
+    }
+
+    pub fn with_model_preferences(mut self, preferences: ModelPreferences) -> Self {
+        self.model_preferences = Some(preferences);
+        self
+    }
+
+    pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(prompt.into());
+        self
+    }
+
+    pub fn with_include_context(mut self, include: IncludeContext) -> Self {
+        self.include_context = Some(include);
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_stop_sequence(mut self, stop: impl Into<String>) -> Self {
+        self.stop_sequences.push(stop.into());
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Checks the request against the spec's constraints
+    pub fn validate(&self) -> Result<()> {
+        if self.messages.is_empty() {
+            return Err(Error::ValidationError(
+                "A sampling request needs at least one message".to_string(),
+            ));
+        }
+        if self.max_tokens == 0 {
+            return Err(Error::ValidationError("maxTokens must be positive".to_string()));
+        }
+        match &self.model_preferences {
+            Some(preferences) => preferences.validate(),
+            None => Ok(()),
+        }
+    }
+
+    /// The validated request as `sampling/createMessage` params
+    pub fn params(&self) -> Result<Value> {
+        self.validate()?;
+        Ok(serde_json::to_value(self)?)
+    }
+}
+
+impl ClientLink {
+    /// Asks the client's LLM for a completion
+    ///
+    /// Fails if the client didn't declare the `sampling` capability, the
+    /// request is invalid, or the client rejects it or doesn't answer in
+    /// time.
+    pub async fn create_message(&self, request: &CreateMessageRequest) -> Result<CreateMessageResult> {
+        let result = self.request("sampling/createMessage", request.params()?).await?;
+        serde_json::from_value(result)
+            .map_err(|e| Error::InternalError(format!("Invalid sampling result: {}", e)))
+    }
+}
+
+/// [`ClientLink::create_message`] on the client of the request being
+/// handled
+pub async fn create_message(request: &CreateMessageRequest) -> Result<CreateMessageResult> {
+    let link = ClientLink::current()
+        .ok_or_else(|| Error::InternalError("Sampling needs a request being handled".to_string()))?;
+    link.create_message(request).await
+}
\ No newline at end of file
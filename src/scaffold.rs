@@ -0,0 +1,402 @@
+//! Boilerplate for new tools, resources and prompts
+//!
+//! [`Scaffold`] renders the handler module, an integration test, and the
+//! lines that register the handler, following this crate's layout:
+//! `src/tools/<name>_tool.rs`, `src/resources/<name>_resource.rs` and
+//! `src/prompts/<name>_prompt.rs`, with tests under `tests/`.
+
+use crate::utils::{Error, Result};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "native", derive(clap::ValueEnum))]
+pub enum ScaffoldKind {
+    Tool,
+    Resource,
+    Prompt,
+}
+
+impl ScaffoldKind {
+    fn suffix(self) -> &'static str {
+        match self {
+            ScaffoldKind::Tool => "tool",
+            ScaffoldKind::Resource => "resource",
+            ScaffoldKind::Prompt => "prompt",
+        }
+    }
+
+    fn module_dir(self) -> &'static str {
+        match self {
+            ScaffoldKind::Tool => "tools",
+            ScaffoldKind::Resource => "resources",
+            ScaffoldKind::Prompt => "prompts",
+        }
+    }
+
+    fn templates(self) -> (&'static str, &'static str) {
+        match self {
+            ScaffoldKind::Tool => (TOOL_TEMPLATE, TOOL_TEST_TEMPLATE),
+            ScaffoldKind::Resource => (RESOURCE_TEMPLATE, RESOURCE_TEST_TEMPLATE),
+            ScaffoldKind::Prompt => (PROMPT_TEMPLATE, PROMPT_TEST_TEMPLATE),
+        }
+    }
+}
+
+/// A file the generator would write, relative to the crate root
+#[derive(Clone, Debug)]
+pub struct GeneratedFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// A named handler to generate
+pub struct Scaffold {
+    kind: ScaffoldKind,
+    /// Name as clients see it, e.g. `word-count`
+    name: String,
+}
+
+impl Scaffold {
+    /// `name` is the kebab-case name clients use, e.g. `word-count`
+    pub fn new(kind: ScaffoldKind, name: &str) -> Result<Self> {
+        let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid {
+            return Err(Error::ValidationError(format!(
+                "Invalid name {:?}: use lowercase letters, digits, '-' and '_', starting with a letter",
+                name
+            )));
+        }
+        Ok(Self {
+            kind,
+            name: name.to_string(),
+        })
+    }
+
+    /// `word_count_tool`
+    pub fn module_name(&self) -> String {
+        format!("{}_{}", self.name.replace('-', "_"), self.kind.suffix())
+    }
+
+    /// `WordCountTool`
+    pub fn type_name(&self) -> String {
+        let mut type_name: String = self
+            .name
+            .split(['-', '_'])
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect();
+        let suffix = self.kind.suffix();
+        type_name.push_str(&(suffix[..1].to_ascii_uppercase() + &suffix[1..]));
+        type_name
+    }
+
+    /// The handler module and its integration test
+    pub fn files(&self) -> Vec<GeneratedFile> {
+        let (module, test) = self.kind.templates();
+        vec![
+            GeneratedFile {
+                path: Path::new("src")
+                    .join(self.kind.module_dir())
+                    .join(format!("{}.rs", self.module_name())),
+                contents: self.render(module),
+            },
+            GeneratedFile {
+                path: Path::new("tests").join(format!("{}.rs", self.module_name())),
+                contents: self.render(test),
+            },
+        ]
+    }
+
+    /// Lines to add by hand: the `mod` declaration and the registration call
+    pub fn registration_snippet(&self) -> String {
+        let register = match self.kind {
+            ScaffoldKind::Tool => "register_tool",
+            ScaffoldKind::Resource => "register_resource",
+            ScaffoldKind::Prompt => "register_prompt",
+        };
+        let key = match self.kind {
+            ScaffoldKind::Resource => format!("{}://", self.name),
+            _ => self.name.clone(),
+        };
+        format!(
+            "// src/{dir}/mod.rs\npub mod {module};\n\n\
+             // where the server is set up\n\
+             server.{register}(\"{key}\".to_string(), Arc::new({dir}::{module}::{ty}::new())).await?;\n",
+            dir = self.kind.module_dir(),
+            module = self.module_name(),
+            register = register,
+            key = key,
+            ty = self.type_name(),
+        )
+    }
+
+    /// Writes [`Scaffold::files`] under `root`, refusing to overwrite
+    /// existing files unless `force` is set; returns the written paths
+    pub fn write(&self, root: &Path, force: bool) -> Result<Vec<PathBuf>> {
+        let files = self.files();
+        if !force
+            && let Some(existing) = files.iter().map(|f| root.join(&f.path)).find(|p| p.exists())
+        {
+            return Err(Error::ValidationError(format!(
+                "{} already exists (pass --force to overwrite)",
+                existing.display()
+            )));
+        }
+
+        let mut written = Vec::new();
+        for file in files {
+            let path = root.join(&file.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, file.contents)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    fn render(&self, template: &str) -> String {
+        template
+            .replace("{{name}}", &self.name)
+            .replace("{{module}}", &self.module_name())
+            .replace("{{type}}", &self.type_name())
+    }
+}
+
+const TOOL_TEMPLATE: &str = r#"use super::{Tool, CallToolResult, TextContent, ToolHandler};
+use serde_json::{json, Value};
+use async_trait::async_trait;
+use crate::context::RequestContext;
+use crate::utils::i18n::MessageKey;
+use crate::utils::{Result, Error, Logger};
+
+pub struct {{type}} {
+    logger: Logger,
+}
+
+impl {{type}} {
+    pub fn new() -> Self {
+        Self {
+            logger: Logger::new("{{type}}"),
+        }
+    }
+
+    pub fn tool_definition() -> Tool {
+        Tool {
+            name: "{{name}}".to_string(),
+            description: "TODO: describe what {{name}} does".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "input": {
+                        "type": "string",
+                        "description": "TODO: describe the input"
+                    }
+                },
+                "required": ["input"]
+            }),
+            annotations: None,
+            output_schema: None,
+        }
+    }
+}
+
+impl Default for {{type}} {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for {{type}} {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        let input = arguments
+            .get("input")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| Error::invalid_params(MessageKey::MissingParameter, &["input"]))?;
+
+        self.logger.debug_with_context("Tool called with input", input);
+
+        Ok(CallToolResult::success(vec![TextContent::new(input)]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Self::tool_definition())
+    }
+}
+"#;
+
+const TOOL_TEST_TEMPLATE: &str = r#"use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{{module}}::{{type}};
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::json;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn {{module}}_echoes_its_input() {
+    let server = McpServer::new(ServerConfig::new("Test", "0.1.0"));
+    server
+        .register_tool("{{name}}".to_string(), Arc::new({{type}}::new()))
+        .await
+        .unwrap();
+    let client = McpTestClient::new(server);
+
+    let result = client.call_tool("{{name}}", json!({ "input": "hi" })).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "hi");
+}
+"#;
+
+const RESOURCE_TEMPLATE: &str = r#"use super::{Resource, ResourceReadResult, ResourceHandler};
+use async_trait::async_trait;
+use crate::context::RequestContext;
+use crate::utils::{Result, Logger};
+
+pub struct {{type}} {
+    logger: Logger,
+}
+
+impl {{type}} {
+    pub fn new() -> Self {
+        Self {
+            logger: Logger::new("{{type}}"),
+        }
+    }
+}
+
+impl Default for {{type}} {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ResourceHandler for {{type}} {
+    async fn read(&self, _context: &RequestContext, uri: &str) -> Result<ResourceReadResult> {
+        self.logger.debug_with_context("Reading {{name}} resource", uri);
+
+        Ok(ResourceReadResult {
+            contents: vec![Resource {
+                uri: uri.to_string(),
+                mime_type: "text/plain".to_string(),
+                text: Some("TODO: {{name}} contents".to_string()),
+                blob: None,
+                size: None,
+            }],
+            meta: None,
+        })
+    }
+}
+"#;
+
+const RESOURCE_TEST_TEMPLATE: &str = r#"use mcp_server_rust::resources::{{module}}::{{type}};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{McpServer, ServerConfig};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn {{module}}_is_readable() {
+    let server = McpServer::new(ServerConfig::new("Test", "0.1.0"));
+    server
+        .register_resource("{{name}}://".to_string(), Arc::new({{type}}::new()))
+        .await
+        .unwrap();
+    let client = McpTestClient::new(server);
+
+    let result = client.read_resource("{{name}}://example").await.unwrap();
+    assert_eq!(result.contents[0].uri, "{{name}}://example");
+}
+"#;
+
+const PROMPT_TEMPLATE: &str = r#"use super::{Content, GetPromptResult, Message, Prompt, PromptArgument, PromptHandler};
+use crate::context::RequestContext;
+use crate::utils::{Error, Logger, Result};
+use crate::utils::i18n::MessageKey;
+use async_trait::async_trait;
+use serde_json::Value;
+
+pub struct {{type}} {
+    logger: Logger,
+}
+
+impl {{type}} {
+    pub fn new() -> Self {
+        Self {
+            logger: Logger::new("{{type}}"),
+        }
+    }
+
+    pub fn prompt_definition() -> Prompt {
+        Prompt {
+            name: "{{name}}".to_string(),
+            description: "TODO: describe the {{name}} prompt".to_string(),
+            arguments: Some(vec![PromptArgument {
+                name: "topic".to_string(),
+                description: "TODO: describe the argument".to_string(),
+                required: Some(true),
+            }]),
+        }
+    }
+}
+
+impl Default for {{type}} {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PromptHandler for {{type}} {
+    async fn get(&self, _context: &RequestContext, arguments: Option<Value>) -> Result<GetPromptResult> {
+        let args =
+            arguments.ok_or_else(|| Error::invalid_params(MessageKey::MissingArguments, &[]))?;
+
+        let topic = args
+            .get("topic")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| Error::invalid_params(MessageKey::MissingArgument, &["topic"]))?;
+
+        self.logger.debug_with_context("Generating {{name}} prompt", topic);
+
+        Ok(GetPromptResult {
+            description: Some("TODO: describe the generated prompt".to_string()),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: Content::text(format!("TODO: ask about {}", topic)),
+            }],
+            meta: None,
+        })
+    }
+
+    fn definition(&self) -> Option<Prompt> {
+        Some(Self::prompt_definition())
+    }
+}
+"#;
+
+const PROMPT_TEST_TEMPLATE: &str = r#"use mcp_server_rust::prompts::{{module}}::{{type}};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::json;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn {{module}}_renders() {
+    let server = McpServer::new(ServerConfig::new("Test", "0.1.0"));
+    server
+        .register_prompt("{{name}}".to_string(), Arc::new({{type}}::new()))
+        .await
+        .unwrap();
+    let client = McpTestClient::new(server);
+
+    let result = client.get_prompt("{{name}}", json!({ "topic": "rust" })).await.unwrap();
+    assert!(result.messages[0].content.as_text().unwrap().contains("rust"));
+}
+"#;
@@ -3,18 +3,105 @@ use crate::resources::*;
 use crate::resources::config_resource::ConfigResource;
 use crate::prompts::*;
 use crate::prompts::code_review_prompt::CodeReviewPrompt;
-use crate::utils::{Result, Error, Logger};
+use crate::access::{self, AccessPolicy};
+use crate::approval::{Approval, ApprovalPolicy, ApprovalRequest};
+use crate::audit::{AuditEntry, AuditLog};
+use crate::compat;
+use crate::context::{self, Extensions, RequestContext};
+use crate::completion::{Completion, CompletionRequest, Reference};
+use crate::lifecycle::{Lifecycle, Phase};
+use crate::middleware::{Middleware, RequestInfo};
+use crate::logging::{LogLevel, LogLevels};
+use crate::notifications::{self, NotificationSender, SessionChannels, send_on};
+use crate::roots::{self, Root, RootsCache, RootsChanged};
+use crate::runtime;
+use crate::spec;
+use crate::protocol::{self, RawRequest, Reply, RequestEnvelope, Response, ValueRequest};
+use crate::protocol::{
+    CallToolParams, CancelledParams, FromParams, GetPromptParams, Implementation, InitializeParams,
+    InitializeResult, PaginatedParams, ReadResourceParams, SearchToolsParams, SetLevelParams,
+    SubscribeParams,
+};
+use crate::session::{self, IdlePolicy, SessionStore};
+use crate::state::{MemoryStateStore, StateStore};
+use crate::utils::{
+    Clock, Coalescer, Error, ErrorCode, ErrorCodeRegistry, InFlight, InFlightRequest, Logger, PendingRequests, MemoryGuard, RequestLimiter, RequestLimits,
+    Result, Snapshot, SystemClock,
+};
+use crate::utils::error_codes;
+use crate::utils::memory::approx_size;
+use crate::utils::deadline;
+use crate::utils::pagination;
+use crate::utils::meta::{self, RequestMeta};
+use crate::utils::i18n::{self, MessageKey};
+use futures::future::{Abortable, Aborted, Either};
+use tokio_util::sync::CancellationToken;
+use serde::Serialize;
+use serde_json::value::{RawValue, to_raw_value};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::{RwLock, broadcast, mpsc};
 use crate::tools::greeting_tool::GreetingTool;
 use crate::tools::calculator_tool::CalculatorTool;
 use crate::tools::weather_tool::WeatherTool;
+use crate::tools::pool::{ToolPool, ToolPoolConfig};
+use crate::tools::schema::{self, OutputValidation};
+use crate::tools::search::{DEFAULT_SEARCH_LIMIT, EmbeddingBackend, ToolSearch};
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ServerConfig {
     pub name: String,
     pub version: String,
+    /// Human-readable name for clients to display instead of `name`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website_url: Option<String>,
+    /// Images clients may show for the server
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub icons: Vec<Icon>,
+    #[serde(default)]
+    pub features: FeatureFlags,
+    #[serde(default)]
+    pub limits: RequestLimits,
+    #[serde(default)]
+    pub tool_pool: ToolPoolConfig,
+    #[serde(default)]
+    pub output_validation: OutputValidation,
+    /// How network transports treat sessions that go quiet
+    #[serde(default)]
+    pub idle: IdlePolicy,
+    /// Check every outgoing message against the MCP schema (for
+    /// development and tests)
+    #[serde(default)]
+    pub validate_messages: bool,
+    /// Who may use which tools, resources and prompts; everyone may use
+    /// everything when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<AccessPolicy>,
+    /// How to use this server, for the client to pass on to its model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// Capabilities advertised on top of those the enabled features imply
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub capabilities: serde_json::Map<String, Value>,
+    /// Most items per page of `tools/list`, `resources/list` and
+    /// `prompts/list`; unset lists everything on one page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<usize>,
+    /// Advertises `listChanged` for tools, resources and prompts, so
+    /// clients are told when the registries change after `initialize`
+    #[serde(default)]
+    pub list_changed: bool,
+    /// Rejects requests other than `initialize` and `ping` until the
+    /// client completed the handshake; see [`crate::lifecycle`]
+    #[serde(default)]
+    pub strict_lifecycle: bool,
+    /// Accepts `resources/subscribe`, advertising `resources.subscribe`
+    #[serde(default)]
+    pub resource_subscriptions: bool,
 }
 
 impl ServerConfig {
@@ -22,17 +109,448 @@ impl ServerConfig {
         Self {
             name: name.into(),
             version: version.into(),
+            features: FeatureFlags::default(),
+            limits: RequestLimits::default(),
+            tool_pool: ToolPoolConfig::default(),
+            output_validation: OutputValidation::default(),
+            idle: IdlePolicy::default(),
+            validate_messages: false,
+            access: None,
+            instructions: None,
+            title: None,
+            website_url: None,
+            icons: Vec::new(),
+            capabilities: serde_json::Map::new(),
+            page_size: None,
+            list_changed: false,
+            strict_lifecycle: false,
+            resource_subscriptions: false,
+        }
+    }
+
+    pub fn with_features(mut self, features: FeatureFlags) -> Self {
+        self.features = features;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Runs tool calls on a dedicated pool of workers instead of the task
+    /// that received the request
+    pub fn with_tool_pool(mut self, tool_pool: ToolPoolConfig) -> Self {
+        self.tool_pool = tool_pool;
+        self
+    }
+
+    pub fn with_idle_policy(mut self, idle: IdlePolicy) -> Self {
+        self.idle = idle;
+        self
+    }
+
+    /// Checks every response and notification against the bundled MCP
+    /// schema for the negotiated revision before it is sent. A violation
+    /// panics in debug builds and is logged otherwise.
+    pub fn with_message_validation(mut self, validate_messages: bool) -> Self {
+        self.validate_messages = validate_messages;
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn with_website_url(mut self, url: impl Into<String>) -> Self {
+        self.website_url = Some(url.into());
+        self
+    }
+
+    pub fn with_icon(mut self, icon: Icon) -> Self {
+        self.icons.push(icon);
+        self
+    }
+
+    /// Sent to clients in the `initialize` result
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Advertises `capabilities` (an object) in addition to those implied by
+    /// the features; an object is merged into the one already advertised
+    /// under the same key, e.g. to add `experimental` entries
+    pub fn with_capabilities(mut self, capabilities: Value) -> Self {
+        if let Value::Object(capabilities) = capabilities {
+            merge_capabilities(&mut self.capabilities, capabilities);
+        }
+        self
+    }
+
+    /// Advertises `experimental[name]`, e.g. for an extension method
+    pub fn with_experimental(self, name: impl Into<String>, capability: Value) -> Self {
+        let mut experimental = serde_json::Map::new();
+        experimental.insert(name.into(), capability);
+        self.with_capabilities(json!({ "experimental": experimental }))
+    }
+
+    /// See [`ServerConfig::resource_subscriptions`]
+    pub fn with_resource_subscriptions(mut self, resource_subscriptions: bool) -> Self {
+        self.resource_subscriptions = resource_subscriptions;
+        self
+    }
+
+    /// Splits list results into pages of at most `page_size` items, linked
+    /// by `nextCursor`
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size.max(1));
+        self
+    }
+
+    /// See [`ServerConfig::list_changed`]
+    pub fn with_list_changed(mut self, list_changed: bool) -> Self {
+        self.list_changed = list_changed;
+        self
+    }
+
+    /// See [`ServerConfig::strict_lifecycle`]
+    pub fn with_strict_lifecycle(mut self, strict_lifecycle: bool) -> Self {
+        self.strict_lifecycle = strict_lifecycle;
+        self
+    }
+
+    /// Restricts each caller to what its roles allow
+    pub fn with_access_policy(mut self, access: AccessPolicy) -> Self {
+        self.access = Some(access);
+        self
+    }
+
+    /// Sets how results that don't match their tool's `outputSchema` are
+    /// handled (rejected by default)
+    pub fn with_output_validation(mut self, output_validation: OutputValidation) -> Self {
+        self.output_validation = output_validation;
+        self
+    }
+}
+
+/// Checks a call's arguments against the tool's `inputSchema`; returns
+/// the error result listing the violations when they don't conform
+fn check_input(tool: &Tool, arguments: &Value) -> Result<Option<CallToolResult>> {
+    let problems = schema::violations(&tool.input_schema, arguments)
+        .map_err(|e| Error::InternalError(format!("Tool {} has an invalid inputSchema: {}", tool.name, e)))?;
+    if problems.is_empty() {
+        return Ok(None);
+    }
+    let message = format!("Invalid arguments for tool {}: {}", tool.name, problems.join("; "));
+    Ok(Some(
+        CallToolResult::error(message).with_structured_content(json!({ "violations": problems })),
+    ))
+}
+
+/// Merges `extra` into `capabilities`, one level deep
+fn merge_capabilities(
+    capabilities: &mut serde_json::Map<String, Value>,
+    extra: serde_json::Map<String, Value>,
+) {
+    for (key, value) in extra {
+        match (capabilities.get_mut(&key), value) {
+            (Some(Value::Object(existing)), Value::Object(value)) => existing.extend(value),
+            (_, value) => {
+                capabilities.insert(key, value);
+            }
         }
     }
 }
 
+/// An image identifying the server, sent in `serverInfo.icons`
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Icon {
+    /// An `https:` or `data:` URI
+    pub src: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Sizes the image suits, such as `48x48` or `any`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sizes: Vec<String>,
+}
+
+impl Icon {
+    pub fn new(src: impl Into<String>) -> Self {
+        Self {
+            src: src.into(),
+            mime_type: None,
+            sizes: Vec::new(),
+        }
+    }
+
+    pub fn with_mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    pub fn with_size(mut self, size: impl Into<String>) -> Self {
+        self.sizes.push(size.into());
+        self
+    }
+}
+
+/// Switches for whole subsystems, applied at startup
+///
+/// A disabled subsystem is left out of the advertised capabilities, its
+/// registry stays empty, and its methods answer with "method not found".
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct FeatureFlags {
+    pub tools: bool,
+    pub resources: bool,
+    pub prompts: bool,
+}
+
+impl FeatureFlags {
+    /// Disables the named subsystem; returns false for unknown names
+    pub fn disable(&mut self, name: &str) -> bool {
+        match name {
+            "tools" => self.tools = false,
+            "resources" => self.resources = false,
+            "prompts" => self.prompts = false,
+            _ => return false,
+        }
+        true
+    }
+
+    fn allows_method(&self, method: &str) -> bool {
+        match method.split('/').next() {
+            Some("tools") => self.tools,
+            Some("resources") => self.resources,
+            Some("prompts") => self.prompts,
+            _ => true,
+        }
+    }
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self {
+            tools: true,
+            resources: true,
+            prompts: true,
+        }
+    }
+}
+
+/// Handlers visible to a single session on top of the global registries
+#[derive(Default)]
+struct SessionOverlay {
+    tools: HashMap<String, Arc<dyn ToolHandler>>,
+    resources: HashMap<String, Arc<dyn ResourceHandler>>,
+    prompts: HashMap<String, Arc<dyn PromptHandler>>,
+}
+
 pub struct McpServer {
     config: ServerConfig,
     logger: Logger,
-    tools: Arc<Mutex<HashMap<String, Arc<dyn ToolHandler>>>>,
-    resources: Arc<Mutex<HashMap<String, Arc<dyn ResourceHandler>>>>,
-    prompts: Arc<Mutex<HashMap<String, Arc<dyn PromptHandler>>>>,
-    initialized: Arc<Mutex<bool>>,
+    // Read on every call, written on (un)registration: readers take
+    // snapshots instead of locks
+    tools: Snapshot<HashMap<String, Arc<dyn ToolHandler>>>,
+    resources: Snapshot<HashMap<String, Arc<dyn ResourceHandler>>>,
+    prompts: Snapshot<HashMap<String, Arc<dyn PromptHandler>>>,
+    overlays: Arc<RwLock<HashMap<String, SessionOverlay>>>,
+    state_store: Arc<dyn StateStore>,
+    sessions: Arc<SessionStore>,
+    audit: Arc<AuditLog>,
+    approval: Option<Arc<dyn ApprovalPolicy>>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    extensions: Arc<Extensions>,
+    tool_search: Arc<ToolSearch>,
+    error_codes: Arc<RwLock<ErrorCodeRegistry>>,
+    limiter: Arc<RequestLimiter>,
+    memory: Arc<MemoryGuard>,
+    clock: Arc<dyn Clock>,
+    notifications: broadcast::Sender<Value>,
+    tools_list_changed: AtomicBool,
+    resources_list_changed: AtomicBool,
+    prompts_list_changed: AtomicBool,
+    negotiated: Negotiations,
+    session_channels: SessionChannels,
+    log_levels: LogLevels,
+    client_requests: Arc<PendingRequests>,
+    roots: Arc<RootsCache>,
+    tool_calls: Coalescer<CallToolResult>,
+    tool_pool: ToolPool,
+    in_flight: InFlight,
+    lifecycle: Lifecycle,
+    resource_subscriptions: std::sync::Mutex<HashMap<Option<String>, HashSet<String>>>,
+}
+
+/// What each client agreed to in its `initialize`, keyed by session;
+/// `None` is the client outside sessions
+type Negotiations = Arc<std::sync::Mutex<HashMap<Option<String>, Negotiated>>>;
+
+/// Capabilities exchanged in one client's `initialize`
+struct Negotiated {
+    protocol_version: &'static str,
+    client: Value,
+    client_info: Option<Value>,
+    server: Value,
+    /// Language of the client's error messages
+    locale: &'static str,
+}
+
+/// Whether `negotiated` lets the server send `method`
+fn permits(negotiated: Option<&Negotiated>, method: &str) -> bool {
+    let client = |capability: &str| negotiated.is_some_and(|n| n.client.get(capability).is_some());
+    let server = |capability: &str, flag: &str| {
+        negotiated.is_some_and(|n| n.server[capability][flag] == true)
+    };
+    match method {
+        "sampling/createMessage" => client("sampling"),
+        "roots/list" => client("roots"),
+        "elicitation/create" => client("elicitation"),
+        "notifications/resources/updated" => server("resources", "subscribe"),
+        _ => match method
+            .strip_prefix("notifications/")
+            .and_then(|m| m.strip_suffix("/list_changed"))
+        {
+            Some(list) => server(list, "listChanged"),
+            None => true,
+        },
+    }
+}
+
+/// Server-initiated notifications buffered per subscriber before it lags
+const NOTIFICATION_BUFFER: usize = 64;
+
+/// Keys of the session record's `data` holding the client's log level and
+/// resource subscriptions
+const LOG_LEVEL_KEY: &str = "logLevel";
+const SUBSCRIPTIONS_KEY: &str = "resourceSubscriptions";
+
+/// How long the client gets to answer a server-initiated request
+pub const CLIENT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+tokio::task_local! {
+    static CURRENT_CLIENT: ClientLink;
+}
+
+/// Everything needed to send one client a request and await its reply,
+/// detached from the server so background tasks can own it
+///
+/// Handlers reach the client of the request they handle through
+/// [`ClientLink::current`], e.g. to ask its LLM for a completion with
+/// [`ClientLink::create_message`].
+#[derive(Clone)]
+pub struct ClientLink {
+    session: Option<String>,
+    pending: Arc<PendingRequests>,
+    notifications: broadcast::Sender<Value>,
+    session_channels: SessionChannels,
+    clock: Arc<dyn Clock>,
+    gate: notifications::Gate,
+    roots: Arc<RootsCache>,
+}
+
+impl ClientLink {
+    /// Link to the client of the request being handled; `None` outside a
+    /// request
+    pub fn current() -> Option<Self> {
+        CURRENT_CLIENT.try_with(Clone::clone).ok()
+    }
+
+    /// Runs `future` with `link` as the current request's
+    pub fn scope<F: Future>(link: ClientLink, future: F) -> impl Future<Output = F::Output> {
+        CURRENT_CLIENT.scope(link, future)
+    }
+
+    /// Session the client belongs to, if any
+    pub fn session(&self) -> Option<&str> {
+        self.session.as_deref()
+    }
+
+    /// The client's roots, asking it with `roots/list` unless they are
+    /// cached
+    pub async fn roots(&self) -> Result<Vec<Root>> {
+        if let Some(roots) = self.roots.get(&self.session) {
+            return Ok(roots);
+        }
+        let roots = roots::parse_roots(self.request("roots/list", json!({})).await?)?;
+        self.roots.store(self.session.clone(), roots.clone());
+        Ok(roots)
+    }
+
+    /// The client's roots if they have been read
+    pub fn cached_roots(&self) -> Option<Vec<Root>> {
+        self.roots.get(&self.session)
+    }
+
+    /// Reads the client's roots again in the background and publishes them
+    fn refresh_roots(&self, logger: Logger) {
+        let link = self.clone();
+        runtime::spawn(async move {
+            let listed = link.request("roots/list", json!({})).await;
+            match listed.and_then(roots::parse_roots) {
+                Ok(listed) => link.roots.replace(link.session.clone(), listed),
+                Err(e) => logger.warn(&format!("Could not refresh roots: {}", e)),
+            }
+        });
+    }
+
+    /// Sends a request down the session's channel, or to the notification
+    /// subscribers outside sessions, and waits for the reply
+    ///
+    /// Fails like [`McpServer::request_client`].
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        if !(self.gate)(method) {
+            return Err(Error::InvalidRequest(format!(
+                "Client did not declare support for {}",
+                method
+            )));
+        }
+        let (id, reply) = self.pending.begin();
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params
+        });
+        let sent = match &self.session {
+            Some(session) => send_on(&self.session_channels, session, request),
+            None => self.notifications.send(request).is_ok(),
+        };
+        if !sent {
+            self.pending.forget(&id);
+            return Err(Error::InternalError(format!("No channel to send {} to the client", method)));
+        }
+
+        let timeout = self.clock.sleep(CLIENT_REQUEST_TIMEOUT);
+        match futures::future::select(reply, timeout).await {
+            Either::Left((Ok(result), _)) => result,
+            Either::Left((Err(_), _)) => Err(Error::InternalError(format!("{} was abandoned", method))),
+            Either::Right(_) => {
+                self.pending.forget(&id);
+                Err(Error::InternalError(format!(
+                    "Client did not answer {} within {:?}",
+                    method, CLIENT_REQUEST_TIMEOUT
+                )))
+            }
+        }
+    }
+}
+
+/// The example tools every server starts with; unregister or replace them
+/// like any other tool
+fn builtin_tools() -> HashMap<String, Arc<dyn ToolHandler>> {
+    let tools: [Arc<dyn ToolHandler>; 3] = [
+        Arc::new(GreetingTool::new()),
+        Arc::new(CalculatorTool::new()),
+        Arc::new(WeatherTool::new()),
+    ];
+    tools
+        .into_iter()
+        .filter_map(|tool| Some((tool.definition()?.name, tool)))
+        .collect()
 }
 
 impl McpServer {
@@ -43,268 +561,1717 @@ impl McpServer {
             config.name, config.version
         ));
 
+        let limiter = Arc::new(RequestLimiter::new(config.limits.clone()));
+        let memory = Arc::new(MemoryGuard::new(config.limits.max_memory_bytes));
+        let state_store: Arc<dyn StateStore> = Arc::new(MemoryStateStore::new());
+        let tool_pool = ToolPool::new(config.tool_pool.clone());
+        let list_changed = config.list_changed;
+        let tools = if config.features.tools {
+            builtin_tools()
+        } else {
+            HashMap::new()
+        };
+        let mut resources: HashMap<String, Arc<dyn ResourceHandler>> = HashMap::new();
+        if config.features.resources {
+            resources.insert("config://".to_string(), Arc::new(ConfigResource::new()));
+        }
+        let mut prompts: HashMap<String, Arc<dyn PromptHandler>> = HashMap::new();
+        if config.features.prompts {
+            prompts.insert("review-code".to_string(), Arc::new(CodeReviewPrompt::new()));
+        }
+
         Self {
             config,
             logger,
-            tools: Arc::new(Mutex::new(HashMap::new())),
-            resources: Arc::new(Mutex::new(HashMap::new())),
-            prompts: Arc::new(Mutex::new(HashMap::new())),
-            initialized: Arc::new(Mutex::new(false)),
+            tools: Snapshot::new(tools),
+            resources: Snapshot::new(resources),
+            prompts: Snapshot::new(prompts),
+            overlays: Arc::new(RwLock::new(HashMap::new())),
+            sessions: Arc::new(SessionStore::new(state_store.clone())),
+            audit: Arc::new(AuditLog::new(state_store.clone())),
+            approval: None,
+            middleware: Vec::new(),
+            extensions: Arc::new(Extensions::new()),
+            tool_search: Arc::new(ToolSearch::new()),
+            state_store,
+            error_codes: Arc::new(RwLock::new(ErrorCodeRegistry::new())),
+            limiter,
+            memory,
+            clock: Arc::new(SystemClock),
+            notifications: broadcast::channel(NOTIFICATION_BUFFER).0,
+            tools_list_changed: AtomicBool::new(list_changed),
+            resources_list_changed: AtomicBool::new(list_changed),
+            prompts_list_changed: AtomicBool::new(list_changed),
+            negotiated: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            session_channels: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            log_levels: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            client_requests: Arc::new(PendingRequests::new()),
+            roots: Arc::new(RootsCache::new()),
+            tool_calls: Coalescer::new(),
+            tool_pool,
+            in_flight: InFlight::new(),
+            lifecycle: Lifecycle::new(),
+            resource_subscriptions: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replaces the default in-memory state store, e.g. with a durable
+    /// `SledStateStore`, so stateful handlers survive restarts
+    pub fn with_state_store(mut self, state_store: Arc<dyn StateStore>) -> Self {
+        self.sessions = Arc::new(SessionStore::new(state_store.clone()));
+        self.audit = Arc::new(AuditLog::new(state_store.clone()));
+        self.state_store = state_store;
+        self
+    }
+
+    /// Reviews every call of a tool annotated `destructiveHint: true`
+    /// with `policy` before it runs
+    pub fn with_approval_policy(mut self, policy: Arc<dyn ApprovalPolicy>) -> Self {
+        self.approval = Some(policy);
+        self
+    }
+
+    /// Runs `middleware` around every method; middleware added first runs
+    /// first on the way in and last on the way out
+    pub fn with_middleware(mut self, middleware: Arc<dyn Middleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Shares `state` with every handler, which reads it back by type with
+    /// [`RequestContext::state`]; replaces earlier state of the same type
+    pub fn with_state<T: Send + Sync + 'static>(mut self, state: Arc<T>) -> Self {
+        Arc::make_mut(&mut self.extensions).insert(state);
+        self
+    }
+
+    /// Shares every value in `extensions` with the handlers, replacing the
+    /// state added so far
+    pub fn with_extensions(mut self, extensions: Extensions) -> Self {
+        self.extensions = Arc::new(extensions);
+        self
+    }
+
+    /// Shared state of type `T` added with [`McpServer::with_state`]
+    pub fn state<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.extensions.get()
+    }
+
+    /// Ranks tools for `x-tools/search` by semantic similarity with
+    /// `backend`'s embeddings instead of by keywords
+    pub fn with_embedding_backend(mut self, backend: Arc<dyn EmbeddingBackend>) -> Self {
+        self.tool_search = Arc::new(ToolSearch::with_embeddings(backend));
+        self
+    }
+
+    /// Security-relevant decisions, such as tool call approvals
+    pub fn audit_log(&self) -> Arc<AuditLog> {
+        self.audit.clone()
+    }
+
+    pub fn state_store(&self) -> Arc<dyn StateStore> {
+        self.state_store.clone()
+    }
+
+    /// Client sessions, persisted in the state store
+    pub fn sessions(&self) -> Arc<SessionStore> {
+        self.sessions.clone()
+    }
+
+    pub fn config(&self) -> &ServerConfig {
+        &self.config
+    }
+
+    /// Opens the channel carrying server-to-client messages for session
+    /// `id`, replacing any channel opened before
+    pub fn open_session_channel(&self, id: &str) -> mpsc::UnboundedReceiver<Value> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.lock_session_channels().insert(id.to_string(), tx);
+        rx
+    }
+
+    /// Sends a message down session `id`'s channel; returns whether the
+    /// session has one open
+    pub fn send_to_session(&self, id: &str, message: Value) -> bool {
+        send_on(&self.session_channels, id, message)
+    }
+
+    /// Ends session `id`: cancels its outstanding requests, drops its
+    /// handlers and channel and deletes its record. Returns whether it
+    /// existed.
+    pub async fn end_session(&self, id: &str) -> Result<bool> {
+        self.in_flight.cancel_session(id);
+        self.clear_session(id).await;
+        self.lock_session_channels().remove(id);
+        self.lock_log_levels().remove(&Some(id.to_string()));
+        self.lock_negotiated().remove(&Some(id.to_string()));
+        self.roots.invalidate(&Some(id.to_string()));
+        self.lifecycle.forget(id);
+        self.lock_resource_subscriptions().remove(&Some(id.to_string()));
+        self.sessions.remove(id).await
+    }
+
+    /// Resumes what a session restored from the state store had set up:
+    /// its lifecycle phase, log level and resource subscriptions. What this
+    /// process already knows about the session is kept.
+    #[cfg(feature = "native")]
+    pub(crate) fn restore_session(&self, state: &session::SessionState) {
+        let phase = if state.initialized { Phase::Operating } else { Phase::Initializing };
+        self.lifecycle.restore(state.id.clone(), phase);
+        let session = Some(state.id.clone());
+        if let Ok(Some(level)) = state.get::<LogLevel>(LOG_LEVEL_KEY) {
+            self.lock_log_levels().entry(session.clone()).or_insert(level);
+        }
+        if let Ok(Some(uris)) = state.get::<HashSet<String>>(SUBSCRIPTIONS_KEY) {
+            self.lock_resource_subscriptions().entry(session).or_insert(uris);
         }
     }
 
+    /// Records `value` under `key` in the current session's record, so it
+    /// survives a restart with a durable store; requests outside sessions
+    /// have nothing to record it in
+    async fn save_session_data<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        let Some(id) = session::current_session() else {
+            return Ok(());
+        };
+        let Some(mut state) = self.sessions.get(&id).await? else {
+            return Ok(());
+        };
+        state.set(key, value)?;
+        self.sessions.save(&state).await
+    }
+
+    fn lock_resource_subscriptions(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<Option<String>, HashSet<String>>> {
+        self.resource_subscriptions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_negotiated(&self) -> std::sync::MutexGuard<'_, HashMap<Option<String>, Negotiated>> {
+        self.negotiated.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_log_levels(&self) -> std::sync::MutexGuard<'_, HashMap<Option<String>, LogLevel>> {
+        self.log_levels.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_session_channels(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<String, mpsc::UnboundedSender<Value>>> {
+        self.session_channels.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Memory accounting shared by the server and any caches or blob
+    /// producers that want their usage to count towards the ceiling
+    pub fn memory_guard(&self) -> Arc<MemoryGuard> {
+        self.memory.clone()
+    }
+
+    /// Replaces the system clock, e.g. with a `MockClock` in tests
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        self.clock.clone()
+    }
+
     pub async fn register_tool(&self, name: String, handler: Arc<dyn ToolHandler>) -> Result<()> {
+        if !self.config.features.tools {
+            self.logger.warn(&format!("Tools disabled, skipping tool: {}", name));
+            return Ok(());
+        }
         self.logger.info(&format!("Registering tool: {}", name));
-        self.tools.lock().await.insert(name, handler);
+        self.tools.update(|tools| tools.insert(name, handler));
+        self.announce_list_changed("tools", None);
         Ok(())
     }
 
+    /// Removes a registered tool; returns whether it existed
+    pub async fn unregister_tool(&self, name: &str) -> bool {
+        let removed = self.tools.update(|tools| tools.remove(name)).is_some();
+        if removed {
+            self.logger.info(&format!("Unregistered tool: {}", name));
+            self.announce_list_changed("tools", None);
+        }
+        removed
+    }
+
+    /// Swaps the handler of a registered tool, returning the one it
+    /// replaced; fails if no tool is registered under `name`
+    pub async fn replace_tool(
+        &self,
+        name: &str,
+        handler: Arc<dyn ToolHandler>,
+    ) -> Result<Arc<dyn ToolHandler>> {
+        let previous = self.tools.update(|tools| {
+            let slot = tools
+                .get_mut(name)
+                .ok_or_else(|| Error::method_not_found(MessageKey::ToolNotFound, &[name]))?;
+            Ok::<_, Error>(std::mem::replace(slot, handler))
+        })?;
+        self.logger.info(&format!("Replaced tool: {}", name));
+        self.announce_list_changed("tools", None);
+        Ok(previous)
+    }
+
+    /// Advertises `tools.listChanged` to clients initializing from now on;
+    /// call it when tools can come and go at runtime
+    pub fn enable_tools_list_changed(&self) {
+        self.tools_list_changed.store(true, Ordering::Relaxed);
+    }
+
+    /// Advertises `resources.listChanged` to clients initializing from now on
+    pub fn enable_resources_list_changed(&self) {
+        self.resources_list_changed.store(true, Ordering::Relaxed);
+    }
+
+    /// Advertises `prompts.listChanged` to clients initializing from now on
+    pub fn enable_prompts_list_changed(&self) {
+        self.prompts_list_changed.store(true, Ordering::Relaxed);
+    }
+
+    /// Sends `notifications/<list>/list_changed` to the clients that were
+    /// told to expect it: those of `session` only, or everyone
+    fn announce_list_changed(&self, list: &str, session: Option<&str>) {
+        let method = format!("notifications/{}/list_changed", list);
+        // Registrations before `initialize` have nobody to tell
+        if !self.may_send(&method) {
+            return;
+        }
+        match session {
+            Some(session) => {
+                self.send_to_session(session, json!({ "jsonrpc": "2.0", "method": method, "params": {} }));
+            }
+            None => self.notify(&method, json!({})),
+        }
+    }
+
+    /// Sends a JSON-RPC notification to every connected client
+    ///
+    /// Transports that can push to their client (stdio) forward these as
+    /// they are sent; with no client subscribed the notification is dropped.
+    /// So is a notification no client was told to expect; see
+    /// [`McpServer::may_send`]. Transports serving several clients (TCP)
+    /// pass each one only what it negotiated.
+    pub fn notify(&self, method: &str, params: Value) {
+        if !self.lock_negotiated().values().any(|n| permits(Some(n), method)) {
+            self.logger.debug(&format!("Suppressing {}: not negotiated with any client", method));
+            return;
+        }
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        });
+        if self.config.validate_messages {
+            let version = self
+                .lock_negotiated()
+                .get(&session::current_session())
+                .map_or(compat::LATEST, |n| n.protocol_version);
+            let violations = spec::check_notification(version, &notification);
+            self.report_violations(&format!("{} notification", method), violations);
+        }
+        let _ = self.notifications.send(notification);
+    }
+
+    /// Sends `notifications/resources/updated` for `uri` to the clients
+    /// that subscribed to it; returns how many were told
+    pub fn resource_updated(&self, uri: &str) -> usize {
+        let subscribers: Vec<Option<String>> = self
+            .lock_resource_subscriptions()
+            .iter()
+            .filter(|(_, uris)| uris.contains(uri))
+            .map(|(session, _)| session.clone())
+            .collect();
+        let params = json!({ "uri": uri });
+        subscribers
+            .into_iter()
+            .filter(|session| match session {
+                Some(session) => self.send_to_session(
+                    session,
+                    json!({ "jsonrpc": "2.0", "method": "notifications/resources/updated", "params": params }),
+                ),
+                None => {
+                    self.notify("notifications/resources/updated", params.clone());
+                    true
+                }
+            })
+            .count()
+    }
+
+    /// Whether the client of the current request agreed to receive `method`
+    /// from the server
+    ///
+    /// List-change and resource-update notifications need the matching
+    /// capability to have been advertised in `initialize`, and sampling,
+    /// roots and elicitation requests need the client to have declared
+    /// support. Before a client initializes, none of these are sent; other
+    /// methods (progress, logging, ...) always are.
+    pub fn may_send(&self, method: &str) -> bool {
+        permits(self.lock_negotiated().get(&session::current_session()), method)
+    }
+
+    /// A [`NotificationSender`] for the client of the current request; the
+    /// handlers get theirs from [`notifications::current`]
+    pub fn notification_sender(&self) -> NotificationSender {
+        NotificationSender::new(
+            session::current_session(),
+            self.notifications.clone(),
+            self.session_channels.clone(),
+            self.gate(),
+            self.log_levels.clone(),
+        )
+    }
+
+    /// Capabilities the client of the current request declared in its
+    /// `initialize`
+    pub fn client_capabilities(&self) -> Option<Value> {
+        self.lock_negotiated().get(&session::current_session()).map(|n| n.client.clone())
+    }
+
+    /// `clientInfo` of the client of the current request, from its
+    /// `initialize`
+    pub async fn client_info(&self) -> Option<Value> {
+        if let Some(session) = session::current_session()
+            && let Ok(Some(state)) = self.sessions.get(&session).await
+            && state.client_info.is_some()
+        {
+            return state.client_info;
+        }
+        self.lock_negotiated()
+            .get(&session::current_session())
+            .and_then(|n| n.client_info.clone())
+    }
+
+    /// Protocol revision negotiated with the client of the current request
+    pub async fn protocol_version(&self) -> Option<&'static str> {
+        if let Some(session) = session::current_session()
+            && let Ok(Some(state)) = self.sessions.get(&session).await
+            && let Some(requested) = state.protocol_version
+        {
+            return Some(compat::negotiate(Some(&requested)).unwrap_or(compat::LATEST));
+        }
+        self.lock_negotiated()
+            .get(&session::current_session())
+            .map(|n| n.protocol_version)
+    }
+
+    /// Locale the client of the current request asked for in its
+    /// `initialize`, or the default
+    fn locale(&self) -> String {
+        self.lock_negotiated()
+            .get(&session::current_session())
+            .map_or(i18n::DEFAULT_LOCALE, |n| n.locale)
+            .to_string()
+    }
+
+    /// Lifecycle phase of the client of the current request
+    pub fn lifecycle_phase(&self) -> Phase {
+        self.lifecycle.phase(&session::current_session())
+    }
+
+    /// Enters the shutdown phase: from now on every request but `ping` is
+    /// answered with an error
+    pub fn begin_shutdown(&self) {
+        self.logger.info("Shutting down: no longer accepting requests");
+        self.lifecycle.begin_shutdown();
+    }
+
+    /// Shuts down gracefully: stops taking requests, then waits up to
+    /// `grace` for those in flight to be answered. Requests still running
+    /// after that are cancelled and returned, so they can be reported.
+    ///
+    /// Transports stop reading once shutdown begins and return from `serve`
+    /// after writing their last responses.
+    pub async fn shutdown(&self, grace: Duration) -> Vec<InFlightRequest> {
+        self.begin_shutdown();
+        let drained = Box::pin(self.in_flight.drained());
+        if let Either::Left(_) = futures::future::select(drained, Box::pin(runtime::sleep(grace))).await {
+            self.logger.info("All requests answered");
+            return Vec::new();
+        }
+        let abandoned = self.in_flight.list();
+        self.in_flight.cancel_all();
+        self.logger.warn(&format!(
+            "Cancelled {} requests still running after {:?}",
+            abandoned.len(),
+            grace
+        ));
+        abandoned
+    }
+
+    /// Resolves once shutdown has begun, for transports to stop reading
+    pub async fn shutdown_requested(&self) {
+        self.lifecycle.shutting_down().await
+    }
+
+    #[cfg(feature = "native")]
+    pub(crate) fn lifecycle(&self) -> &Lifecycle {
+        &self.lifecycle
+    }
+
+    /// Requests received but not yet answered, oldest first
+    pub fn in_flight(&self) -> Vec<InFlightRequest> {
+        self.in_flight.list()
+    }
+
+    /// Stops the request with `id` from `session` (`None` outside a
+    /// session); it gets no response. False if it isn't in flight.
+    pub fn cancel_request(&self, session: Option<&str>, id: &Value) -> bool {
+        let cancelled = self.in_flight.cancel(session, id);
+        if cancelled {
+            self.logger.info(&format!("Cancelled request {}", id));
+        }
+        cancelled
+    }
+
+    /// Sends a request to the client and waits for its result
+    ///
+    /// The request goes to the current session's client, or outside
+    /// sessions to the transport forwarding notifications (stdio). It fails
+    /// if the client did not declare support for `method` (see
+    /// [`McpServer::may_send`]), nothing can carry it, or no reply arrives
+    /// within [`CLIENT_REQUEST_TIMEOUT`]. Stdio keeps reading while
+    /// requests are handled, so a handler can await the reply there. TCP
+    /// and in-memory connections read it only after the current message
+    /// has been handled, so call this from a spawned task on those.
+    pub async fn request_client(&self, method: &str, params: Value) -> Result<Value> {
+        self.client_link().request(method, params).await
+    }
+
+    /// A [`ClientLink`] to the client of the current request; the handlers
+    /// get theirs from [`ClientLink::current`]
+    pub fn client_link(&self) -> ClientLink {
+        ClientLink {
+            session: session::current_session(),
+            pending: self.client_requests.clone(),
+            notifications: self.notifications.clone(),
+            session_channels: self.session_channels.clone(),
+            clock: self.clock.clone(),
+            gate: self.gate(),
+            roots: self.roots.clone(),
+        }
+    }
+
+    /// Whether the client of the current request agreed to receive a
+    /// method, detached from the server
+    fn gate(&self) -> notifications::Gate {
+        self.session_gate(session::current_session())
+    }
+
+    /// Whether the client of `session` agreed to receive a method, detached
+    /// from the server
+    pub(crate) fn session_gate(&self, session: Option<String>) -> notifications::Gate {
+        let negotiated = self.negotiated.clone();
+        Arc::new(move |method| {
+            permits(negotiated.lock().unwrap_or_else(|e| e.into_inner()).get(&session), method)
+        })
+    }
+
+    /// The current session's roots, asking the client with `roots/list`
+    /// unless they are cached
+    pub async fn roots(&self) -> Result<Vec<Root>> {
+        self.client_link().roots().await
+    }
+
+    /// Receives each session's roots after the client reported a change
+    /// and they were read again
+    pub fn subscribe_roots(&self) -> broadcast::Receiver<RootsChanged> {
+        self.roots.subscribe()
+    }
+
+    /// Receives the notifications passed to [`McpServer::notify`]
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<Value> {
+        self.notifications.subscribe()
+    }
+
+    /// Registers a resource handler; `name` is a URI prefix, and a read is
+    /// routed to the handler with the longest prefix of the requested URI
     pub async fn register_resource(
         &self,
         name: String,
         handler: Arc<dyn ResourceHandler>,
     ) -> Result<()> {
+        if !self.config.features.resources {
+            self.logger.warn(&format!("Resources disabled, skipping resource: {}", name));
+            return Ok(());
+        }
         self.logger.info(&format!("Registering resource: {}", name));
-        self.resources.lock().await.insert(name, handler);
+        self.resources.update(|resources| resources.insert(name, handler));
+        self.announce_list_changed("resources", None);
         Ok(())
     }
 
+    /// Removes the resource handler registered for `prefix`; returns
+    /// whether it existed
+    pub async fn unregister_resource(&self, prefix: &str) -> bool {
+        let removed = self.resources.update(|resources| resources.remove(prefix)).is_some();
+        if removed {
+            self.logger.info(&format!("Unregistered resource: {}", prefix));
+            self.announce_list_changed("resources", None);
+        }
+        removed
+    }
+
+    /// Swaps the handler registered for `prefix`, returning the one it
+    /// replaced; fails if no handler is registered for it
+    pub async fn replace_resource(
+        &self,
+        prefix: &str,
+        handler: Arc<dyn ResourceHandler>,
+    ) -> Result<Arc<dyn ResourceHandler>> {
+        let previous = self.resources.update(|resources| {
+            let slot = resources
+                .get_mut(prefix)
+                .ok_or_else(|| Error::resource_error(MessageKey::ResourceNotFound, &[prefix]))?;
+            Ok::<_, Error>(std::mem::replace(slot, handler))
+        })?;
+        self.logger.info(&format!("Replaced resource: {}", prefix));
+        self.announce_list_changed("resources", None);
+        Ok(previous)
+    }
+
     pub async fn register_prompt(
         &self,
         name: String,
         handler: Arc<dyn PromptHandler>,
     ) -> Result<()> {
+        if !self.config.features.prompts {
+            self.logger.warn(&format!("Prompts disabled, skipping prompt: {}", name));
+            return Ok(());
+        }
         self.logger.info(&format!("Registering prompt: {}", name));
-        self.prompts.lock().await.insert(name, handler);
+        self.prompts.update(|prompts| prompts.insert(name, handler));
+        self.announce_list_changed("prompts", None);
+        Ok(())
+    }
+
+    /// Removes a registered prompt; returns whether it existed
+    pub async fn unregister_prompt(&self, name: &str) -> bool {
+        let removed = self.prompts.update(|prompts| prompts.remove(name)).is_some();
+        if removed {
+            self.logger.info(&format!("Unregistered prompt: {}", name));
+            self.announce_list_changed("prompts", None);
+        }
+        removed
+    }
+
+    /// Swaps the handler of a registered prompt, returning the one it
+    /// replaced; fails if no prompt is registered under `name`
+    pub async fn replace_prompt(
+        &self,
+        name: &str,
+        handler: Arc<dyn PromptHandler>,
+    ) -> Result<Arc<dyn PromptHandler>> {
+        let previous = self.prompts.update(|prompts| {
+            let slot = prompts
+                .get_mut(name)
+                .ok_or_else(|| Error::method_not_found(MessageKey::PromptNotFound, &[name]))?;
+            Ok::<_, Error>(std::mem::replace(slot, handler))
+        })?;
+        self.logger.info(&format!("Replaced prompt: {}", name));
+        self.announce_list_changed("prompts", None);
+        Ok(previous)
+    }
+
+    /// Registers a tool visible only to `session_id`, shadowing a global
+    /// tool of the same name for that session
+    pub async fn register_session_tool(
+        &self,
+        session_id: &str,
+        name: String,
+        handler: Arc<dyn ToolHandler>,
+    ) -> Result<()> {
+        if !self.config.features.tools {
+            self.logger.warn(&format!("Tools disabled, skipping tool: {}", name));
+            return Ok(());
+        }
+        self.logger
+            .info(&format!("Registering tool {} for session {}", name, session_id));
+        let mut overlays = self.overlays.write().await;
+        overlays.entry(session_id.to_string()).or_default().tools.insert(name, handler);
+        drop(overlays);
+        self.announce_list_changed("tools", Some(session_id));
         Ok(())
     }
 
+    /// Registers a resource visible only to `session_id`; like global
+    /// resources, `prefix` is matched against the start of requested URIs
+    pub async fn register_session_resource(
+        &self,
+        session_id: &str,
+        prefix: String,
+        handler: Arc<dyn ResourceHandler>,
+    ) -> Result<()> {
+        if !self.config.features.resources {
+            self.logger.warn(&format!("Resources disabled, skipping resource: {}", prefix));
+            return Ok(());
+        }
+        self.logger
+            .info(&format!("Registering resource {} for session {}", prefix, session_id));
+        let mut overlays = self.overlays.write().await;
+        overlays.entry(session_id.to_string()).or_default().resources.insert(prefix, handler);
+        drop(overlays);
+        self.announce_list_changed("resources", Some(session_id));
+        Ok(())
+    }
+
+    /// Registers a prompt visible only to `session_id`
+    pub async fn register_session_prompt(
+        &self,
+        session_id: &str,
+        name: String,
+        handler: Arc<dyn PromptHandler>,
+    ) -> Result<()> {
+        if !self.config.features.prompts {
+            self.logger.warn(&format!("Prompts disabled, skipping prompt: {}", name));
+            return Ok(());
+        }
+        self.logger
+            .info(&format!("Registering prompt {} for session {}", name, session_id));
+        let mut overlays = self.overlays.write().await;
+        overlays.entry(session_id.to_string()).or_default().prompts.insert(name, handler);
+        drop(overlays);
+        self.announce_list_changed("prompts", Some(session_id));
+        Ok(())
+    }
+
+    /// Drops every handler registered for `session_id`
+    pub async fn clear_session(&self, session_id: &str) {
+        self.overlays.write().await.remove(session_id);
+    }
+
+    /// Reserves an implementation-defined JSON-RPC error code (-32099..=-32000)
+    /// for an application error type returned via `Error::application`
+    pub async fn reserve_error_code(&self, code: i64, name: impl Into<String>) -> Result<ErrorCode> {
+        let name = name.into();
+        self.logger.info(&format!("Reserving error code {}: {}", code, name));
+        self.error_codes.write().await.reserve(code, name)
+    }
+
     pub async fn handle_request(&self, message: Value) -> Result<Option<Value>> {
-        // Parse JSON-RPC message
-        let jsonrpc = message
-            .get("jsonrpc")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| Error::InvalidRequest("Missing jsonrpc field".to_string()))?;
+        if message.is_array() {
+            return self.handle_message(&message.to_string()).await;
+        }
+        let is_reply = message.get("method").is_none()
+            && (message.get("result").is_some() || message.get("error").is_some());
+        if is_reply && self.client_requests.complete(message.clone()) {
+            return Ok(None);
+        }
+        let request_size = approx_size(&message);
+        let envelope = match ValueRequest::from_value(message) {
+            Ok(envelope) => envelope,
+            Err(e) => return self.invalid_request(None, e).await.into_value().map(Some),
+        };
+        if let Err(e) = envelope.validate() {
+            return self.invalid_request(envelope.id, e).await.into_value().map(Some);
+        }
+
+        let RequestEnvelope { id, method, params, .. } = envelope;
+        let method = method.unwrap_or_default();
+        self.handle_validated(id, &method, params, request_size)
+            .await?
+            .map(Response::into_value)
+            .transpose()
+    }
+
+    /// Handles one raw JSON-RPC frame without building a `Value` for the
+    /// whole message; params are parsed only once the envelope is valid.
+    ///
+    /// Malformed JSON fails with `Error::Json`; structurally invalid requests
+    /// are answered with -32600. A JSON-RPC batch is answered with an array.
+    pub async fn handle_message(&self, raw: &str) -> Result<Option<Value>> {
+        self.handle_frame(raw)
+            .await?
+            .map(Reply::into_value)
+            .transpose()
+    }
+
+    /// Like [`McpServer::handle_message`], but returns the reply with its
+    /// results still serialized, ready to be written by a transport
+    pub async fn handle_frame(&self, raw: &str) -> Result<Option<Reply>> {
+        if protocol::is_batch(raw) {
+            return self.handle_batch(raw).await;
+        }
+        Ok(self.handle_single(raw).await?.map(Reply::Single))
+    }
 
-        if jsonrpc != "2.0" {
-            return Err(Error::InvalidRequest("Invalid jsonrpc version".to_string()));
+    /// Handles the messages of a JSON-RPC batch concurrently and gathers
+    /// their responses into one array; a batch of notifications gets none
+    async fn handle_batch(&self, raw: &str) -> Result<Option<Reply>> {
+        let entries: Vec<&RawValue> = serde_json::from_str(raw)?;
+        let max = self.config.limits.max_batch_size;
+        let rejection = if entries.is_empty() {
+            Some("Empty batch".to_string())
+        } else if max > 0 && entries.len() > max {
+            Some(format!("Batch of {} messages exceeds the limit of {}", entries.len(), max))
+        } else {
+            None
+        };
+        if let Some(rejection) = rejection {
+            let response = self.invalid_request(None, Error::InvalidRequest(rejection)).await;
+            return Ok(Some(Reply::Single(response)));
         }
 
+        let handled = futures::future::join_all(
+            entries.iter().map(|entry| self.handle_batch_entry(entry.get())),
+        )
+        .await;
+        let mut responses = Vec::with_capacity(handled.len());
+        for (entry, result) in entries.iter().zip(handled) {
+            match result {
+                Ok(response) => responses.extend(response),
+                Err(e) if e.is_fatal() => return Err(e),
+                Err(e) => {
+                    self.logger.error_with_context("Failed to handle batched message", &e.to_string());
+                    responses.extend(Response::for_failed_frame(entry.get(), &e));
+                }
+            }
+        }
+        Ok((!responses.is_empty()).then_some(Reply::Batch(responses)))
+    }
+
+    async fn handle_batch_entry(&self, raw: &str) -> Result<Option<Response>> {
+        let rejection = if protocol::is_batch(raw) {
+            Some("Batches can't be nested")
+        } else if RawRequest::parse(raw).is_ok_and(|e| e.method.as_deref() == Some("initialize")) {
+            // The handshake has to come on its own
+            Some("initialize can't be part of a batch")
+        } else {
+            None
+        };
+        match rejection {
+            Some(rejection) => {
+                let id = RawRequest::parse(raw).ok().and_then(|e| e.id);
+                let error = Error::InvalidRequest(rejection.to_string());
+                Ok(Some(self.invalid_request(id, error).await))
+            }
+            None => self.handle_single(raw).await,
+        }
+    }
+
+    /// Handles a frame holding a single message
+    async fn handle_single(&self, raw: &str) -> Result<Option<Response>> {
+        let envelope = match RawRequest::parse(raw) {
+            Ok(envelope) => envelope,
+            Err(Error::Json(e)) => return Err(Error::Json(e)),
+            Err(e) => return Ok(Some(self.invalid_request(None, e).await)),
+        };
+        if envelope.method.is_none() && envelope.id.is_some() && self.complete_client_request(raw) {
+            return Ok(None);
+        }
+        if let Err(e) = envelope.validate() {
+            return Ok(Some(self.invalid_request(envelope.id, e).await));
+        }
+
+        let params = envelope.params_value()?;
+        let method = envelope.method.as_deref().unwrap_or_default();
+        self.handle_validated(envelope.id.clone(), method, params, raw.len() as u64)
+            .await
+    }
+
+    /// Hands a response from the client to the request it answers; false
+    /// if `raw` is no response or nothing is waiting for it
+    fn complete_client_request(&self, raw: &str) -> bool {
+        match serde_json::from_str::<Value>(raw) {
+            Ok(reply) if reply.get("result").is_some() || reply.get("error").is_some() => {
+                self.client_requests.complete(reply)
+            }
+            _ => false,
+        }
+    }
+
+    /// Answers an invalid request with -32600, using a null id when the
+    /// request did not carry one
+    async fn invalid_request(&self, id: Option<Value>, e: Error) -> Response {
+        self.logger.warn(&format!("Rejecting invalid request: {}", e));
+        let error = self.error_object(e).await;
+        Response::error(id.unwrap_or(Value::Null), error)
+    }
+
+    async fn handle_validated(
+        &self,
+        id: Option<Value>,
+        method: &str,
+        params: Option<Value>,
+        request_size: u64,
+    ) -> Result<Option<Response>> {
+        let response = self.answer(id, method, params, request_size).await?;
+        if self.config.validate_messages
+            && let Some(response) = &response
+        {
+            let version = self.protocol_version().await.unwrap_or(compat::LATEST);
+            let violations = spec::check_response(version, method, &serde_json::to_value(response)?);
+            self.report_violations(&format!("{} response", method), violations);
+        }
+        Ok(response)
+    }
+
+    /// Reports messages that break the MCP schema: loudly in debug builds,
+    /// as an error log otherwise
+    fn report_violations(&self, message: &str, violations: Vec<String>) {
+        if violations.is_empty() {
+            return;
+        }
+        let report = format!("{} violates the MCP schema: {}", message, violations.join("; "));
+        if cfg!(debug_assertions) {
+            panic!("{}", report);
+        }
+        self.logger.error(&report);
+    }
+
+    async fn answer(
+        &self,
+        id: Option<Value>,
+        method: &str,
+        params: Option<Value>,
+        request_size: u64,
+    ) -> Result<Option<Response>> {
         // Check if it's a notification (no id field)
-        let id = message.get("id");
         let is_notification = id.is_none();
 
-        let method = message
-            .get("method")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| Error::InvalidRequest("Missing method".to_string()))?;
+        // The deadline starts on arrival, so time spent queued counts
+        let timeout = deadline::timeout_hint(params.as_ref()).or_else(|| {
+            let default = self.config.limits.default_timeout_ms;
+            (default > 0).then(|| Duration::from_millis(default))
+        });
+        let request_deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let request_meta = RequestMeta::from_params(params.as_ref());
 
-        self.logger.debug(&format!("Handling request: {}", method));
+        if self.logger.debug_enabled() {
+            self.logger.debug(&format!("Handling request: {}", method));
+        }
 
-        let result = match method {
-            "initialize" => self.handle_initialize(&message).await,
-            "initialized" => self.handle_initialized(&message).await,
-            "ping" => self.handle_ping(&message).await,
-            "tools/list" => self.handle_tools_list(&message).await,
-            "tools/call" => self.handle_tools_call(&message).await,
-            "resources/list" => self.handle_resources_list(&message).await,
-            "resources/read" => self.handle_resources_read(&message).await,
-            "prompts/list" => self.handle_prompts_list(&message).await,
-            "prompts/get" => self.handle_prompts_get(&message).await,
-            _ => Err(Error::MethodNotFound(method.to_string())),
+        let session = session::current_session();
+        if let Err(e) = self.lifecycle.admit(&session, method, self.config.strict_lifecycle) {
+            self.logger.warn(&format!("Rejecting {}: {}", method, e));
+            return Ok(self.respond(id, Err(e)).await);
+        }
+
+        // Ids must be unique among a session's outstanding requests
+        let (_in_flight, registration, cancellation) = match &id {
+            Some(request_id) => {
+                match self.in_flight.begin(session.clone(), request_id, method, self.clock.now()) {
+                    Ok((guard, registration, cancellation)) => {
+                        (Some(guard), Some(registration), cancellation)
+                    }
+                    Err(e) => return Ok(self.respond(id, Err(e)).await),
+                }
+            }
+            None => (None, None, CancellationToken::new()),
         };
 
-        // Handle result and create response
-        if is_notification {
-            Ok(None) // No response for notifications
+        let _permit = if RequestLimiter::is_exempt(method) {
+            None
         } else {
-            let response = match result {
-                Ok(result_value) => {
-                    json!({
-                        "jsonrpc": "2.0",
-                        "id": id,
-                        "result": result_value
-                    })
+            match self.limiter.acquire(method).await {
+                Ok(permit) => permit,
+                Err(e) if is_notification => {
+                    self.logger.warn(&format!("Dropping notification {}: {}", method, e));
+                    return Ok(None);
                 }
+                Err(e) => return Ok(self.respond(id, Err(e)).await),
+            }
+        };
+
+        let _request_memory = self.memory.reserve(request_size);
+        if self.memory.under_pressure() && matches!(method, "tools/call" | "resources/read") {
+            self.logger.warn(&format!("Shedding {} under memory pressure", method));
+            let error = self.memory.pressure_error(self.memory.used(), 0);
+            return Ok(self.respond(id, Err(error)).await);
+        }
+
+        let context = RequestContext::new(self.notification_sender())
+            .with_request_id(id.clone())
+            .with_client_info(self.client_info().await)
+            .with_progress_token(request_meta.as_ref().and_then(|m| m.progress_token.clone()))
+            .with_cancellation(cancellation)
+            .with_extensions(self.extensions.clone())
+            .with_clock(self.clock.clone());
+        let locale = self.locale();
+        // Boxed: the nested scopes make this future too large for a worker
+        // thread's stack in debug builds
+        let request = Box::pin(i18n::with_locale(
+            locale,
+            deadline::with_deadline(
+                request_deadline,
+                meta::with_meta(
+                    request_meta,
+                    notifications::with_sender(
+                        self.notification_sender(),
+                        ClientLink::scope(
+                            self.client_link(),
+                            context::with_context(context, self.dispatch_through_middleware(&id, method, params)),
+                        ),
+                    ),
+                ),
+            ),
+        ));
+        let result = match registration {
+            Some(registration) => match Abortable::new(request, registration).await {
+                Ok(result) => result,
+                // Cancelled requests are not answered
+                Err(Aborted) => return Ok(None),
+            },
+            None => request.await,
+        };
+        let result = match result {
+            Ok(raw) => self.downgrade(method, raw).await,
+            Err(e) => Err(e),
+        };
+        let _result_memory = result
+            .as_ref()
+            .ok()
+            .map(|raw| self.memory.reserve(raw.get().len() as u64));
+
+        Ok(self.respond(id, result).await)
+    }
+
+    /// Rewrites a result for a client on an older protocol revision
+    async fn downgrade(&self, method: &str, raw: Box<RawValue>) -> Result<Box<RawValue>> {
+        match self.protocol_version().await {
+            Some(version) if compat::needs_downgrade(version) => {
+                let mut result: Value = serde_json::from_str(raw.get())?;
+                compat::downgrade(version, method, &mut result);
+                Ok(to_raw_value(&result)?)
+            }
+            _ => Ok(raw),
+        }
+    }
+
+    /// Builds the response for a request; notifications (no id) get none
+    async fn respond(&self, id: Option<Value>, result: Result<Box<RawValue>>) -> Option<Response> {
+        let id = id?;
+        Some(match result {
+            Ok(result) => Response::success(id, result),
+            Err(e) => Response::error(id, self.error_object(e).await),
+        })
+    }
+
+    /// Dispatches `method` with the middleware's hooks around it
+    async fn dispatch_through_middleware(
+        &self,
+        id: &Option<Value>,
+        method: &str,
+        mut params: Option<Value>,
+    ) -> Result<Box<RawValue>> {
+        if self.middleware.is_empty() {
+            return self.dispatch(method, params).await;
+        }
+        let request = RequestInfo {
+            method: method.to_string(),
+            id: id.clone(),
+            session: session::current_session(),
+            identity: access::current_identity(),
+            started: Instant::now(),
+        };
+
+        // A rejection stops the way in; only the middleware it got through,
+        // the rejecting one included, hears about it
+        let mut entered = 0;
+        let mut rejection = None;
+        for middleware in &self.middleware {
+            entered += 1;
+            if let Err(e) = middleware.before_request(&request, &mut params).await {
+                rejection = Some(e);
+                break;
+            }
+        }
+        let mut result = match rejection {
+            Some(e) => Err(e),
+            None => self.dispatch(method, params).await,
+        };
+
+        for middleware in self.middleware[..entered].iter().rev() {
+            result = match result {
+                Ok(raw) => match middleware.after_response(&request, &raw).await {
+                    Ok(()) => Ok(raw),
+                    Err(e) => {
+                        middleware.on_error(&request, &e).await;
+                        Err(e)
+                    }
+                },
                 Err(e) => {
-                    let (code, message) = match e {
-                        Error::MethodNotFound(_) => (-32601, e.to_string()),
-                        Error::InvalidParams(_) => (-32602, e.to_string()),
-                        Error::InvalidRequest(_) => (-32600, e.to_string()),
-                        _ => (-32603, e.to_string()),
-                    };
-
-                    json!({
-                        "jsonrpc": "2.0",
-                        "id": id,
-                        "error": {
-                            "code": code,
-                            "message": message
-                        }
-                    })
+                    middleware.on_error(&request, &e).await;
+                    Err(e)
                 }
             };
+        }
+        result
+    }
 
-            Ok(Some(response))
+    async fn dispatch(&self, method: &str, params: Option<Value>) -> Result<Box<RawValue>> {
+        self.authorize(method, params.as_ref())?;
+        if !self.config.features.allows_method(method) {
+            return Err(Error::MethodNotFound(method.to_string()));
         }
+        let cursor = match method {
+            "tools/list" | "resources/list" | "prompts/list" => {
+                PaginatedParams::from_optional_params(params.clone())?.cursor
+            }
+            _ => None,
+        };
+        // Results that can be large are serialized by their handlers
+        // straight from the typed result
+        let result = match method {
+            "initialize" => self.handle_initialize(params).await,
+            "initialized" | "notifications/initialized" => self.handle_initialized(params).await,
+            "notifications/cancelled" => self.handle_cancelled(params).await,
+            "notifications/roots/list_changed" => self.handle_roots_list_changed(params).await,
+            "ping" => self.handle_ping(params).await,
+            "logging/setLevel" => self.handle_set_level(params).await,
+            "completion/complete" => self.handle_complete(params).await,
+            "tools/list" => self.handle_tools_list(params).await,
+            "tools/call" => return self.handle_tools_call(params).await,
+            "x-tools/search" => self.handle_tools_search(params).await,
+            "resources/list" => self.handle_resources_list(params).await,
+            "resources/read" => return self.handle_resources_read(params).await,
+            "resources/subscribe" => self.handle_resources_subscribe(params, true).await,
+            "resources/unsubscribe" => self.handle_resources_subscribe(params, false).await,
+            "prompts/list" => self.handle_prompts_list(params).await,
+            "prompts/get" => self.handle_prompts_get(params).await,
+            _ => Err(Error::MethodNotFound(method.to_string())),
+        };
+        let mut result = result?;
+        self.hide_denied(method, &mut result);
+        self.paginate(method, cursor.as_deref(), &mut result)?;
+        Ok(to_raw_value(&result)?)
     }
 
-    async fn handle_initialize(&self, _message: &Value) -> Result<Value> {
+    /// Rejects a call the access policy doesn't allow the caller to make
+    fn authorize(&self, method: &str, params: Option<&Value>) -> Result<()> {
+        let Some(policy) = &self.config.access else {
+            return Ok(());
+        };
+        let (target, key) = match method {
+            "tools/call" => (access::Target::Tool, "name"),
+            "resources/read" | "resources/subscribe" => (access::Target::Resource, "uri"),
+            "prompts/get" => (access::Target::Prompt, "name"),
+            _ => return Ok(()),
+        };
+        // Missing names are left for the handler to reject
+        let Some(name) = params.and_then(|p| p.get(key)).and_then(Value::as_str) else {
+            return Ok(());
+        };
+        let identity = access::current_identity();
+        if policy.allows(identity.as_deref(), target, name) {
+            Ok(())
+        } else {
+            self.logger.warn(&format!(
+                "Denied {} {} to {}",
+                method,
+                name,
+                identity.as_deref().unwrap_or("anonymous")
+            ));
+            Err(access::denied(identity.as_deref(), target, name))
+        }
+    }
+
+    /// Leaves what the caller may not use out of a listing
+    fn hide_denied(&self, method: &str, result: &mut Value) {
+        let Some(policy) = &self.config.access else {
+            return;
+        };
+        let (target, list, key) = match method {
+            "tools/list" => (access::Target::Tool, "tools", "name"),
+            "resources/list" => (access::Target::Resource, "resources", "uri"),
+            "prompts/list" => (access::Target::Prompt, "prompts", "name"),
+            _ => return,
+        };
+        let identity = access::current_identity();
+        if let Some(items) = result.get_mut(list).and_then(Value::as_array_mut) {
+            items.retain(|item| {
+                let name = item.get(key).and_then(Value::as_str).unwrap_or_default();
+                policy.allows(identity.as_deref(), target, name)
+            });
+        }
+    }
+
+    /// Cuts a list result down to the page at `cursor`, adding the next
+    /// page's cursor
+    fn paginate(&self, method: &str, cursor: Option<&str>, result: &mut Value) -> Result<()> {
+        let list = match method {
+            "tools/list" => "tools",
+            "resources/list" => "resources",
+            "prompts/list" => "prompts",
+            _ => return Ok(()),
+        };
+        let Some(items) = result.get_mut(list).and_then(Value::as_array_mut) else {
+            return Ok(());
+        };
+        if let Some(next) = pagination::paginate(items, cursor, self.config.page_size)? {
+            result["nextCursor"] = json!(next);
+        }
+        Ok(())
+    }
+
+    /// Resolves a tool for the current request: the session's overlay
+    /// first, then the global registry
+    async fn find_tool(&self, name: &str) -> Option<Arc<dyn ToolHandler>> {
+        if let Some(session) = session::current_session()
+            && let Some(tool) = self
+                .overlays
+                .read()
+                .await
+                .get(&session)
+                .and_then(|o| o.tools.get(name))
+        {
+            return Some(tool.clone());
+        }
+        self.tools.load().get(name).cloned()
+    }
+
+    /// Resolves the resource handler with the longest prefix of `uri`,
+    /// preferring the session's overlay
+    async fn find_resource(&self, uri: &str) -> Option<Arc<dyn ResourceHandler>> {
+        fn longest_match(
+            handlers: &HashMap<String, Arc<dyn ResourceHandler>>,
+            uri: &str,
+        ) -> Option<Arc<dyn ResourceHandler>> {
+            handlers
+                .iter()
+                .filter(|(prefix, _)| uri.starts_with(prefix.as_str()))
+                .max_by_key(|(prefix, _)| prefix.len())
+                .map(|(_, handler)| handler.clone())
+        }
+
+        if let Some(session) = session::current_session()
+            && let Some(handler) = self
+                .overlays
+                .read()
+                .await
+                .get(&session)
+                .and_then(|o| longest_match(&o.resources, uri))
+        {
+            return Some(handler);
+        }
+        longest_match(&self.resources.load(), uri)
+    }
+
+    async fn find_prompt(&self, name: &str) -> Option<Arc<dyn PromptHandler>> {
+        if let Some(session) = session::current_session()
+            && let Some(prompt) = self
+                .overlays
+                .read()
+                .await
+                .get(&session)
+                .and_then(|o| o.prompts.get(name))
+        {
+            return Some(prompt.clone());
+        }
+        self.prompts.load().get(name).cloned()
+    }
+
+    async fn error_object(&self, e: Error) -> Value {
+        if let Error::Application { code, message, data } = e {
+            if !error_codes::is_standard(code) && !self.error_codes.read().await.is_reserved(code) {
+                self.logger.warn(&format!("Unreserved application error code: {}", code));
+                return json!({
+                    "code": -32603,
+                    "message": format!("Internal error: {}", message)
+                });
+            }
+
+            let mut error = json!({
+                "code": code,
+                "message": message
+            });
+            if let Some(data) = data {
+                error["data"] = data;
+            }
+            return error;
+        }
+
+        let locale = self.locale();
+        let (message, key) = i18n::localize_error(&locale, &e);
+        let mut error = json!({
+            "code": e.code(),
+            "message": message
+        });
+        if let Some(key) = key {
+            error["data"] = json!({ "messageKey": key });
+        }
+        error
+    }
+
+    /// The capabilities to advertise to the current client, derived from
+    /// the enabled features and what is registered for it
+    ///
+    /// A list is offered once it has something in it, or when `listChanged`
+    /// will tell the client as soon as it has.
+    async fn capabilities(&self) -> serde_json::Map<String, Value> {
+        let features = &self.config.features;
+        let overlay = match session::current_session() {
+            Some(session) => self.overlays.read().await.get(&session).map(|o| {
+                (!o.tools.is_empty(), !o.resources.is_empty(), !o.prompts.is_empty())
+            }),
+            None => None,
+        };
+        let (session_tools, session_resources, session_prompts) = overlay.unwrap_or_default();
+        let offer = |enabled: bool, registered: bool, list_changed: &AtomicBool| {
+            let list_changed = list_changed.load(Ordering::Relaxed);
+            if !enabled || !(registered || list_changed) {
+                return None;
+            }
+            Some(if list_changed { json!({ "listChanged": true }) } else { json!({}) })
+        };
+        let tools = offer(
+            features.tools,
+            session_tools || !self.tools.load().is_empty(),
+            &self.tools_list_changed,
+        );
+        let mut resources = offer(
+            features.resources,
+            session_resources || !self.resources.load().is_empty(),
+            &self.resources_list_changed,
+        );
+        let prompts = offer(
+            features.prompts,
+            session_prompts || !self.prompts.load().is_empty(),
+            &self.prompts_list_changed,
+        );
+        if self.config.resource_subscriptions
+            && let Some(resources) = &mut resources
+        {
+            resources["subscribe"] = json!(true);
+        }
+
+        let mut capabilities = serde_json::Map::new();
+        capabilities.insert("logging".to_string(), json!({}));
+        if resources.is_some() || prompts.is_some() {
+            capabilities.insert("completions".to_string(), json!({}));
+        }
+        if tools.is_some() {
+            capabilities.insert("experimental".to_string(), json!({ "x-tools/search": {} }));
+        }
+        for (name, list) in [("tools", tools), ("resources", resources), ("prompts", prompts)] {
+            if let Some(list) = list {
+                capabilities.insert(name.to_string(), list);
+            }
+        }
+        merge_capabilities(&mut capabilities, self.config.capabilities.clone());
+        capabilities
+    }
+
+    async fn handle_initialize(&self, params: Option<Value>) -> Result<Value> {
         self.logger.info("Handling initialize request:");
+        let params = InitializeParams::from_optional_params(params)?;
+        let protocol_version = compat::negotiate(params.protocol_version.as_deref())?;
+
+        let locale = match &params.locale {
+            Some(requested) => {
+                let locale = i18n::negotiate(requested);
+                self.logger.debug(&format!("Client locale {} -> {}", requested, locale));
+                locale
+            }
+            None => i18n::DEFAULT_LOCALE,
+        };
 
-        let mut initialized = self.initialized.lock().await;
-        *initialized = true;
-        drop(initialized);
+        let capabilities = self.capabilities().await;
 
-        Ok(json!({
-            "protocolVersion": crate::PROTOCOL_VERSION,
-            "capabilities": {
-                "tools": {},
-                "resources": {},
-                "prompts": {}
+        let negotiated = Negotiated {
+            protocol_version,
+            client: params.capabilities,
+            client_info: params.client_info.map(serde_json::to_value).transpose()?,
+            server: Value::Object(capabilities.clone()),
+            locale,
+        };
+        self.lock_negotiated().insert(session::current_session(), negotiated);
+
+        let result = InitializeResult {
+            protocol_version: protocol_version.to_string(),
+            capabilities,
+            server_info: Implementation {
+                name: self.config.name.clone(),
+                version: self.config.version.clone(),
+                title: self.config.title.clone(),
+                website_url: self.config.website_url.clone(),
+                icons: self.config.icons.clone(),
             },
-            "serverInfo": {
-                "name": self.config.name,
-                "version": self.config.version
-            }
-        }))
+            instructions: self.config.instructions.clone(),
+        };
+        self.lifecycle.enter(session::current_session(), Phase::Initializing);
+        Ok(serde_json::to_value(result)?)
     }
 
-    async fn handle_initialized(&self, _message: &Value) -> Result<Value> {
+    async fn handle_initialized(&self, _params: Option<Value>) -> Result<Value> {
         self.logger.info("Server initialized");
+        self.lifecycle.enter(session::current_session(), Phase::Operating);
+        // Warm the roots cache so handlers find them without waiting on the
+        // client
+        if self.may_send("roots/list") {
+            self.client_link().refresh_roots(self.logger.clone());
+        }
+        Ok(json!({}))
+    }
+
+    async fn handle_cancelled(&self, params: Option<Value>) -> Result<Value> {
+        let CancelledParams { request_id, .. } = CancelledParams::from_params(params)?;
+        let session = session::current_session();
+        if !self.cancel_request(session.as_deref(), &request_id) {
+            // The request may have finished already; that's not an error
+            self.logger.debug(&format!("Nothing to cancel for request {}", request_id));
+        }
+        Ok(json!({}))
+    }
+
+    async fn handle_roots_list_changed(&self, _params: Option<Value>) -> Result<Value> {
+        let session = session::current_session();
+        self.roots.invalidate(&session);
+        if !self.may_send("roots/list") {
+            return Ok(json!({}));
+        }
+
+        // Read them again in the background: stdio only reads the client's
+        // reply once this notification has been handled
+        self.client_link().refresh_roots(self.logger.clone());
         Ok(json!({}))
     }
 
-    async fn handle_ping(&self, _message: &Value) -> Result<Value> {
+    async fn handle_ping(&self, _params: Option<Value>) -> Result<Value> {
         self.logger.debug("Handling ping");
         Ok(json!({}))
     }
 
-    async fn handle_tools_list(&self, _message: &Value) -> Result<Value> {
+    /// Sets the least severe `notifications/message` the caller's session
+    /// receives
+    async fn handle_set_level(&self, params: Option<Value>) -> Result<Value> {
+        let SetLevelParams { level } = SetLevelParams::from_params(params)?;
+        self.logger.debug(&format!("Client log level set to {:?}", level));
+        self.lock_log_levels().insert(session::current_session(), level);
+        self.save_session_data(LOG_LEVEL_KEY, &level).await?;
+        Ok(json!({}))
+    }
+
+    /// Asks the prompt or resource handler the request refers to for
+    /// suggestions; handlers that don't complete suggest nothing
+    async fn handle_complete(&self, params: Option<Value>) -> Result<Value> {
+        let request = CompletionRequest::from_params(params)?;
+        let completion = match &request.reference {
+            Reference::Prompt { name } => {
+                let handler = self
+                    .find_prompt(name)
+                    .await
+                    .ok_or_else(|| Error::invalid_params(MessageKey::PromptNotFound, &[name]))?;
+                match handler.completable() {
+                    Some(completable) => completable.complete(&request).await?,
+                    None => Completion::default(),
+                }
+            }
+            Reference::Resource { uri } => {
+                let handler = self
+                    .find_resource(uri)
+                    .await
+                    .ok_or_else(|| Error::invalid_params(MessageKey::ResourceNotFound, &[uri]))?;
+                match handler.completable() {
+                    Some(completable) => completable.complete(&request).await?,
+                    None => Completion::default(),
+                }
+            }
+        };
+        Ok(json!({ "completion": completion }))
+    }
+
+    async fn handle_tools_list(&self, _params: Option<Value>) -> Result<Value> {
         self.logger.debug("Listing tools");
 
-        let tools = vec![
-            GreetingTool::tool_definition(),
-            CalculatorTool::tool_definition(),
-            WeatherTool::tool_definition(),
-        ];
+        let mut registered: HashMap<String, Tool> = self
+            .tools
+            .load()
+            .values()
+            .filter_map(|t| t.definition())
+            .map(|t| (t.name.clone(), t))
+            .collect();
+        if let Some(session) = session::current_session()
+            && let Some(overlay) = self.overlays.read().await.get(&session)
+        {
+            registered.extend(
+                overlay
+                    .tools
+                    .values()
+                    .filter_map(|t| t.definition())
+                    .map(|t| (t.name.clone(), t)),
+            );
+        }
+        let mut tools: Vec<Tool> = registered.into_values().collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
 
         Ok(json!({
             "tools": tools
         }))
     }
 
-    async fn handle_tools_call(&self, message: &Value) -> Result<Value> {
-        let params = message
-            .get("params")
-            .ok_or_else(|| Error::InvalidParams("Missing params".to_string()))?;
+    /// Ranks the tools the caller may use against `query`, best first,
+    /// adding each match's `score`
+    async fn handle_tools_search(&self, params: Option<Value>) -> Result<Value> {
+        if !self.config.features.tools {
+            return Err(Error::MethodNotFound("x-tools/search".to_string()));
+        }
+        let params = SearchToolsParams::from_params(params)?;
+        let query = params.query.as_str();
+        let limit = params.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
 
-        let tool_name = params
-            .get("name")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| Error::InvalidParams("Missing tool name".to_string()))?;
+        let mut listed = self.handle_tools_list(None).await?;
+        self.hide_denied("tools/list", &mut listed);
+        let tools: Vec<Tool> = serde_json::from_value(listed["tools"].take())?;
+        let ranked = self.tool_search.rank(query, tools, limit).await?;
+        let tools: Vec<Value> = ranked
+            .into_iter()
+            .map(|(tool, score)| {
+                let mut tool = serde_json::to_value(tool)?;
+                tool["score"] = json!(score);
+                Ok(tool)
+            })
+            .collect::<Result<_>>()?;
+        Ok(json!({ "tools": tools }))
+    }
 
-        let arguments = params
-            .get("arguments")
-            .cloned()
-            .unwrap_or(json!({}));
+    async fn handle_tools_call(&self, params: Option<Value>) -> Result<Box<RawValue>> {
+        let CallToolParams { name, arguments } = CallToolParams::from_params(params)?;
+        let tool_name = name.as_str();
 
         self.logger.debug(&format!("Calling tool: {}", tool_name));
 
-        // Match tool by name and call the appropriate handler
-        let result = match tool_name {
-            "greet" => {
-                let handler = GreetingTool::new();
-                handler.call(arguments).await?
-            }
-            "calculate-bmi" => {
-                let handler = CalculatorTool::new();
-                handler.call(arguments).await?
+        let handler = self
+            .find_tool(tool_name)
+            .await
+            .ok_or_else(|| Error::method_not_found(MessageKey::ToolNotFound, &[tool_name]))?;
+        let definition = handler.definition();
+        let hint = |name: &str| {
+            definition
+                .as_ref()
+                .and_then(|tool| tool.annotations.as_ref())
+                .is_some_and(|a| a.get(name) == Some(&Value::Bool(true)))
+        };
+        if let Some(tool) = &definition
+            && let Some(invalid) = check_input(tool, &arguments)?
+        {
+            return Ok(to_raw_value(&invalid)?);
+        }
+        if hint("destructiveHint")
+            && let Some(denied) = self.review_call(tool_name, &arguments).await?
+        {
+            return Ok(to_raw_value(&denied)?);
+        }
+        // Identical concurrent calls of an idempotent tool, e.g. from
+        // client retries, share one execution
+        let idempotent = hint("idempotentHint");
+        let result = if idempotent {
+            let key = json!([session::current_session(), tool_name, arguments]).to_string();
+            let pool = self.tool_pool.clone();
+            let name = tool_name.to_string();
+            let context = self.request_context();
+            let (result, shared) = self
+                .tool_calls
+                .run(key, async move {
+                    pool.run(&name, async move { handler.call(&context, arguments).await }).await?
+                })
+                .await;
+            if shared {
+                self.logger.debug(&format!("Coalesced call of {}", tool_name));
             }
-            "fetch-weather" => {
-                let handler = WeatherTool::new();
-                handler.call(arguments).await?
+            result?
+        } else {
+            self.execute_tool(tool_name, handler, arguments).await?
+        };
+        if let Some(tool) = &definition {
+            self.check_output(tool, &result)?;
+        }
+        Ok(to_raw_value(&result)?)
+    }
+
+    /// Asks the approval policy, if any, whether a destructive tool may run
+    /// and audits the answer; returns the result to send instead when denied
+    async fn review_call(&self, tool_name: &str, arguments: &Value) -> Result<Option<CallToolResult>> {
+        let Some(policy) = &self.approval else {
+            return Ok(None);
+        };
+        let request = ApprovalRequest {
+            tool: tool_name.to_string(),
+            arguments: arguments.clone(),
+            session: session::current_session(),
+            identity: access::current_identity(),
+        };
+        let approval = policy
+            .review(self, &request)
+            .await
+            .unwrap_or_else(|e| Approval::denied(format!("Approval failed: {}", e)));
+
+        let (outcome, reason) = match &approval {
+            Approval::Approved => ("approved", None),
+            Approval::Denied { reason } => ("denied", Some(reason.clone())),
+        };
+        let mut entry = AuditEntry::new("tools/call", tool_name, outcome)
+            .with_session(request.session)
+            .with_identity(request.identity);
+        entry.reason = reason;
+        self.audit.record(&entry).await?;
+
+        Ok(match approval {
+            Approval::Approved => None,
+            Approval::Denied { reason } => Some(CallToolResult::error(format!(
+                "Call of {} was not approved: {}",
+                tool_name, reason
+            ))),
+        })
+    }
+
+    /// Checks a successful result against the tool's `outputSchema`
+    fn check_output(&self, tool: &Tool, result: &CallToolResult) -> Result<()> {
+        let Some(output_schema) = &tool.output_schema else {
+            return Ok(());
+        };
+        if self.config.output_validation == OutputValidation::Off || result.is_error == Some(true) {
+            return Ok(());
+        }
+        let problems = match &result.structured_content {
+            Some(content) => {
+                schema::violations(output_schema, content).unwrap_or_else(|e| vec![e.to_string()])
             }
-            _ => return Err(Error::MethodNotFound(format!("Tool not found: {}", tool_name))),
+            None => vec!["structuredContent is missing".to_string()],
         };
+        if problems.is_empty() {
+            return Ok(());
+        }
 
-        Ok(json!(result))
+        let message = format!(
+            "Tool {} returned a result that does not match its outputSchema: {}",
+            tool.name,
+            problems.join("; ")
+        );
+        if self.config.output_validation == OutputValidation::Warn {
+            self.logger.warn(&message);
+            return Ok(());
+        }
+        Err(Error::InternalError(message))
+    }
+
+    /// Runs a tool call on the tool pool, if one is configured
+    async fn execute_tool(
+        &self,
+        tool_name: &str,
+        handler: Arc<dyn ToolHandler>,
+        arguments: Value,
+    ) -> Result<CallToolResult> {
+        let context = self.request_context();
+        self.tool_pool
+            .run(tool_name, async move { handler.call(&context, arguments).await })
+            .await?
     }
 
-    async fn handle_resources_list(&self, _message: &Value) -> Result<Value> {
+    /// Context of the request being handled, for the handler it is
+    /// dispatched to
+    fn request_context(&self) -> RequestContext {
+        context::current().unwrap_or_else(|| {
+            RequestContext::new(self.notification_sender())
+                .with_extensions(self.extensions.clone())
+                .with_clock(self.clock.clone())
+        })
+    }
+
+    async fn handle_resources_list(&self, _params: Option<Value>) -> Result<Value> {
         self.logger.debug("Listing resources");
 
+        let mut handlers = HashMap::clone(&self.resources.load());
+        if let Some(session) = session::current_session()
+            && let Some(overlay) = self.overlays.read().await.get(&session)
+        {
+            handlers.extend(overlay.resources.iter().map(|(p, h)| (p.clone(), h.clone())));
+        }
+        let mut handlers: Vec<_> = handlers.into_iter().collect();
+        handlers.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut resources = Vec::new();
+        for (_, handler) in handlers {
+            resources.extend(handler.list().await?);
+        }
+
         Ok(json!({
-            "resources": [
-                {
-                    "uri": "config://app",
-                    "name": "Application Configuration",
-                    "description": "Current application configuration",
-                    "mimeType": "application/json"
-                }
-            ]
+            "resources": resources
         }))
     }
 
-    async fn handle_resources_read(&self, message: &Value) -> Result<Value> {
-        let params = message
-            .get("params")
-            .ok_or_else(|| Error::InvalidParams("Missing params".to_string()))?;
+    /// Records or drops the caller's interest in updates to a resource
+    async fn handle_resources_subscribe(&self, params: Option<Value>, subscribe: bool) -> Result<Value> {
+        if !self.config.resource_subscriptions {
+            let method = if subscribe { "resources/subscribe" } else { "resources/unsubscribe" };
+            return Err(Error::MethodNotFound(method.to_string()));
+        }
+        let SubscribeParams { uri } = SubscribeParams::from_params(params)?;
+        let uris = {
+            let mut subscriptions = self.lock_resource_subscriptions();
+            let uris = subscriptions.entry(session::current_session()).or_default();
+            if subscribe {
+                uris.insert(uri);
+            } else {
+                uris.remove(&uri);
+            }
+            uris.clone()
+        };
+        self.save_session_data(SUBSCRIPTIONS_KEY, &uris).await?;
+        Ok(json!({}))
+    }
 
-        let uri = params
-            .get("uri")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| Error::InvalidParams("Missing resource URI".to_string()))?;
+    async fn handle_resources_read(&self, params: Option<Value>) -> Result<Box<RawValue>> {
+        let ReadResourceParams { uri, range } = ReadResourceParams::from_params(params)?;
+        let uri = uri.as_str();
 
         self.logger.debug(&format!("Reading resource: {}", uri));
 
-        let result = if uri.starts_with("config://") {
-            let handler = ConfigResource::new();
-            handler.read(uri).await?
-        } else {
-            return Err(Error::ResourceError(format!("Resource not found: {}", uri)));
+        let handler = self
+            .find_resource(uri)
+            .await
+            .ok_or_else(|| Error::resource_error(MessageKey::ResourceNotFound, &[uri]))?;
+        let context = self.request_context();
+        let result = match range {
+            Some(range) => handler.read_range(&context, uri, range).await?,
+            None => handler.read(&context, uri).await?,
         };
 
-        Ok(json!(result))
+        Ok(to_raw_value(&result)?)
     }
 
-    async fn handle_prompts_list(&self, _message: &Value) -> Result<Value> {
+    async fn handle_prompts_list(&self, _params: Option<Value>) -> Result<Value> {
         self.logger.debug("Listing prompts");
 
+        let mut prompts: HashMap<String, Prompt> = self
+            .prompts
+            .load()
+            .values()
+            .filter_map(|p| p.definition())
+            .map(|p| (p.name.clone(), p))
+            .collect();
+        if let Some(session) = session::current_session()
+            && let Some(overlay) = self.overlays.read().await.get(&session)
+        {
+            prompts.extend(
+                overlay
+                    .prompts
+                    .values()
+                    .filter_map(|p| p.definition())
+                    .map(|p| (p.name.clone(), p)),
+            );
+        }
+        let mut prompts: Vec<Prompt> = prompts.into_values().collect();
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+
         Ok(json!({
-            "prompts": [
-                CodeReviewPrompt::prompt_definition()
-            ]
+            "prompts": prompts
         }))
     }
 
-    async fn handle_prompts_get(&self, message: &Value) -> Result<Value> {
-        let params = message
-            .get("params")
-            .ok_or_else(|| Error::InvalidParams("Missing params".to_string()))?;
-
-        let prompt_name = params
-            .get("name")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| Error::InvalidParams("Missing prompt name".to_string()))?;
-
-        let arguments = params.get("arguments").cloned();
+    async fn handle_prompts_get(&self, params: Option<Value>) -> Result<Value> {
+        let GetPromptParams { name, arguments } = GetPromptParams::from_params(params)?;
+        let prompt_name = name.as_str();
 
         self.logger.debug(&format!("Getting prompt: {}", prompt_name));
 
-        let result = match prompt_name {
-            "review-code" => {
-                let handler = CodeReviewPrompt::new();
-                handler.get(arguments).await?
-            }
-            _ => {
-                return Err(Error::MethodNotFound(format!(
-                    "Prompt not found: {}",
-                    prompt_name
-                )))
-            }
-        };
-
-        Ok(json!(result))
+        let handler = self
+            .find_prompt(prompt_name)
+            .await
+            .ok_or_else(|| Error::method_not_found(MessageKey::PromptNotFound, &[prompt_name]))?;
+        Ok(json!(handler.get(&self.request_context(), arguments).await?))
     }
 }
\ No newline at end of file
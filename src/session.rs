@@ -0,0 +1,282 @@
+//! Client sessions persisted in the server's state store
+//!
+//! Transports that multiplex clients (Streamable HTTP) identify each one by
+//! an `Mcp-Session-Id`. Session records live in the [`StateStore`], so with
+//! a durable store a restarted server resumes the sessions its clients
+//! already hold instead of forcing them to reinitialize.
+//!
+//! Clients that disappear without ending their session are noticed by an
+//! [`IdleMonitor`]: it pings sessions that have gone quiet and ends those
+//! that stay unresponsive, as set by the server's [`IdlePolicy`].
+
+use crate::McpServer;
+use crate::state::StateStore;
+use crate::utils::{Logger, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+tokio::task_local! {
+    static CURRENT_SESSION: Option<String>;
+}
+
+/// State store namespace holding session records
+pub const SESSION_NAMESPACE: &str = "sessions";
+
+/// Id of the session the current request belongs to, if any
+pub fn current_session() -> Option<String> {
+    CURRENT_SESSION.try_with(Clone::clone).ok().flatten()
+}
+
+/// Runs `future` as part of the session `id`
+pub fn with_session<F: Future>(id: Option<String>, future: F) -> impl Future<Output = F::Output> {
+    CURRENT_SESSION.scope(id, future)
+}
+
+/// What the server remembers about one client session
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionState {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_info: Option<Value>,
+    #[serde(default)]
+    pub initialized: bool,
+    /// Per-feature state keyed by feature: the client's log level and
+    /// resource subscriptions, restored when the session is resumed
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub data: BTreeMap<String, Value>,
+}
+
+impl SessionState {
+    /// A new session from the client's initialize params
+    pub fn from_initialize(params: Option<&Value>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            protocol_version: params
+                .and_then(|p| p.get("protocolVersion"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            client_info: params.and_then(|p| p.get("clientInfo")).cloned(),
+            initialized: false,
+            data: BTreeMap::new(),
+        }
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        self.data
+            .get(key)
+            .map(|value| serde_json::from_value(value.clone()))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    pub fn set<T: Serialize>(&mut self, key: &str, value: &T) -> Result<()> {
+        self.data.insert(key.to_string(), serde_json::to_value(value)?);
+        Ok(())
+    }
+}
+
+/// Session records backed by a [`StateStore`], with an in-process cache
+pub struct SessionStore {
+    store: Arc<dyn StateStore>,
+    cache: RwLock<HashMap<String, SessionState>>,
+    /// When each session was last heard from, since this process started
+    /// or adopted it
+    activity: Mutex<HashMap<String, Instant>>,
+}
+
+impl SessionStore {
+    pub fn new(store: Arc<dyn StateStore>) -> Self {
+        Self {
+            store,
+            cache: RwLock::new(HashMap::new()),
+            activity: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Looks a session up, loading it from the store after a restart
+    pub async fn get(&self, id: &str) -> Result<Option<SessionState>> {
+        if let Some(session) = self.cache.read().await.get(id) {
+            return Ok(Some(session.clone()));
+        }
+
+        let Some(value) = self.store.get_json(SESSION_NAMESPACE, id).await? else {
+            return Ok(None);
+        };
+        let session: SessionState = serde_json::from_value(value)?;
+        self.cache
+            .write()
+            .await
+            .insert(id.to_string(), session.clone());
+        Ok(Some(session))
+    }
+
+    pub async fn save(&self, session: &SessionState) -> Result<()> {
+        self.store
+            .put_json(SESSION_NAMESPACE, &session.id, &serde_json::to_value(session)?)
+            .await?;
+        self.cache
+            .write()
+            .await
+            .insert(session.id.clone(), session.clone());
+        Ok(())
+    }
+
+    /// Records that the client of session `id` was heard from at `now`
+    pub fn touch(&self, id: &str, now: Instant) {
+        self.lock_activity().insert(id.to_string(), now);
+    }
+
+    /// Starts the idle clock at `now` for stored sessions this process
+    /// hasn't heard from, such as those left by a previous run, so they are
+    /// ended if their clients never return. Returns how many it adopted.
+    pub async fn track_stored(&self, now: Instant) -> Result<usize> {
+        let stored = self.store.list(SESSION_NAMESPACE).await?;
+        let mut activity = self.lock_activity();
+        let before = activity.len();
+        for (id, _) in stored {
+            activity.entry(id).or_insert(now);
+        }
+        Ok(activity.len() - before)
+    }
+
+    /// Sessions heard from in this process or adopted by
+    /// [`SessionStore::track_stored`], with how long each has been quiet
+    /// at `now`
+    pub fn idle_times(&self, now: Instant) -> Vec<(String, Duration)> {
+        self.lock_activity()
+            .iter()
+            .map(|(id, seen)| (id.clone(), now.saturating_duration_since(*seen)))
+            .collect()
+    }
+
+    fn lock_activity(&self) -> std::sync::MutexGuard<'_, HashMap<String, Instant>> {
+        self.activity.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Ends a session; returns whether it existed
+    pub async fn remove(&self, id: &str) -> Result<bool> {
+        self.lock_activity().remove(id);
+        let cached = self.cache.write().await.remove(id).is_some();
+        let stored = self.store.delete(SESSION_NAMESPACE, id).await?;
+        Ok(cached || stored)
+    }
+}
+
+/// When quiet sessions are pinged and when unresponsive ones are ended
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdlePolicy {
+    /// Quiet time after which a session is pinged, again on every check
+    /// until it answers (0 = never ping)
+    pub ping_after_ms: u64,
+    /// Quiet time after which a session and everything it holds is torn
+    /// down (0 = never)
+    pub close_after_ms: u64,
+}
+
+impl Default for IdlePolicy {
+    fn default() -> Self {
+        Self {
+            ping_after_ms: 60_000,
+            close_after_ms: 300_000,
+        }
+    }
+}
+
+impl IdlePolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.ping_after_ms > 0 || self.close_after_ms > 0
+    }
+}
+
+/// What one [`IdleMonitor::sweep`] did
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IdleSweep {
+    pub pinged: Vec<String>,
+    pub closed: Vec<String>,
+}
+
+/// Applies an [`IdlePolicy`] to a server's sessions
+///
+/// Any message from a session, including its answer to a ping, counts as
+/// activity. Pings go out on the session's server-to-client channel (see
+/// [`McpServer::open_session_channel`]); a session without one can't be
+/// pinged and is simply ended once it has been quiet long enough.
+pub struct IdleMonitor {
+    policy: IdlePolicy,
+    logger: Logger,
+}
+
+impl IdleMonitor {
+    pub fn new(policy: IdlePolicy) -> Self {
+        Self {
+            policy,
+            logger: Logger::new("IdleMonitor"),
+        }
+    }
+
+    /// Pings and ends sessions as the policy says, once
+    pub async fn sweep(&self, server: &McpServer) -> Result<IdleSweep> {
+        let ping_after = Duration::from_millis(self.policy.ping_after_ms);
+        let close_after = Duration::from_millis(self.policy.close_after_ms);
+        let mut sweep = IdleSweep::default();
+
+        for (id, idle) in server.sessions().idle_times(server.clock().now()) {
+            if !close_after.is_zero() && idle >= close_after {
+                self.logger.info(&format!("Ending session {} after {:?} without a reply", id, idle));
+                server.end_session(&id).await?;
+                sweep.closed.push(id);
+            } else if !ping_after.is_zero() && idle >= ping_after {
+                let ping = json!({
+                    "jsonrpc": "2.0",
+                    "id": format!("ping-{}", uuid::Uuid::new_v4()),
+                    "method": "ping"
+                });
+                if server.send_to_session(&id, ping) {
+                    sweep.pinged.push(id);
+                }
+            }
+        }
+        Ok(sweep)
+    }
+
+    /// Sweeps in the background, checking twice per ping or close
+    /// interval, until the server is dropped. Sessions already in the state
+    /// store when it starts are swept too, counting from then.
+    pub fn spawn(self, server: &Arc<McpServer>) {
+        let shortest = [self.policy.ping_after_ms, self.policy.close_after_ms]
+            .into_iter()
+            .filter(|ms| *ms > 0)
+            .min();
+        let Some(shortest) = shortest else {
+            return;
+        };
+        let interval = Duration::from_millis(shortest / 2).max(Duration::from_millis(1));
+        let clock = server.clock();
+        let sessions = server.sessions();
+        let server = Arc::downgrade(server);
+        crate::runtime::spawn(async move {
+            if let Err(e) = sessions.track_stored(clock.now()).await {
+                self.logger.warn(&format!("Could not load stored sessions: {}", e));
+            }
+            loop {
+                clock.sleep(interval).await;
+                let Some(server) = server.upgrade() else {
+                    break;
+                };
+                if let Err(e) = self.sweep(&server).await {
+                    self.logger.warn(&format!("Idle session sweep failed: {}", e));
+                }
+            }
+        });
+    }
+}
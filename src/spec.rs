@@ -0,0 +1,104 @@
+//! Checks outgoing messages against the MCP schema
+//!
+//! `schemas/mcp/` bundles the published schema of every supported protocol
+//! revision, trimmed to the messages a server sends. When
+//! `ServerConfig::validate_messages` is on, the server runs each response
+//! and notification through [`check_response`] or [`check_notification`]
+//! before sending it.
+
+use crate::compat;
+use jsonschema::Validator;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+fn bundled(version: &str) -> &'static str {
+    match version {
+        "2025-03-26" => include_str!("../schemas/mcp/2025-03-26.json"),
+        "2024-11-05" => include_str!("../schemas/mcp/2024-11-05.json"),
+        _ => include_str!("../schemas/mcp/2025-06-18.json"),
+    }
+}
+
+/// Definition a method's result must match
+fn result_definition(method: &str) -> Option<&'static str> {
+    Some(match method {
+        "initialize" => "InitializeResult",
+        "ping" => "EmptyResult",
+        "tools/list" => "ListToolsResult",
+        "tools/call" => "CallToolResult",
+        "resources/list" => "ListResourcesResult",
+        "resources/read" => "ReadResourceResult",
+        "prompts/list" => "ListPromptsResult",
+        "prompts/get" => "GetPromptResult",
+        _ => return None,
+    })
+}
+
+/// Definition a notification's params must match
+fn params_definition(method: &str) -> Option<&'static str> {
+    Some(match method {
+        "notifications/progress" => "ProgressNotificationParams",
+        "notifications/message" => "LoggingMessageNotificationParams",
+        "notifications/resources/updated" => "ResourceUpdatedNotificationParams",
+        "notifications/cancelled" => "CancelledNotificationParams",
+        _ => return None,
+    })
+}
+
+/// Describes each way a response to `method` violates revision `version`
+pub fn check_response(version: &str, method: &str, response: &Value) -> Vec<String> {
+    if response.get("error").is_some() {
+        return check(version, "JSONRPCError", response);
+    }
+    let mut violations = check(version, "JSONRPCResponse", response);
+    if let (Some(definition), Some(result)) = (result_definition(method), response.get("result")) {
+        violations.extend(check(version, definition, result));
+    }
+    violations
+}
+
+/// Describes each way a server-sent notification violates revision `version`
+pub fn check_notification(version: &str, notification: &Value) -> Vec<String> {
+    let mut violations = check(version, "JSONRPCNotification", notification);
+    let method = notification.get("method").and_then(Value::as_str).unwrap_or_default();
+    if let (Some(definition), Some(params)) = (params_definition(method), notification.get("params")) {
+        violations.extend(check(version, definition, params));
+    }
+    violations
+}
+
+fn check(version: &str, definition: &'static str, instance: &Value) -> Vec<String> {
+    validator(compat::negotiate(Some(version)).unwrap_or(compat::LATEST), definition)
+        .iter_errors(instance)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() {
+                format!("{}: {}", definition, e)
+            } else {
+                format!("{} {}: {}", definition, path, e)
+            }
+        })
+        .collect()
+}
+
+/// Compiled definitions, keyed by revision and definition name
+type Validators = HashMap<(&'static str, &'static str), Arc<Validator>>;
+
+/// Compiles each definition once per revision
+fn validator(version: &'static str, definition: &'static str) -> Arc<Validator> {
+    static VALIDATORS: OnceLock<Mutex<Validators>> = OnceLock::new();
+    let mut validators = VALIDATORS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    validators
+        .entry((version, definition))
+        .or_insert_with(|| {
+            let mut schema: Value =
+                serde_json::from_str(bundled(version)).expect("bundled MCP schema is valid JSON");
+            schema["$ref"] = json!(format!("#/$defs/{}", definition));
+            Arc::new(jsonschema::validator_for(&schema).expect("bundled MCP schema compiles"))
+        })
+        .clone()
+}
@@ -0,0 +1,151 @@
+use super::StateStore;
+use crate::openapi::Secret;
+use crate::utils::{Error, Result};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::sync::Arc;
+
+/// Leading byte of every sealed value, so the format can change later.
+/// Version 1 authenticated `namespace/key`, which let two slots collide.
+const FORMAT_VERSION: u8 = 2;
+const NONCE_LEN: usize = 12;
+
+/// A 256-bit AES-GCM key
+#[derive(Clone)]
+pub struct EncryptionKey(Key<Aes256Gcm>);
+
+impl EncryptionKey {
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes.into())
+    }
+
+    /// Reads a base64-encoded 32-byte key
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| Error::ValidationError(format!("Encryption key is not base64: {}", e)))?;
+        let bytes: [u8; 32] = bytes.try_into().map_err(|bytes: Vec<u8>| {
+            Error::ValidationError(format!("Encryption key must be 32 bytes, got {}", bytes.len()))
+        })?;
+        Ok(Self::from_bytes(bytes))
+    }
+
+    /// Resolves a base64-encoded key given inline or in an environment
+    /// variable
+    pub fn from_secret(secret: &Secret) -> Result<Self> {
+        Self::from_base64(&secret.resolve()?)
+    }
+
+    /// A fresh random key, base64-encoded for storing in a secret
+    pub fn generate() -> String {
+        STANDARD.encode(Aes256Gcm::generate_key(OsRng))
+    }
+}
+
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+/// Encrypts values with AES-256-GCM before they reach another store
+///
+/// Namespaces and keys stay readable so the inner store can list them;
+/// values are sealed with a fresh nonce each time they are written. The
+/// namespace and key are authenticated with the value, so a value copied
+/// to another key fails to decrypt instead of being read back.
+pub struct EncryptedStateStore {
+    inner: Arc<dyn StateStore>,
+    cipher: Aes256Gcm,
+}
+
+impl EncryptedStateStore {
+    pub fn new(inner: Arc<dyn StateStore>, key: &EncryptionKey) -> Self {
+        Self {
+            inner,
+            cipher: Aes256Gcm::new(&key.0),
+        }
+    }
+
+    fn seal(&self, namespace: &str, key: &str, value: &[u8]) -> Result<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = associated_data(namespace, key);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, Payload { msg: value, aad: &aad })
+            .map_err(|_| Error::StorageError(format!("Failed to encrypt {}", slot(namespace, key))))?;
+
+        let mut sealed = Vec::with_capacity(1 + NONCE_LEN + ciphertext.len());
+        sealed.push(FORMAT_VERSION);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    fn open(&self, namespace: &str, key: &str, sealed: &[u8]) -> Result<Vec<u8>> {
+        let unreadable = || {
+            Error::StorageError(format!(
+                "Cannot decrypt {}: wrong key or corrupted value",
+                slot(namespace, key)
+            ))
+        };
+        let Some((&FORMAT_VERSION, rest)) = sealed.split_first() else {
+            return Err(unreadable());
+        };
+        if rest.len() < NONCE_LEN {
+            return Err(unreadable());
+        }
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: &associated_data(namespace, key) })
+            .map_err(|_| unreadable())
+    }
+}
+
+fn slot(namespace: &str, key: &str) -> String {
+    format!("{}/{}", namespace, key)
+}
+
+/// What a value is authenticated with: the namespace, prefixed by its
+/// length so that no other namespace and key pair produces the same bytes
+fn associated_data(namespace: &str, key: &str) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(8 + namespace.len() + key.len());
+    aad.extend_from_slice(&(namespace.len() as u64).to_be_bytes());
+    aad.extend_from_slice(namespace.as_bytes());
+    aad.extend_from_slice(key.as_bytes());
+    aad
+}
+
+#[async_trait]
+impl StateStore for EncryptedStateStore {
+    async fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        match self.inner.get(namespace, key).await? {
+            Some(sealed) => Ok(Some(self.open(namespace, key, &sealed)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn put(&self, namespace: &str, key: &str, value: Vec<u8>) -> Result<()> {
+        let sealed = self.seal(namespace, key, &value)?;
+        self.inner.put(namespace, key, sealed).await
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> Result<bool> {
+        self.inner.delete(namespace, key).await
+    }
+
+    async fn list(&self, namespace: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        self.inner
+            .list(namespace)
+            .await?
+            .into_iter()
+            .map(|(key, sealed)| {
+                let value = self.open(namespace, &key, &sealed)?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,57 @@
+use super::StateStore;
+use async_trait::async_trait;
+use crate::utils::Result;
+use std::collections::{BTreeMap, HashMap};
+use tokio::sync::RwLock;
+
+/// Non-durable store used when no state directory is configured
+pub struct MemoryStateStore {
+    namespaces: RwLock<HashMap<String, BTreeMap<String, Vec<u8>>>>,
+}
+
+impl MemoryStateStore {
+    pub fn new() -> Self {
+        Self {
+            namespaces: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for MemoryStateStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl StateStore for MemoryStateStore {
+    async fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        let namespaces = self.namespaces.read().await;
+        Ok(namespaces.get(namespace).and_then(|ns| ns.get(key)).cloned())
+    }
+
+    async fn put(&self, namespace: &str, key: &str, value: Vec<u8>) -> Result<()> {
+        let mut namespaces = self.namespaces.write().await;
+        namespaces
+            .entry(namespace.to_string())
+            .or_default()
+            .insert(key.to_string(), value);
+        Ok(())
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> Result<bool> {
+        let mut namespaces = self.namespaces.write().await;
+        Ok(namespaces
+            .get_mut(namespace)
+            .and_then(|ns| ns.remove(key))
+            .is_some())
+    }
+
+    async fn list(&self, namespace: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let namespaces = self.namespaces.read().await;
+        Ok(namespaces
+            .get(namespace)
+            .map(|ns| ns.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default())
+    }
+}
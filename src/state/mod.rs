@@ -0,0 +1,39 @@
+pub mod memory_store;
+#[cfg(feature = "native")]
+pub mod sled_store;
+#[cfg(feature = "native")]
+pub mod encrypted_store;
+
+pub use memory_store::MemoryStateStore;
+#[cfg(feature = "native")]
+pub use sled_store::SledStateStore;
+#[cfg(feature = "native")]
+pub use encrypted_store::{EncryptedStateStore, EncryptionKey};
+
+use async_trait::async_trait;
+use crate::utils::Result;
+use serde_json::Value;
+
+/// Key/value storage for tools and resources that need state across calls
+///
+/// Keys are grouped into namespaces so independent components (a KV
+/// resource, a job manager, an idempotency cache) can share one store
+/// without colliding.
+#[async_trait]
+pub trait StateStore: Send + Sync {
+    async fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>>;
+    async fn put(&self, namespace: &str, key: &str, value: Vec<u8>) -> Result<()>;
+    async fn delete(&self, namespace: &str, key: &str) -> Result<bool>;
+    async fn list(&self, namespace: &str) -> Result<Vec<(String, Vec<u8>)>>;
+
+    async fn get_json(&self, namespace: &str, key: &str) -> Result<Option<Value>> {
+        match self.get(namespace, key).await? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn put_json(&self, namespace: &str, key: &str, value: &Value) -> Result<()> {
+        self.put(namespace, key, serde_json::to_vec(value)?).await
+    }
+}
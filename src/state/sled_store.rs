@@ -0,0 +1,64 @@
+use super::StateStore;
+use async_trait::async_trait;
+use crate::utils::{Error, Logger, Result};
+use std::path::Path;
+
+/// Durable store backed by an embedded sled database
+///
+/// Each namespace maps to a sled tree. Writes are flushed before returning
+/// so state survives a crash or restart.
+pub struct SledStateStore {
+    logger: Logger,
+    db: sled::Db,
+}
+
+impl SledStateStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let logger = Logger::new("SledStateStore");
+        logger.info_with_context("Opening state store", &path.as_ref().display().to_string());
+
+        let db = sled::open(path.as_ref()).map_err(storage_error)?;
+        Ok(Self { logger, db })
+    }
+
+    fn tree(&self, namespace: &str) -> Result<sled::Tree> {
+        self.db.open_tree(namespace).map_err(storage_error)
+    }
+}
+
+fn storage_error(e: sled::Error) -> Error {
+    Error::StorageError(e.to_string())
+}
+
+#[async_trait]
+impl StateStore for SledStateStore {
+    async fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        let value = self.tree(namespace)?.get(key).map_err(storage_error)?;
+        Ok(value.map(|v| v.to_vec()))
+    }
+
+    async fn put(&self, namespace: &str, key: &str, value: Vec<u8>) -> Result<()> {
+        self.logger.debug_with_context("Storing key", &format!("{}/{}", namespace, key));
+        let tree = self.tree(namespace)?;
+        tree.insert(key, value).map_err(storage_error)?;
+        tree.flush_async().await.map_err(storage_error)?;
+        Ok(())
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> Result<bool> {
+        let tree = self.tree(namespace)?;
+        let removed = tree.remove(key).map_err(storage_error)?.is_some();
+        tree.flush_async().await.map_err(storage_error)?;
+        Ok(removed)
+    }
+
+    async fn list(&self, namespace: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        self.tree(namespace)?
+            .iter()
+            .map(|entry| {
+                let (key, value) = entry.map_err(storage_error)?;
+                Ok((String::from_utf8_lossy(&key).into_owned(), value.to_vec()))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,88 @@
+//! systemd socket activation and readiness notification
+//!
+//! With a `.socket` unit, systemd binds the listening socket and passes it
+//! to the server (`sd_listen_fds`), so the server can start on demand and
+//! restart without dropping connections. With `Type=notify`, the server
+//! reports when it is ready to serve (`sd_notify`).
+
+use crate::utils::Result;
+use std::os::fd::{FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixDatagram;
+
+/// First file descriptor systemd passes sockets on
+const SD_LISTEN_FDS_START: RawFd = 3;
+
+/// A listening socket inherited from systemd
+pub enum ActivatedListener {
+    Tcp(tokio::net::TcpListener),
+    Unix(tokio::net::UnixListener),
+}
+
+/// The sockets systemd passed to this process, in unit file order
+///
+/// Empty unless `LISTEN_PID` names this process, so children started by the
+/// server never pick up its sockets. Must be called from within the tokio
+/// runtime, and at most once: the listeners take ownership of the fds.
+pub fn listen_fds() -> Result<Vec<ActivatedListener>> {
+    let for_us = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        .is_some_and(|pid| pid == std::process::id());
+    let count: RawFd = match std::env::var("LISTEN_FDS").ok().and_then(|n| n.parse().ok()) {
+        Some(count) if for_us => count,
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut listeners = Vec::new();
+    for fd in SD_LISTEN_FDS_START..SD_LISTEN_FDS_START + count {
+        // Keep the sockets from leaking into spawned children
+        unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) };
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        // A Unix socket has no inet address, so the TCP view fails on it
+        let tcp = std::net::TcpListener::from(fd);
+        let listener = if tcp.local_addr().is_ok() {
+            tcp.set_nonblocking(true)?;
+            ActivatedListener::Tcp(tokio::net::TcpListener::from_std(tcp)?)
+        } else {
+            let unix = std::os::unix::net::UnixListener::from(OwnedFd::from(tcp));
+            unix.set_nonblocking(true)?;
+            ActivatedListener::Unix(tokio::net::UnixListener::from_std(unix)?)
+        };
+        listeners.push(listener);
+    }
+    Ok(listeners)
+}
+
+/// Sends `state` (e.g. `READY=1`) to the service manager; returns false
+/// when the server is not running under one
+pub fn notify(state: &str) -> Result<bool> {
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return Ok(false);
+    };
+    let socket = UnixDatagram::unbound()?;
+    let path = path.to_string_lossy();
+    match path.strip_prefix('@') {
+        // Abstract socket namespace
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes())?;
+            socket.send_to_addr(state.as_bytes(), &addr)?;
+        }
+        _ => {
+            socket.send_to(state.as_bytes(), path.as_ref())?;
+        }
+    }
+    Ok(true)
+}
+
+/// Tells the service manager startup has finished
+pub fn notify_ready() -> Result<bool> {
+    notify("READY=1")
+}
+
+/// Tells the service manager the server is shutting down
+pub fn notify_stopping() -> Result<bool> {
+    notify("STOPPING=1")
+}
@@ -0,0 +1,431 @@
+//! Test support for servers built on this crate
+//!
+//! [`McpTestClient`] drives an [`McpServer`] in-process over a
+//! [`MemoryTransport`] with typed helpers, so integration tests can exercise
+//! the full JSON-RPC path, framing and notifications included, in a few
+//! lines:
+//!
+//! ```no_run
+//! # async fn example() -> mcp_server_rust::Result<()> {
+//! use mcp_server_rust::{testing::McpTestClient, McpServer, ServerConfig};
+//! use serde_json::json;
+//!
+//! let client = McpTestClient::new(McpServer::new(ServerConfig::new("test", "0.1.0")));
+//! client.initialize().await?;
+//! let result = client.call_tool("greet", json!({ "name": "Ada" })).await?;
+//! assert_eq!(result.content[0].as_text().unwrap(), "Hello, Ada! Welcome to MCP.");
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod fixtures;
+
+use crate::McpServer;
+use crate::access;
+use crate::protocol;
+use crate::prompts::{GetPromptResult, Prompt};
+use crate::resources::ResourceReadResult;
+use crate::runtime;
+use crate::session;
+use crate::tools::{CallToolResult, Tool};
+use crate::transport::{MemoryTransport, Transport, TransportSender};
+use crate::utils::{Error, Result};
+use futures::FutureExt;
+use futures::future::{AbortHandle, Abortable, Either, select};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Notify, oneshot};
+
+/// Typed in-process client for exercising an [`McpServer`] in tests
+///
+/// The server end of the transport is served in the background, with the
+/// client's session and identity, if any, set for every message it handles,
+/// so the client must be created inside a runtime [`runtime::spawn`] can
+/// use. Dropping the client disconnects it.
+pub struct McpTestClient {
+    server: Arc<McpServer>,
+    session: Option<String>,
+    identity: Option<String>,
+    sender: Arc<dyn TransportSender>,
+    serving: Serving,
+    acknowledgements: Acknowledgements,
+    inbox: Arc<Inbox>,
+    tasks: Vec<AbortHandle>,
+    next_id: AtomicU64,
+}
+
+/// Messages from the server that aren't responses to the client's requests
+#[derive(Default)]
+struct Inbox {
+    messages: Mutex<VecDeque<Value>>,
+    arrived: Notify,
+}
+
+impl Inbox {
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<Value>> {
+        self.messages.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Waiters for the notifications the client sent, in the order it sent
+/// them; each gets what the server answered, which should be nothing
+type Acknowledgements = Arc<Mutex<VecDeque<oneshot::Sender<Result<Option<Value>>>>>>;
+
+/// The server end of the client's transport
+#[derive(Clone)]
+struct Serving {
+    server: Arc<McpServer>,
+    session: Option<String>,
+    identity: Option<String>,
+    to_client: Arc<dyn TransportSender>,
+    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value>>>>>,
+    /// Why a request failed to be answered, if the server panicked
+    panicked: Arc<Mutex<Option<Box<dyn Any + Send>>>>,
+    requests: Arc<Mutex<Vec<AbortHandle>>>,
+}
+
+impl Serving {
+    /// Handles `message` with the client's session and identity, sending
+    /// the server's answer, if any, to the client and returning it; `None`
+    /// if the server panicked
+    async fn handle(self, message: Value) -> Option<Result<Option<Value>>> {
+        let id = message.get("id").and_then(Value::as_u64);
+        let handled = session::with_session(
+            self.session.clone(),
+            access::with_identity(self.identity.clone(), self.server.handle_request(message)),
+        );
+        // A panic fails the requests waiting on the server, and is raised
+        // again in their tasks so tests see it
+        let answer = match AssertUnwindSafe(handled).catch_unwind().await {
+            Ok(answer) => answer,
+            Err(panic) => {
+                *self.panicked.lock().unwrap_or_else(|e| e.into_inner()) = Some(panic);
+                self.pending.lock().unwrap_or_else(|e| e.into_inner()).clear();
+                return None;
+            }
+        };
+        Some(match answer {
+            Ok(Some(answer)) => {
+                let _ = self.to_client.send(answer.clone()).await;
+                Ok(Some(answer))
+            }
+            // Errors the server raises instead of answering go straight to
+            // the request they failed
+            Err(e) => match id.and_then(|id| self.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(&id)) {
+                Some(waiter) => {
+                    let _ = waiter.send(Err(e));
+                    Ok(None)
+                }
+                None => Err(e),
+            },
+            Ok(None) => Ok(None),
+        })
+    }
+}
+
+impl McpTestClient {
+    pub fn new(server: McpServer) -> Self {
+        Self::from_arc(Arc::new(server))
+    }
+
+    pub fn from_arc(server: Arc<McpServer>) -> Self {
+        Self::connect(server, None, None)
+    }
+
+    /// Reconnects as part of session `id`, receiving the messages the server
+    /// sends that session
+    pub fn with_session(self, id: impl Into<String>) -> Self {
+        Self::connect(self.server.clone(), Some(id.into()), self.identity.clone())
+    }
+
+    /// Reconnects on behalf of `identity`, as an authenticating transport
+    /// would
+    pub fn with_identity(self, identity: impl Into<String>) -> Self {
+        Self::connect(self.server.clone(), self.session.clone(), Some(identity.into()))
+    }
+
+    fn connect(server: Arc<McpServer>, session: Option<String>, identity: Option<String>) -> Self {
+        let (mut client, mut server_end) = MemoryTransport::pair();
+        let sender = client.sender();
+        let serving = Serving {
+            server: server.clone(),
+            session: session.clone(),
+            identity: identity.clone(),
+            to_client: server_end.sender(),
+            pending: Arc::default(),
+            panicked: Arc::default(),
+            requests: Arc::default(),
+        };
+        let acknowledgements: Acknowledgements = Arc::default();
+        let inbox = Arc::new(Inbox::default());
+
+        let mut tasks = Vec::new();
+        if let Some(id) = session.as_deref() {
+            // Messages the server sends the session go down the same
+            // transport
+            let mut messages = server.open_session_channel(id);
+            let to_client = serving.to_client.clone();
+            tasks.push(spawn(async move {
+                while let Some(message) = messages.recv().await {
+                    if to_client.send(message).await.is_err() {
+                        break;
+                    }
+                }
+            }));
+        }
+        tasks.push(spawn({
+            let serving = serving.clone();
+            let acknowledgements = acknowledgements.clone();
+            async move {
+                let mut notifications = serving.server.subscribe_notifications();
+                loop {
+                    let next = {
+                        let message = Box::pin(server_end.receive());
+                        let notification = Box::pin(notifications.recv());
+                        match select(message, notification).await {
+                            Either::Left((message, _)) => Either::Left(message),
+                            Either::Right((notification, _)) => Either::Right(notification),
+                        }
+                    };
+                    match next {
+                        // Requests are handled concurrently, as a transport
+                        // multiplexing them would, so a handler can wait on
+                        // a later message from the client
+                        Either::Left(Ok(Some(message))) if is_request(&message) => {
+                            let serving_request = serving.clone();
+                            let request = spawn(async move {
+                                let _ = serving_request.handle(message).await;
+                            });
+                            serving.requests.lock().unwrap_or_else(|e| e.into_inner()).push(request);
+                        }
+                        Either::Left(Ok(Some(message))) => {
+                            let acknowledged = acknowledgements.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+                            if let (Some(answer), Some(acknowledged)) =
+                                (serving.clone().handle(message).await, acknowledged)
+                            {
+                                let _ = acknowledged.send(answer);
+                            }
+                        }
+                        Either::Left(_) => break,
+                        Either::Right(Ok(notification)) => {
+                            if serving.to_client.send(notification).await.is_err() {
+                                break;
+                            }
+                        }
+                        Either::Right(Err(_)) => {}
+                    }
+                }
+            }
+        }));
+        tasks.push(spawn({
+            let pending = serving.pending.clone();
+            let inbox = inbox.clone();
+            async move {
+                while let Ok(Some(message)) = client.receive().await {
+                    let waiter = match (message.get("method"), message.get("id").and_then(Value::as_u64)) {
+                        (None, Some(id)) => pending.lock().unwrap_or_else(|e| e.into_inner()).remove(&id),
+                        _ => None,
+                    };
+                    match waiter {
+                        Some(waiter) => {
+                            let _ = waiter.send(Ok(message));
+                        }
+                        None => {
+                            inbox.lock().push_back(message);
+                            inbox.arrived.notify_waiters();
+                        }
+                    }
+                }
+            }
+        }));
+
+        Self {
+            server,
+            session,
+            identity,
+            sender,
+            serving,
+            acknowledgements,
+            inbox,
+            tasks,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub fn server(&self) -> &Arc<McpServer> {
+        &self.server
+    }
+
+    /// Sends a request and returns its `result`, or the JSON-RPC error as
+    /// `Error::Rpc`
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let response = self.send_request(method, params).await?;
+        protocol::response_result(response)
+    }
+
+    /// Sends a notification; the server must not answer it
+    ///
+    /// Returns once the server has handled it: the server handles
+    /// notifications in order, so it has by the time it answers a `ping`
+    /// sent after it.
+    pub async fn notify(&self, method: &str, params: Value) -> Result<()> {
+        let (acknowledged, acknowledgement) = oneshot::channel();
+        self.acknowledgements.lock().unwrap_or_else(|e| e.into_inner()).push_back(acknowledged);
+        self.sender
+            .send(json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": params
+            }))
+            .await?;
+
+        match acknowledgement.await {
+            Ok(answer) => match answer? {
+                Some(response) => Err(Error::InternalError(format!(
+                    "Server answered notification {}: {}",
+                    method, response
+                ))),
+                None => Ok(()),
+            },
+            Err(_) => Err(self.disconnected(method)),
+        }
+    }
+
+    /// Next notification or request the server sent this client, waiting
+    /// for one to arrive
+    pub async fn next_notification(&self) -> Value {
+        loop {
+            let arrived = self.inbox.arrived.notified();
+            if let Some(message) = self.inbox.lock().pop_front() {
+                return message;
+            }
+            arrived.await;
+        }
+    }
+
+    /// Notifications and requests the server has sent this client so far
+    pub fn take_notifications(&self) -> Vec<Value> {
+        self.inbox.lock().drain(..).collect()
+    }
+
+    async fn send_request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (answered, answer) = oneshot::channel();
+        self.serving.pending.lock().unwrap_or_else(|e| e.into_inner()).insert(id, answered);
+        self.sender
+            .send(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": method,
+                "params": params
+            }))
+            .await?;
+        answer.await.unwrap_or_else(|_| Err(self.disconnected(method)))
+    }
+
+    /// Why `method` went unanswered; raises the server's panic again if
+    /// it panicked
+    fn disconnected(&self, method: &str) -> Error {
+        match self.serving.panicked.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            Some(panic) => std::panic::resume_unwind(panic),
+            None => Error::InternalError(format!("No response to {}", method)),
+        }
+    }
+
+    /// Performs the initialize handshake and returns the initialize result
+    pub async fn initialize(&self) -> Result<Value> {
+        let result = self
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": crate::PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": "McpTestClient", "version": env!("CARGO_PKG_VERSION") }
+                }),
+            )
+            .await?;
+        self.notify("notifications/initialized", json!({})).await?;
+        Ok(result)
+    }
+
+    pub async fn ping(&self) -> Result<()> {
+        self.request("ping", json!({})).await.map(|_| ())
+    }
+
+    pub async fn list_tools(&self) -> Result<Vec<Tool>> {
+        self.list("tools/list", "tools").await
+    }
+
+    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult> {
+        let result = self
+            .request(
+                "tools/call",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    pub async fn list_resources(&self) -> Result<Vec<Value>> {
+        self.list("resources/list", "resources").await
+    }
+
+    pub async fn read_resource(&self, uri: &str) -> Result<ResourceReadResult> {
+        let result = self
+            .request("resources/read", json!({ "uri": uri }))
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    pub async fn list_prompts(&self) -> Result<Vec<Prompt>> {
+        self.list("prompts/list", "prompts").await
+    }
+
+    pub async fn get_prompt(&self, name: &str, arguments: Value) -> Result<GetPromptResult> {
+        let result = self
+            .request(
+                "prompts/get",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    async fn list<T: DeserializeOwned>(&self, method: &str, field: &str) -> Result<Vec<T>> {
+        let mut result = self.request(method, json!({})).await?;
+        let items = result
+            .get_mut(field)
+            .map(Value::take)
+            .ok_or_else(|| Error::InternalError(format!("{} result has no '{}'", method, field)))?;
+        Ok(serde_json::from_value(items)?)
+    }
+}
+
+impl Drop for McpTestClient {
+    fn drop(&mut self) {
+        let requests = self.serving.requests.lock().unwrap_or_else(|e| e.into_inner());
+        for task in self.tasks.iter().chain(requests.iter()) {
+            task.abort();
+        }
+    }
+}
+
+/// Runs `task` in the background until it ends or is aborted
+fn spawn(task: impl std::future::Future<Output = ()> + Send + 'static) -> AbortHandle {
+    let (handle, registration) = AbortHandle::new_pair();
+    runtime::spawn(async move {
+        let _ = Abortable::new(task, registration).await;
+    });
+    handle
+}
+
+/// Whether `message` expects an answer: a request, or a batch, which may
+/// hold some
+fn is_request(message: &Value) -> bool {
+    message.is_array() || (message.get("id").is_some() && message.get("method").is_some())
+}
@@ -0,0 +1,77 @@
+//! Canonical requests for golden-response tests
+//!
+//! [`cases`] covers every built-in handler, including its error paths.
+//! Tests send each case to a server initialized with
+//! [`initialize_request`] and snapshot the responses, so any change in the
+//! JSON the server produces shows up as a snapshot diff.
+
+use serde_json::{Value, json};
+
+/// A named request; the name doubles as the snapshot name
+pub struct Case {
+    pub name: &'static str,
+    pub request: Value,
+}
+
+impl Case {
+    fn new(name: &'static str, id: u64, method: &str, params: Value) -> Self {
+        Self {
+            name,
+            request: json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }),
+        }
+    }
+}
+
+/// The `initialize` request the cases assume was sent first
+pub fn initialize_request() -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": crate::PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "fixtures", "version": "1.0.0" }
+        }
+    })
+}
+
+/// One request per handler and outcome, in a stable order
+pub fn cases() -> Vec<Case> {
+    vec![
+        Case::new("ping", 1, "ping", json!({})),
+        Case::new("tools_list", 2, "tools/list", json!({})),
+        Case::new("tools_call_greet", 3, "tools/call", json!({ "name": "greet", "arguments": { "name": "Ada" } })),
+        Case::new(
+            "tools_call_bmi",
+            4,
+            "tools/call",
+            json!({ "name": "calculate-bmi", "arguments": { "weightKg": 70, "heightM": 1.75 } }),
+        ),
+        Case::new(
+            "tools_call_weather",
+            5,
+            "tools/call",
+            json!({ "name": "fetch-weather", "arguments": { "city": "Paris" } }),
+        ),
+        Case::new("tools_search", 6, "x-tools/search", json!({ "query": "weather" })),
+        Case::new("resources_list", 7, "resources/list", json!({})),
+        Case::new("resources_read", 8, "resources/read", json!({ "uri": "config://app" })),
+        Case::new("prompts_list", 9, "prompts/list", json!({})),
+        Case::new(
+            "prompts_get",
+            10,
+            "prompts/get",
+            json!({ "name": "review-code", "arguments": { "code": "fn main() {}", "focus": "style" } }),
+        ),
+        Case::new("error_unknown_method", 11, "no/such/method", json!({})),
+        Case::new("error_unknown_tool", 12, "tools/call", json!({ "name": "no-such-tool", "arguments": {} })),
+        Case::new("error_missing_argument", 13, "tools/call", json!({ "name": "fetch-weather", "arguments": {} })),
+        Case::new("error_unknown_resource", 14, "resources/read", json!({ "uri": "unknown://missing" })),
+        Case::new("error_unknown_prompt", 15, "prompts/get", json!({ "name": "no-such-prompt" })),
+        Case {
+            name: "error_invalid_request",
+            request: json!({ "jsonrpc": "1.0", "id": 16, "method": "ping" }),
+        },
+    ]
+}
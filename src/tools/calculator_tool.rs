@@ -1,7 +1,9 @@
 use super::{Tool, CallToolResult, TextContent, ToolHandler};
 use serde_json::{json, Value};
 use async_trait::async_trait;
+use crate::context::RequestContext;
 use crate::utils::{Result, Error, Logger};
+use crate::utils::i18n::{self, MessageKey};
 
 pub struct CalculatorTool {
     logger: Logger,
@@ -37,6 +39,7 @@ impl CalculatorTool {
                 "title": "BMI Calculator",
                 "readOnlyHint": true
             })),
+            output_schema: None,
         }
     }
 }
@@ -49,19 +52,19 @@ impl Default for CalculatorTool {
 
 #[async_trait]
 impl ToolHandler for CalculatorTool {
-    async fn call(&self, arguments: Value) -> Result<CallToolResult> {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
         let weight_kg = arguments
             .get("weightKg")
             .and_then(|v| v.as_f64())
-            .ok_or_else(|| Error::InvalidParams("Missing or invalid 'weightKg'".to_string()))?;
+            .ok_or_else(|| Error::invalid_params(MessageKey::InvalidParameter, &["weightKg"]))?;
 
         let height_m = arguments
             .get("heightM")
             .and_then(|v| v.as_f64())
-            .ok_or_else(|| Error::InvalidParams("Missing or invalid 'heightM'".to_string()))?;
+            .ok_or_else(|| Error::invalid_params(MessageKey::InvalidParameter, &["heightM"]))?;
 
         if height_m <= 0.0 {
-            return Ok(CallToolResult::error("Height must be positive"));
+            return Ok(CallToolResult::error(i18n::t(MessageKey::HeightMustBePositive, &[])));
         }
 
         self.logger.debug_with_context(
@@ -74,4 +77,8 @@ impl ToolHandler for CalculatorTool {
 
         Ok(CallToolResult::success(vec![TextContent::new(message)]))
     }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Self::tool_definition())
+    }
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
 use super::{Tool, CallToolResult, TextContent, ToolHandler};
 use serde_json::{json, Value};
 use async_trait::async_trait;
+use crate::context::RequestContext;
+use crate::utils::i18n::MessageKey;
 use crate::utils::{Result, Error, Logger};
 
 pub struct GreetingTool {
@@ -32,6 +34,7 @@ impl GreetingTool {
                 "title": "Greet Tool",
                 "readOnlyHint": true
             })),
+            output_schema: None,
         }
     }
 }
@@ -44,15 +47,19 @@ impl Default for GreetingTool {
 
 #[async_trait]
 impl ToolHandler for GreetingTool {
-    async fn call(&self, arguments: Value) -> Result<CallToolResult> {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
         let name = arguments
             .get("name")
             .and_then(|v| v.as_str())
-            .ok_or_else(|| Error::InvalidParams("Missing 'name' parameter".to_string()))?;
+            .ok_or_else(|| Error::invalid_params(MessageKey::MissingParameter, &["name"]))?;
 
         self.logger.debug_with_context("Tool called with name", name);
 
         let message = format!("Hello, {}! Welcome to MCP.", name);
         Ok(CallToolResult::success(vec![TextContent::new(message)]))
     }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Self::tool_definition())
+    }
 }
\ No newline at end of file
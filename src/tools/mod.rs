@@ -1,11 +1,18 @@
 pub mod greeting_tool;
 pub mod calculator_tool;
 pub mod weather_tool;
+pub mod pool;
+pub mod schema;
+pub mod search;
+pub mod typed;
 
 use serde_json::{Value};
 use async_trait::async_trait;
+use crate::context::RequestContext;
 use crate::utils::Result;
 
+pub use crate::content::{AudioContent, Content, EmbeddedResource, ImageContent, TextContent};
+
 /// Represents a tool that can be invoked by the LLM
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Tool {
@@ -15,52 +22,68 @@ pub struct Tool {
     pub input_schema: Value,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub annotations: Option<Value>,
-}
-
-/// Content returned from a tool execution
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct TextContent {
-    #[serde(rename = "type")]
-    pub content_type: String,
-    pub text: String,
-}
-
-impl TextContent {
-    pub fn new(text: impl Into<String>) -> Self {
-        Self {
-            content_type: "text".to_string(),
-            text: text.into(),
-        }
-    }
+    /// Schema that `structuredContent` of this tool's results must match
+    #[serde(rename = "outputSchema", default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
 }
 
 /// Result of a tool call
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct CallToolResult {
-    pub content: Vec<TextContent>,
+    pub content: Vec<Content>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "isError")]
     pub is_error: Option<bool>,
+    /// Machine-readable result, alongside its rendering in `content`
+    #[serde(rename = "structuredContent", default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+    /// Protocol and vendor metadata about the result
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
 }
 
 impl CallToolResult {
-    pub fn success(content: Vec<TextContent>) -> Self {
+    pub fn success(content: Vec<impl Into<Content>>) -> Self {
         Self {
-            content,
+            content: content.into_iter().map(Into::into).collect(),
             is_error: Some(false),
+            structured_content: None,
+            meta: None,
         }
     }
 
     pub fn error(message: impl Into<String>) -> Self {
         Self {
-            content: vec![TextContent::new(message)],
+            content: vec![Content::text(message)],
             is_error: Some(true),
+            structured_content: None,
+            meta: None,
         }
     }
+
+    pub fn with_structured_content(mut self, structured_content: Value) -> Self {
+        self.structured_content = Some(structured_content);
+        self
+    }
+
+    pub fn with_meta(mut self, meta: Value) -> Self {
+        self.meta = Some(meta);
+        self
+    }
 }
 
 /// Trait for implementing tool handlers
+///
+/// The server keeps the registered instance and calls it for every request,
+/// concurrently, so a handler may hold connections, clients or caches; state
+/// that changes between calls needs interior mutability.
 #[async_trait]
 pub trait ToolHandler: Send + Sync {
-    async fn call(&self, arguments: Value) -> Result<CallToolResult>;
+    /// Runs the tool for the request described by `context`
+    async fn call(&self, context: &RequestContext, arguments: Value) -> Result<CallToolResult>;
+
+    /// Definition advertised in `tools/list` when the handler is registered
+    fn definition(&self) -> Option<Tool> {
+        None
+    }
 }
\ No newline at end of file
@@ -0,0 +1,148 @@
+use crate::notifications;
+use crate::server::ClientLink;
+use crate::session;
+use crate::utils::{deadline, i18n, meta};
+use crate::utils::{Error, Result};
+use futures::future::BoxFuture;
+use futures::{FutureExt, select_biased};
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{Mutex, mpsc, oneshot};
+
+type Job = BoxFuture<'static, ()>;
+
+/// Sizing of the tool execution pool
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ToolPoolConfig {
+    /// Number of workers running tool calls (0 = run calls on the task
+    /// that received the request)
+    pub workers: usize,
+    /// Tools pinned to one worker by index, e.g. to serialize calls of a
+    /// tool that is not safe to run concurrently
+    pub affinity: HashMap<String, usize>,
+}
+
+impl ToolPoolConfig {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            workers,
+            affinity: HashMap::new(),
+        }
+    }
+
+    /// Runs every call of `tool` on worker `worker` (modulo the pool size)
+    pub fn with_affinity(mut self, tool: impl Into<String>, worker: usize) -> Self {
+        self.affinity.insert(tool.into(), worker);
+        self
+    }
+}
+
+/// A fixed set of workers that tool calls are handed to
+///
+/// Calls of pinned tools queue on their worker; everything else goes to a
+/// shared queue any idle worker takes from, with a worker's pinned calls
+/// served first. The request's session, locale, deadline, `_meta`,
+/// notification sender and client link travel with each call. Workers are
+/// started on first use, so a pool can be built outside a runtime, and stop
+/// once the last handle to the pool is dropped.
+#[derive(Clone)]
+pub struct ToolPool {
+    config: Arc<ToolPoolConfig>,
+    queues: Arc<OnceLock<Queues>>,
+}
+
+struct Queues {
+    shared: mpsc::UnboundedSender<Job>,
+    pinned: Vec<mpsc::UnboundedSender<Job>>,
+}
+
+impl ToolPool {
+    pub fn new(config: ToolPoolConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            queues: Arc::new(OnceLock::new()),
+        }
+    }
+
+    pub fn workers(&self) -> usize {
+        self.config.workers
+    }
+
+    /// Runs `call` for `tool` on a worker and waits for its output
+    ///
+    /// The call keeps running if the caller stops waiting for it.
+    pub async fn run<F, T>(&self, tool: &str, call: F) -> Result<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        if self.config.workers == 0 {
+            return Ok(call.await);
+        }
+
+        let queues = self.queues.get_or_init(|| self.start());
+        let (tx, rx) = oneshot::channel();
+        let session = session::current_session();
+        let locale = i18n::current_locale();
+        let request_deadline = deadline::current();
+        let request_meta = meta::current();
+        let notifier = notifications::current();
+        let client = ClientLink::current();
+        let job = async move {
+            let call = match client {
+                Some(client) => ClientLink::scope(client, call).boxed(),
+                None => call.boxed(),
+            };
+            let call = match notifier {
+                Some(notifier) => notifications::with_sender(notifier, call).boxed(),
+                None => call,
+            };
+            let call = deadline::with_deadline(request_deadline, meta::with_meta(request_meta, call));
+            let output = session::with_session(session, i18n::with_locale(locale, call)).await;
+            let _ = tx.send(output);
+        }
+        .boxed();
+
+        let queue = match self.config.affinity.get(tool) {
+            Some(worker) => &queues.pinned[worker % queues.pinned.len()],
+            None => &queues.shared,
+        };
+        queue
+            .send(job)
+            .map_err(|_| Error::InternalError("The tool pool has shut down".to_string()))?;
+        rx.await
+            .map_err(|_| Error::ToolError(format!("Tool {} panicked", tool)))
+    }
+
+    fn start(&self) -> Queues {
+        let (shared, shared_rx) = mpsc::unbounded_channel();
+        let shared_rx = Arc::new(Mutex::new(shared_rx));
+        let pinned = (0..self.config.workers)
+            .map(|_| {
+                let (tx, rx) = mpsc::unbounded_channel();
+                crate::runtime::spawn(worker(rx, shared_rx.clone()));
+                tx
+            })
+            .collect();
+        Queues { shared, pinned }
+    }
+}
+
+async fn worker(
+    mut pinned: mpsc::UnboundedReceiver<Job>,
+    shared: Arc<Mutex<mpsc::UnboundedReceiver<Job>>>,
+) {
+    loop {
+        let job = select_biased! {
+            job = pinned.recv().fuse() => job,
+            job = async { shared.lock().await.recv().await }.fuse() => job,
+        };
+        let Some(job) = job else { break };
+        // A panicking call drops its result sender, which its caller
+        // reports; the worker itself carries on
+        let _ = AssertUnwindSafe(job).catch_unwind().await;
+    }
+}
@@ -0,0 +1,33 @@
+use crate::utils::{Error, Result};
+use serde_json::Value;
+
+/// What the server does when a tool's `structuredContent` doesn't match its
+/// declared `outputSchema`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputValidation {
+    /// Replace the result with an internal error
+    #[default]
+    Enforce,
+    /// Log a warning and send the result anyway
+    Warn,
+    /// Don't check results
+    Off,
+}
+
+/// Describes each way `instance` violates `schema`; empty when it conforms
+pub fn violations(schema: &Value, instance: &Value) -> Result<Vec<String>> {
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| Error::ValidationError(format!("Invalid schema: {}", e)))?;
+    Ok(validator
+        .iter_errors(instance)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() {
+                e.to_string()
+            } else {
+                format!("{}: {}", path, e)
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,146 @@
+//! Ranking tools against a natural-language query, for `x-tools/search`
+//!
+//! Clients aggregating hundreds of tools can ask for the few relevant to a
+//! task before prompting the model with them. By default tools are scored
+//! by keyword overlap with their name and description; an
+//! [`EmbeddingBackend`] switches ranking to semantic similarity.
+
+use super::Tool;
+use crate::utils::{Error, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Results returned when the query doesn't set a limit
+pub const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+/// Turns texts into vectors whose cosine similarity reflects their meaning
+#[async_trait]
+pub trait EmbeddingBackend: Send + Sync {
+    /// One vector per text, in order
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Ranks tools against queries
+#[derive(Default)]
+pub struct ToolSearch {
+    embeddings: Option<Arc<dyn EmbeddingBackend>>,
+    // Tool texts rarely change, so their vectors are computed once
+    cache: Mutex<HashMap<String, Vec<f32>>>,
+}
+
+impl ToolSearch {
+    /// Keyword scoring
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Semantic scoring with `backend`
+    pub fn with_embeddings(backend: Arc<dyn EmbeddingBackend>) -> Self {
+        Self {
+            embeddings: Some(backend),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The `limit` best matches for `query`, best first; tools that don't
+    /// match at all are left out
+    pub async fn rank(&self, query: &str, tools: Vec<Tool>, limit: usize) -> Result<Vec<(Tool, f64)>> {
+        let scores = match &self.embeddings {
+            Some(backend) => self.semantic_scores(backend.as_ref(), query, &tools).await?,
+            None => tools.iter().map(|tool| keyword_score(query, tool)).collect(),
+        };
+        let mut ranked: Vec<(Tool, f64)> = tools
+            .into_iter()
+            .zip(scores)
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        // Stable, so equal scores keep the listing order
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(limit);
+        Ok(ranked)
+    }
+
+    async fn semantic_scores(
+        &self,
+        backend: &dyn EmbeddingBackend,
+        query: &str,
+        tools: &[Tool],
+    ) -> Result<Vec<f64>> {
+        let texts: Vec<String> = tools.iter().map(search_text).collect();
+        let missing: Vec<String> = {
+            let cache = self.lock_cache();
+            texts.iter().filter(|t| !cache.contains_key(*t)).cloned().collect()
+        };
+
+        let mut inputs = vec![query.to_string()];
+        inputs.extend(missing.iter().cloned());
+        let mut vectors = backend.embed(&inputs).await?;
+        if vectors.len() != inputs.len() {
+            return Err(Error::InternalError(format!(
+                "Embedding backend returned {} vectors for {} texts",
+                vectors.len(),
+                inputs.len()
+            )));
+        }
+        let query_vector = vectors.remove(0);
+
+        let mut cache = self.lock_cache();
+        cache.extend(missing.into_iter().zip(vectors));
+        Ok(texts
+            .iter()
+            .map(|text| cache.get(text).map_or(0.0, |v| cosine(&query_vector, v)))
+            .collect())
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, HashMap<String, Vec<f32>>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// What a tool is matched on
+fn search_text(tool: &Tool) -> String {
+    format!("{}: {}", tool.name, tool.description)
+}
+
+/// Lowercase words, splitting names like `fetch-weather` and `db_query`
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Share of the query's words found in the tool, counting a word in the
+/// name three times as much as one only in the description, and prefixes
+/// ("calc" for "calculate") half as much as whole words
+fn keyword_score(query: &str, tool: &Tool) -> f64 {
+    let query = words(query);
+    if query.is_empty() {
+        return 0.0;
+    }
+    let name = words(&tool.name);
+    let description = words(&tool.description);
+    let matches = |word: &str, in_words: &[String]| -> f64 {
+        let prefix = |w: &String| w.len() >= 3 && (w.starts_with(word) || word.starts_with(w.as_str()));
+        if in_words.iter().any(|w| w == word) {
+            1.0
+        } else if word.len() >= 3 && in_words.iter().any(prefix) {
+            0.5
+        } else {
+            0.0
+        }
+    };
+    let total: f64 = query
+        .iter()
+        .map(|word| (3.0 * matches(word, &name)).max(matches(word, &description)))
+        .sum();
+    total / (3.0 * query.len() as f64)
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| f64::from(*x) * f64::from(*y)).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| f64::from(*x).powi(2)).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 { 0.0 } else { dot / norms }
+}
@@ -0,0 +1,65 @@
+//! Support for tools defined from typed functions with
+//! [`#[mcp_tool]`](crate::mcp_tool)
+//!
+//! The macro deserializes the call's arguments into a struct generated
+//! from the function's parameters, derives the tool's input schema from
+//! that struct with `schemars`, and turns whatever the function returns
+//! into a [`CallToolResult`].
+
+use super::{CallToolResult, TextContent};
+use crate::utils::{Error, Result};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Return values of `#[mcp_tool]` functions
+pub trait IntoCallToolResult {
+    fn into_call_tool_result(self) -> Result<CallToolResult>;
+}
+
+impl IntoCallToolResult for CallToolResult {
+    fn into_call_tool_result(self) -> Result<CallToolResult> {
+        Ok(self)
+    }
+}
+
+/// Text content
+impl IntoCallToolResult for String {
+    fn into_call_tool_result(self) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![TextContent::new(self)]))
+    }
+}
+
+/// Pretty-printed JSON, also set as the structured content
+impl IntoCallToolResult for Value {
+    fn into_call_tool_result(self) -> Result<CallToolResult> {
+        let text = serde_json::to_string_pretty(&self)?;
+        Ok(CallToolResult::success(vec![TextContent::new(text)]).with_structured_content(self))
+    }
+}
+
+impl<T: IntoCallToolResult> IntoCallToolResult for Result<T> {
+    fn into_call_tool_result(self) -> Result<CallToolResult> {
+        self.and_then(T::into_call_tool_result)
+    }
+}
+
+/// Deserializes a call's arguments, treating missing arguments as `{}`
+pub fn parse_arguments<T: DeserializeOwned>(arguments: Value) -> Result<T> {
+    let arguments = match arguments {
+        Value::Null => Value::Object(serde_json::Map::new()),
+        arguments => arguments,
+    };
+    serde_json::from_value(arguments).map_err(|e| Error::InvalidParams(e.to_string()))
+}
+
+/// The input schema of a tool taking `T` as its arguments
+pub fn input_schema<T: JsonSchema>() -> Value {
+    let mut schema = schemars::schema_for!(T).to_value();
+    if let Some(schema) = schema.as_object_mut() {
+        // Names the generated struct, which means nothing to clients
+        schema.remove("title");
+        schema.remove("$schema");
+    }
+    schema
+}
\ No newline at end of file
@@ -1,19 +1,28 @@
 use super::{Tool, CallToolResult, TextContent, ToolHandler};
 use serde_json::{json, Value};
 use async_trait::async_trait;
-use crate::utils::{Result, Error, Logger};
+use crate::context::RequestContext;
+use crate::utils::i18n::MessageKey;
+use crate::utils::{Result, Error, Logger, RetryPolicy};
 
 pub struct WeatherTool {
     logger: Logger,
+    retry_policy: RetryPolicy,
 }
 
 impl WeatherTool {
     pub fn new() -> Self {
         Self {
             logger: Logger::new("WeatherTool"),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     pub fn tool_definition() -> Tool {
         Tool {
             name: "fetch-weather".to_string(),
@@ -33,8 +42,30 @@ impl WeatherTool {
                 "readOnlyHint": true,
                 "openWorldHint": true
             })),
+            output_schema: Some(json!({
+                "type": "object",
+                "properties": {
+                    "city": { "type": "string" },
+                    "temperature": { "type": "string" },
+                    "condition": { "type": "string" },
+                    "humidity": { "type": "string" },
+                    "windSpeed": { "type": "string" }
+                },
+                "required": ["city", "temperature", "condition", "humidity", "windSpeed"]
+            })),
         }
     }
+
+    async fn fetch(&self, city: &str) -> Result<Value> {
+        // Simulate weather data (in real scenario, call external API)
+        Ok(json!({
+            "city": city,
+            "temperature": "72°F",
+            "condition": "Partly Cloudy",
+            "humidity": "65%",
+            "windSpeed": "10 mph"
+        }))
+    }
 }
 
 impl Default for WeatherTool {
@@ -45,22 +76,17 @@ impl Default for WeatherTool {
 
 #[async_trait]
 impl ToolHandler for WeatherTool {
-    async fn call(&self, arguments: Value) -> Result<CallToolResult> {
+    async fn call(&self, context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
         let city = arguments
             .get("city")
             .and_then(|v| v.as_str())
-            .ok_or_else(|| Error::InvalidParams("Missing 'city' parameter".to_string()))?;
+            .ok_or_else(|| Error::invalid_params(MessageKey::MissingParameter, &["city"]))?;
 
         self.logger.debug_with_context("Fetching weather for city", city);
 
-        // Simulate weather data (in real scenario, call external API)
-        let weather_data = json!({
-            "city": city,
-            "temperature": "72°F",
-            "condition": "Partly Cloudy",
-            "humidity": "65%",
-            "windSpeed": "10 mph"
-        });
+        // Retries wait on the server's clock, so tests can drive them
+        let retry_policy = self.retry_policy.clone().with_clock(context.clock().clone());
+        let weather_data = retry_policy.retry(|| self.fetch(city)).await?;
 
         let message = format!(
             "Weather for {}:\n{}",
@@ -68,6 +94,11 @@ impl ToolHandler for WeatherTool {
             serde_json::to_string_pretty(&weather_data)?
         );
 
-        Ok(CallToolResult::success(vec![TextContent::new(message)]))
+        Ok(CallToolResult::success(vec![TextContent::new(message)])
+            .with_structured_content(weather_data))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Self::tool_definition())
     }
 }
\ No newline at end of file
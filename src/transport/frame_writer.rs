@@ -0,0 +1,154 @@
+use crate::transport::TransportSender;
+use crate::utils::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::Mutex as StdMutex;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+/// Cleared buffers kept for reuse
+const MAX_POOLED_BUFFERS: usize = 16;
+
+/// Buffers that grew past this are dropped rather than pooled, so one huge
+/// message doesn't pin its memory
+const MAX_POOLED_CAPACITY: usize = 64 * 1024;
+
+/// Queued frames are gathered into writes of about this size
+const BATCH_BYTES: usize = 64 * 1024;
+
+/// Outbound half of a newline-delimited stream, shared by everything that
+/// writes to it
+///
+/// Messages are serialized into pooled buffers and queued; whoever gets the
+/// stream next writes every queued frame and flushes once, so a burst of
+/// notifications costs one flush instead of one per message.
+pub struct FrameWriter {
+    stream: Mutex<Stream>,
+    queue: StdMutex<Queue>,
+}
+
+struct Stream {
+    writer: Box<dyn AsyncWrite + Send + Unpin>,
+    batch: Vec<u8>,
+}
+
+#[derive(Default)]
+struct Queue {
+    frames: Vec<Vec<u8>>,
+    pool: Vec<Vec<u8>>,
+    /// Frames ever queued and ever taken for writing; a frame's sequence
+    /// number is the value of `queued` before it was added
+    queued: u64,
+    taken: u64,
+}
+
+impl FrameWriter {
+    pub fn new<W>(writer: W) -> Self
+    where
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        Self {
+            stream: Mutex::new(Stream {
+                writer: Box::new(writer),
+                batch: Vec::new(),
+            }),
+            queue: StdMutex::new(Queue::default()),
+        }
+    }
+
+    /// Queues `message` as one frame without writing it
+    pub fn enqueue<T: Serialize>(&self, message: &T) -> Result<()> {
+        self.push(message).map(|_| ())
+    }
+
+    /// Writes `message` and everything queued before it
+    pub async fn send<T: Serialize>(&self, message: &T) -> Result<()> {
+        let sequence = self.push(message)?;
+        let mut stream = self.stream.lock().await;
+        // Another sender may have written this frame while we waited
+        if self.lock_queue().taken > sequence {
+            return Ok(());
+        }
+        self.write_queued(&mut stream).await
+    }
+
+    /// Writes every queued frame
+    pub async fn flush(&self) -> Result<()> {
+        let mut stream = self.stream.lock().await;
+        self.write_queued(&mut stream).await
+    }
+
+    fn push<T: Serialize>(&self, message: &T) -> Result<u64> {
+        let mut buf = self.lock_queue().pool.pop().unwrap_or_default();
+        if let Err(e) = serde_json::to_writer(&mut buf, message) {
+            self.recycle(vec![buf]);
+            return Err(e.into());
+        }
+        buf.push(b'\n');
+
+        let mut queue = self.lock_queue();
+        let sequence = queue.queued;
+        queue.frames.push(buf);
+        queue.queued += 1;
+        Ok(sequence)
+    }
+
+    async fn write_queued(&self, stream: &mut Stream) -> Result<()> {
+        let frames = {
+            let mut queue = self.lock_queue();
+            queue.taken = queue.queued;
+            std::mem::take(&mut queue.frames)
+        };
+        if frames.is_empty() {
+            return Ok(());
+        }
+
+        let result = async {
+            let Stream { writer, batch } = stream;
+            for frame in &frames {
+                if frame.len() >= BATCH_BYTES {
+                    writer.write_all(batch).await?;
+                    batch.clear();
+                    writer.write_all(frame).await?;
+                    continue;
+                }
+                batch.extend_from_slice(frame);
+                if batch.len() >= BATCH_BYTES {
+                    writer.write_all(batch).await?;
+                    batch.clear();
+                }
+            }
+            writer.write_all(batch).await?;
+            writer.flush().await
+        }
+        .await;
+        stream.batch.clear();
+        self.recycle(frames);
+        Ok(result?)
+    }
+
+    fn recycle(&self, frames: Vec<Vec<u8>>) {
+        let mut queue = self.lock_queue();
+        for mut frame in frames {
+            if queue.pool.len() >= MAX_POOLED_BUFFERS {
+                break;
+            }
+            if frame.capacity() <= MAX_POOLED_CAPACITY {
+                frame.clear();
+                queue.pool.push(frame);
+            }
+        }
+    }
+
+    fn lock_queue(&self) -> std::sync::MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+
+#[async_trait]
+impl TransportSender for FrameWriter {
+    async fn send(&self, message: serde_json::Value) -> Result<()> {
+        FrameWriter::send(self, &message).await
+    }
+}
\ No newline at end of file
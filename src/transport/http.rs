@@ -0,0 +1,262 @@
+//! JSON-RPC over HTTP
+//!
+//! Each `POST /mcp` carries one JSON-RPC message. Requests are answered with
+//! the JSON-RPC response; notifications with `202 Accepted` and no body.
+//!
+//! A successful initialize opens a session whose id is returned in the
+//! `Mcp-Session-Id` header. Requests carrying an unknown session id get
+//! `404`, and `DELETE /mcp` ends a session. Sessions are kept in the
+//! server's state store, so with a durable store they survive restarts.
+//! Requests without the header are served outside any session. Handlers
+//! registered with `McpServer::register_session_*` are only visible to
+//! requests in their session.
+//!
+//! `GET /mcp` with a session id opens a server-sent event stream carrying
+//! server-to-client messages for that session, such as pings; the client
+//! posts its replies like any other message. [`serve_listener`] runs an
+//! [`IdleMonitor`] with the server's idle policy, so sessions whose client
+//! has gone away are eventually torn down.
+//!
+//! [`with_bearer_auth`] puts a bearer-token check in front of the router.
+//! The identity a token maps to is what the server's access policy sees.
+//! Other authentication layers can insert an [`Identity`] extension
+//! themselves.
+
+use crate::McpServer;
+use crate::access;
+use crate::lifecycle::Phase;
+use crate::protocol::{self, RawRequest};
+use crate::session::{self, IdleMonitor, SessionState};
+use crate::utils::{Error, Logger, Result};
+use axum::extract::{Request, State};
+use axum::middleware::{self, Next};
+use axum::{Extension, Router};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::serve::Listener;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::json;
+use serde_json::value::RawValue;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt::Debug;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Header carrying the Streamable HTTP session id
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// The authenticated caller, set as a request extension by an
+/// authentication layer in front of the router
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Identity(pub String);
+
+/// Requires an `Authorization: Bearer` token from `tokens`, which maps each
+/// token to the identity it authenticates; other requests get `401`
+pub fn with_bearer_auth(router: Router, tokens: HashMap<String, String>) -> Router {
+    router.layer(middleware::from_fn_with_state(Arc::new(tokens), bearer_auth))
+}
+
+async fn bearer_auth(
+    State(tokens): State<Arc<HashMap<String, String>>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match token.and_then(|token| tokens.get(token)) {
+        Some(identity) => {
+            request.extensions_mut().insert(Identity(identity.clone()));
+            next.run(request).await
+        }
+        None => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
+
+/// Router serving `server` at `POST`, `GET` and `DELETE /mcp`
+pub fn router(server: Arc<McpServer>) -> Router {
+    Router::new()
+        .route("/mcp", post(handle_post).get(handle_get).delete(handle_delete))
+        .with_state(server)
+}
+
+/// Serves `server` over HTTP until the listener fails
+pub async fn serve(server: Arc<McpServer>, addr: SocketAddr) -> Result<()> {
+    serve_listener(server, tokio::net::TcpListener::bind(addr).await?).await
+}
+
+/// Serves `server` on an already bound listener, e.g. a Unix socket or one
+/// inherited through systemd socket activation
+pub async fn serve_listener<L>(server: Arc<McpServer>, listener: L) -> Result<()>
+where
+    L: Listener,
+    L::Addr: Debug,
+{
+    let logger = Logger::new("HttpTransport");
+    logger.info(&format!("Serving /mcp on {:?}", listener.local_addr()?));
+    IdleMonitor::new(server.config().idle.clone()).spawn(&server);
+    // Once the server shuts down, stop accepting connections and wait for
+    // the requests being answered
+    let stopping = server.clone();
+    axum::serve(listener, router(server))
+        .with_graceful_shutdown(async move { stopping.shutdown_requested().await })
+        .await?;
+    Ok(())
+}
+
+async fn handle_post(
+    State(server): State<Arc<McpServer>>,
+    identity: Option<Extension<Identity>>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    let sessions = server.sessions();
+    let mut session = match session_id(&headers) {
+        Some(id) => match sessions.get(id).await {
+            Ok(Some(session)) => Some(session),
+            Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+            Err(e) => return internal_error(e),
+        },
+        None => None,
+    };
+    if let Some(session) = &session {
+        sessions.touch(&session.id, server.clock().now());
+        // The session may have been started by another instance sharing
+        // the store
+        server.restore_session(session);
+    }
+
+    // The frame may not parse; it is answered below
+    let envelope = RawRequest::parse(&body).ok();
+    let method = envelope.as_ref().and_then(|e| e.method.as_deref());
+    // initialize starts a new session, and is answered as part of it so
+    // what the client negotiates is kept for that session alone
+    let new_session = (method == Some("initialize")).then(|| uuid::Uuid::new_v4().to_string());
+    let session_id = new_session.clone().or_else(|| session.as_ref().map(|s| s.id.clone()));
+
+    // A reply to a server-initiated request: hand it to whoever is waiting
+    // for it (a ping's reply has no one; being heard from is enough)
+    if is_reply(&body) {
+        let _ = session::with_session(session_id, server.handle_frame(&body)).await;
+        return StatusCode::ACCEPTED.into_response();
+    }
+
+    let identity = identity.map(|Extension(Identity(identity))| identity);
+    let handled = access::with_identity(identity, server.handle_frame(&body));
+    let response = match session::with_session(session_id, handled).await {
+        Ok(response) => response,
+        Err(Error::Json(e)) => {
+            return (StatusCode::BAD_REQUEST, axum::Json(protocol::Response::parse_error(&e))).into_response();
+        }
+        Err(e) => return internal_error(e),
+    };
+
+    let mut created = None;
+    match method {
+        Some("initialize") if response.as_ref().and_then(protocol::Reply::single).is_some_and(|r| r.error.is_none()) => {
+            let params = envelope.as_ref().and_then(|e| e.params_value().ok()).flatten();
+            let session = SessionState {
+                id: new_session.unwrap_or_default(),
+                ..SessionState::from_initialize(params.as_ref())
+            };
+            if let Err(e) = sessions.save(&session).await {
+                return internal_error(e);
+            }
+            sessions.touch(&session.id, server.clock().now());
+            server.lifecycle().enter(Some(session.id.clone()), Phase::Initializing);
+            created = Some(session.id);
+        }
+        Some("notifications/initialized") => {
+            if let Some(session) = session.as_mut().filter(|s| !s.initialized) {
+                session.initialized = true;
+                if let Err(e) = sessions.save(session).await {
+                    return internal_error(e);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut reply = match response {
+        Some(response) => match serde_json::to_vec(&response) {
+            Ok(body) => ([(header::CONTENT_TYPE, "application/json")], body).into_response(),
+            Err(e) => return internal_error(e.into()),
+        },
+        None => StatusCode::ACCEPTED.into_response(),
+    };
+    if let Some(id) = created.and_then(|id| id.parse().ok()) {
+        reply.headers_mut().insert(SESSION_HEADER, id);
+    }
+    reply
+}
+
+/// Streams the session's server-to-client messages as server-sent events
+async fn handle_get(State(server): State<Arc<McpServer>>, headers: HeaderMap) -> Response {
+    let Some(id) = session_id(&headers) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    match server.sessions().get(id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => return internal_error(e),
+    }
+    server.sessions().touch(id, server.clock().now());
+
+    let messages = server.open_session_channel(id);
+    let events = futures::stream::unfold(messages, |mut messages| async move {
+        let message = messages.recv().await?;
+        Some((Ok::<_, Infallible>(Event::default().data(message.to_string())), messages))
+    });
+    // An open stream would keep a graceful shutdown waiting forever
+    let stopping = server.clone();
+    let events = events.take_until(async move { stopping.shutdown_requested().await });
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+async fn handle_delete(State(server): State<Arc<McpServer>>, headers: HeaderMap) -> Response {
+    let Some(id) = session_id(&headers) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    match server.end_session(id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// Whether `body` is a JSON-RPC response rather than a request
+fn is_reply(body: &str) -> bool {
+    #[derive(Deserialize)]
+    struct Reply<'a> {
+        #[serde(borrow)]
+        method: Option<&'a RawValue>,
+        #[serde(borrow)]
+        result: Option<&'a RawValue>,
+        #[serde(borrow)]
+        error: Option<&'a RawValue>,
+    }
+    !protocol::is_batch(body)
+        && serde_json::from_str::<Reply>(body).is_ok_and(|r| r.method.is_none() && (r.result.is_some() || r.error.is_some()))
+}
+
+fn session_id(headers: &HeaderMap) -> Option<&str> {
+    headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok())
+}
+
+fn internal_error(e: Error) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        axum::Json(json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32603, "message": "Internal error", "data": e.to_string() }
+        })),
+    )
+        .into_response()
+}
@@ -0,0 +1,101 @@
+use crate::McpServer;
+use crate::transport::{Transport, TransportSender};
+use crate::utils::{Error, Logger, Result};
+use async_trait::async_trait;
+use futures::future::{Either, select};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+
+/// One end of an in-memory duplex channel
+///
+/// [`MemoryTransport::pair`] connects a client end to a server end, so tests
+/// can drive an [`McpServer`] end to end, notifications included, without
+/// stdin and stdout. Closing either end ends the other's `receive`.
+pub struct MemoryTransport {
+    logger: Logger,
+    outgoing: Option<mpsc::UnboundedSender<Value>>,
+    incoming: mpsc::UnboundedReceiver<Value>,
+}
+
+impl MemoryTransport {
+    /// A connected `(client, server)` pair
+    pub fn pair() -> (MemoryTransport, MemoryTransport) {
+        let (to_server, from_client) = mpsc::unbounded_channel();
+        let (to_client, from_server) = mpsc::unbounded_channel();
+        let end = |outgoing, incoming| MemoryTransport {
+            logger: Logger::new("MemoryTransport"),
+            outgoing: Some(outgoing),
+            incoming,
+        };
+        (end(to_server, from_server), end(to_client, from_client))
+    }
+
+    /// Serves `server` until the client end closes, forwarding the server's
+    /// notifications as they are sent
+    pub async fn serve(&mut self, server: &McpServer) -> Result<()> {
+        let mut notifications = server.subscribe_notifications();
+        loop {
+            let next = {
+                let message = Box::pin(self.incoming.recv());
+                let notification = Box::pin(notifications.recv());
+                match select(message, notification).await {
+                    Either::Left((message, _)) => Either::Left(message),
+                    Either::Right((notification, _)) => Either::Right(notification),
+                }
+            };
+            match next {
+                Either::Left(Some(message)) => match server.handle_request(message).await {
+                    Ok(Some(response)) => self.send(response).await?,
+                    Ok(None) => {}
+                    Err(e) => self.logger.error_with_context("Failed to handle message", &e.to_string()),
+                },
+                Either::Left(None) => break,
+                Either::Right(Ok(notification)) => self.send(notification).await?,
+                Either::Right(Err(broadcast::error::RecvError::Lagged(missed))) => {
+                    self.logger.warn(&format!("Dropped {} notifications", missed));
+                }
+                Either::Right(Err(broadcast::error::RecvError::Closed)) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn send(&mut self, message: Value) -> Result<()> {
+        send_on(self.outgoing.as_ref(), message)
+    }
+
+    async fn receive(&mut self) -> Result<Option<Value>> {
+        Ok(self.incoming.recv().await)
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.outgoing = None;
+        self.incoming.close();
+        Ok(())
+    }
+
+    fn sender(&self) -> Arc<dyn TransportSender> {
+        Arc::new(MemorySender(self.outgoing.clone()))
+    }
+}
+
+/// Sends on the channel of a [`MemoryTransport`]; the peer's `receive`
+/// ends once the transport and all of these are closed or dropped
+struct MemorySender(Option<mpsc::UnboundedSender<Value>>);
+
+#[async_trait]
+impl TransportSender for MemorySender {
+    async fn send(&self, message: Value) -> Result<()> {
+        send_on(self.0.as_ref(), message)
+    }
+}
+
+fn send_on(outgoing: Option<&mpsc::UnboundedSender<Value>>, message: Value) -> Result<()> {
+    outgoing
+        .and_then(|outgoing| outgoing.send(message).ok())
+        .ok_or_else(|| Error::Io(std::io::ErrorKind::BrokenPipe.into()))
+}
\ No newline at end of file
@@ -0,0 +1,186 @@
+use crate::McpServer;
+use crate::transport::{Transport, TransportSender};
+use crate::utils::{Error, Logger, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// One client message from a conversation fixture and the reply it expects
+///
+/// `expect` is matched as a subset of the actual reply: objects must contain
+/// the expected keys (extra keys are ignored) and arrays must match element
+/// by element. A step without `expect` asserts that the server sends nothing,
+/// as for notifications.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct FixtureStep {
+    pub client: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expect: Option<Value>,
+}
+
+/// Transport that plays back a scripted client conversation and checks the
+/// server's replies against it
+pub struct MockTransport {
+    pending: VecDeque<FixtureStep>,
+    // Shared with the handles from `sender`, whose messages are checked
+    // like any other reply
+    playback: Arc<Mutex<Playback>>,
+}
+
+/// Where the conversation stands and what went wrong so far
+struct Playback {
+    logger: Logger,
+    current: Option<FixtureStep>,
+    replied: bool,
+    step: usize,
+    failures: Vec<String>,
+}
+
+impl MockTransport {
+    pub fn new(steps: Vec<FixtureStep>) -> Self {
+        Self {
+            pending: steps.into(),
+            playback: Arc::new(Mutex::new(Playback {
+                logger: Logger::new("MockTransport"),
+                current: None,
+                replied: false,
+                step: 0,
+                failures: Vec::new(),
+            })),
+        }
+    }
+
+    /// Loads a fixture file containing a JSON array of [`FixtureStep`]s
+    pub fn from_fixture(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref())?;
+        let steps: Vec<FixtureStep> = serde_json::from_str(&content)?;
+        Ok(Self::new(steps))
+    }
+
+    /// Feeds every scripted message to `server` and returns an error listing
+    /// all steps whose replies did not match
+    pub async fn replay(&mut self, server: &McpServer) -> Result<()> {
+        while let Some(message) = self.receive().await? {
+            match server.handle_request(message).await {
+                Ok(Some(response)) => self.send(response).await?,
+                Ok(None) => {}
+                Err(e) => self.lock().fail(format!("handler error: {}", e)),
+            }
+        }
+        self.close().await?;
+        self.verify()
+    }
+
+    /// Returns an error describing every mismatch seen so far
+    pub fn verify(&self) -> Result<()> {
+        let playback = self.lock();
+        if playback.failures.is_empty() {
+            return Ok(());
+        }
+        Err(Error::ValidationError(format!(
+            "Fixture replay failed:\n{}",
+            playback.failures.join("\n")
+        )))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Playback> {
+        self.playback.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Playback {
+    fn fail(&mut self, reason: String) {
+        let failure = format!("step {}: {}", self.step, reason);
+        self.logger.error(&failure);
+        self.failures.push(failure);
+    }
+
+    /// Records a missing reply for the step that just finished
+    fn finish_step(&mut self) {
+        if let Some(FixtureStep {
+            expect: Some(expected),
+            ..
+        }) = self.current.take()
+            && !self.replied
+        {
+            self.fail(format!(
+                "expected a reply matching {} but none was sent",
+                expected
+            ));
+        }
+    }
+}
+
+/// Whether `actual` contains everything in `expected`
+pub fn json_matches(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|a| json_matches(a, value))),
+        (Value::Array(actual), Value::Array(expected)) => {
+            actual.len() == expected.len()
+                && actual.iter().zip(expected).all(|(a, e)| json_matches(a, e))
+        }
+        _ => actual == expected,
+    }
+}
+
+impl Playback {
+    /// Checks `message` against the reply the current step expects
+    fn check(&mut self, message: Value) {
+        let expected = self.current.as_ref().and_then(|step| step.expect.clone());
+        match expected {
+            _ if self.replied => self.fail(format!("unexpected extra message {}", message)),
+            Some(expected) if !json_matches(&message, &expected) => {
+                self.fail(format!("expected {} but got {}", expected, message))
+            }
+            Some(_) => {}
+            None => self.fail(format!("expected no reply but got {}", message)),
+        }
+        self.replied = true;
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send(&mut self, message: Value) -> Result<()> {
+        self.lock().check(message);
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> Result<Option<Value>> {
+        let step = self.pending.pop_front();
+        let mut playback = self.lock();
+        playback.finish_step();
+        let Some(step) = step else {
+            return Ok(None);
+        };
+        playback.step += 1;
+        playback.replied = false;
+        let message = step.client.clone();
+        playback.current = Some(step);
+        Ok(Some(message))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.lock().finish_step();
+        Ok(())
+    }
+
+    fn sender(&self) -> Arc<dyn TransportSender> {
+        Arc::new(MockSender(self.playback.clone()))
+    }
+}
+
+/// Checks messages sent outside `replay` against the script too
+struct MockSender(Arc<Mutex<Playback>>);
+
+#[async_trait]
+impl TransportSender for MockSender {
+    async fn send(&self, message: Value) -> Result<()> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).check(message);
+        Ok(())
+    }
+}
@@ -1,13 +1,52 @@
+#[cfg(feature = "native")]
 pub mod stdio;
+#[cfg(feature = "native")]
+pub mod frame_writer;
+pub mod mock;
+pub mod memory;
+pub mod wasi;
+#[cfg(feature = "native")]
+pub mod http;
+#[cfg(feature = "native")]
+pub mod rest;
+#[cfg(feature = "native")]
+pub mod tcp;
 
+#[cfg(feature = "native")]
 pub use stdio::StdioTransport;
+#[cfg(feature = "native")]
+pub use frame_writer::FrameWriter;
+#[cfg(feature = "native")]
+pub use tcp::TcpTransport;
+pub use mock::MockTransport;
+pub use memory::MemoryTransport;
+pub use wasi::WasiTransport;
 
 use async_trait::async_trait;
 use crate::utils::Result;
+use std::sync::Arc;
 
+/// `_meta` key carrying a stdio server's shared secret in the client's
+/// first message
+pub const AUTH_TOKEN_KEY: &str = "authToken";
+
+/// A message stream to and from the peer
+///
+/// `send` and `receive` take `&mut self`, so while one task is blocked in
+/// `receive` others write through [`Transport::sender`] instead.
 #[async_trait]
 pub trait Transport: Send + Sync {
     async fn send(&mut self, message: serde_json::Value) -> Result<()>;
     async fn receive(&mut self) -> Result<Option<serde_json::Value>>;
     async fn close(&mut self) -> Result<()>;
+
+    /// A handle writing to the same peer, usable concurrently with
+    /// `receive`, e.g. to push server-initiated notifications
+    fn sender(&self) -> Arc<dyn TransportSender>;
+}
+
+/// The sending half of a [`Transport`]; any number of tasks may share it
+#[async_trait]
+pub trait TransportSender: Send + Sync {
+    async fn send(&self, message: serde_json::Value) -> Result<()>;
 }
\ No newline at end of file
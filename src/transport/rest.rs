@@ -0,0 +1,118 @@
+//! Plain HTTP facade over a server's tools and resources
+//!
+//! For automation that does not speak JSON-RPC:
+//!
+//! - `POST /tools/{name}` with the tool arguments as the JSON body (an empty
+//!   body means no arguments) returns the `CallToolResult`
+//! - `GET /resources?uri=...` returns the resource read result
+//!
+//! Calls go through the server's normal dispatch path. JSON-RPC errors are
+//! returned as the error object with a matching HTTP status.
+
+use crate::McpServer;
+use crate::protocol;
+use crate::utils::{Error, Logger, Result};
+use crate::utils::error_codes::{ACCESS_DENIED, MEMORY_PRESSURE, SERVER_OVERLOADED};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Id for a request the facade dispatches. Calls share a session, so each
+/// needs an id of its own to be in flight alongside the others
+fn rest_request_id() -> String {
+    format!("rest-{}", uuid::Uuid::new_v4())
+}
+
+/// Router exposing `server`'s tools and resources
+pub fn router(server: Arc<McpServer>) -> Router {
+    Router::new()
+        .route("/tools/{name}", post(call_tool))
+        .route("/resources", get(read_resource))
+        .with_state(server)
+}
+
+/// Serves the facade until the listener fails
+pub async fn serve(server: Arc<McpServer>, addr: SocketAddr) -> Result<()> {
+    let logger = Logger::new("RestGateway");
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    logger.info(&format!("REST gateway listening on http://{}", listener.local_addr()?));
+    axum::serve(listener, router(server)).await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ResourceQuery {
+    uri: String,
+}
+
+async fn call_tool(
+    State(server): State<Arc<McpServer>>,
+    Path(name): Path<String>,
+    body: String,
+) -> Response {
+    let arguments = if body.trim().is_empty() {
+        json!({})
+    } else {
+        match serde_json::from_str::<Value>(&body) {
+            Ok(arguments) => arguments,
+            Err(e) => {
+                return error_response(-32700, format!("Parse error: {}", e), None);
+            }
+        }
+    };
+
+    dispatch(&server, "tools/call", json!({ "name": name, "arguments": arguments })).await
+}
+
+async fn read_resource(
+    State(server): State<Arc<McpServer>>,
+    Query(query): Query<ResourceQuery>,
+) -> Response {
+    dispatch(&server, "resources/read", json!({ "uri": query.uri })).await
+}
+
+async fn dispatch(server: &McpServer, method: &str, params: Value) -> Response {
+    let response = server
+        .handle_request(json!({
+            "jsonrpc": "2.0",
+            "id": rest_request_id(),
+            "method": method,
+            "params": params
+        }))
+        .await;
+
+    let result = match response {
+        Ok(Some(response)) => protocol::response_result(response),
+        Ok(None) => Err(Error::InternalError(format!("No response to {}", method))),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(result) => Json(result).into_response(),
+        Err(Error::Rpc { code, message, data }) => error_response(code, message, data),
+        Err(e) => error_response(e.code(), e.to_string(), None),
+    }
+}
+
+fn error_response(code: i64, message: String, data: Option<Value>) -> Response {
+    let status = match code {
+        -32700 | -32600 | -32602 => StatusCode::BAD_REQUEST,
+        -32601 | -32002 => StatusCode::NOT_FOUND,
+        c if c == ACCESS_DENIED.code() => StatusCode::FORBIDDEN,
+        c if c == SERVER_OVERLOADED.code() || c == MEMORY_PRESSURE.code() => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    (status, Json(json!({ "error": error }))).into_response()
+}
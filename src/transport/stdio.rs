@@ -1,10 +1,27 @@
-use crate::utils::{Result, Logger};
-use serde_json::json;
+use crate::McpServer;
+use crate::protocol::{self, RawRequest, Reply, Response};
+use crate::runtime;
+use crate::utils::error_codes::ACCESS_DENIED;
+use crate::utils::{Error, Result, Logger};
+use serde::Serialize;
+use serde_json::{Value, json};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
-use tokio::sync::Mutex;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite};
+use tokio::sync::{Mutex, broadcast, oneshot};
 use futures::future::BoxFuture;
-use crate::transport::Transport;
+use futures::stream::{FuturesUnordered, Stream, StreamExt};
+use crate::transport::{AUTH_TOKEN_KEY, FrameWriter, Transport, TransportSender};
+
+/// Most notifications written in one batch
+const MAX_NOTIFICATION_BATCH: usize = 64;
+
+/// Most requests handled at once unless configured otherwise
+const DEFAULT_MAX_CONCURRENCY: usize = 64;
+
+/// A request being handled, resolving to its frame and what handling it
+/// produced
+type Dispatched<'a> = BoxFuture<'a, (String, Result<Option<Reply>>)>;
+
 /// Standard Input/Output transport for MCP servers
 /// 
 /// Messages are sent as newline-delimited JSON-RPC 2.0 messages
@@ -12,20 +29,304 @@ use crate::transport::Transport;
 pub struct StdioTransport {
     logger: Logger,
     // For testing and flexibility, we use in-memory buffers wrapped in Arc<Mutex>
-    reader: Arc<Mutex<tokio::io::BufReader<tokio::io::Stdin>>>,
-    writer: Arc<Mutex<tokio::io::Stdout>>,
+    reader: Arc<Mutex<Box<dyn AsyncBufRead + Send + Unpin>>>,
+    writer: Arc<FrameWriter>,
+    // Reused across messages to avoid per-message allocations
+    read_buf: String,
+    shared_secret: Option<String>,
+    max_concurrency: usize,
 }
 
 impl StdioTransport {
     pub fn new() -> Self {
+        Self::from_io(tokio::io::BufReader::new(tokio::io::stdin()), tokio::io::stdout())
+    }
+
+    /// Uses the same newline-delimited framing over arbitrary streams, e.g.
+    /// in-memory buffers in tests and fuzzing
+    pub fn from_io<R, W>(reader: R, writer: W) -> Self
+    where
+        R: AsyncBufRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
         let logger = Logger::new("StdioTransport");
         logger.info("Initializing StdioTransport");
 
         Self {
             logger,
-            reader: Arc::new(Mutex::new(tokio::io::BufReader::new(tokio::io::stdin()))),
-            writer: Arc::new(Mutex::new(tokio::io::stdout())),
+            reader: Arc::new(Mutex::new(Box::new(reader))),
+            writer: Arc::new(FrameWriter::new(writer)),
+            read_buf: String::new(),
+            shared_secret: None,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+        }
+    }
+
+    /// Requires the client's first message to carry `secret` under
+    /// `_meta.authToken`; a client that doesn't present it is answered with
+    /// an error and disconnected before any request is handled
+    pub fn with_shared_secret(mut self, secret: impl Into<String>) -> Self {
+        self.shared_secret = Some(secret.into());
+        self
+    }
+
+    /// Handles at most `max` requests at once (64 by default); no further
+    /// frames are read until one of them is answered. 1 handles requests one
+    /// at a time.
+    pub fn with_max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = max.max(1);
+        self
+    }
+
+    /// Whether `frame` carries the shared secret
+    fn authenticates(&self, frame: &str) -> bool {
+        let Some(secret) = &self.shared_secret else {
+            return true;
+        };
+        let token = RawRequest::parse(frame)
+            .ok()
+            .and_then(|envelope| envelope.params_value().ok().flatten())
+            .and_then(|params| params["_meta"][AUTH_TOKEN_KEY].as_str().map(str::to_string));
+        token.is_some_and(|token| constant_time_eq(token.as_bytes(), secret.as_bytes()))
+    }
+
+    /// Parses one newline-delimited frame; blank lines yield `None`
+    pub fn parse_line(line: &str) -> Result<Option<serde_json::Value>> {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(trimmed)?))
+    }
+
+    /// Serves `server` until stdin closes or the server shuts down
+    ///
+    /// Frames are handed to [`McpServer::handle_frame`] as read, and
+    /// responses are serialized straight into the write buffer, so neither
+    /// side of a request is materialized as a `Value`. Requests are handled
+    /// concurrently, up to [`StdioTransport::with_max_concurrency`], and
+    /// answered in the order they finish, so a slow tool call holds up
+    /// nothing else. Notifications sent through [`McpServer::notify`] are
+    /// written as they arrive. Serving ends when stdin closes or
+    /// [`McpServer::shutdown`] begins, once the requests already read are
+    /// answered.
+    ///
+    /// The requests are polled by the task calling `serve`, since they
+    /// borrow `server`: they interleave at their awaits but never run in
+    /// parallel, and a handler that blocks the thread stalls them all.
+    /// [`StdioTransport::serve_shared`] runs each request as its own task.
+    pub async fn serve(&mut self, server: &McpServer) -> Result<()> {
+        self.serve_with(server, |frame| {
+            Box::pin(async move {
+                let handled = server.handle_frame(&frame).await;
+                (frame, handled)
+            })
+        })
+        .await
+    }
+
+    /// Like [`StdioTransport::serve`], but spawns each request as a task of
+    /// its own, so requests run in parallel on a multi-threaded runtime.
+    /// At most [`StdioTransport::with_max_concurrency`] of them are in
+    /// flight at once.
+    pub async fn serve_shared(&mut self, server: Arc<McpServer>) -> Result<()> {
+        let serving = server.clone();
+        self.serve_with(&serving, move |frame| {
+            let (answered, answer) = oneshot::channel();
+            let server = server.clone();
+            let request = frame.clone();
+            runtime::spawn(async move {
+                let handled = server.handle_frame(&request).await;
+                let _ = answered.send(handled);
+            });
+            Box::pin(async move {
+                // The task only drops the sender if the handler panicked
+                let handled = answer.await.unwrap_or_else(|_| {
+                    Err(Error::InternalError("Request handler panicked".to_string()))
+                });
+                (frame, handled)
+            })
+        })
+        .await
+    }
+
+    async fn serve_with<'a>(
+        &mut self,
+        server: &'a McpServer,
+        dispatch: impl Fn(String) -> Dispatched<'a>,
+    ) -> Result<()> {
+        self.logger.info("Starting to serve on stdio");
+        let forwarder = self.forward_notifications(server.subscribe_notifications());
+        let result = self.serve_requests(server, dispatch).await;
+        forwarder.abort();
+        // Write out notifications queued before the forwarder stopped
+        if let Err(e) = self.writer.flush().await {
+            self.logger.warn(&format!("Failed to flush stdout: {}", e));
         }
+        result
+    }
+
+    async fn serve_requests<'a>(
+        &mut self,
+        server: &'a McpServer,
+        dispatch: impl Fn(String) -> Dispatched<'a>,
+    ) -> Result<()> {
+        let mut authenticated = self.shared_secret.is_none();
+        let mut lines = std::pin::pin!(read_lines(self.reader.clone()));
+        let mut shutdown = std::pin::pin!(server.shutdown_requested());
+        let mut handling: FuturesUnordered<Dispatched<'a>> = FuturesUnordered::new();
+
+        loop {
+            let read = tokio::select! {
+                biased;
+                Some((frame, handled)) = handling.next(), if !handling.is_empty() => {
+                    if !self.write_reply(&frame, handled).await? {
+                        return Ok(());
+                    }
+                    continue;
+                }
+                _ = &mut shutdown => {
+                    self.logger.info("Server shutting down, no longer reading stdin");
+                    break;
+                }
+                read = lines.next(), if handling.len() < self.max_concurrency => read,
+            };
+            let frame = match read {
+                None => {
+                    self.logger.info("Stdin closed, shutting down");
+                    break;
+                }
+                Some(Ok(line)) if line.trim().is_empty() => {
+                    // Mirror listen(): a blank line ends the session
+                    self.logger.info("Stdin closed, shutting down");
+                    break;
+                }
+                Some(Ok(line)) => line.trim().to_string(),
+                Some(Err(e)) => {
+                    let e = Error::Io(e);
+                    if e.is_fatal() {
+                        self.logger.error(&format!("Transport error: {}", e));
+                        break;
+                    }
+                    // The unreadable line was consumed; answer it and move on
+                    self.logger.warn(&format!("Received unreadable message: {}", e));
+                    self.write_message(&Response::parse_error(&e)).await?;
+                    continue;
+                }
+            };
+
+            if self.logger.debug_enabled() {
+                self.logger.debug_with_context("Received message", &frame);
+            }
+
+            if !authenticated {
+                if !self.authenticates(&frame) {
+                    self.logger.warn("Client did not present the shared secret, disconnecting");
+                    let id = RawRequest::parse(&frame).ok().and_then(|e| e.id).unwrap_or(Value::Null);
+                    let error = json!({ "code": ACCESS_DENIED.code(), "message": "Authentication required" });
+                    self.write_message(&Response::error(id, error)).await?;
+                    break;
+                }
+                authenticated = true;
+            }
+
+            // Notifications and replies take effect in the order they were
+            // sent, e.g. `notifications/initialized` before the requests
+            // following it; requests run alongside each other
+            let is_request = protocol::is_batch(&frame)
+                || RawRequest::parse(&frame).map_or(true, |envelope| envelope.id.is_some());
+            if is_request {
+                handling.push(dispatch(frame));
+            } else {
+                let handled = server.handle_frame(&frame).await;
+                if !self.write_reply(&frame, handled).await? {
+                    return Ok(());
+                }
+            }
+        }
+
+        // Requests already read are still answered
+        while let Some((frame, handled)) = handling.next().await {
+            if !self.write_reply(&frame, handled).await? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes what handling `frame` produced; false if the transport broke
+    async fn write_reply(&mut self, frame: &str, handled: Result<Option<Reply>>) -> Result<bool> {
+        match handled {
+            Ok(Some(reply)) => self.write_message(&reply).await?,
+            Ok(None) => {
+                // Notification; no response needed
+                self.logger.debug("Notification processed, no response sent");
+            }
+            Err(Error::Json(e)) => {
+                self.logger.warn(&format!("Received invalid JSON: {}", e));
+                self.write_message(&Response::parse_error(&e)).await?;
+            }
+            Err(e) if e.is_fatal() => {
+                self.logger.error(&format!("Transport error: {}", e));
+                return Ok(false);
+            }
+            Err(e) => {
+                self.logger.error_with_context("Failed to handle message", &e.to_string());
+                if let Some(response) = Response::for_failed_frame(frame, &e) {
+                    self.write_message(&response).await?;
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Writes server notifications as whole frames; the shared writer keeps
+    /// them from interleaving with responses. Notifications that queued up
+    /// meanwhile go out in the same write.
+    fn forward_notifications(
+        &self,
+        mut notifications: broadcast::Receiver<serde_json::Value>,
+    ) -> tokio::task::JoinHandle<()> {
+        let writer = self.writer.clone();
+        let logger = Logger::new("StdioTransport");
+        tokio::spawn(async move {
+            loop {
+                let notification = match notifications.recv().await {
+                    Ok(notification) => notification,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        logger.warn(&format!("Dropped {} notifications", missed));
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let mut queued = writer.enqueue(&notification);
+                for _ in 1..MAX_NOTIFICATION_BATCH {
+                    match notifications.try_recv() {
+                        Ok(notification) => queued = queued.and(writer.enqueue(&notification)),
+                        Err(broadcast::error::TryRecvError::Lagged(missed)) => {
+                            logger.warn(&format!("Dropped {} notifications", missed));
+                        }
+                        Err(_) => break,
+                    }
+                }
+                if let Err(e) = queued {
+                    logger.error(&format!("Failed to serialize notification: {}", e));
+                }
+                if let Err(e) = writer.flush().await {
+                    logger.error(&format!("Failed to send notification: {}", e));
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Writes `message` as one newline-terminated frame, along with any
+    /// notifications queued before it
+    pub async fn write_message<T: Serialize>(&mut self, message: &T) -> Result<()> {
+        if self.logger.debug_enabled() {
+            self.logger.debug_with_context("Sending message", &serde_json::to_string(message)?);
+        }
+        self.writer.send(message).await
     }
 
     pub async fn listen<F>(
@@ -40,20 +341,22 @@ impl StdioTransport {
         loop {
             match self.receive().await {
                 Ok(Some(message)) => {
-                    self.logger.debug_with_context("Received message", &message.to_string());
+                    if self.logger.debug_enabled() {
+                        self.logger.debug_with_context("Received message", &message.to_string());
+                    }
 
-                    match handler(message.clone()).await {
-                        Ok(Some(response)) => {
-                            self.logger.debug_with_context("Sending response", &response.to_string());
-                            self.send(response).await?;
-                        }
+                    // Only the id is needed after the handler takes the message
+                    let id = message.get("id").cloned();
+
+                    match handler(message).await {
+                        Ok(Some(response)) => self.send(response).await?,
                         Ok(None) => {
                             // Notification; no response needed
                             self.logger.debug("Notification processed, no response sent");
                         }
                         Err(e) => {
                             self.logger.error_with_context("Handler error", &e.to_string());
-                            if let Some(id) = message.get("id") {
+                            if let Some(id) = id {
                                 let error_response = json!({
                                     "jsonrpc": "2.0",
                                     "id": id,
@@ -72,10 +375,18 @@ impl StdioTransport {
                     self.logger.info("Stdin closed, shutting down");
                     break;
                 }
-                Err(e) => {
+                Err(Error::Json(e)) => {
+                    self.logger.warn(&format!("Received invalid JSON: {}", e));
+                    self.write_message(&Response::parse_error(&e)).await?;
+                }
+                Err(e) if e.is_fatal() => {
                     self.logger.error(&format!("Transport error: {}", e));
                     break;
                 }
+                Err(e) => {
+                    self.logger.warn(&format!("Received unreadable message: {}", e));
+                    self.write_message(&Response::parse_error(&e)).await?;
+                }
             }
         }
 
@@ -83,6 +394,28 @@ impl StdioTransport {
     }
 }
 
+/// The lines of `reader`, newlines included; a line is never lost to a
+/// `select!` that stops waiting for it, since the read in progress lives in
+/// the stream
+fn read_lines(
+    reader: Arc<Mutex<Box<dyn AsyncBufRead + Send + Unpin>>>,
+) -> impl Stream<Item = std::io::Result<String>> {
+    futures::stream::unfold(reader, |reader| async move {
+        let mut line = String::new();
+        let read = reader.lock().await.read_line(&mut line).await;
+        match read {
+            Ok(0) => None,
+            Ok(_) => Some((Ok(line), reader)),
+            Err(e) => Some((Err(e), reader)),
+        }
+    })
+}
+
+/// Compares secrets without leaking where they first differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
 impl Default for StdioTransport {
     fn default() -> Self {
         Self::new()
@@ -92,27 +425,15 @@ impl Default for StdioTransport {
 #[async_trait::async_trait]
 impl super::Transport for StdioTransport {
     async fn send(&mut self, message: serde_json::Value) -> Result<()> {
-        let json_str = serde_json::to_string(&message)?;
-        let mut writer = self.writer.lock().await;
-        writer.write_all(json_str.as_bytes()).await?;
-        writer.write_all(b"\n").await?;
-        writer.flush().await?;
-        Ok(())
+        self.write_message(&message).await
     }
 
     async fn receive(&mut self) -> Result<Option<serde_json::Value>> {
         let mut reader = self.reader.lock().await;
-        let mut line = String::new();
-        match reader.read_line(&mut line).await? {
+        self.read_buf.clear();
+        match reader.read_line(&mut self.read_buf).await? {
             0 => Ok(None), // EOF
-            _ => {
-                let trimmed = line.trim();
-                if trimmed.is_empty() {
-                    return Ok(None);
-                }
-                let json = serde_json::from_str(trimmed)?;
-                Ok(Some(json))
-            }
+            _ => Self::parse_line(&self.read_buf),
         }
     }
 
@@ -120,4 +441,8 @@ impl super::Transport for StdioTransport {
         self.logger.info("Closing StdioTransport");
         Ok(())
     }
+
+    fn sender(&self) -> Arc<dyn TransportSender> {
+        self.writer.clone()
+    }
 }
\ No newline at end of file
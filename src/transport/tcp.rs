@@ -0,0 +1,209 @@
+//! Newline-delimited JSON-RPC over TCP
+//!
+//! Each connection carries the same framing as stdio and is served as its
+//! own session: handlers registered with `McpServer::register_session_*`,
+//! the negotiated protocol revision and server-to-client messages are kept
+//! apart per connection. The session ends when the connection closes.
+
+use crate::McpServer;
+use crate::notifications::Gate;
+use crate::protocol::{RawRequest, Reply, Response};
+use crate::session::{self, SessionState};
+use crate::transport::FrameWriter;
+use crate::utils::{Error, Logger, Result};
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinSet;
+
+/// Accepts MCP clients on a TCP socket
+pub struct TcpTransport {
+    logger: Logger,
+    listener: TcpListener,
+}
+
+impl TcpTransport {
+    pub async fn bind(addr: impl ToSocketAddrs) -> Result<Self> {
+        Ok(Self::from_listener(TcpListener::bind(addr).await?))
+    }
+
+    /// Serves on an already bound listener, e.g. one inherited through
+    /// systemd socket activation
+    pub fn from_listener(listener: TcpListener) -> Self {
+        Self {
+            logger: Logger::new("TcpTransport"),
+            listener,
+        }
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serves each accepted connection concurrently until accepting fails or
+    /// the server shuts down; then waits for the connections to answer the
+    /// requests they are handling
+    pub async fn serve(&self, server: Arc<McpServer>) -> Result<()> {
+        self.logger.info(&format!("Serving on tcp://{}", self.local_addr()?));
+        let mut connections = JoinSet::new();
+        let mut shutdown = std::pin::pin!(server.shutdown_requested());
+        loop {
+            let (stream, peer) = tokio::select! {
+                accepted = self.listener.accept() => accepted?,
+                _ = &mut shutdown => break,
+                // Reap finished connections as we go
+                Some(_) = connections.join_next(), if !connections.is_empty() => continue,
+            };
+            let server = server.clone();
+            let logger = self.logger.clone();
+            connections.spawn(async move {
+                logger.debug(&format!("Accepted connection from {}", peer));
+                if let Err(e) = serve_connection(server, stream).await {
+                    logger.warn(&format!("Connection from {} failed: {}", peer, e));
+                }
+            });
+        }
+        self.logger.info("Server shutting down, no longer accepting connections");
+        while connections.join_next().await.is_some() {}
+        Ok(())
+    }
+}
+
+/// Serves one client on `stream` until it disconnects
+pub async fn serve_connection(server: Arc<McpServer>, stream: TcpStream) -> Result<()> {
+    let (reader, writer) = stream.into_split();
+    let writer = Arc::new(FrameWriter::new(writer));
+    let mut session = SessionState::from_initialize(None);
+    server.sessions().save(&session).await?;
+    let id = session.id.clone();
+
+    let forwarder = tokio::spawn(forward(
+        writer.clone(),
+        server.open_session_channel(&id),
+        server.subscribe_notifications(),
+        server.session_gate(Some(id.clone())),
+    ));
+    let result = session::with_session(
+        Some(id.clone()),
+        serve_frames(&server, &mut session, BufReader::new(reader), &writer),
+    )
+    .await;
+    forwarder.abort();
+    server.end_session(&id).await?;
+    result
+}
+
+async fn serve_frames<R>(
+    server: &McpServer,
+    session: &mut SessionState,
+    mut reader: R,
+    writer: &FrameWriter,
+) -> Result<()>
+where
+    R: tokio::io::AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    let mut shutdown = std::pin::pin!(server.shutdown_requested());
+    loop {
+        line.clear();
+        // Requests are handled one at a time, so nothing is in flight while
+        // waiting for the next line
+        let read = tokio::select! {
+            read = reader.read_line(&mut line) => read,
+            _ = &mut shutdown => return Ok(()),
+        };
+        match read {
+            Ok(0) => return Ok(()),
+            Ok(_) => {}
+            Err(e) => {
+                let e = Error::Io(e);
+                if e.is_fatal() {
+                    return Err(e);
+                }
+                writer.send(&Response::parse_error(&e)).await?;
+                continue;
+            }
+        }
+        let frame = line.trim();
+        if frame.is_empty() {
+            continue;
+        }
+        server.sessions().touch(&session.id, server.clock().now());
+
+        match server.handle_frame(frame).await {
+            Ok(response) => {
+                remember(server, session, frame, response.as_ref().and_then(Reply::single)).await?;
+                if let Some(response) = response {
+                    writer.send(&response).await?;
+                }
+            }
+            Err(Error::Json(e)) => writer.send(&Response::parse_error(&e)).await?,
+            Err(e) if e.is_fatal() => return Err(e),
+            Err(e) => {
+                if let Some(response) = Response::for_failed_frame(frame, &e) {
+                    writer.send(&response).await?;
+                }
+            }
+        }
+    }
+}
+
+/// Records what `initialize` and `notifications/initialized` tell about the
+/// session
+async fn remember(
+    server: &McpServer,
+    session: &mut SessionState,
+    frame: &str,
+    response: Option<&Response>,
+) -> Result<()> {
+    let Ok(envelope) = RawRequest::parse(frame) else {
+        return Ok(());
+    };
+    match envelope.method.as_deref() {
+        Some("initialize") if response.is_some_and(|r| r.error.is_none()) => {
+            let params = envelope.params_value()?;
+            let id = std::mem::take(&mut session.id);
+            *session = SessionState {
+                id,
+                ..SessionState::from_initialize(params.as_ref())
+            };
+        }
+        Some("notifications/initialized") if !session.initialized => {
+            // Requests since initialize may have saved state to the record
+            if let Some(stored) = server.sessions().get(&session.id).await? {
+                *session = stored;
+            }
+            session.initialized = true;
+        }
+        _ => return Ok(()),
+    }
+    server.sessions().save(session).await
+}
+
+/// Writes the session's messages and the server-wide notifications its
+/// client negotiated as they arrive
+async fn forward(
+    writer: Arc<FrameWriter>,
+    mut messages: mpsc::UnboundedReceiver<Value>,
+    mut notifications: broadcast::Receiver<Value>,
+    gate: Gate,
+) {
+    loop {
+        let message = tokio::select! {
+            Some(message) = messages.recv() => message,
+            notification = notifications.recv() => match notification {
+                Ok(notification) if gate(notification["method"].as_str().unwrap_or_default()) => notification,
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            else => break,
+        };
+        if writer.send(&message).await.is_err() {
+            break;
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,157 @@
+use crate::McpServer;
+use crate::protocol::Response;
+use crate::transport::TransportSender;
+use crate::utils::{Error, Logger, Result};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Write};
+use std::sync::{Arc, Mutex};
+
+/// Newline-delimited JSON-RPC over blocking stdin/stdout
+///
+/// For runtimes without non-blocking stdio or worker threads, such as WASI:
+/// each frame is read with a blocking read and handled to completion before
+/// the next one, so a current-thread runtime is enough to drive
+/// [`WasiTransport::serve`]. Server notifications are written after the
+/// response to the request that triggered them.
+pub struct WasiTransport {
+    logger: Logger,
+    reader: Mutex<Box<dyn BufRead + Send>>,
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    // Reused across messages to avoid per-message allocations
+    read_buf: String,
+    write_buf: Vec<u8>,
+}
+
+impl WasiTransport {
+    pub fn new() -> Self {
+        Self::from_io(BufReader::new(std::io::stdin()), std::io::stdout())
+    }
+
+    pub fn from_io<R, W>(reader: R, writer: W) -> Self
+    where
+        R: BufRead + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        Self {
+            logger: Logger::new("WasiTransport"),
+            reader: Mutex::new(Box::new(reader)),
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+            read_buf: String::new(),
+            write_buf: Vec::new(),
+        }
+    }
+
+    /// Serves `server` until stdin closes or a blank line is read
+    pub async fn serve(&mut self, server: &McpServer) -> Result<()> {
+        self.logger.info("Starting to serve on stdio");
+        let mut notifications = server.subscribe_notifications();
+
+        loop {
+            match self.read_frame() {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) if e.is_fatal() => return Err(e),
+                Err(e) => {
+                    self.logger.warn(&format!("Received unreadable message: {}", e));
+                    self.write_message(&Response::parse_error(&e))?;
+                    continue;
+                }
+            }
+            let frame = self.read_buf.trim();
+            if self.logger.debug_enabled() {
+                self.logger.debug_with_context("Received message", frame);
+            }
+
+            match server.handle_frame(frame).await {
+                Ok(Some(response)) => self.write_message(&response)?,
+                Ok(None) => self.logger.debug("Notification processed, no response sent"),
+                Err(Error::Json(e)) => {
+                    self.logger.warn(&format!("Received invalid JSON: {}", e));
+                    self.write_message(&Response::parse_error(&e))?;
+                }
+                Err(e) if e.is_fatal() => {
+                    self.logger.error(&format!("Transport error: {}", e));
+                    break;
+                }
+                Err(e) => {
+                    self.logger.error_with_context("Failed to handle message", &e.to_string());
+                    if let Some(response) = Response::for_failed_frame(frame, &e) {
+                        self.write_message(&response)?;
+                    }
+                }
+            }
+
+            while let Ok(notification) = notifications.try_recv() {
+                self.write_message(&notification)?;
+            }
+        }
+
+        self.logger.info("Stdin closed, shutting down");
+        Ok(())
+    }
+
+    /// Reads the next frame into `read_buf`; false at EOF or a blank line
+    fn read_frame(&mut self) -> Result<bool> {
+        self.read_buf.clear();
+        let mut reader = self.reader.lock().unwrap_or_else(|e| e.into_inner());
+        let read = reader.read_line(&mut self.read_buf)?;
+        Ok(read > 0 && !self.read_buf.trim().is_empty())
+    }
+
+    pub fn write_message<T: Serialize>(&mut self, message: &T) -> Result<()> {
+        self.write_buf.clear();
+        serde_json::to_writer(&mut self.write_buf, message)?;
+        self.write_buf.push(b'\n');
+
+        write_frame(&self.writer, &self.write_buf)
+    }
+}
+
+impl Default for WasiTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl super::Transport for WasiTransport {
+    async fn send(&mut self, message: serde_json::Value) -> Result<()> {
+        self.write_message(&message)
+    }
+
+    async fn receive(&mut self) -> Result<Option<serde_json::Value>> {
+        if !self.read_frame()? {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(self.read_buf.trim())?))
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.logger.info("Closing WasiTransport");
+        Ok(())
+    }
+
+    fn sender(&self) -> Arc<dyn TransportSender> {
+        Arc::new(WasiSender(self.writer.clone()))
+    }
+}
+
+/// Writes to a [`WasiTransport`]'s stdout
+struct WasiSender(Arc<Mutex<Box<dyn Write + Send>>>);
+
+#[async_trait::async_trait]
+impl TransportSender for WasiSender {
+    async fn send(&self, message: serde_json::Value) -> Result<()> {
+        let mut frame = serde_json::to_vec(&message)?;
+        frame.push(b'\n');
+        write_frame(&self.0, &frame)
+    }
+}
+
+/// Writes one whole frame; the lock keeps frames from interleaving
+fn write_frame(writer: &Mutex<Box<dyn Write + Send>>, frame: &[u8]) -> Result<()> {
+    let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+    writer.write_all(frame)?;
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::utils::{Error, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use futures::io::{AsyncRead, AsyncReadExt};
+
+/// Default upper bound on the raw size of a blob that may be base64-encoded
+pub const DEFAULT_MAX_BLOB_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Raw bytes read per chunk; a multiple of 3 so chunks encode without padding
+const CHUNK_SIZE: usize = 3 * 64 * 1024;
+
+/// Base64-encodes everything read from `reader`, failing once more than
+/// `max_bytes` raw bytes have been seen
+///
+/// Input is consumed in fixed-size chunks and appended to the output as it is
+/// encoded, so only the encoded string and one chunk are held in memory.
+pub async fn encode_reader<R>(mut reader: R, max_bytes: u64, size_hint: Option<u64>) -> Result<String>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut encoded = String::with_capacity(encoded_len(size_hint.unwrap_or(0).min(max_bytes)));
+    let mut chunk = vec![0u8; CHUNK_SIZE];
+    let mut filled = 0;
+    let mut total: u64 = 0;
+
+    loop {
+        let read = reader.read(&mut chunk[filled..]).await?;
+        if read == 0 {
+            break;
+        }
+        total += read as u64;
+        if total > max_bytes {
+            return Err(blob_too_large(max_bytes));
+        }
+        filled += read;
+        if filled == CHUNK_SIZE {
+            STANDARD.encode_string(&chunk, &mut encoded);
+            filled = 0;
+        }
+    }
+
+    STANDARD.encode_string(&chunk[..filled], &mut encoded);
+    Ok(encoded)
+}
+
+/// Base64-encodes a file, rejecting it up front if it exceeds `max_bytes`
+#[cfg(feature = "native")]
+pub async fn encode_file(path: impl AsRef<std::path::Path>, max_bytes: u64) -> Result<String> {
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    let file = tokio::fs::File::open(path.as_ref()).await?;
+    let size = file.metadata().await?.len();
+    if size > max_bytes {
+        return Err(blob_too_large(max_bytes));
+    }
+    encode_reader(file.compat(), max_bytes, Some(size)).await
+}
+
+/// Base64-encodes bytes already in memory, e.g. a memory-mapped file
+pub fn encode_bytes(bytes: &[u8], max_bytes: u64) -> Result<String> {
+    if bytes.len() as u64 > max_bytes {
+        return Err(blob_too_large(max_bytes));
+    }
+    Ok(STANDARD.encode(bytes))
+}
+
+/// Length of the padded base64 encoding of `raw_len` bytes
+pub fn encoded_len(raw_len: u64) -> usize {
+    raw_len.div_ceil(3).saturating_mul(4) as usize
+}
+
+fn blob_too_large(max_bytes: u64) -> Error {
+    Error::ResourceError(format!("Blob exceeds maximum size of {} bytes", max_bytes))
+}
@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Source of time for timeouts, caches and rate limiters
+///
+/// Production code uses [`SystemClock`]; tests inject a [`MockClock`] and
+/// advance it explicitly so time-dependent behavior is deterministic.
+#[async_trait]
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    async fn sleep(&self, duration: Duration);
+}
+
+/// Wall-clock time backed by the runtime's timers
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        crate::runtime::sleep(duration).await;
+    }
+}
+
+/// Manually driven clock; time only moves when [`MockClock::advance`] is called
+#[derive(Clone)]
+pub struct MockClock {
+    start: Instant,
+    elapsed: Arc<Mutex<Duration>>,
+    ticks: watch::Sender<Duration>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        let (ticks, _) = watch::channel(Duration::ZERO);
+        Self {
+            start: Instant::now(),
+            elapsed: Arc::new(Mutex::new(Duration::ZERO)),
+            ticks,
+        }
+    }
+
+    /// Moves time forward, waking any sleeper whose deadline has passed
+    pub fn advance(&self, duration: Duration) {
+        let elapsed = {
+            let mut elapsed = self.elapsed.lock().unwrap_or_else(|e| e.into_inner());
+            *elapsed += duration;
+            *elapsed
+        };
+        self.ticks.send_replace(elapsed);
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        let deadline = self.elapsed() + duration;
+        let mut ticks = self.ticks.subscribe();
+        while *ticks.borrow_and_update() < deadline {
+            if ticks.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
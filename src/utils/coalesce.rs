@@ -0,0 +1,106 @@
+use crate::utils::{Error, Result};
+use futures::FutureExt;
+use futures::future::{BoxFuture, Shared};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+type SharedCall<T> = Shared<BoxFuture<'static, Arc<Result<T>>>>;
+
+/// Runs identical concurrent calls once and hands every caller the result
+///
+/// Calls are identified by a key. While a call with the same key is in
+/// flight, later callers wait for it instead of starting their own; once
+/// it finishes the key is free again, so results are never cached.
+pub struct Coalescer<T> {
+    in_flight: Arc<Mutex<HashMap<String, SharedCall<T>>>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> Coalescer<T> {
+    pub fn new() -> Self {
+        Self {
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Runs `call` unless a call with `key` is already in flight, in which
+    /// case its result is shared. Returns whether the result was shared.
+    pub async fn run<F>(&self, key: String, call: F) -> (Result<T>, bool)
+    where
+        F: Future<Output = Result<T>> + Send + 'static,
+    {
+        let (shared, joined) = {
+            let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+            match in_flight.get(&key) {
+                Some(shared) => (shared.clone(), true),
+                None => {
+                    let registry = self.in_flight.clone();
+                    let cleanup_key = key.clone();
+                    let shared = async move {
+                        let result = call.await;
+                        registry
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .remove(&cleanup_key);
+                        Arc::new(result)
+                    }
+                    .boxed()
+                    .shared();
+                    in_flight.insert(key, shared.clone());
+                    (shared, false)
+                }
+            }
+        };
+
+        let result = match shared.await.as_ref() {
+            Ok(value) => Ok(value.clone()),
+            Err(e) => Err(duplicate(e)),
+        };
+        (result, joined)
+    }
+
+    /// Number of distinct calls currently in flight
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Default for Coalescer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Copies an error for another waiter, keeping its JSON-RPC code and message
+fn duplicate(e: &Error) -> Error {
+    match e {
+        Error::Json(e) => Error::SerializationError(e.to_string()),
+        Error::Io(e) => Error::Io(std::io::Error::new(e.kind(), e.to_string())),
+        Error::InvalidRequest(m) => Error::InvalidRequest(m.clone()),
+        Error::MethodNotFound(m) => Error::MethodNotFound(m.clone()),
+        Error::InvalidParams(m) => Error::InvalidParams(m.clone()),
+        Error::InternalError(m) => Error::InternalError(m.clone()),
+        Error::ToolError(m) => Error::ToolError(m.clone()),
+        Error::ResourceError(m) => Error::ResourceError(m.clone()),
+        Error::ParseError(m) => Error::ParseError(m.clone()),
+        Error::SerializationError(m) => Error::SerializationError(m.clone()),
+        Error::AsyncError(m) => Error::AsyncError(m.clone()),
+        Error::ValidationError(m) => Error::ValidationError(m.clone()),
+        Error::StorageError(m) => Error::StorageError(m.clone()),
+        Error::Localized { source, key, args } => Error::Localized {
+            source: Box::new(duplicate(source)),
+            key: *key,
+            args: args.clone(),
+        },
+        Error::Rpc { code, message, data } => Error::Rpc {
+            code: *code,
+            message: message.clone(),
+            data: data.clone(),
+        },
+        Error::Application { code, message, data } => Error::Application {
+            code: *code,
+            message: message.clone(),
+            data: data.clone(),
+        },
+    }
+}
@@ -0,0 +1,44 @@
+//! Deadlines of the requests being handled
+//!
+//! A client can bound how long it will wait for a request with a
+//! `timeoutMs` hint in the request's `_meta`; otherwise the server's
+//! default timeout applies, if one is configured. Handlers read what is left
+//! with [`remaining`] and pass it on, e.g. to an HTTP client's timeout, or
+//! give up early once [`expired`] rather than doing work nobody waits for.
+
+use serde_json::Value;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+tokio::task_local! {
+    static CURRENT_DEADLINE: Option<Instant>;
+}
+
+/// Deadline of the request being handled, if it has one
+pub fn current() -> Option<Instant> {
+    CURRENT_DEADLINE.try_with(|deadline| *deadline).ok().flatten()
+}
+
+/// Time left until the current request's deadline; zero once it has passed
+pub fn remaining() -> Option<Duration> {
+    current().map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+/// Whether the current request's deadline has passed
+pub fn expired() -> bool {
+    remaining() == Some(Duration::ZERO)
+}
+
+/// Runs `future` with `deadline` as the current request deadline
+pub fn with_deadline<F: Future>(deadline: Option<Instant>, future: F) -> impl Future<Output = F::Output> {
+    CURRENT_DEADLINE.scope(deadline, future)
+}
+
+/// Timeout hint carried in a request's `_meta.timeoutMs`
+pub fn timeout_hint(params: Option<&Value>) -> Option<Duration> {
+    params?
+        .get("_meta")?
+        .get("timeoutMs")?
+        .as_u64()
+        .map(Duration::from_millis)
+}
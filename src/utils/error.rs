@@ -1,3 +1,6 @@
+use crate::utils::i18n::MessageKey;
+use serde_json::Value;
+use std::io::ErrorKind;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -37,6 +40,82 @@ pub enum Error {
 
     #[error("Validation error: {0}")]
     ValidationError(String),
+
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
+    #[error("{source}")]
+    Localized {
+        source: Box<Error>,
+        key: MessageKey,
+        args: Vec<String>,
+    },
+
+    #[error("JSON-RPC error {code}: {message}")]
+    Rpc {
+        code: i64,
+        message: String,
+        data: Option<Value>,
+    },
+
+    #[error("{message}")]
+    Application {
+        code: i64,
+        message: String,
+        data: Option<Value>,
+    },
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// JSON-RPC error code reported to the client for this error
+    pub fn code(&self) -> i64 {
+        match self {
+            Error::InvalidRequest(_) => -32600,
+            Error::MethodNotFound(_) => -32601,
+            Error::InvalidParams(_) => -32602,
+            Error::Application { code, .. } | Error::Rpc { code, .. } => *code,
+            Error::Localized { source, .. } => source.code(),
+            _ => -32603,
+        }
+    }
+
+    /// Whether the error is a transient failure worth retrying
+    ///
+    /// Connection-level IO failures and timeouts are retryable; protocol,
+    /// validation and parameter errors are not, since repeating the same
+    /// call would fail the same way.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Io(e) => matches!(
+                e.kind(),
+                ErrorKind::ConnectionRefused
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::NotConnected
+                    | ErrorKind::BrokenPipe
+                    | ErrorKind::TimedOut
+                    | ErrorKind::Interrupted
+                    | ErrorKind::WouldBlock
+                    | ErrorKind::UnexpectedEof
+            ),
+            Error::AsyncError(_) => true,
+            Error::Localized { source, .. } => source.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Whether the error breaks the connection rather than just one message
+    ///
+    /// Only I/O failures on the stream itself are fatal; a frame that isn't
+    /// valid UTF-8 or JSON, or a request that fails, leaves the stream
+    /// usable for the next message.
+    pub fn is_fatal(&self) -> bool {
+        match self {
+            Error::Io(e) => !matches!(e.kind(), ErrorKind::InvalidData | ErrorKind::InvalidInput),
+            Error::Localized { source, .. } => source.is_fatal(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::utils::{Error, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// Lowest code of the JSON-RPC "implementation-defined server error" range
+pub const SERVER_ERROR_MIN: i64 = -32099;
+/// Highest code of the JSON-RPC "implementation-defined server error" range
+pub const SERVER_ERROR_MAX: i64 = -32000;
+
+/// Defined by JSON-RPC itself, for params a method can't accept
+pub const INVALID_PARAMS: ErrorCode = ErrorCode::new(-32602);
+
+/// Whether JSON-RPC itself defines `code`, so it may be sent without being
+/// reserved
+pub fn is_standard(code: i64) -> bool {
+    matches!(code, -32700 | -32603..=-32600)
+}
+
+/// Returned when request admission limits are exceeded; `data.retryAfterMs`
+/// carries a retry hint
+pub const SERVER_OVERLOADED: ErrorCode = ErrorCode::new(-32000);
+
+/// Returned when new large work is shed because the memory ceiling is reached
+pub const MEMORY_PRESSURE: ErrorCode = ErrorCode::new(-32001);
+
+/// Returned when the access policy doesn't let the caller use a tool,
+/// resource or prompt
+pub const ACCESS_DENIED: ErrorCode = ErrorCode::new(-32003);
+
+/// Returned under a strict lifecycle for requests sent before the
+/// `initialize` handshake completed
+pub const SERVER_NOT_INITIALIZED: ErrorCode = ErrorCode::new(-32004);
+
+/// Returned for requests that arrive once shutdown has begun
+pub const SERVER_SHUTTING_DOWN: ErrorCode = ErrorCode::new(-32005);
+
+/// Codes the crate reserves for itself in the server error range
+const BUILTIN_CODES: &[(ErrorCode, &str)] = &[
+    (SERVER_OVERLOADED, "ServerOverloaded"),
+    (MEMORY_PRESSURE, "MemoryPressure"),
+    (ACCESS_DENIED, "AccessDenied"),
+    (SERVER_NOT_INITIALIZED, "ServerNotInitialized"),
+    (SERVER_SHUTTING_DOWN, "ServerShuttingDown"),
+];
+
+/// A JSON-RPC error code an application has reserved for its own errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ErrorCode(i64);
+
+impl ErrorCode {
+    pub const fn new(code: i64) -> Self {
+        Self(code)
+    }
+
+    pub const fn code(&self) -> i64 {
+        self.0
+    }
+}
+
+/// Domain error types implement this to be returned from handlers with
+/// their own JSON-RPC error code via [`Error::application`]
+pub trait ApplicationError: Display {
+    fn error_code(&self) -> ErrorCode;
+
+    fn error_data(&self) -> Option<Value> {
+        None
+    }
+}
+
+impl Error {
+    pub fn application(error: impl ApplicationError) -> Self {
+        Error::Application {
+            code: error.error_code().code(),
+            message: error.to_string(),
+            data: error.error_data(),
+        }
+    }
+}
+
+/// Codes reserved in the server error range, by the crate or by applications
+///
+/// Only reserved codes and those JSON-RPC defines are sent to clients; an
+/// `Error::Application` carrying any other code is reported as an internal
+/// error so codes stay consistent across every server embedding the crate.
+#[derive(Clone, Debug)]
+pub struct ErrorCodeRegistry {
+    codes: BTreeMap<i64, String>,
+}
+
+impl ErrorCodeRegistry {
+    pub fn new() -> Self {
+        Self {
+            codes: BUILTIN_CODES
+                .iter()
+                .map(|(code, name)| (code.code(), name.to_string()))
+                .collect(),
+        }
+    }
+
+    pub fn reserve(&mut self, code: i64, name: impl Into<String>) -> Result<ErrorCode> {
+        if !(SERVER_ERROR_MIN..=SERVER_ERROR_MAX).contains(&code) {
+            return Err(Error::ValidationError(format!(
+                "Error code {} is outside the implementation-defined range {}..={}",
+                code, SERVER_ERROR_MIN, SERVER_ERROR_MAX
+            )));
+        }
+        if let Some(existing) = self.codes.get(&code) {
+            return Err(Error::ValidationError(format!(
+                "Error code {} is already reserved for {}",
+                code, existing
+            )));
+        }
+        self.codes.insert(code, name.into());
+        Ok(ErrorCode(code))
+    }
+
+    pub fn is_reserved(&self, code: i64) -> bool {
+        self.codes.contains_key(&code)
+    }
+
+    pub fn name(&self, code: i64) -> Option<&str> {
+        self.codes.get(&code).map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ErrorCode, &str)> {
+        self.codes.iter().map(|(code, name)| (ErrorCode(*code), name.as_str()))
+    }
+}
+
+impl Default for ErrorCodeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,218 @@
+use crate::utils::Error;
+use std::future::Future;
+
+/// Locale used when the client does not ask for one or asks for an unknown one
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Locales with a message catalog
+pub const SUPPORTED_LOCALES: &[&str] = &["en", "es", "fr"];
+
+tokio::task_local! {
+    static CURRENT_LOCALE: String;
+}
+
+/// Stable identifiers for user-visible messages
+///
+/// The string form (see [`MessageKey::as_str`]) is sent to clients as
+/// `error.data.messageKey` and must not change between releases, so clients
+/// can react to errors without parsing translated text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MessageKey {
+    MissingParams,
+    MissingArguments,
+    MissingArgument,
+    MissingParameter,
+    InvalidParameter,
+    MissingToolName,
+    ToolNotFound,
+    MissingResourceUri,
+    ResourceNotFound,
+    MissingPromptName,
+    PromptNotFound,
+    HeightMustBePositive,
+}
+
+impl MessageKey {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageKey::MissingParams => "params.missing",
+            MessageKey::MissingArguments => "arguments.missing",
+            MessageKey::MissingArgument => "argument.missing",
+            MessageKey::MissingParameter => "parameter.missing",
+            MessageKey::InvalidParameter => "parameter.invalid",
+            MessageKey::MissingToolName => "tool.name_missing",
+            MessageKey::ToolNotFound => "tool.not_found",
+            MessageKey::MissingResourceUri => "resource.uri_missing",
+            MessageKey::ResourceNotFound => "resource.not_found",
+            MessageKey::MissingPromptName => "prompt.name_missing",
+            MessageKey::PromptNotFound => "prompt.not_found",
+            MessageKey::HeightMustBePositive => "bmi.height_not_positive",
+        }
+    }
+}
+
+/// Error-kind prefixes, keyed like [`MessageKey`] but not exposed to clients
+#[derive(Clone, Copy)]
+enum KindKey {
+    InvalidParams,
+    MethodNotFound,
+    ResourceError,
+    ToolError,
+}
+
+fn message_template(locale: &str, key: MessageKey) -> Option<&'static str> {
+    use MessageKey::*;
+    let template = match (locale, key) {
+        ("en", MissingParams) => "Missing params",
+        ("en", MissingArguments) => "Missing arguments",
+        ("en", MissingArgument) => "Missing '{0}' argument",
+        ("en", MissingParameter) => "Missing '{0}' parameter",
+        ("en", InvalidParameter) => "Missing or invalid '{0}'",
+        ("en", MissingToolName) => "Missing tool name",
+        ("en", ToolNotFound) => "Tool not found: {0}",
+        ("en", MissingResourceUri) => "Missing resource URI",
+        ("en", ResourceNotFound) => "Resource not found: {0}",
+        ("en", MissingPromptName) => "Missing prompt name",
+        ("en", PromptNotFound) => "Prompt not found: {0}",
+        ("en", HeightMustBePositive) => "Height must be positive",
+
+        ("es", MissingParams) => "Faltan los parámetros",
+        ("es", MissingArguments) => "Faltan los argumentos",
+        ("es", MissingArgument) => "Falta el argumento '{0}'",
+        ("es", MissingParameter) => "Falta el parámetro '{0}'",
+        ("es", InvalidParameter) => "Falta o no es válido '{0}'",
+        ("es", MissingToolName) => "Falta el nombre de la herramienta",
+        ("es", ToolNotFound) => "Herramienta no encontrada: {0}",
+        ("es", MissingResourceUri) => "Falta la URI del recurso",
+        ("es", ResourceNotFound) => "Recurso no encontrado: {0}",
+        ("es", MissingPromptName) => "Falta el nombre del prompt",
+        ("es", PromptNotFound) => "Prompt no encontrado: {0}",
+        ("es", HeightMustBePositive) => "La altura debe ser positiva",
+
+        ("fr", MissingParams) => "Paramètres manquants",
+        ("fr", MissingArguments) => "Arguments manquants",
+        ("fr", MissingArgument) => "Argument '{0}' manquant",
+        ("fr", MissingParameter) => "Paramètre '{0}' manquant",
+        ("fr", InvalidParameter) => "'{0}' manquant ou invalide",
+        ("fr", MissingToolName) => "Nom de l'outil manquant",
+        ("fr", ToolNotFound) => "Outil introuvable : {0}",
+        ("fr", MissingResourceUri) => "URI de la ressource manquante",
+        ("fr", ResourceNotFound) => "Ressource introuvable : {0}",
+        ("fr", MissingPromptName) => "Nom du prompt manquant",
+        ("fr", PromptNotFound) => "Prompt introuvable : {0}",
+        ("fr", HeightMustBePositive) => "La taille doit être positive",
+
+        _ => return None,
+    };
+    Some(template)
+}
+
+fn kind_template(locale: &str, key: KindKey) -> &'static str {
+    match (locale, key) {
+        ("es", KindKey::InvalidParams) => "Parámetros no válidos: {0}",
+        ("es", KindKey::MethodNotFound) => "Método no encontrado: {0}",
+        ("es", KindKey::ResourceError) => "Error de recurso: {0}",
+        ("es", KindKey::ToolError) => "Error de herramienta: {0}",
+        ("fr", KindKey::InvalidParams) => "Paramètres invalides : {0}",
+        ("fr", KindKey::MethodNotFound) => "Méthode introuvable : {0}",
+        ("fr", KindKey::ResourceError) => "Erreur de ressource : {0}",
+        ("fr", KindKey::ToolError) => "Erreur d'outil : {0}",
+        (_, KindKey::InvalidParams) => "Invalid params: {0}",
+        (_, KindKey::MethodNotFound) => "Method not found: {0}",
+        (_, KindKey::ResourceError) => "Resource error: {0}",
+        (_, KindKey::ToolError) => "Tool error: {0}",
+    }
+}
+
+fn fill(template: &str, args: &[String]) -> String {
+    args.iter().enumerate().fold(template.to_string(), |text, (i, arg)| {
+        text.replace(&format!("{{{}}}", i), arg)
+    })
+}
+
+/// Maps a client locale tag (e.g. "es-MX") onto a supported catalog
+pub fn negotiate(requested: &str) -> &'static str {
+    let language = requested
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    SUPPORTED_LOCALES
+        .iter()
+        .find(|supported| **supported == language)
+        .copied()
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Renders a message in the given locale, falling back to English
+pub fn translate(locale: &str, key: MessageKey, args: &[String]) -> String {
+    let template = message_template(negotiate(locale), key)
+        .or_else(|| message_template(DEFAULT_LOCALE, key))
+        .unwrap_or_else(|| key.as_str());
+    fill(template, args)
+}
+
+/// Renders a message in the locale of the request being handled
+pub fn t(key: MessageKey, args: &[&str]) -> String {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    translate(&current_locale(), key, &args)
+}
+
+/// Locale of the request being handled, or the default outside a request
+pub fn current_locale() -> String {
+    CURRENT_LOCALE
+        .try_with(|locale| locale.clone())
+        .unwrap_or_else(|_| DEFAULT_LOCALE.to_string())
+}
+
+/// Runs `future` with `locale` as the current request locale
+pub fn with_locale<F: Future>(locale: String, future: F) -> impl Future<Output = F::Output> {
+    CURRENT_LOCALE.scope(locale, future)
+}
+
+/// Renders an error for a client in `locale`
+///
+/// Returns the message and, for localized errors, the stable message key.
+pub fn localize_error(locale: &str, error: &Error) -> (String, Option<&'static str>) {
+    let Error::Localized { source, key, args } = error else {
+        return (error.to_string(), None);
+    };
+
+    let locale = negotiate(locale);
+    let message = translate(locale, *key, args);
+    let kind = match source.as_ref() {
+        Error::InvalidParams(_) => KindKey::InvalidParams,
+        Error::MethodNotFound(_) => KindKey::MethodNotFound,
+        Error::ResourceError(_) => KindKey::ResourceError,
+        Error::ToolError(_) => KindKey::ToolError,
+        _ => return (message, Some(key.as_str())),
+    };
+    (fill(kind_template(locale, kind), &[message]), Some(key.as_str()))
+}
+
+impl Error {
+    fn localized(kind: fn(String) -> Error, key: MessageKey, args: &[&str]) -> Self {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let english = translate(DEFAULT_LOCALE, key, &args);
+        Error::Localized {
+            source: Box::new(kind(english)),
+            key,
+            args,
+        }
+    }
+
+    /// `InvalidParams` error whose message can be localized for the client
+    pub fn invalid_params(key: MessageKey, args: &[&str]) -> Self {
+        Self::localized(Error::InvalidParams, key, args)
+    }
+
+    /// `MethodNotFound` error whose message can be localized for the client
+    pub fn method_not_found(key: MessageKey, args: &[&str]) -> Self {
+        Self::localized(Error::MethodNotFound, key, args)
+    }
+
+    /// `ResourceError` whose message can be localized for the client
+    pub fn resource_error(key: MessageKey, args: &[&str]) -> Self {
+        Self::localized(Error::ResourceError, key, args)
+    }
+}
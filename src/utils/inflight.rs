@@ -0,0 +1,178 @@
+use crate::utils::{Error, Result};
+use futures::future::{AbortHandle, AbortRegistration};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// Requests that have been received but not yet answered, per session
+///
+/// Ids only have to be unique among a session's outstanding requests, so a
+/// second request reusing an id that is still in flight is rejected, while
+/// reusing it afterwards is fine. Each entry can be aborted, which is how
+/// `notifications/cancelled` stops a request; aborting also cancels the
+/// request's token, for work its handler runs outside the request.
+#[derive(Default)]
+pub struct InFlight {
+    requests: Mutex<HashMap<RequestKey, Entry>>,
+    drained: Notify,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct RequestKey {
+    session: Option<String>,
+    // Serialized, so `1` and `"1"` stay distinct
+    id: String,
+}
+
+struct Entry {
+    id: Value,
+    method: String,
+    started: Instant,
+    abort: AbortHandle,
+    cancellation: CancellationToken,
+}
+
+/// An outstanding request
+#[derive(Clone, Debug, PartialEq)]
+pub struct InFlightRequest {
+    pub session: Option<String>,
+    pub id: Value,
+    pub method: String,
+    pub started: Instant,
+}
+
+/// Removes its request from the table when dropped
+pub struct InFlightGuard<'a> {
+    table: &'a InFlight,
+    key: RequestKey,
+}
+
+impl InFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a request as in flight until the guard is dropped; the
+    /// registration aborts the request's future and the token is cancelled
+    /// when the request is
+    pub fn begin(
+        &self,
+        session: Option<String>,
+        id: &Value,
+        method: &str,
+        started: Instant,
+    ) -> Result<(InFlightGuard<'_>, AbortRegistration, CancellationToken)> {
+        let key = RequestKey { session, id: id.to_string() };
+        let mut requests = self.lock();
+        if requests.contains_key(&key) {
+            return Err(Error::InvalidRequest(format!(
+                "Request id {} is already in flight",
+                key.id
+            )));
+        }
+        let (abort, registration) = AbortHandle::new_pair();
+        let cancellation = CancellationToken::new();
+        let entry = Entry {
+            id: id.clone(),
+            method: method.to_string(),
+            started,
+            abort,
+            cancellation: cancellation.clone(),
+        };
+        requests.insert(key.clone(), entry);
+        Ok((InFlightGuard { table: self, key }, registration, cancellation))
+    }
+
+    /// Aborts the request with `id` in `session`; false if it isn't in flight
+    pub fn cancel(&self, session: Option<&str>, id: &Value) -> bool {
+        let key = RequestKey {
+            session: session.map(str::to_string),
+            id: id.to_string(),
+        };
+        match self.lock().get(&key) {
+            Some(entry) => {
+                entry.abort.abort();
+                entry.cancellation.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Aborts every request outstanding in `session`; returns how many
+    /// there were
+    pub fn cancel_session(&self, session: &str) -> usize {
+        let requests = self.lock();
+        let mut cancelled = 0;
+        for (key, entry) in requests.iter() {
+            if key.session.as_deref() == Some(session) {
+                entry.abort.abort();
+                entry.cancellation.cancel();
+                cancelled += 1;
+            }
+        }
+        cancelled
+    }
+
+    /// Every outstanding request, oldest first
+    pub fn list(&self) -> Vec<InFlightRequest> {
+        let mut requests: Vec<_> = self
+            .lock()
+            .iter()
+            .map(|(key, entry)| InFlightRequest {
+                session: key.session.clone(),
+                id: entry.id.clone(),
+                method: entry.method.clone(),
+                started: entry.started,
+            })
+            .collect();
+        requests.sort_by_key(|request| request.started);
+        requests
+    }
+
+    /// Aborts every outstanding request; returns how many there were
+    pub fn cancel_all(&self) -> usize {
+        let requests = self.lock();
+        for entry in requests.values() {
+            entry.abort.abort();
+            entry.cancellation.cancel();
+        }
+        requests.len()
+    }
+
+    /// Resolves once no request is outstanding
+    pub async fn drained(&self) {
+        loop {
+            let drained = self.drained.notified();
+            if self.is_empty() {
+                return;
+            }
+            drained.await;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<RequestKey, Entry>> {
+        self.requests.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        let mut requests = self.table.lock();
+        requests.remove(&self.key);
+        if requests.is_empty() {
+            self.table.drained.notify_waiters();
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::utils::error_codes::SERVER_OVERLOADED;
+use crate::utils::{Error, Result};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// Server-wide request admission limits
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct RequestLimits {
+    /// Maximum number of requests executing at once (0 = unlimited)
+    pub max_in_flight: usize,
+    /// Maximum number of requests per method waiting for a free slot
+    pub max_queue_per_method: usize,
+    /// Retry hint returned to clients whose request was rejected
+    pub retry_after_ms: u64,
+    /// Approximate memory ceiling above which large reads and tool calls are
+    /// rejected (0 = unlimited)
+    pub max_memory_bytes: u64,
+    /// Deadline given to requests without a `_meta.timeoutMs` hint
+    /// (0 = none)
+    pub default_timeout_ms: u64,
+    /// Most messages accepted in one JSON-RPC batch (0 = unlimited)
+    pub max_batch_size: usize,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 64,
+            max_queue_per_method: 32,
+            retry_after_ms: 1000,
+            max_memory_bytes: 512 * 1024 * 1024,
+            default_timeout_ms: 0,
+            max_batch_size: 100,
+        }
+    }
+}
+
+/// Scheduling class of a request waiting for an execution slot
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Normal,
+    /// Cheap control-plane traffic served ahead of queued work
+    Control,
+}
+
+impl Priority {
+    /// Health checks, handshakes, notifications (including cancellations)
+    /// and catalog listings are control-plane; everything else is normal.
+    /// Pings and notifications never wait for a slot in the first place,
+    /// see [`RequestLimiter::is_exempt`]
+    pub fn of(method: &str) -> Self {
+        match method {
+            "ping" | "initialize" | "initialized" => Priority::Control,
+            _ if method.starts_with("notifications/") || method.ends_with("/list") => {
+                Priority::Control
+            }
+            _ => Priority::Normal,
+        }
+    }
+}
+
+/// Admits requests according to [`RequestLimits`]
+///
+/// Requests that cannot run immediately wait in a per-method queue; once a
+/// method's queue is full further requests are rejected with
+/// `SERVER_OVERLOADED` instead of piling up. A freed slot goes to the
+/// longest-waiting [`Priority::Control`] request before any normal one, so
+/// handshakes and listings are not stuck behind queued tool calls.
+pub struct RequestLimiter {
+    limits: RequestLimits,
+    slots: Option<Arc<Mutex<Slots>>>,
+    queued: Mutex<HashMap<String, usize>>,
+}
+
+impl RequestLimiter {
+    pub fn new(limits: RequestLimits) -> Self {
+        let slots = (limits.max_in_flight > 0).then(|| {
+            Arc::new(Mutex::new(Slots {
+                available: limits.max_in_flight,
+                control: VecDeque::new(),
+                normal: VecDeque::new(),
+            }))
+        });
+        Self {
+            limits,
+            slots,
+            queued: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `method` runs without taking a slot. Pings and notifications
+    /// are cheap and answered inline, so they must not wait behind slow
+    /// work: a ping would time out and a cancellation would never arrive.
+    pub fn is_exempt(method: &str) -> bool {
+        method == "ping" || method.starts_with("notifications/")
+    }
+
+    /// Waits for an execution slot; the slot is released when the permit drops
+    pub async fn acquire(&self, method: &str) -> Result<Option<SlotPermit>> {
+        let Some(slots) = &self.slots else {
+            return Ok(None);
+        };
+
+        let (receiver, _queued) = {
+            let mut state = slots.lock().unwrap_or_else(|e| e.into_inner());
+            if state.available > 0 {
+                state.available -= 1;
+                return Ok(Some(SlotPermit { slots: slots.clone() }));
+            }
+
+            let queued = QueueSlot::enter(&self.queued, method, self.limits.max_queue_per_method)
+                .ok_or_else(|| self.overloaded(method))?;
+            let (sender, receiver) = oneshot::channel();
+            match Priority::of(method) {
+                Priority::Control => state.control.push_back(sender),
+                Priority::Normal => state.normal.push_back(sender),
+            }
+            (receiver, queued)
+        };
+
+        let mut waiting = Waiting {
+            receiver,
+            slots: slots.clone(),
+        };
+        (&mut waiting.receiver)
+            .await
+            .map_err(|e| Error::AsyncError(e.to_string()))?;
+        Ok(Some(SlotPermit { slots: slots.clone() }))
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.slots
+            .as_ref()
+            .map(|s| self.limits.max_in_flight - s.lock().unwrap_or_else(|e| e.into_inner()).available)
+            .unwrap_or(0)
+    }
+
+    fn overloaded(&self, method: &str) -> Error {
+        Error::Application {
+            code: SERVER_OVERLOADED.code(),
+            message: format!("Server overloaded: too many queued '{}' requests", method),
+            data: Some(json!({ "retryAfterMs": self.limits.retry_after_ms })),
+        }
+    }
+}
+
+/// Occupies one place in a method's queue until dropped
+struct QueueSlot<'a> {
+    queued: &'a Mutex<HashMap<String, usize>>,
+    method: String,
+}
+
+impl<'a> QueueSlot<'a> {
+    fn enter(queued: &'a Mutex<HashMap<String, usize>>, method: &str, max: usize) -> Option<Self> {
+        let mut counts = queued.lock().unwrap_or_else(|e| e.into_inner());
+        let count = counts.entry(method.to_string()).or_insert(0);
+        if *count >= max {
+            return None;
+        }
+        *count += 1;
+        Some(Self {
+            queued,
+            method: method.to_string(),
+        })
+    }
+}
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        let mut counts = self.queued.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = counts.get_mut(&self.method) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&self.method);
+            }
+        }
+    }
+}
+
+/// Free execution slots and the requests waiting for one, by priority
+struct Slots {
+    available: usize,
+    control: VecDeque<oneshot::Sender<()>>,
+    normal: VecDeque<oneshot::Sender<()>>,
+}
+
+impl Slots {
+    /// Hands a freed slot to the next live waiter, or returns it to the pool
+    fn release(&mut self) {
+        while let Some(waiter) = self.control.pop_front().or_else(|| self.normal.pop_front()) {
+            if waiter.send(()).is_ok() {
+                return;
+            }
+        }
+        self.available += 1;
+    }
+}
+
+/// An execution slot, released when dropped
+pub struct SlotPermit {
+    slots: Arc<Mutex<Slots>>,
+}
+
+impl Drop for SlotPermit {
+    fn drop(&mut self) {
+        self.slots.lock().unwrap_or_else(|e| e.into_inner()).release();
+    }
+}
+
+/// A queued request; if it is dropped after being handed a slot but before
+/// taking it, the slot is passed on
+struct Waiting {
+    receiver: oneshot::Receiver<()>,
+    slots: Arc<Mutex<Slots>>,
+}
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        self.receiver.close();
+        if self.receiver.try_recv().is_ok() {
+            self.slots.lock().unwrap_or_else(|e| e.into_inner()).release();
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, warn, Level};
 
 #[derive(Clone)]
 pub struct Logger {
@@ -28,6 +28,12 @@ impl Logger {
         error!(logger = %self.name, "{}", msg);
     }
 
+    /// Whether debug events would be recorded; use it to skip building
+    /// expensive log context on hot paths
+    pub fn debug_enabled(&self) -> bool {
+        tracing::enabled!(Level::DEBUG)
+    }
+
     pub fn info_with_context(&self, msg: &str, context: &str) {
         info!(logger = %self.name, context = %context, "{}", msg);
     }
@@ -0,0 +1,121 @@
+use crate::utils::error_codes::MEMORY_PRESSURE;
+use crate::utils::{Error, Result};
+use serde_json::Value;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Approximate accounting of memory held by in-flight requests, caches and
+/// blobs, with load shedding above a ceiling
+///
+/// Figures are estimates (payload sizes, not allocator statistics); the goal
+/// is to refuse new large work before the process runs out of memory.
+#[derive(Debug)]
+pub struct MemoryGuard {
+    ceiling: u64,
+    used: AtomicU64,
+}
+
+impl MemoryGuard {
+    /// Creates a guard; a ceiling of 0 disables shedding but keeps accounting
+    pub fn new(ceiling: u64) -> Self {
+        Self {
+            ceiling,
+            used: AtomicU64::new(0),
+        }
+    }
+
+    pub fn used(&self) -> u64 {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    pub fn ceiling(&self) -> u64 {
+        self.ceiling
+    }
+
+    /// Whether usage has reached the ceiling
+    pub fn under_pressure(&self) -> bool {
+        self.ceiling > 0 && self.used() >= self.ceiling
+    }
+
+    /// Accounts for `bytes` regardless of the ceiling
+    pub fn reserve(self: &Arc<Self>, bytes: u64) -> MemoryReservation {
+        self.used.fetch_add(bytes, Ordering::Relaxed);
+        MemoryReservation {
+            guard: self.clone(),
+            bytes,
+        }
+    }
+
+    /// Accounts for `bytes` only if that keeps usage within the ceiling
+    pub fn try_reserve(self: &Arc<Self>, bytes: u64) -> Result<MemoryReservation> {
+        if self.ceiling == 0 {
+            return Ok(self.reserve(bytes));
+        }
+
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                let next = used.saturating_add(bytes);
+                (next <= self.ceiling).then_some(next)
+            })
+            .map_err(|used| self.pressure_error(used, bytes))?;
+
+        Ok(MemoryReservation {
+            guard: self.clone(),
+            bytes,
+        })
+    }
+
+    /// Error returned when work is shed because of memory pressure
+    pub fn pressure_error(&self, used: u64, requested: u64) -> Error {
+        Error::Application {
+            code: MEMORY_PRESSURE.code(),
+            message: "Server under memory pressure, try again later".to_string(),
+            data: Some(serde_json::json!({
+                "usedBytes": used,
+                "requestedBytes": requested,
+                "ceilingBytes": self.ceiling
+            })),
+        }
+    }
+}
+
+impl Default for MemoryGuard {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Bytes accounted against a [`MemoryGuard`], released on drop
+#[derive(Debug)]
+pub struct MemoryReservation {
+    guard: Arc<MemoryGuard>,
+    bytes: u64,
+}
+
+impl MemoryReservation {
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.guard.used.fetch_sub(self.bytes, Ordering::Relaxed);
+    }
+}
+
+/// Rough serialized size of a JSON value, without serializing it
+pub fn approx_size(value: &Value) -> u64 {
+    match value {
+        Value::Null | Value::Bool(_) => 5,
+        Value::Number(_) => 8,
+        Value::String(s) => s.len() as u64 + 2,
+        Value::Array(items) => 2 + items.iter().map(|v| approx_size(v) + 1).sum::<u64>(),
+        Value::Object(map) => {
+            2 + map
+                .iter()
+                .map(|(k, v)| k.len() as u64 + 4 + approx_size(v))
+                .sum::<u64>()
+        }
+    }
+}
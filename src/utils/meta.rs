@@ -0,0 +1,50 @@
+//! The `_meta` object of the request being handled
+//!
+//! Clients attach `_meta` to a request's params for protocol-level data
+//! such as a `progressToken`, and for vendor keys that only some clients
+//! and servers understand. The server parses it once per request and keeps
+//! every key, so handlers can read it with [`current`]; results carry their
+//! own `_meta` fields for handlers to fill.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT_META: Option<RequestMeta>;
+}
+
+/// A request's `_meta`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestMeta {
+    /// Token the client expects progress notifications to refer to
+    #[serde(rename = "progressToken", default, skip_serializing_if = "Option::is_none")]
+    pub progress_token: Option<Value>,
+    /// Every other key, as sent
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl RequestMeta {
+    /// Reads `_meta` from request params; a `_meta` that isn't an object is
+    /// ignored
+    pub fn from_params(params: Option<&Value>) -> Option<Self> {
+        let meta = params?.get("_meta")?;
+        Self::deserialize(meta).ok()
+    }
+
+    /// A vendor key, e.g. `com.example/trace-id`
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.extra.get(key)
+    }
+}
+
+/// `_meta` of the request being handled, if it sent one
+pub fn current() -> Option<RequestMeta> {
+    CURRENT_META.try_with(Clone::clone).ok().flatten()
+}
+
+/// Runs `future` with `meta` as the current request's `_meta`
+pub fn with_meta<F: Future>(meta: Option<RequestMeta>, future: F) -> impl Future<Output = F::Output> {
+    CURRENT_META.scope(meta, future)
+}
@@ -1,5 +1,28 @@
 pub mod logger;
 pub mod error;
+pub mod clock;
+pub mod error_codes;
+pub mod retry;
+pub mod blob;
+pub mod limits;
+pub mod memory;
+pub mod i18n;
+pub mod coalesce;
+pub mod deadline;
+pub mod meta;
+pub mod inflight;
+pub mod pending;
+pub mod pagination;
+pub mod snapshot;
 
 pub use logger::Logger;
-pub use error::{Error, Result};
\ No newline at end of file
+pub use error::{Error, Result};
+pub use error_codes::{ApplicationError, ErrorCode, ErrorCodeRegistry};
+pub use retry::RetryPolicy;
+pub use clock::{Clock, MockClock, SystemClock};
+pub use limits::{Priority, RequestLimiter, RequestLimits, SlotPermit};
+pub use memory::{MemoryGuard, MemoryReservation};
+pub use coalesce::Coalescer;
+pub use inflight::{InFlight, InFlightRequest};
+pub use pending::PendingRequests;
+pub use snapshot::Snapshot;
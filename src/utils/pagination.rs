@@ -0,0 +1,42 @@
+//! Cursor pagination of the list endpoints
+//!
+//! A cursor is an opaque string encoding the offset of the next page into
+//! the sorted list. Clients must not build cursors themselves, so anything
+//! that doesn't decode is rejected as invalid params.
+
+use crate::utils::{Error, Result};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde_json::Value;
+
+/// The cursor of the page starting at `offset`
+pub fn encode_cursor(offset: usize) -> String {
+    URL_SAFE_NO_PAD.encode(format!("offset:{}", offset))
+}
+
+/// The offset a cursor from [`encode_cursor`] points at
+pub fn decode_cursor(cursor: &str) -> Result<usize> {
+    URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .and_then(|decoded| decoded.strip_prefix("offset:")?.parse().ok())
+        .ok_or_else(|| Error::InvalidParams(format!("Invalid cursor: {}", cursor)))
+}
+
+/// Cuts `items` down to the page at `cursor` (the first page when `None`)
+/// and returns the cursor of the next page, if there is one
+///
+/// Without a `page_size` every item from the cursor on fits on the page.
+pub fn paginate(items: &mut Vec<Value>, cursor: Option<&str>, page_size: Option<usize>) -> Result<Option<String>> {
+    let offset = cursor.map(decode_cursor).transpose()?.unwrap_or(0);
+    // The list may have shrunk since the cursor was handed out
+    items.drain(..offset.min(items.len()));
+    match page_size {
+        Some(page_size) if items.len() > page_size => {
+            items.truncate(page_size);
+            Ok(Some(encode_cursor(offset + page_size)))
+        }
+        _ => Ok(None),
+    }
+}
\ No newline at end of file
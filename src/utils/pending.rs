@@ -0,0 +1,65 @@
+use crate::protocol;
+use crate::utils::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::oneshot;
+
+/// Requests the server sent to its client and is waiting on a reply for
+///
+/// Ids are allocated here, so they never collide with one another; the
+/// client echoes them back in its responses, which [`PendingRequests::complete`]
+/// hands to whoever is waiting.
+#[derive(Default)]
+pub struct PendingRequests {
+    next_id: AtomicU64,
+    waiting: Mutex<HashMap<String, oneshot::Sender<Result<Value>>>>,
+}
+
+impl PendingRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocates an id for a new request; the receiver gets the client's
+    /// result, or its error as `Error::Rpc`
+    pub fn begin(&self) -> (Value, oneshot::Receiver<Result<Value>>) {
+        let id = Value::from(self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let (tx, rx) = oneshot::channel();
+        self.lock().insert(id.to_string(), tx);
+        (id, rx)
+    }
+
+    /// Passes a JSON-RPC response to the request it answers; false if no
+    /// request with its id is waiting
+    pub fn complete(&self, response: Value) -> bool {
+        let Some(id) = response.get("id") else {
+            return false;
+        };
+        match self.lock().remove(&id.to_string()) {
+            Some(tx) => {
+                let _ = tx.send(protocol::response_result(response));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stops waiting for request `id`, e.g. after it timed out
+    pub fn forget(&self, id: &Value) {
+        self.lock().remove(&id.to_string());
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, oneshot::Sender<Result<Value>>>> {
+        self.waiting.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,149 @@
+use crate::utils::{Clock, Error, Logger, Result, SystemClock};
+use rand::Rng;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Exponential backoff policy for outbound calls
+///
+/// The delay before retry `n` (1-based) is `initial_delay * multiplier^(n-1)`,
+/// capped at `max_delay`, with a random fraction of it removed according to
+/// `jitter` (0.0 = no jitter, 1.0 = "full jitter").
+#[derive(Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+    clock: Arc<dyn Clock>,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// A policy that performs a single attempt and never retries
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    pub fn with_initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Clock used to wait between attempts
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Backoff delay before the given retry (1 = first retry), jitter excluded
+    pub fn base_delay(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let secs = self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+        let capped = secs.min(self.max_delay.as_secs_f64());
+        Duration::from_secs_f64(capped.max(0.0))
+    }
+
+    /// Backoff delay before the given retry with jitter applied
+    pub fn delay(&self, retry: u32) -> Duration {
+        let base = self.base_delay(retry);
+        if self.jitter <= 0.0 {
+            return base;
+        }
+        let reduction = rand::rng().random_range(0.0..=self.jitter);
+        base.mul_f64(1.0 - reduction)
+    }
+
+    /// Runs `operation` until it succeeds, attempts are exhausted, or it fails
+    /// with an error that [`Error::is_retryable`] rejects
+    pub async fn retry<T, F, Fut>(&self, operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.retry_if(Error::is_retryable, operation).await
+    }
+
+    /// Like [`RetryPolicy::retry`] with a caller-supplied retry classification
+    pub async fn retry_if<T, E, F, Fut, C>(
+        &self,
+        classify: C,
+        mut operation: F,
+    ) -> std::result::Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+        C: Fn(&E) -> bool,
+        E: std::fmt::Display,
+    {
+        let logger = Logger::new("Retry");
+        let mut attempt = 1;
+
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.max_attempts && classify(&e) => {
+                    let delay = self.delay(attempt);
+                    logger.debug_with_context(
+                        &format!(
+                            "Attempt {}/{} failed, retrying in {:?}",
+                            attempt, self.max_attempts, delay
+                        ),
+                        &e.to_string(),
+                    );
+                    self.clock.sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: 0.5,
+            clock: Arc::new(SystemClock),
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_delay", &self.initial_delay)
+            .field("max_delay", &self.max_delay)
+            .field("multiplier", &self.multiplier)
+            .field("jitter", &self.jitter)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::{Arc, Mutex, RwLock};
+
+/// A value read through immutable snapshots and replaced copy-on-write
+///
+/// [`load`](Snapshot::load) hands out the current version as an `Arc`, so a
+/// reader never holds a lock while it works with the value, and readers
+/// don't wait on each other. Writers copy the value, change the copy and
+/// swap it in; readers only ever wait out that swap, never a writer's
+/// changes. Snapshots taken earlier keep the version they saw. This suits
+/// registries that are read on every request and written rarely.
+pub struct Snapshot<T> {
+    current: RwLock<Arc<T>>,
+    // Serializes writers, so none loses another's change
+    writer: Mutex<()>,
+}
+
+impl<T: Clone> Snapshot<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(value)),
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// The current version
+    pub fn load(&self) -> Arc<T> {
+        self.current.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Applies `change` to a copy of the current version and publishes it
+    pub fn update<R>(&self, change: impl FnOnce(&mut T) -> R) -> R {
+        let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let mut next = T::clone(&self.load());
+        let result = change(&mut next);
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(next);
+        result
+    }
+}
+
+impl<T: Clone + Default> Default for Snapshot<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
@@ -0,0 +1,100 @@
+use mcp_server_rust::access::{AccessPolicy, Role, Target};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::transport::http::{self, SESSION_HEADER};
+use mcp_server_rust::{Error, McpServer, ServerConfig};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn policy() -> AccessPolicy {
+    AccessPolicy::new()
+        .with_role("admin", Role::new().with_tools(&["*"]).with_resources(&["*"]).with_prompts(&["*"]))
+        .with_role("reader", Role::new().with_tools(&["greet"]).with_resources(&["config://*"]))
+        .with_identity("alice", &["admin"])
+        .with_identity("bob", &["reader"])
+}
+
+fn server() -> McpServer {
+    McpServer::new(ServerConfig::new("Rbac", "0.1.0").with_access_policy(policy()))
+}
+
+fn denied_code(error: Error) -> i64 {
+    match error {
+        Error::Rpc { code, .. } => code,
+        other => panic!("expected a JSON-RPC error, got {}", other),
+    }
+}
+
+#[tokio::test]
+async fn roles_limit_calls_and_listings() {
+    let client = McpTestClient::new(server());
+    {
+        let client = McpTestClient::from_arc(client.server().clone()).with_identity("bob");
+        let tools: Vec<_> = client.list_tools().await.unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(tools, vec!["greet"]);
+        assert!(client.call_tool("greet", json!({ "name": "Bob" })).await.is_ok());
+        let error = client
+            .call_tool("calculate-bmi", json!({ "weightKg": 70, "heightM": 1.75 }))
+            .await
+            .unwrap_err();
+        assert_eq!(denied_code(error), -32003);
+
+        assert!(client.read_resource("config://app").await.is_ok());
+        assert!(client.list_prompts().await.unwrap().is_empty());
+        let error = client.get_prompt("review-code", json!({ "code": "x" })).await.unwrap_err();
+        assert_eq!(denied_code(error), -32003);
+    }
+
+    let alice = McpTestClient::from_arc(client.server().clone()).with_identity("alice");
+    assert_eq!(alice.list_tools().await.unwrap().len(), 3);
+    assert!(alice.get_prompt("review-code", json!({ "code": "x" })).await.is_ok());
+
+    // Without an identity only the (empty) anonymous roles apply
+    assert!(client.list_tools().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn bearer_tokens_establish_the_identity() {
+    let tokens = HashMap::from([("bob-token".to_string(), "bob".to_string())]);
+    let router = http::with_bearer_auth(http::router(Arc::new(server())), tokens);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let client = reqwest::Client::new();
+    let list = json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" });
+    let anonymous = client.post(&url).json(&list).send().await.unwrap();
+    assert_eq!(anonymous.status(), 401);
+
+    let response = client
+        .post(&url)
+        .bearer_auth("bob-token")
+        .json(&list)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers().get(SESSION_HEADER).is_none());
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["result"]["tools"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn patterns_match_with_wildcards() {
+    let policy = AccessPolicy::new()
+        .with_role(
+            "ops",
+            Role::new()
+                .with_tools(&["*-report", "db.*.read"])
+                .with_resources(&["file:///shared/*"]),
+        )
+        .with_anonymous(&["ops"]);
+
+    assert!(policy.allows(None, Target::Tool, "daily-report"));
+    assert!(policy.allows(None, Target::Tool, "db.users.read"));
+    assert!(!policy.allows(None, Target::Tool, "db.users.write"));
+    assert!(!policy.allows(None, Target::Tool, "report"));
+    assert!(policy.allows(Some("stranger"), Target::Resource, "file:///shared/a/b.txt"));
+    assert!(!policy.allows(None, Target::Resource, "file:///private/key"));
+    assert!(!policy.allows(None, Target::Prompt, "anything"));
+}
@@ -0,0 +1,75 @@
+use mcp_server_rust::aggregator::{Aggregator, AggregatorConfig};
+use serde_json::{Value, json};
+
+fn downstream(namespace: &str) -> Value {
+    json!({ "namespace": namespace, "command": env!("CARGO_BIN_EXE_mcp-server-rust") })
+}
+
+async fn request(aggregator: &Aggregator, id: u64, method: &str, params: Value) -> Value {
+    aggregator
+        .handle_request(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn merges_and_forwards_to_downstream_servers() {
+    let config: AggregatorConfig = serde_json::from_value(json!({
+        "name": "Gateway",
+        "version": "0.1.0",
+        "servers": [downstream("a"), downstream("b")]
+    }))
+    .unwrap();
+    let aggregator = Aggregator::connect(config).await.unwrap();
+
+    let response = request(&aggregator, 1, "tools/list", json!({})).await;
+    let names: Vec<&str> = response["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|t| t["name"].as_str())
+        .collect();
+    assert!(names.contains(&"a__greet") && names.contains(&"b__greet"));
+
+    let response = request(
+        &aggregator,
+        2,
+        "tools/call",
+        json!({ "name": "b__greet", "arguments": { "name": "Ada" } }),
+    )
+    .await;
+    assert_eq!(
+        response["result"]["content"][0]["text"],
+        "Hello, Ada! Welcome to MCP."
+    );
+
+    let response = request(
+        &aggregator,
+        3,
+        "resources/read",
+        json!({ "uri": "config://app" }),
+    )
+    .await;
+    assert!(response["result"]["contents"].is_array());
+
+    let response = request(&aggregator, 4, "tools/call", json!({ "name": "c__greet" })).await;
+    assert_eq!(response["error"]["code"], -32601);
+}
+
+#[test]
+fn validation_reports_bad_namespaces() {
+    let config: AggregatorConfig = serde_json::from_value(json!({
+        "name": "Gateway",
+        "version": "0.1.0",
+        "servers": [downstream("a__b"), downstream("c"), downstream("c")]
+    }))
+    .unwrap();
+    assert_eq!(
+        config.validate(),
+        vec![
+            "Namespace a__b contains the separator __".to_string(),
+            "Duplicate namespace: c".to_string()
+        ]
+    );
+}
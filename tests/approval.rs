@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use axum::{Json, Router, routing::post};
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::approval::{
+    Approval, ApprovalPolicy, ApprovalRequest, ElicitationApproval, WebhookApproval,
+};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{Error, McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Deletes nothing, but counts how often it was allowed to run
+struct DropTable {
+    runs: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ToolHandler for DropTable {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        Ok(CallToolResult::success(vec![TextContent::new(format!("Dropped {}", arguments["table"]))]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "drop-table".to_string(),
+            description: "Drops a table".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: Some(json!({ "destructiveHint": true })),
+            output_schema: None,
+        })
+    }
+}
+
+/// Approves every table but `users`; fails for `broken`
+struct ProtectUsers;
+
+#[async_trait]
+impl ApprovalPolicy for ProtectUsers {
+    async fn review(&self, _server: &McpServer, request: &ApprovalRequest) -> Result<Approval> {
+        match request.arguments["table"].as_str() {
+            Some("users") => Ok(Approval::denied("users is protected")),
+            Some("broken") => Err(Error::InternalError("approver unavailable".to_string())),
+            _ => Ok(Approval::Approved),
+        }
+    }
+}
+
+async fn client(policy: Arc<dyn ApprovalPolicy>) -> (McpTestClient, Arc<AtomicUsize>) {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let server = McpServer::new(ServerConfig::new("Approval", "0.1.0")).with_approval_policy(policy);
+    server
+        .register_tool("drop-table".to_string(), Arc::new(DropTable { runs: runs.clone() }))
+        .await
+        .unwrap();
+    (McpTestClient::new(server), runs)
+}
+
+#[tokio::test]
+async fn destructive_calls_run_only_when_approved() {
+    let (client, runs) = client(Arc::new(ProtectUsers)).await;
+
+    let approved = client.call_tool("drop-table", json!({ "table": "tmp" })).await.unwrap();
+    assert_eq!(approved.is_error, Some(false));
+    let denied = client.call_tool("drop-table", json!({ "table": "users" })).await.unwrap();
+    assert_eq!(denied.is_error, Some(true));
+    assert!(denied.content[0].as_text().unwrap().contains("users is protected"), "{}", denied.content[0].as_text().unwrap());
+    let failed = client.call_tool("drop-table", json!({ "table": "broken" })).await.unwrap();
+    assert_eq!(failed.is_error, Some(true));
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    // Tools without the hint are not reviewed, or audited
+    client.call_tool("greet", json!({ "name": "Ann" })).await.unwrap();
+
+    let entries = client.server().audit_log().entries().await.unwrap();
+    let outcomes: Vec<_> = entries.iter().map(|e| (e.target.as_str(), e.outcome.as_str())).collect();
+    assert_eq!(
+        outcomes,
+        vec![("drop-table", "approved"), ("drop-table", "denied"), ("drop-table", "denied")]
+    );
+    assert_eq!(entries[1].reason.as_deref(), Some("users is protected"));
+    assert!(entries[2].reason.as_deref().unwrap().contains("approver unavailable"));
+}
+
+#[tokio::test]
+async fn webhooks_decide_remotely() {
+    let router = Router::new().route(
+        "/approve",
+        post(|Json(request): Json<Value>| async move {
+            let approved = request["arguments"]["table"] != "users";
+            Json(json!({ "approved": approved, "reason": "change freeze" }))
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/approve", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let (client, runs) = client(Arc::new(WebhookApproval::new(url))).await;
+    client.call_tool("drop-table", json!({ "table": "tmp" })).await.unwrap();
+    let denied = client.call_tool("drop-table", json!({ "table": "users" })).await.unwrap();
+    assert!(denied.content[0].as_text().unwrap().contains("change freeze"));
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn users_confirm_through_elicitation() {
+    let (client, runs) = client(Arc::new(ElicitationApproval)).await;
+    client
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": "2025-06-18",
+                "capabilities": { "elicitation": {} },
+                "clientInfo": { "name": "approval-test", "version": "0.1.0" }
+            }),
+        )
+        .await
+        .unwrap();
+
+    // Play the user: accept the first prompt, decline the second
+    let server = client.server().clone();
+    let mut outgoing = server.subscribe_notifications();
+    tokio::spawn(async move {
+        for approve in [true, false] {
+            let request = outgoing.recv().await.unwrap();
+            assert_eq!(request["method"], "elicitation/create");
+            let reply = json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": { "action": "accept", "content": { "approve": approve } }
+            });
+            server.handle_request(reply).await.unwrap();
+        }
+    });
+
+    let approved = client.call_tool("drop-table", json!({ "table": "tmp" })).await.unwrap();
+    assert_eq!(approved.is_error, Some(false));
+    let declined = client.call_tool("drop-table", json!({ "table": "tmp" })).await.unwrap();
+    assert_eq!(declined.is_error, Some(true));
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
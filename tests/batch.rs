@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::utils::RequestLimits;
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Barrier;
+
+/// Returns only once two calls are waiting at the barrier, so it finishes
+/// only if the calls run at the same time
+struct Rendezvous(Arc<Barrier>);
+
+#[async_trait]
+impl ToolHandler for Rendezvous {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        self.0.wait().await;
+        Ok(CallToolResult::success(vec![TextContent::new("met")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "rendezvous".to_string(),
+            description: "Waits for a second caller".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+fn server() -> McpServer {
+    McpServer::new(ServerConfig::new("Batch", "0.1.0"))
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+#[tokio::test]
+async fn batches_are_answered_with_an_array() {
+    let batch = json!([
+        request(1, "ping", json!({})),
+        { "jsonrpc": "2.0", "method": "notifications/progress", "params": {} },
+        request(2, "tools/call", json!({ "name": "greet", "arguments": { "name": "Ada" } })),
+        request(3, "no/such/method", json!({})),
+    ]);
+    let responses = server().handle_request(batch).await.unwrap().unwrap();
+    let responses = responses.as_array().unwrap();
+
+    // Notifications get no entry
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0], json!({ "jsonrpc": "2.0", "id": 1, "result": {} }));
+    assert_eq!(responses[1]["id"], 2);
+    assert_eq!(responses[1]["result"]["content"][0]["text"], "Hello, Ada! Welcome to MCP.");
+    assert_eq!(responses[2]["id"], 3);
+    assert_eq!(responses[2]["error"]["code"], -32601);
+}
+
+#[tokio::test]
+async fn batched_requests_run_concurrently() {
+    let server = server();
+    let tool = Arc::new(Rendezvous(Arc::new(Barrier::new(2))));
+    server.register_tool("rendezvous".to_string(), tool).await.unwrap();
+    let call = json!({ "name": "rendezvous", "arguments": {} });
+    let frame = json!([request(1, "tools/call", call.clone()), request(2, "tools/call", call)]).to_string();
+
+    let responses = tokio::time::timeout(Duration::from_secs(5), server.handle_message(&frame))
+        .await
+        .expect("batched calls ran one after the other")
+        .unwrap()
+        .unwrap();
+    assert_eq!(responses.as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn notification_only_batches_get_no_reply() {
+    let batch = json!([
+        { "jsonrpc": "2.0", "method": "notifications/progress", "params": {} },
+        { "jsonrpc": "2.0", "method": "notifications/cancelled", "params": { "requestId": 9 } },
+    ]);
+    assert_eq!(server().handle_request(batch).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn invalid_entries_are_answered_individually() {
+    let frame = r#"[{"jsonrpc":"2.0","id":1,"method":"ping"}, 42, {"jsonrpc":"1.0","id":3,"method":"ping"}, [], {"jsonrpc":"2.0","id":5,"method":"initialize","params":{}}]"#;
+    let responses = server().handle_message(frame).await.unwrap().unwrap();
+    let responses = responses.as_array().unwrap();
+
+    assert_eq!(responses.len(), 5);
+    assert_eq!(responses[0]["result"], json!({}));
+    let rejected: Vec<_> = responses[1..].iter().map(|r| (r["id"].clone(), r["error"]["code"].clone())).collect();
+    assert_eq!(
+        rejected,
+        vec![(Value::Null, json!(-32600)), (json!(3), json!(-32600)), (Value::Null, json!(-32600)), (json!(5), json!(-32600))]
+    );
+}
+
+#[tokio::test]
+async fn empty_and_oversized_batches_are_refused_whole() {
+    let empty = server().handle_message("[]").await.unwrap().unwrap();
+    assert_eq!(empty["id"], Value::Null);
+    assert_eq!(empty["error"]["code"], -32600);
+
+    let limits = RequestLimits {
+        max_batch_size: 2,
+        ..RequestLimits::default()
+    };
+    let server = McpServer::new(ServerConfig::new("Batch", "0.1.0").with_limits(limits));
+    let batch = json!([request(1, "ping", json!({})), request(2, "ping", json!({})), request(3, "ping", json!({}))]);
+    let oversized = server.handle_request(batch).await.unwrap().unwrap();
+    assert_eq!(oversized["error"]["code"], -32600);
+    assert!(oversized["error"]["message"].as_str().unwrap().contains("limit of 2"));
+
+    assert!(server.handle_message("[{\"jsonrpc\":").await.is_err());
+}
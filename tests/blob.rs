@@ -0,0 +1,73 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use futures::io::{AsyncRead, Cursor};
+use mcp_server_rust::utils::blob::{encode_bytes, encode_file, encode_reader, encoded_len};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Hands out at most `step` bytes per read, like a slow socket
+struct Trickle {
+    data: Vec<u8>,
+    at: usize,
+    step: usize,
+}
+
+impl AsyncRead for Trickle {
+    fn poll_read(mut self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<std::io::Result<usize>> {
+        let n = self.step.min(buf.len()).min(self.data.len() - self.at);
+        buf[..n].copy_from_slice(&self.data[self.at..self.at + n]);
+        self.at += n;
+        Poll::Ready(Ok(n))
+    }
+}
+
+fn bytes(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+#[tokio::test]
+async fn readers_encode_like_the_whole_input_at_once() {
+    // Lengths around the chunk size, where partial chunks carry over
+    for len in [0, 1, 2, 3, 3 * 64 * 1024 - 1, 3 * 64 * 1024, 3 * 64 * 1024 + 1, 1_000_000] {
+        let data = bytes(len);
+        let encoded = encode_reader(Cursor::new(data.clone()), u64::MAX, None).await.unwrap();
+        assert_eq!(encoded, STANDARD.encode(&data), "{} bytes", len);
+        assert_eq!(encoded.len(), encoded_len(len as u64));
+
+        let trickle = Trickle { data: data.clone(), at: 0, step: 1000 };
+        assert_eq!(encode_reader(trickle, u64::MAX, Some(len as u64)).await.unwrap(), encoded);
+    }
+}
+
+#[tokio::test]
+async fn reading_stops_once_the_cap_is_passed() {
+    let data = bytes(10_000);
+    assert!(encode_reader(Cursor::new(data.clone()), 10_000, None).await.is_ok());
+
+    let error = encode_reader(Cursor::new(data), 9_999, None).await.unwrap_err();
+    assert!(error.to_string().contains("9999 bytes"), "{}", error);
+}
+
+#[test]
+fn bytes_over_the_cap_are_rejected() {
+    assert_eq!(encode_bytes(b"abc", 3).unwrap(), "YWJj");
+    assert!(encode_bytes(b"abcd", 3).is_err());
+}
+
+#[test]
+fn encoded_lengths_include_padding() {
+    assert_eq!([0, 1, 2, 3, 4, 6].map(encoded_len), [0, 4, 4, 4, 8, 8]);
+}
+
+#[tokio::test]
+async fn files_are_checked_against_the_cap_before_reading() {
+    let path = std::env::temp_dir().join(format!("mcp-blob-{}", uuid::Uuid::new_v4()));
+    std::fs::write(&path, bytes(300)).unwrap();
+
+    assert_eq!(encode_file(&path, 300).await.unwrap(), STANDARD.encode(bytes(300)));
+    assert!(encode_file(&path, 299).await.unwrap_err().to_string().contains("299 bytes"));
+    // A missing file is reported as such
+    let missing = encode_file(path.with_extension("missing"), 300).await.unwrap_err();
+    assert!(matches!(missing, mcp_server_rust::Error::Io(ref e) if e.kind() == std::io::ErrorKind::NotFound));
+    let _ = std::fs::remove_file(path);
+}
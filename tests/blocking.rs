@@ -0,0 +1,45 @@
+use mcp_server_rust::ServerConfig;
+use mcp_server_rust::blocking::BlockingServer;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool};
+use serde_json::json;
+
+#[test]
+fn sync_code_can_embed_a_server() {
+    let server = BlockingServer::new(ServerConfig::new("Sync", "0.1.0")).unwrap();
+    let tool = Tool {
+        name: "shout".to_string(),
+        description: "Uppercases its input".to_string(),
+        input_schema: json!({ "type": "object" }),
+        annotations: None,
+        output_schema: None,
+    };
+    server
+        .register_tool_fn(tool, |args| {
+            let text = args["text"].as_str().unwrap_or_default().to_uppercase();
+            Ok(CallToolResult::success(vec![TextContent::new(text)]))
+        })
+        .unwrap();
+
+    let response = server
+        .handle_request(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": "shout", "arguments": { "text": "hi" } }
+        }))
+        .unwrap()
+        .unwrap();
+    assert_eq!(response["result"]["content"][0]["text"], "HI");
+
+    let response = server
+        .handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#)
+        .unwrap()
+        .unwrap();
+    let names: Vec<&str> = response["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|t| t["name"].as_str())
+        .collect();
+    assert!(names.contains(&"shout"));
+}
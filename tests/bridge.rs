@@ -0,0 +1,49 @@
+use mcp_server_rust::bridge::{HttpToStdio, StdioToHttp};
+use mcp_server_rust::client::McpClient;
+use mcp_server_rust::transport::StdioTransport;
+use mcp_server_rust::transport::http::SESSION_HEADER;
+use serde_json::json;
+use tokio::io::{BufReader, duplex};
+
+#[tokio::test]
+async fn stdio_host_reaches_stdio_server_through_http() {
+    // HTTP -> stdio: the crate's own server as a child process
+    let upstream = HttpToStdio::spawn(env!("CARGO_BIN_EXE_mcp-server-rust"), &[])
+        .await
+        .unwrap();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    let router = upstream.router();
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    // stdio -> HTTP: a host talking to the bridge over in-memory pipes
+    let (host_io, bridge_io) = duplex(64 * 1024);
+    let (bridge_read, bridge_write) = tokio::io::split(bridge_io);
+    let input = StdioTransport::from_io(BufReader::new(bridge_read), tokio::io::sink());
+    let output = StdioTransport::from_io(BufReader::new(tokio::io::empty()), bridge_write);
+    let bridge = tokio::spawn(StdioToHttp::new(url.clone()).run(input, output));
+
+    let (host_read, host_write) = tokio::io::split(host_io);
+    let mut client = McpClient::new(StdioTransport::from_io(
+        BufReader::new(host_read),
+        host_write,
+    ));
+    client.initialize().await.unwrap();
+    let result = client
+        .call_tool("greet", json!({ "name": "Ada" }))
+        .await
+        .unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "Hello, Ada! Welcome to MCP.");
+    client.close().await.unwrap();
+    bridge.abort();
+
+    // Requests for a session the server does not know are rejected
+    let response = reqwest::Client::new()
+        .post(&url)
+        .header(SESSION_HEADER, "unknown")
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
@@ -0,0 +1,92 @@
+use mcp_server_rust::resources::file_resource::FileResource;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// A server with the example prompt and resource removed, keeping the tools
+async fn tools_only(config: ServerConfig) -> McpServer {
+    let server = McpServer::new(config);
+    assert!(server.unregister_prompt("review-code").await);
+    assert!(server.unregister_resource("config://").await);
+    server
+}
+
+async fn capabilities(client: &McpTestClient) -> Value {
+    client.initialize().await.unwrap()["capabilities"].clone()
+}
+
+#[tokio::test]
+async fn only_registered_lists_are_advertised() {
+    let client = McpTestClient::new(tools_only(ServerConfig::new("Caps", "0.1.0")).await);
+    let capabilities = capabilities(&client).await;
+
+    assert_eq!(capabilities["tools"], json!({}));
+    assert!(capabilities.get("resources").is_none());
+    assert!(capabilities.get("prompts").is_none());
+    // Completions only serve prompts and resources
+    assert!(capabilities.get("completions").is_none());
+    assert_eq!(capabilities["logging"], json!({}));
+}
+
+#[tokio::test]
+async fn empty_lists_that_announce_changes_are_advertised() {
+    let server = tools_only(ServerConfig::new("Caps", "0.1.0")).await;
+    server.enable_prompts_list_changed();
+    let capabilities = capabilities(&McpTestClient::new(server)).await;
+
+    assert_eq!(capabilities["prompts"], json!({ "listChanged": true }));
+    assert!(capabilities.get("resources").is_none());
+}
+
+#[tokio::test]
+async fn session_registrations_count_for_that_session() {
+    let server = Arc::new(tools_only(ServerConfig::new("Caps", "0.1.0")).await);
+    let files = FileResource::new(std::env::temp_dir());
+    server
+        .register_session_resource("s1", "file:///data/".to_string(), Arc::new(files))
+        .await
+        .unwrap();
+    let own = capabilities(&McpTestClient::from_arc(server.clone()).with_session("s1")).await;
+    assert!(own.get("resources").is_some());
+    let other = capabilities(&McpTestClient::from_arc(server).with_session("s2")).await;
+    assert!(other.get("resources").is_none());
+}
+
+#[tokio::test]
+async fn config_declares_sub_capabilities() {
+    let config = ServerConfig::new("Caps", "0.1.0")
+        .with_resource_subscriptions(true)
+        .with_experimental("x-batch", json!({ "maxSize": 10 }));
+    let capabilities = capabilities(&McpTestClient::new(McpServer::new(config))).await;
+
+    assert_eq!(capabilities["resources"]["subscribe"], true);
+    assert_eq!(capabilities["experimental"], json!({ "x-tools/search": {}, "x-batch": { "maxSize": 10 } }));
+}
+
+#[tokio::test]
+async fn subscribers_hear_about_updates_to_their_resources() {
+    let config = ServerConfig::new("Caps", "0.1.0").with_resource_subscriptions(true);
+    let client = McpTestClient::new(McpServer::new(config));
+    client.initialize().await.unwrap();
+    let mut outgoing = client.server().subscribe_notifications();
+
+    client.request("resources/subscribe", json!({ "uri": "config://app" })).await.unwrap();
+    assert_eq!(client.server().resource_updated("config://other"), 0);
+    assert_eq!(client.server().resource_updated("config://app"), 1);
+    let notification = outgoing.recv().await.unwrap();
+    assert_eq!(notification["method"], "notifications/resources/updated");
+    assert_eq!(notification["params"]["uri"], "config://app");
+
+    client.request("resources/unsubscribe", json!({ "uri": "config://app" })).await.unwrap();
+    assert_eq!(client.server().resource_updated("config://app"), 0);
+}
+
+#[tokio::test]
+async fn subscriptions_need_to_be_enabled() {
+    let client = McpTestClient::new(McpServer::new(ServerConfig::new("Caps", "0.1.0")));
+    let capabilities = capabilities(&client).await;
+    assert!(capabilities["resources"].get("subscribe").is_none());
+    let error = client.request("resources/subscribe", json!({ "uri": "config://app" })).await;
+    assert!(error.unwrap_err().to_string().contains("resources/subscribe"));
+}
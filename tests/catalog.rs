@@ -0,0 +1,33 @@
+use mcp_server_rust::catalog::Catalog;
+use mcp_server_rust::{McpServer, ServerConfig};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn catalogs_list_everything_a_server_offers() {
+    let server = Arc::new(McpServer::new(ServerConfig::new("Catalog", "0.3.0")));
+    let catalog = Catalog::from_server(server).await.unwrap();
+
+    assert_eq!(catalog.server_info["name"], "Catalog");
+    let greet = catalog.tools.iter().find(|t| t.name == "greet").unwrap();
+    assert_eq!(greet.input_schema["required"][0], "name");
+    assert!(greet.annotations.is_some());
+    assert!(catalog.resources.iter().any(|r| r["uri"] == "config://app"));
+    assert!(catalog.prompts.iter().any(|p| p.name == "review-code"));
+
+    let json = serde_json::to_value(&catalog).unwrap();
+    let round_trip: Catalog = serde_json::from_value(json).unwrap();
+    assert_eq!(round_trip.tools.len(), catalog.tools.len());
+}
+
+#[tokio::test]
+async fn catalogs_render_as_markdown() {
+    let server = Arc::new(McpServer::new(ServerConfig::new("Catalog", "0.3.0")));
+    let markdown = Catalog::from_server(server).await.unwrap().to_markdown();
+
+    assert!(markdown.starts_with("# Catalog 0.3.0\n"));
+    assert!(markdown.contains("\n### `greet`\n"));
+    assert!(markdown.contains("| `name` | string | yes | The name of the person to greet |"));
+    assert!(markdown.contains("Annotations: `readOnlyHint: true`"));
+    assert!(markdown.contains("| `config://app` | Application Configuration | application/json |"));
+    assert!(markdown.contains("\n## Prompts\n"));
+}
@@ -0,0 +1,67 @@
+use serde_json::Value;
+use std::process::Command;
+
+fn mcp_server() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_mcp-server-rust"))
+}
+
+#[test]
+fn inspect_prints_another_servers_catalog() {
+    let output = mcp_server()
+        .args(["inspect", env!("CARGO_BIN_EXE_mcp-server-rust"), "serve"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let catalog: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(catalog["serverInfo"]["name"], "RustMcpServer");
+    let tools = catalog["tools"].as_array().unwrap();
+    assert!(tools.iter().any(|tool| tool["name"] == "greet"));
+    assert!(!catalog["resources"].as_array().unwrap().is_empty());
+    assert!(!catalog["prompts"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn validate_checks_manifests_without_starting_them() {
+    let dir = std::env::temp_dir().join(format!("mcp-cli-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let valid = dir.join("valid.yaml");
+    std::fs::write(
+        &valid,
+        "server: { name: ok, version: 0.1.0 }\nresources: [{ uri: 'docs://a', text: hi }]\n",
+    )
+    .unwrap();
+    let invalid = dir.join("invalid.yaml");
+    std::fs::write(
+        &invalid,
+        "server: { name: bad, version: 0.1.0 }\nresources: [{ uri: 'docs://a' }]\n",
+    )
+    .unwrap();
+
+    let output = mcp_server().arg("validate").arg(&valid).output().unwrap();
+    assert!(output.status.success());
+
+    let output = mcp_server().arg("validate").arg(&invalid).output().unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Resource docs://a needs text or a path\n"
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn export_catalog_prints_the_served_catalog() {
+    let output = mcp_server().args(["export-catalog"]).output().unwrap();
+    assert!(output.status.success());
+    let catalog: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(catalog["tools"].as_array().unwrap().iter().any(|t| t["name"] == "greet"));
+
+    let output = mcp_server()
+        .args(["export-catalog", "--format", "markdown"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().starts_with("# RustMcpServer"));
+}
@@ -0,0 +1,40 @@
+use mcp_server_rust::client::McpClient;
+use mcp_server_rust::transport::StdioTransport;
+use mcp_server_rust::{Error, McpServer, ServerConfig};
+use serde_json::json;
+use tokio::io::{BufReader, duplex};
+
+#[tokio::test]
+async fn client_talks_to_server_over_stdio_framing() {
+    let (client_io, server_io) = duplex(64 * 1024);
+    let (server_read, server_write) = tokio::io::split(server_io);
+    let (client_read, client_write) = tokio::io::split(client_io);
+
+    let server = tokio::spawn(async move {
+        let server = McpServer::new(ServerConfig::new("ClientTest", "0.1.0"));
+        let mut transport = StdioTransport::from_io(BufReader::new(server_read), server_write);
+        transport.serve(&server).await
+    });
+
+    let mut client = McpClient::new(StdioTransport::from_io(
+        BufReader::new(client_read),
+        client_write,
+    ));
+    client.initialize().await.unwrap();
+    assert!(client.server_capabilities().unwrap().get("tools").is_some());
+
+    let tools = client.list_tools().await.unwrap();
+    assert!(tools.iter().any(|t| t.name == "greet"));
+
+    let result = client
+        .call_tool("greet", json!({ "name": "Ada" }))
+        .await
+        .unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "Hello, Ada! Welcome to MCP.");
+
+    let error = client.call_tool("missing", json!({})).await.unwrap_err();
+    assert!(matches!(error, Error::Rpc { .. }));
+
+    client.close().await.unwrap();
+    server.abort();
+}
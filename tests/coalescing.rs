@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+struct SlowLookup {
+    idempotent: bool,
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl ToolHandler for SlowLookup {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Ok(CallToolResult::success(vec![TextContent::new(format!(
+            "{} #{}",
+            arguments["key"], call
+        ))]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "lookup".to_string(),
+            description: "Slow lookup".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: Some(json!({ "idempotentHint": self.idempotent })),
+            output_schema: None,
+        })
+    }
+}
+
+async fn client(idempotent: bool) -> (McpTestClient, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let server = McpServer::new(ServerConfig::new("Coalescing", "0.1.0"));
+    let tool = SlowLookup {
+        idempotent,
+        calls: calls.clone(),
+    };
+    server.register_tool("lookup".to_string(), Arc::new(tool)).await.unwrap();
+    (McpTestClient::new(server), calls)
+}
+
+#[tokio::test]
+async fn identical_idempotent_calls_share_one_execution() {
+    let (client, calls) = client(true).await;
+    let (a, b, c) = tokio::join!(
+        client.call_tool("lookup", json!({ "key": 1 })),
+        client.call_tool("lookup", json!({ "key": 1 })),
+        client.call_tool("lookup", json!({ "key": 2 })),
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(a.unwrap().content[0].as_text().unwrap(), b.unwrap().content[0].as_text().unwrap());
+    assert!(c.unwrap().content[0].as_text().unwrap().starts_with("2 #"));
+
+    // Finished calls are not cached
+    client.call_tool("lookup", json!({ "key": 1 })).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn other_tools_always_execute() {
+    let (client, calls) = client(false).await;
+    let (a, b) = tokio::join!(
+        client.call_tool("lookup", json!({ "key": 1 })),
+        client.call_tool("lookup", json!({ "key": 1 })),
+    );
+    a.unwrap();
+    b.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
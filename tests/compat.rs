@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::compat;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{Error, McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Uses everything newer revisions added to tools
+struct Forecast;
+
+#[async_trait]
+impl ToolHandler for Forecast {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![TextContent::new("Sunny, 24°C")])
+            .with_structured_content(json!({ "temperature": 24 })))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "forecast".to_string(),
+            description: "Tomorrow's weather".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: Some(json!({ "readOnlyHint": true })),
+            output_schema: Some(json!({
+                "type": "object",
+                "properties": { "temperature": { "type": "number" } }
+            })),
+        })
+    }
+}
+
+async fn client(protocol_version: &str) -> (McpTestClient, Value) {
+    let server = McpServer::new(ServerConfig::new("Compat", "0.1.0"));
+    server.register_tool("forecast".to_string(), Arc::new(Forecast)).await.unwrap();
+    let client = McpTestClient::new(server);
+    let initialize = client
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": protocol_version,
+                "capabilities": {},
+                "clientInfo": { "name": "t", "version": "1" }
+            }),
+        )
+        .await
+        .unwrap();
+    (client, initialize)
+}
+
+async fn forecast_definition(client: &McpTestClient) -> Value {
+    let tools = client.request("tools/list", json!({})).await.unwrap();
+    tools["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["name"] == "forecast")
+        .cloned()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn current_clients_see_every_field() {
+    let (client, initialize) = client(compat::LATEST).await;
+    assert_eq!(initialize["protocolVersion"], compat::LATEST);
+
+    let tool = forecast_definition(&client).await;
+    assert!(tool.get("outputSchema").is_some());
+    assert!(tool.get("annotations").is_some());
+
+    let result = client.request("tools/call", json!({ "name": "forecast" })).await.unwrap();
+    assert_eq!(result["structuredContent"]["temperature"], 24);
+}
+
+#[tokio::test]
+async fn older_clients_get_results_they_understand() {
+    let (client, initialize) = client("2024-11-05").await;
+    assert_eq!(initialize["protocolVersion"], "2024-11-05");
+
+    let tool = forecast_definition(&client).await;
+    assert!(tool.get("outputSchema").is_none());
+    assert!(tool.get("annotations").is_none());
+
+    let result = client.request("tools/call", json!({ "name": "forecast" })).await.unwrap();
+    assert!(result.get("structuredContent").is_none());
+    assert_eq!(result["content"][0]["text"], "Sunny, 24°C");
+}
+
+#[tokio::test]
+async fn unsupported_revisions_are_refused() {
+    let client = McpTestClient::new(McpServer::new(ServerConfig::new("Compat", "0.1.0")));
+    let params = json!({ "protocolVersion": "1999-01-01", "capabilities": {}, "clientInfo": { "name": "t", "version": "1" } });
+    match client.request("initialize", params).await.unwrap_err() {
+        Error::Rpc { code, message, data } => {
+            assert_eq!(code, -32602);
+            assert_eq!(message, "Unsupported protocol version");
+            assert_eq!(data, Some(json!({ "supported": compat::SUPPORTED, "requested": "1999-01-01" })));
+        }
+        other => panic!("expected a JSON-RPC error, got {}", other),
+    }
+    assert_eq!(client.server().protocol_version().await, None);
+}
+
+#[tokio::test]
+async fn clients_that_omit_the_revision_get_the_latest() {
+    let client = McpTestClient::new(McpServer::new(ServerConfig::new("Compat", "0.1.0")));
+    let initialize = client.request("initialize", json!({ "capabilities": {} })).await.unwrap();
+    assert_eq!(initialize["protocolVersion"], compat::LATEST);
+}
+
+#[test]
+fn content_types_a_client_lacks_become_text() {
+    let mut result = json!({
+        "content": [
+            { "type": "audio", "data": "AAAA", "mimeType": "audio/wav" },
+            { "type": "resource_link", "uri": "file:///notes.md", "name": "notes" }
+        ]
+    });
+
+    let mut march = result.clone();
+    compat::downgrade("2025-03-26", "tools/call", &mut march);
+    assert_eq!(march["content"][0]["type"], "audio");
+    assert_eq!(march["content"][1], json!({ "type": "text", "text": "notes (file:///notes.md)" }));
+
+    compat::downgrade("2024-11-05", "tools/call", &mut result);
+    assert_eq!(result["content"][0], json!({ "type": "text", "text": "[audio/wav audio omitted]" }));
+}
@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::completion::{Completable, Completion, CompletionRequest, MAX_COMPLETION_VALUES};
+use mcp_server_rust::resources::{ResourceHandler, ResourceReadResult};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{Error, McpServer, Result, ServerConfig};
+use serde_json::json;
+use std::sync::Arc;
+
+/// Serves `city://{country}/{city}` and completes both variables
+struct Cities;
+
+#[async_trait]
+impl ResourceHandler for Cities {
+    async fn read(&self, _context: &RequestContext, uri: &str) -> Result<ResourceReadResult> {
+        Err(Error::ResourceError(format!("Not implemented: {}", uri)))
+    }
+
+    fn completable(&self) -> Option<&dyn Completable> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl Completable for Cities {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Completion> {
+        let candidates: &[&str] = match (request.argument.name.as_str(), request.context_argument("country")) {
+            ("country", _) => &["France", "Finland", "Germany"],
+            ("city", Some("France")) => &["Paris", "Lyon", "Lille"],
+            _ => &[],
+        };
+        Ok(Completion::matching(candidates.iter().copied(), &request.argument.value))
+    }
+}
+
+async fn client() -> McpTestClient {
+    let server = McpServer::new(ServerConfig::new("Completion", "0.1.0"));
+    server.register_resource("city://".to_string(), Arc::new(Cities)).await.unwrap();
+    McpTestClient::new(server)
+}
+
+#[tokio::test]
+async fn the_review_prompt_completes_its_focus() {
+    let client = client().await;
+    assert_eq!(client.initialize().await.unwrap()["capabilities"]["completions"], json!({}));
+
+    let result = client
+        .request(
+            "completion/complete",
+            json!({
+                "ref": { "type": "ref/prompt", "name": "review-code" },
+                "argument": { "name": "focus", "value": "s" }
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, json!({ "completion": { "values": ["security", "style"] } }));
+
+    // Arguments without suggestions get none
+    let result = client
+        .request(
+            "completion/complete",
+            json!({
+                "ref": { "type": "ref/prompt", "name": "review-code" },
+                "argument": { "name": "code", "value": "fn" }
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result["completion"]["values"], json!([]));
+}
+
+#[tokio::test]
+async fn resource_templates_complete_with_context() {
+    let client = client().await;
+    let complete = |name: &str, value: &str, context| {
+        client.request(
+            "completion/complete",
+            json!({
+                "ref": { "type": "ref/resource", "uri": "city://{country}/{city}" },
+                "argument": { "name": name, "value": value },
+                "context": { "arguments": context }
+            }),
+        )
+    };
+    let countries = complete("country", "f", json!({})).await.unwrap();
+    assert_eq!(countries["completion"]["values"], json!(["France", "Finland"]));
+    let cities = complete("city", "l", json!({ "country": "France" })).await.unwrap();
+    assert_eq!(cities["completion"]["values"], json!(["Lyon", "Lille"]));
+}
+
+#[tokio::test]
+async fn unknown_references_are_invalid_params() {
+    let client = client().await;
+    for reference in [
+        json!({ "type": "ref/prompt", "name": "retro" }),
+        json!({ "type": "ref/resource", "uri": "nowhere://{x}" }),
+        json!({ "type": "ref/tool", "name": "greet" }),
+    ] {
+        let error = client
+            .request(
+                "completion/complete",
+                json!({ "ref": reference, "argument": { "name": "x", "value": "" } }),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), -32602, "{}", reference);
+    }
+}
+
+#[test]
+fn long_completions_are_cut_to_the_protocol_maximum() {
+    let completion = Completion::new((0..250).map(|n| n.to_string()).collect());
+    assert_eq!(completion.values.len(), MAX_COMPLETION_VALUES);
+    assert_eq!(completion.total, Some(250));
+    assert_eq!(completion.has_more, Some(true));
+}
@@ -0,0 +1,8 @@
+use mcp_server_rust::{conformance, McpServer, ServerConfig};
+
+#[tokio::test]
+async fn default_server_is_compliant() {
+    let report = conformance::run(|| McpServer::new(ServerConfig::new("ConformanceServer", "1.0.0"))).await;
+    println!("{}", report);
+    assert!(report.is_compliant(), "{}", report);
+}
@@ -0,0 +1,188 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::content::AudioContent;
+use mcp_server_rust::prompts::{GetPromptResult, Message, Prompt, PromptHandler};
+use mcp_server_rust::resources::{Resource, ResourceDefinition};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, Content, ImageContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// A 1x1 transparent PNG
+const PIXEL: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+];
+
+/// Draws a chart and describes it
+struct Chart;
+
+#[async_trait]
+impl ToolHandler for Chart {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![
+            Content::text("Sales by month"),
+            ImageContent::from_bytes(PIXEL, "image/png").into(),
+        ]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "chart".to_string(),
+            description: "Draws a chart".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+/// Reads the text aloud
+struct Speak;
+
+#[async_trait]
+impl ToolHandler for Speak {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![AudioContent::from_bytes(b"RIFF", "audio/wav")]))
+    }
+}
+
+/// Hands back a file as a resource
+struct ReadNotes;
+
+#[async_trait]
+impl ToolHandler for ReadNotes {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![Content::resource(Resource {
+            uri: "file:///notes.md".to_string(),
+            mime_type: "text/markdown".to_string(),
+            text: Some("# Notes".to_string()),
+            blob: None,
+            size: None,
+        })]))
+    }
+}
+
+/// Exports a report too large to inline
+struct Export;
+
+#[async_trait]
+impl ToolHandler for Export {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![
+            Content::text("Exported 120000 rows"),
+            Content::resource_link(ResourceDefinition {
+                uri: "reports://2026/q3".to_string(),
+                name: "q3.csv".to_string(),
+                description: Some("Third quarter report".to_string()),
+                mime_type: Some("text/csv".to_string()),
+            }),
+        ]))
+    }
+}
+
+/// Opens with a pronunciation example
+struct Pronounce;
+
+#[async_trait]
+impl PromptHandler for Pronounce {
+    async fn get(&self, _context: &RequestContext, _arguments: Option<Value>) -> Result<GetPromptResult> {
+        Ok(GetPromptResult {
+            description: None,
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: Content::audio("UklGRg==", "audio/wav"),
+            }],
+            meta: None,
+        })
+    }
+
+    fn definition(&self) -> Option<Prompt> {
+        Some(Prompt {
+            name: "pronounce".to_string(),
+            description: "Pronunciation practice".to_string(),
+            arguments: None,
+        })
+    }
+}
+
+async fn client() -> McpTestClient {
+    let server = McpServer::new(ServerConfig::new("Content", "0.1.0"));
+    server.register_tool("chart".to_string(), Arc::new(Chart)).await.unwrap();
+    server.register_tool("speak".to_string(), Arc::new(Speak)).await.unwrap();
+    server.register_tool("read-notes".to_string(), Arc::new(ReadNotes)).await.unwrap();
+    server.register_tool("export".to_string(), Arc::new(Export)).await.unwrap();
+    server.register_prompt("pronounce".to_string(), Arc::new(Pronounce)).await.unwrap();
+    McpTestClient::new(server)
+}
+
+#[tokio::test]
+async fn images_are_sent_as_base64_blocks() {
+    let client = client().await;
+    let raw = client.request("tools/call", json!({ "name": "chart", "arguments": {} })).await.unwrap();
+    assert_eq!(raw["content"][0], json!({ "type": "text", "text": "Sales by month" }));
+    assert_eq!(raw["content"][1]["type"], "image");
+    assert_eq!(raw["content"][1]["mimeType"], "image/png");
+    assert_eq!(raw["content"][1]["data"], "iVBORw0KGgoAAAANSUhEUg==");
+
+    let result = client.call_tool("chart", json!({})).await.unwrap();
+    match &result.content[1] {
+        Content::Image(image) => assert_eq!(image.mime_type, "image/png"),
+        other => panic!("expected an image, got {:?}", other),
+    }
+    assert_eq!(result.content[1].as_text(), None);
+}
+
+#[tokio::test]
+async fn audio_is_sent_from_tools_and_prompts() {
+    let client = client().await;
+    let raw = client.request("tools/call", json!({ "name": "speak", "arguments": {} })).await.unwrap();
+    assert_eq!(raw["content"][0], json!({ "type": "audio", "data": "UklGRg==", "mimeType": "audio/wav" }));
+
+    let prompt = client.get_prompt("pronounce", json!({})).await.unwrap();
+    match &prompt.messages[0].content {
+        Content::Audio(audio) => assert_eq!(audio.data, "UklGRg=="),
+        other => panic!("expected audio, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn resources_are_embedded_with_their_uri() {
+    let client = client().await;
+    let raw = client.request("tools/call", json!({ "name": "read-notes", "arguments": {} })).await.unwrap();
+    assert_eq!(
+        raw["content"][0],
+        json!({
+            "type": "resource",
+            "resource": { "uri": "file:///notes.md", "mimeType": "text/markdown", "text": "# Notes" }
+        })
+    );
+
+    let result = client.call_tool("read-notes", json!({})).await.unwrap();
+    match &result.content[0] {
+        Content::Resource(embedded) => assert_eq!(embedded.resource.text.as_deref(), Some("# Notes")),
+        other => panic!("expected a resource, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn large_results_are_linked() {
+    let client = client().await;
+    let raw = client.request("tools/call", json!({ "name": "export", "arguments": {} })).await.unwrap();
+    assert_eq!(
+        raw["content"][1],
+        json!({
+            "type": "resource_link",
+            "uri": "reports://2026/q3",
+            "name": "q3.csv",
+            "description": "Third quarter report",
+            "mimeType": "text/csv"
+        })
+    );
+
+    let result = client.call_tool("export", json!({})).await.unwrap();
+    match &result.content[1] {
+        Content::ResourceLink(link) => assert_eq!(link.uri, "reports://2026/q3"),
+        other => panic!("expected a resource link, got {:?}", other),
+    }
+}
@@ -0,0 +1,37 @@
+use mcp_server_rust::daemon::PidFile;
+
+fn pid_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("mcp-{}.pid", uuid::Uuid::new_v4()))
+}
+
+#[test]
+fn pid_file_is_written_and_removed() {
+    let path = pid_path();
+    let pid_file = PidFile::create(&path).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap().trim(),
+        std::process::id().to_string()
+    );
+
+    // This process is running, so a second instance must not start
+    assert!(PidFile::create(&path).is_err());
+
+    drop(pid_file);
+    assert!(!path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn stale_pid_files_are_replaced() {
+    let path = pid_path();
+    let mut child = std::process::Command::new("true").spawn().unwrap();
+    let stale = child.id();
+    child.wait().unwrap();
+    std::fs::write(&path, format!("{}\n", stale)).unwrap();
+
+    let pid_file = PidFile::create(&path).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(pid_file.path()).unwrap().trim(),
+        std::process::id().to_string()
+    );
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::pool::ToolPoolConfig;
+use mcp_server_rust::tools::{CallToolResult, TextContent, ToolHandler};
+use mcp_server_rust::utils::deadline;
+use mcp_server_rust::utils::RequestLimits;
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Reports the milliseconds left until its request's deadline
+struct Remaining;
+
+#[async_trait]
+impl ToolHandler for Remaining {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let text = match deadline::remaining() {
+            Some(remaining) => remaining.as_millis().to_string(),
+            None => "none".to_string(),
+        };
+        Ok(CallToolResult::success(vec![TextContent::new(text)]))
+    }
+}
+
+async fn client(config: ServerConfig) -> McpTestClient {
+    let server = McpServer::new(config);
+    server.register_tool("remaining".to_string(), Arc::new(Remaining)).await.unwrap();
+    McpTestClient::new(server)
+}
+
+async fn remaining(client: &McpTestClient, params: Value) -> String {
+    let result = client.request("tools/call", params).await.unwrap();
+    result["content"][0]["text"].as_str().unwrap().to_string()
+}
+
+fn millis(text: &str) -> u64 {
+    text.parse().unwrap()
+}
+
+#[tokio::test]
+async fn requests_without_a_timeout_have_no_deadline() {
+    let client = client(ServerConfig::new("Deadlines", "0.1.0")).await;
+    assert_eq!(remaining(&client, json!({ "name": "remaining" })).await, "none");
+    assert!(deadline::remaining().is_none());
+}
+
+#[tokio::test]
+async fn meta_timeout_hint_sets_the_deadline() {
+    let client = client(ServerConfig::new("Deadlines", "0.1.0")).await;
+    let params = json!({ "name": "remaining", "_meta": { "timeoutMs": 5000 } });
+    let left = millis(&remaining(&client, params).await);
+    assert!(left > 4000 && left <= 5000, "{}", left);
+}
+
+#[tokio::test]
+async fn server_default_applies_without_a_hint() {
+    let limits = RequestLimits {
+        default_timeout_ms: 2000,
+        ..RequestLimits::default()
+    };
+    let client = client(ServerConfig::new("Deadlines", "0.1.0").with_limits(limits)).await;
+    let left = millis(&remaining(&client, json!({ "name": "remaining" })).await);
+    assert!(left > 1000 && left <= 2000, "{}", left);
+
+    // A hint overrides the default
+    let params = json!({ "name": "remaining", "_meta": { "timeoutMs": 60000 } });
+    assert!(millis(&remaining(&client, params).await) > 2000);
+}
+
+#[tokio::test]
+async fn deadline_reaches_pooled_tool_calls() {
+    let config = ServerConfig::new("Deadlines", "0.1.0").with_tool_pool(ToolPoolConfig::new(2));
+    let client = client(config).await;
+    let params = json!({ "name": "remaining", "_meta": { "timeoutMs": 5000 } });
+    assert!(millis(&remaining(&client, params).await) > 4000);
+}
@@ -0,0 +1,156 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::elicitation::{self, ElicitationAction, ElicitationRequest, ElicitationResponse};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Asks the user for an API key when none was passed
+struct Connect;
+
+#[derive(Deserialize)]
+struct Credentials {
+    api_key: String,
+}
+
+#[async_trait]
+impl ToolHandler for Connect {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let request = ElicitationRequest::new("The weather service needs an API key")
+            .with_text("api_key", "API key", true);
+        let answer = elicitation::elicit(&request).await?;
+        Ok(match answer.parse::<Credentials>()? {
+            Some(credentials) => CallToolResult::success(vec![TextContent::new(format!(
+                "Connected with {}",
+                credentials.api_key
+            ))]),
+            None => CallToolResult::error(format!("Not connected ({})", answer.action.as_str())),
+        })
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "connect".to_string(),
+            description: "Connects to the weather service".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+async fn server(client_capabilities: Value) -> Arc<McpServer> {
+    let server = Arc::new(McpServer::new(ServerConfig::new("Elicitation", "0.1.0")));
+    server.register_tool("connect".to_string(), Arc::new(Connect)).await.unwrap();
+    McpTestClient::from_arc(server.clone())
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": "2025-06-18",
+                "capabilities": client_capabilities,
+                "clientInfo": { "name": "elicitation-test", "version": "0.1.0" }
+            }),
+        )
+        .await
+        .unwrap();
+    server
+}
+
+/// Plays the user, answering each `elicitation/create` with the next result
+fn answer(server: &Arc<McpServer>, results: Vec<Value>) -> tokio::task::JoinHandle<()> {
+    let mut outgoing = server.subscribe_notifications();
+    let server = server.clone();
+    tokio::spawn(async move {
+        for result in results {
+            let request = outgoing.recv().await.unwrap();
+            assert_eq!(request["method"], "elicitation/create");
+            assert_eq!(request["params"]["requestedSchema"]["required"], json!(["api_key"]));
+            let reply = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+            server.handle_request(reply).await.unwrap();
+        }
+    })
+}
+
+#[test]
+fn requests_serialize_to_the_spec_shape() {
+    let request = ElicitationRequest::new("Which account should be billed?")
+        .with_choice("account", "Account", ["personal", "team"], true)
+        .with_number("limit", "Spending limit", false)
+        .with_boolean("remember", "Remember this choice", false)
+        .with_field("email", json!({ "type": "string", "format": "email" }), true);
+    assert_eq!(
+        request.params().unwrap(),
+        json!({
+            "message": "Which account should be billed?",
+            "requestedSchema": {
+                "type": "object",
+                "properties": {
+                    "account": { "type": "string", "title": "Account", "enum": ["personal", "team"] },
+                    "limit": { "type": "number", "title": "Spending limit" },
+                    "remember": { "type": "boolean", "title": "Remember this choice" },
+                    "email": { "type": "string", "format": "email" }
+                },
+                "required": ["account", "email"]
+            }
+        })
+    );
+
+    let nested = ElicitationRequest::new("Address?").with_field("address", json!({ "type": "object" }), true);
+    assert!(nested.params().is_err());
+    assert!(ElicitationRequest::new(" ").params().is_err());
+}
+
+#[test]
+fn responses_expose_what_the_user_entered() {
+    let accepted: ElicitationResponse =
+        serde_json::from_value(json!({ "action": "accept", "content": { "name": "Ada", "ok": true } })).unwrap();
+    assert!(accepted.accepted());
+    assert_eq!(accepted.text("name"), Some("Ada"));
+    assert_eq!(accepted.boolean("ok"), Some(true));
+
+    let declined: ElicitationResponse = serde_json::from_value(json!({ "action": "decline" })).unwrap();
+    assert_eq!(declined.action, ElicitationAction::Decline);
+    assert_eq!(declined.parse::<Value>().unwrap(), None);
+}
+
+#[tokio::test]
+async fn tools_ask_the_user_mid_call() {
+    let server = server(json!({ "elicitation": {} })).await;
+    let user = answer(
+        &server,
+        vec![
+            json!({ "action": "accept", "content": { "api_key": "k-123" } }),
+            json!({ "action": "cancel" }),
+        ],
+    );
+
+    let client = McpTestClient::from_arc(server);
+    let connected = client.call_tool("connect", json!({})).await.unwrap();
+    assert_eq!(connected.content[0].as_text().unwrap(), "Connected with k-123");
+    let cancelled = client.call_tool("connect", json!({})).await.unwrap();
+    assert_eq!(cancelled.is_error, Some(true));
+    assert_eq!(cancelled.content[0].as_text().unwrap(), "Not connected (cancel)");
+    user.await.unwrap();
+}
+
+#[tokio::test]
+async fn content_must_match_the_requested_schema() {
+    let server = server(json!({ "elicitation": {} })).await;
+    let user = answer(&server, vec![json!({ "action": "accept", "content": { "api_key": 42 } })]);
+
+    let client = McpTestClient::from_arc(server);
+    let error = client.call_tool("connect", json!({})).await.unwrap_err();
+    assert!(error.to_string().contains("requested schema"), "{}", error);
+    user.await.unwrap();
+}
+
+#[tokio::test]
+async fn elicitation_needs_the_client_capability() {
+    let server = server(json!({})).await;
+    let client = McpTestClient::from_arc(server);
+    let error = client.call_tool("connect", json!({})).await.unwrap_err();
+    assert!(error.to_string().contains("elicitation/create"), "{}", error);
+}
@@ -0,0 +1,84 @@
+use mcp_server_rust::openapi::Secret;
+use mcp_server_rust::state::{
+    EncryptedStateStore, EncryptionKey, MemoryStateStore, SledStateStore, StateStore,
+};
+use std::sync::Arc;
+
+fn key() -> EncryptionKey {
+    EncryptionKey::from_base64(&EncryptionKey::generate()).unwrap()
+}
+
+#[tokio::test]
+async fn values_are_unreadable_in_the_inner_store() {
+    let inner: Arc<dyn StateStore> = Arc::new(MemoryStateStore::new());
+    let store = EncryptedStateStore::new(inner.clone(), &key());
+
+    store.put("kv", "card", b"4111 1111 1111 1111".to_vec()).await.unwrap();
+    assert_eq!(store.get("kv", "card").await.unwrap().unwrap(), b"4111 1111 1111 1111");
+
+    let raw = inner.get("kv", "card").await.unwrap().unwrap();
+    assert!(!raw.windows(4).any(|w| w == b"4111"));
+
+    let listed = store.list("kv").await.unwrap();
+    assert_eq!(listed, vec![("card".to_string(), b"4111 1111 1111 1111".to_vec())]);
+    assert!(store.delete("kv", "card").await.unwrap());
+    assert!(store.get("kv", "card").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn wrong_keys_and_moved_values_fail_to_decrypt() {
+    let inner: Arc<dyn StateStore> = Arc::new(MemoryStateStore::new());
+    let store = EncryptedStateStore::new(inner.clone(), &key());
+    store.put("kv", "a", b"secret".to_vec()).await.unwrap();
+
+    let other = EncryptedStateStore::new(inner.clone(), &key());
+    let error = other.get("kv", "a").await.unwrap_err().to_string();
+    assert!(error.contains("Cannot decrypt kv/a"), "{}", error);
+
+    let sealed = inner.get("kv", "a").await.unwrap().unwrap();
+    inner.put("kv", "b", sealed).await.unwrap();
+    assert!(store.get("kv", "b").await.is_err());
+}
+
+#[tokio::test]
+async fn values_cannot_move_across_the_namespace_boundary() {
+    let inner: Arc<dyn StateStore> = Arc::new(MemoryStateStore::new());
+    let store = EncryptedStateStore::new(inner.clone(), &key());
+    store.put("a/b", "c", b"secret".to_vec()).await.unwrap();
+
+    // Both spell a/b/c once joined with a slash
+    let sealed = inner.get("a/b", "c").await.unwrap().unwrap();
+    inner.put("a", "b/c", sealed).await.unwrap();
+    assert!(store.get("a", "b/c").await.is_err());
+    assert_eq!(store.get("a/b", "c").await.unwrap().unwrap(), b"secret");
+}
+
+#[tokio::test]
+async fn sled_values_are_sealed_on_disk() {
+    let dir = std::env::temp_dir().join(format!("mcp-encrypted-{}", uuid::Uuid::new_v4()));
+    let key = key();
+    let sled: Arc<dyn StateStore> = Arc::new(SledStateStore::open(&dir).unwrap());
+
+    EncryptedStateStore::new(sled.clone(), &key)
+        .put("sessions", "s1", b"{\"id\":\"s1\"}".to_vec())
+        .await
+        .unwrap();
+    let raw = sled.get("sessions", "s1").await.unwrap().unwrap();
+    assert!(!raw.windows(4).any(|w| w == b"\"id\""));
+
+    // A later store with the same key reads it back
+    let store = EncryptedStateStore::new(sled, &key);
+    assert_eq!(store.get("sessions", "s1").await.unwrap().unwrap(), b"{\"id\":\"s1\"}");
+    drop(store);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn keys_come_from_secrets() {
+    let encoded = EncryptionKey::generate();
+    assert!(EncryptionKey::from_secret(&Secret::Literal(encoded)).is_ok());
+
+    let short = EncryptionKey::from_base64("c2hvcnQ=").unwrap_err().to_string();
+    assert!(short.contains("32 bytes"), "{}", short);
+    assert!(EncryptionKey::from_secret(&Secret::Env { env: "MCP_NO_SUCH_KEY".to_string() }).is_err());
+}
@@ -0,0 +1,111 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, Tool, ToolHandler};
+use mcp_server_rust::utils::error_codes::{INVALID_PARAMS, SERVER_OVERLOADED};
+use mcp_server_rust::utils::{ApplicationError, ErrorCode, ErrorCodeRegistry};
+use mcp_server_rust::{Error, McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::fmt;
+use std::sync::Arc;
+
+#[test]
+fn the_crates_own_codes_come_reserved() {
+    let registry = ErrorCodeRegistry::new();
+    assert!(registry.is_reserved(SERVER_OVERLOADED.code()));
+    assert_eq!(registry.name(-32000), Some("ServerOverloaded"));
+    assert_eq!(registry.name(-32050), None);
+}
+
+#[test]
+fn codes_are_reserved_once_within_the_server_range() {
+    let mut registry = ErrorCodeRegistry::new();
+    assert_eq!(registry.reserve(-32050, "QuotaExceeded").unwrap(), ErrorCode::new(-32050));
+    assert_eq!(registry.name(-32050), Some("QuotaExceeded"));
+
+    let conflict = registry.reserve(-32050, "RateLimited").unwrap_err().to_string();
+    assert!(conflict.contains("already reserved for QuotaExceeded"), "{}", conflict);
+    let builtin = registry.reserve(-32000, "Busy").unwrap_err().to_string();
+    assert!(builtin.contains("already reserved for ServerOverloaded"), "{}", builtin);
+    assert_eq!(registry.name(-32050), Some("QuotaExceeded"));
+
+    for outside in [-32100, -31999, -32602, 1] {
+        assert!(registry.reserve(outside, "Outside").is_err(), "{}", outside);
+    }
+    assert!(registry.reserve(-32099, "Lowest").is_ok());
+
+    let codes: Vec<i64> = registry.iter().map(|(code, _)| code.code()).collect();
+    assert!(codes.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", codes);
+    assert!(codes.contains(&-32099) && codes.contains(&-32050));
+}
+
+#[derive(Debug)]
+struct Quota(ErrorCode);
+
+impl fmt::Display for Quota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Quota exceeded")
+    }
+}
+
+impl ApplicationError for Quota {
+    fn error_code(&self) -> ErrorCode {
+        self.0
+    }
+
+    fn error_data(&self) -> Option<Value> {
+        Some(json!({ "limit": 10 }))
+    }
+}
+
+/// Fails with the application error code it is called with
+struct Fails;
+
+#[async_trait]
+impl ToolHandler for Fails {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        let code = arguments["code"].as_i64().unwrap();
+        Err(Error::application(Quota(ErrorCode::new(code))))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "fails".to_string(),
+            description: "Fails with an application error".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+async fn fail_with(client: &McpTestClient, code: ErrorCode) -> (i64, String, Option<Value>) {
+    let params = json!({ "name": "fails", "arguments": { "code": code.code() } });
+    match client.request("tools/call", params).await.unwrap_err() {
+        Error::Rpc { code, message, data } => (code, message, data),
+        other => panic!("expected a JSON-RPC error, got {}", other),
+    }
+}
+
+#[tokio::test]
+async fn only_reserved_and_standard_codes_reach_clients() {
+    let server = Arc::new(McpServer::new(ServerConfig::new("Codes", "0.1.0")));
+    server.register_tool("fails".to_string(), Arc::new(Fails)).await.unwrap();
+    let quota = server.reserve_error_code(-32050, "QuotaExceeded").await.unwrap();
+    assert!(server.reserve_error_code(-32050, "Again").await.is_err());
+    let client = McpTestClient::from_arc(server);
+    client.initialize().await.unwrap();
+
+    let (code, message, data) = fail_with(&client, quota).await;
+    assert_eq!((code, message.as_str()), (-32050, "Quota exceeded"));
+    assert_eq!(data, Some(json!({ "limit": 10 })));
+
+    let (code, _, _) = fail_with(&client, INVALID_PARAMS).await;
+    assert_eq!(code, -32602);
+
+    // Unreserved codes are hidden behind an internal error
+    let (code, message, data) = fail_with(&client, ErrorCode::new(-32077)).await;
+    assert_eq!(code, -32603);
+    assert_eq!(message, "Internal error: Quota exceeded");
+    assert_eq!(data, None);
+}
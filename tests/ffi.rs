@@ -0,0 +1,65 @@
+use mcp_server_rust::ffi::*;
+use serde_json::{Value, json};
+use std::ffi::{CStr, CString, c_char, c_void};
+
+unsafe extern "C" fn shout(_user_data: *mut c_void, arguments: *const c_char) -> *const c_char {
+    let arguments: Value =
+        serde_json::from_str(unsafe { CStr::from_ptr(arguments) }.to_str().unwrap()).unwrap();
+    if arguments["fail"] == true {
+        return std::ptr::null();
+    }
+    c"LOUD".as_ptr()
+}
+
+fn handle(server: *mut McpFfiServer, message: Value) -> Option<Value> {
+    let message = CString::new(message.to_string()).unwrap();
+    let response = unsafe { mcp_server_handle_message(server, message.as_ptr()) };
+    if response.is_null() {
+        return None;
+    }
+    let value = serde_json::from_str(unsafe { CStr::from_ptr(response) }.to_str().unwrap()).unwrap();
+    unsafe { mcp_string_free(response) };
+    Some(value)
+}
+
+#[test]
+fn c_hosts_register_tools_and_exchange_messages() {
+    let server = unsafe { mcp_server_new(c"host".as_ptr(), c"1.0".as_ptr()) };
+    assert!(!server.is_null());
+    let registered = unsafe {
+        mcp_server_register_tool(
+            server,
+            c"shout".as_ptr(),
+            c"Shouts".as_ptr(),
+            std::ptr::null(),
+            Some(shout),
+            std::ptr::null_mut(),
+        )
+    };
+    assert_eq!(registered, 0);
+
+    let call = |id: u64, arguments: Value| {
+        handle(
+            server,
+            json!({ "jsonrpc": "2.0", "id": id, "method": "tools/call",
+                    "params": { "name": "shout", "arguments": arguments } }),
+        )
+        .unwrap()
+    };
+    assert_eq!(call(1, json!({}))["result"]["content"][0]["text"], "LOUD");
+    assert_eq!(call(2, json!({ "fail": true }))["result"]["isError"], true);
+
+    assert_eq!(
+        handle(server, json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })),
+        None
+    );
+
+    let malformed = unsafe { mcp_server_handle_message(server, c"{oops".as_ptr()) };
+    let error: Value =
+        serde_json::from_str(unsafe { CStr::from_ptr(malformed) }.to_str().unwrap()).unwrap();
+    assert_eq!(error["error"]["code"], -32700);
+    unsafe { mcp_string_free(malformed) };
+
+    assert!(unsafe { mcp_server_poll_notification(server) }.is_null());
+    unsafe { mcp_server_free(server) };
+}
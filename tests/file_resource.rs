@@ -0,0 +1,123 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use mcp_server_rust::resources::file_resource::FileResource;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn data_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mcp-file-resource-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let log: String = (0..1000).map(|i| format!("line {}\n", i)).collect();
+    std::fs::write(dir.join("app.txt"), log).unwrap();
+    std::fs::write(dir.join("data.bin"), (0..=255u8).cycle().take(4096).collect::<Vec<_>>())
+        .unwrap();
+    std::fs::write(dir.join("accents.txt"), "héllo").unwrap();
+    dir
+}
+
+async fn client(dir: PathBuf) -> McpTestClient {
+    let server = McpServer::new(ServerConfig::new("Files", "0.1.0"));
+    let files = FileResource::new(dir)
+        .with_mmap_threshold(64)
+        .with_max_window_bytes(100);
+    server
+        .register_resource("file:///data/".to_string(), Arc::new(files))
+        .await
+        .unwrap();
+    McpTestClient::new(server)
+}
+
+async fn read(client: &McpTestClient, params: Value) -> Value {
+    let result = client.request("resources/read", params).await.unwrap();
+    result["contents"][0].clone()
+}
+
+#[tokio::test]
+async fn large_files_are_served_whole_through_a_mapping() {
+    let dir = data_dir();
+    let client = client(dir.clone()).await;
+
+    let text = read(&client, json!({ "uri": "file:///data/app.txt" })).await;
+    assert_eq!(text["text"], std::fs::read_to_string(dir.join("app.txt")).unwrap());
+
+    let binary = read(&client, json!({ "uri": "file:///data/data.bin" })).await;
+    let decoded = STANDARD.decode(binary["blob"].as_str().unwrap()).unwrap();
+    assert_eq!(decoded, std::fs::read(dir.join("data.bin")).unwrap());
+    assert_eq!(binary["size"], 4096);
+}
+
+#[tokio::test]
+async fn ranged_reads_return_bounded_windows() {
+    let client = client(data_dir()).await;
+
+    let params = json!({ "uri": "file:///data/app.txt", "range": { "offset": 7, "length": 14 } });
+    let window = read(&client, params).await;
+    assert_eq!(window["text"], "line 1\nline 2\n");
+    assert_eq!(window["size"], 8890);
+
+    // Windows are capped and stop at the end of the file
+    let params = json!({ "uri": "file:///data/data.bin", "range": { "offset": 10, "length": 1000 } });
+    let window = read(&client, params).await;
+    let decoded = STANDARD.decode(window["blob"].as_str().unwrap()).unwrap();
+    assert_eq!(decoded, (10..110u8).collect::<Vec<_>>());
+
+    let params = json!({ "uri": "file:///data/data.bin", "range": { "offset": 4090, "length": 50 } });
+    let window = read(&client, params).await;
+    assert_eq!(STANDARD.decode(window["blob"].as_str().unwrap()).unwrap().len(), 6);
+}
+
+#[tokio::test]
+async fn text_windows_splitting_a_character_are_returned_as_blobs() {
+    let client = client(data_dir()).await;
+    let params = json!({ "uri": "file:///data/accents.txt", "range": { "offset": 0, "length": 2 } });
+    let window = read(&client, params).await;
+    assert!(window.get("text").is_none());
+    assert_eq!(STANDARD.decode(window["blob"].as_str().unwrap()).unwrap(), b"h\xc3");
+}
+
+#[tokio::test]
+async fn bad_ranges_are_rejected() {
+    let client = client(data_dir()).await;
+    let past_end = json!({ "uri": "file:///data/accents.txt", "range": { "offset": 100, "length": 1 } });
+    assert!(client.request("resources/read", past_end).await.is_err());
+
+    // Resources without ranged reads say so
+    let config = json!({ "uri": "config://app", "range": { "offset": 0, "length": 1 } });
+    assert!(client.request("resources/read", config).await.is_err());
+}
+
+#[tokio::test]
+async fn unreadable_files_report_the_io_error() {
+    let client = client(data_dir()).await;
+    let missing = json!({ "uri": "file:///data/missing.bin" });
+    let error = client.request("resources/read", missing).await.unwrap_err().to_string();
+    assert!(error.contains("IO error") && error.contains("No such file"), "{}", error);
+
+    let range = json!({ "uri": "file:///data/missing.txt", "range": { "offset": 0, "length": 1 } });
+    let error = client.request("resources/read", range).await.unwrap_err().to_string();
+    assert!(error.contains("IO error") && error.contains("No such file"), "{}", error);
+}
+
+#[tokio::test]
+async fn blobs_over_the_cap_are_refused() {
+    let dir = data_dir();
+    let server = McpServer::new(ServerConfig::new("Files", "0.1.0"));
+    let files = FileResource::new(dir).with_max_blob_bytes(1024);
+    server
+        .register_resource("file:///data/".to_string(), Arc::new(files))
+        .await
+        .unwrap();
+    let client = McpTestClient::new(server);
+
+    let error = client
+        .request("resources/read", json!({ "uri": "file:///data/data.bin" }))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("1024 bytes"), "{}", error);
+    // Text isn't encoded, so the cap doesn't apply
+    assert!(read(&client, json!({ "uri": "file:///data/app.txt" })).await["text"].is_string());
+}
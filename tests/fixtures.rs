@@ -0,0 +1,9 @@
+use mcp_server_rust::transport::MockTransport;
+use mcp_server_rust::{McpServer, ServerConfig};
+
+#[tokio::test]
+async fn replays_basic_flow() {
+    let server = McpServer::new(ServerConfig::new("FixtureServer", "1.0.0"));
+    let mut transport = MockTransport::from_fixture("tests/fixtures/basic_flow.json").unwrap();
+    transport.replay(&server).await.unwrap();
+}
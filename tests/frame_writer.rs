@@ -0,0 +1,99 @@
+use mcp_server_rust::transport::FrameWriter;
+use serde_json::{Value, json};
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::io::AsyncWrite;
+
+/// Records what was written and how often the stream was flushed
+#[derive(Clone, Default)]
+struct Recorder {
+    bytes: Arc<Mutex<Vec<u8>>>,
+    flushes: Arc<Mutex<usize>>,
+}
+
+impl Recorder {
+    fn frames(&self) -> Vec<Value> {
+        let bytes = self.bytes.lock().unwrap();
+        String::from_utf8(bytes.clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn flushes(&self) -> usize {
+        *self.flushes.lock().unwrap()
+    }
+}
+
+impl AsyncWrite for Recorder {
+    fn poll_write(self: Pin<&mut Self>, _: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.bytes.lock().unwrap().extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        *self.flushes.lock().unwrap() += 1;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn queued_frames_share_one_flush() {
+    let recorder = Recorder::default();
+    let writer = FrameWriter::new(recorder.clone());
+    for i in 0..10 {
+        writer.enqueue(&json!({ "method": "notifications/progress", "params": { "progress": i } })).unwrap();
+    }
+    assert!(recorder.frames().is_empty());
+
+    writer.send(&json!({ "id": 1, "result": {} })).await.unwrap();
+    let frames = recorder.frames();
+    assert_eq!(frames.len(), 11);
+    assert_eq!(frames[9]["params"]["progress"], 9);
+    assert_eq!(frames[10]["id"], 1);
+    assert_eq!(recorder.flushes(), 1);
+
+    // Nothing queued, nothing written
+    writer.flush().await.unwrap();
+    assert_eq!(recorder.flushes(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_senders_never_interleave_frames() {
+    let recorder = Recorder::default();
+    let writer = Arc::new(FrameWriter::new(recorder.clone()));
+    let padding = "x".repeat(10_000);
+    let mut handles = Vec::new();
+    for sender in 0..8 {
+        let writer = writer.clone();
+        let padding = padding.clone();
+        handles.push(tokio::spawn(async move {
+            for i in 0..50 {
+                let message = json!({ "sender": sender, "i": i, "padding": padding });
+                writer.send(&message).await.unwrap();
+            }
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    let frames = recorder.frames();
+    assert_eq!(frames.len(), 400);
+    for sender in 0..8 {
+        let order: Vec<u64> = frames
+            .iter()
+            .filter(|f| f["sender"] == sender)
+            .map(|f| f["i"].as_u64().unwrap())
+            .collect();
+        assert_eq!(order, (0..50).collect::<Vec<_>>());
+    }
+    assert!(recorder.flushes() <= 400);
+}
@@ -0,0 +1,153 @@
+use mcp_server_rust::session;
+use mcp_server_rust::state::{MemoryStateStore, StateStore};
+use mcp_server_rust::transport::http::{self, SESSION_HEADER};
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+async fn start(store: Arc<dyn StateStore>) -> String {
+    let server = McpServer::new(ServerConfig::new("SessionTest", "0.1.0")).with_state_store(store);
+    serve(Arc::new(server)).await
+}
+
+async fn serve(server: Arc<McpServer>) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, http::router(server)).await });
+    url
+}
+
+/// Initializes a session declaring `capabilities`; returns its id
+async fn initialize(client: &reqwest::Client, url: &str, capabilities: Value) -> String {
+    let response = client
+        .post(url)
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": capabilities,
+                "clientInfo": { "name": "t", "version": "1" }
+            }
+        }))
+        .send()
+        .await
+        .unwrap();
+    response.headers()[SESSION_HEADER].to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn sessions_survive_a_restart_with_the_same_store() {
+    let store: Arc<dyn StateStore> = Arc::new(MemoryStateStore::new());
+    let client = reqwest::Client::new();
+
+    let url = start(store.clone()).await;
+    let response = client
+        .post(&url)
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": { "protocolVersion": "2024-11-05", "clientInfo": { "name": "t", "version": "1" } }
+        }))
+        .send()
+        .await
+        .unwrap();
+    let session = response.headers()[SESSION_HEADER]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    // A second server over the same store stands in for a restart
+    let url = start(store).await;
+    let ping = json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" });
+    let response = client
+        .post(&url)
+        .header(SESSION_HEADER, &session)
+        .json(&ping)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let response = client
+        .delete(&url)
+        .header(SESSION_HEADER, &session)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+
+    let response = client
+        .post(&url)
+        .header(SESSION_HEADER, &session)
+        .json(&ping)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn each_session_keeps_the_capabilities_it_negotiated() {
+    let server = Arc::new(McpServer::new(ServerConfig::new("SessionTest", "0.1.0")));
+    let url = serve(server.clone()).await;
+    let client = reqwest::Client::new();
+
+    let sampler = initialize(&client, &url, json!({ "sampling": {} })).await;
+    // A later client without sampling doesn't take it away from the first
+    let plain = initialize(&client, &url, json!({})).await;
+
+    let may_sample = |id: &str| {
+        let server = server.clone();
+        session::with_session(Some(id.to_string()), async move { server.may_send("sampling/createMessage") })
+    };
+    assert!(may_sample(&sampler).await);
+    assert!(!may_sample(&plain).await);
+    let capabilities = session::with_session(Some(plain.clone()), async { server.client_capabilities() }).await;
+    assert_eq!(capabilities, Some(json!({})));
+    // Nothing was negotiated outside the sessions
+    assert!(!server.may_send("sampling/createMessage"));
+
+    let response = client.delete(&url).header(SESSION_HEADER, &sampler).send().await.unwrap();
+    assert_eq!(response.status(), 204);
+    assert!(!may_sample(&sampler).await);
+}
+
+#[tokio::test]
+async fn resumed_sessions_keep_their_log_level_and_subscriptions() {
+    let store: Arc<dyn StateStore> = Arc::new(MemoryStateStore::new());
+    let config = || ServerConfig::new("SessionTest", "0.1.0").with_resource_subscriptions(true);
+    let client = reqwest::Client::new();
+
+    let url = serve(Arc::new(McpServer::new(config()).with_state_store(store.clone()))).await;
+    let id = initialize(&client, &url, json!({})).await;
+    for (method, params) in [
+        ("logging/setLevel", json!({ "level": "error" })),
+        ("resources/subscribe", json!({ "uri": "config://app" })),
+    ] {
+        let response = client
+            .post(&url)
+            .header(SESSION_HEADER, &id)
+            .json(&json!({ "jsonrpc": "2.0", "id": 2, "method": method, "params": params }))
+            .send()
+            .await
+            .unwrap();
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["result"], json!({}), "{}", method);
+    }
+
+    // A second server over the same store stands in for a restart
+    let server = Arc::new(McpServer::new(config()).with_state_store(store));
+    let url = serve(server.clone()).await;
+    let ping = json!({ "jsonrpc": "2.0", "id": 3, "method": "ping" });
+    let response = client.post(&url).header(SESSION_HEADER, &id).json(&ping).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+
+    let level = session::with_session(Some(id.clone()), async { server.notification_sender().log_level() }).await;
+    assert_eq!(level, mcp_server_rust::logging::LogLevel::Error);
+    let mut messages = server.open_session_channel(&id);
+    assert_eq!(server.resource_updated("config://app"), 1);
+    assert_eq!(messages.recv().await.unwrap()["params"]["uri"], "config://app");
+}
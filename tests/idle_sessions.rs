@@ -0,0 +1,151 @@
+use mcp_server_rust::session::{IdleMonitor, IdlePolicy, SESSION_NAMESPACE, SessionState};
+use mcp_server_rust::state::{MemoryStateStore, StateStore};
+use mcp_server_rust::transport::http::{self, SESSION_HEADER};
+use mcp_server_rust::utils::MockClock;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+
+const POLICY: IdlePolicy = IdlePolicy {
+    ping_after_ms: 10_000,
+    close_after_ms: 60_000,
+};
+
+async fn start(clock: &MockClock) -> (Arc<McpServer>, String) {
+    let server = McpServer::new(ServerConfig::new("Idle", "0.1.0")).with_clock(Arc::new(clock.clone()));
+    let server = Arc::new(server);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    let router = http::router(server.clone());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    (server, url)
+}
+
+async fn initialize(client: &reqwest::Client, url: &str) -> String {
+    let response = client
+        .post(url)
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": { "protocolVersion": "2024-11-05", "clientInfo": { "name": "t", "version": "1" } }
+        }))
+        .send()
+        .await
+        .unwrap();
+    response.headers()[SESSION_HEADER].to_str().unwrap().to_string()
+}
+
+/// Reads server-sent events until one carries a message
+async fn next_event(stream: &mut reqwest::Response) -> Value {
+    loop {
+        let chunk = stream.chunk().await.unwrap().expect("stream ended");
+        let text = String::from_utf8(chunk.to_vec()).unwrap();
+        if let Some(data) = text.lines().find_map(|line| line.strip_prefix("data: ")) {
+            return serde_json::from_str(data).unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn quiet_sessions_are_pinged_and_answering_keeps_them_alive() {
+    let clock = MockClock::new();
+    let (server, url) = start(&clock).await;
+    let client = reqwest::Client::new();
+    let session = initialize(&client, &url).await;
+    let monitor = IdleMonitor::new(POLICY);
+
+    let mut stream = client
+        .get(&url)
+        .header(SESSION_HEADER, &session)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(stream.status(), 200);
+
+    clock.advance(Duration::from_secs(5));
+    assert!(monitor.sweep(&server).await.unwrap().pinged.is_empty());
+
+    clock.advance(Duration::from_secs(6));
+    assert_eq!(monitor.sweep(&server).await.unwrap().pinged, vec![session.clone()]);
+    let ping = next_event(&mut stream).await;
+    assert_eq!(ping["method"], "ping");
+
+    let reply = client
+        .post(&url)
+        .header(SESSION_HEADER, &session)
+        .json(&json!({ "jsonrpc": "2.0", "id": ping["id"], "result": {} }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(reply.status(), 202);
+    assert_eq!(monitor.sweep(&server).await.unwrap(), Default::default());
+}
+
+#[tokio::test]
+async fn unresponsive_sessions_are_torn_down() {
+    let clock = MockClock::new();
+    let (server, url) = start(&clock).await;
+    let client = reqwest::Client::new();
+    let session = initialize(&client, &url).await;
+    let monitor = IdleMonitor::new(POLICY);
+
+    clock.advance(Duration::from_secs(60));
+    let sweep = monitor.sweep(&server).await.unwrap();
+    assert_eq!(sweep.closed, vec![session.clone()]);
+    assert!(server.sessions().get(&session).await.unwrap().is_none());
+
+    let response = client
+        .post(&url)
+        .header(SESSION_HEADER, &session)
+        .json(&json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn spawned_monitor_runs_on_the_server_clock() {
+    let clock = MockClock::new();
+    let (server, url) = start(&clock).await;
+    let client = reqwest::Client::new();
+    let session = initialize(&client, &url).await;
+    IdleMonitor::new(POLICY).spawn(&server);
+
+    for _ in 0..20 {
+        clock.advance(Duration::from_secs(5));
+        tokio::task::yield_now().await;
+        if server.sessions().get(&session).await.unwrap().is_none() {
+            return;
+        }
+    }
+    panic!("session outlived its idle timeout");
+}
+
+#[tokio::test]
+async fn sessions_left_by_a_previous_run_are_swept_too() {
+    let store: Arc<dyn StateStore> = Arc::new(MemoryStateStore::new());
+    let earlier = McpServer::new(ServerConfig::new("Idle", "0.1.0")).with_state_store(store.clone());
+    let left = SessionState::from_initialize(None);
+    earlier.sessions().save(&left).await.unwrap();
+    drop(earlier);
+
+    // The restarted server has never heard from the session's client
+    let clock = MockClock::new();
+    let server = McpServer::new(ServerConfig::new("Idle", "0.1.0"))
+        .with_state_store(store.clone())
+        .with_clock(Arc::new(clock.clone()));
+    let server = Arc::new(server);
+    IdleMonitor::new(POLICY).spawn(&server);
+
+    for _ in 0..20 {
+        clock.advance(Duration::from_secs(5));
+        tokio::task::yield_now().await;
+        if store.get(SESSION_NAMESPACE, &left.id).await.unwrap().is_none() {
+            return;
+        }
+    }
+    panic!("stored session outlived its idle timeout");
+}
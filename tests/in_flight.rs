@@ -0,0 +1,154 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::session;
+use mcp_server_rust::tools::{CallToolResult, TextContent, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// Blocks until released
+struct Gate(Arc<Notify>);
+
+#[async_trait]
+impl ToolHandler for Gate {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        self.0.notified().await;
+        Ok(CallToolResult::success(vec![TextContent::new("released")]))
+    }
+}
+
+async fn server() -> (Arc<McpServer>, Arc<Notify>) {
+    let gate = Arc::new(Notify::new());
+    let server = McpServer::new(ServerConfig::new("InFlight", "0.1.0"));
+    server.register_tool("gate".to_string(), Arc::new(Gate(gate.clone()))).await.unwrap();
+    (Arc::new(server), gate)
+}
+
+fn call_gate(id: u64) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": "gate", "arguments": {} }
+    })
+    .to_string()
+}
+
+async fn handle(server: &McpServer, frame: &str) -> Option<Value> {
+    let response = server.handle_frame(frame).await.unwrap();
+    response.map(|r| r.into_value().unwrap())
+}
+
+/// Starts `frame` in the background and waits until it is in flight
+async fn start(server: &Arc<McpServer>, frame: String) -> tokio::task::JoinHandle<Option<Value>> {
+    let before = server.in_flight().len();
+    let task = {
+        let server = server.clone();
+        tokio::spawn(async move { handle(&server, &frame).await })
+    };
+    while server.in_flight().len() == before {
+        tokio::task::yield_now().await;
+    }
+    task
+}
+
+#[tokio::test]
+async fn duplicate_ids_are_rejected_while_the_first_is_outstanding() {
+    let (server, gate) = server().await;
+    let first = start(&server, call_gate(1)).await;
+
+    let in_flight = server.in_flight();
+    assert_eq!(in_flight.len(), 1);
+    assert_eq!(in_flight[0].id, json!(1));
+    assert_eq!(in_flight[0].method, "tools/call");
+
+    let duplicate = handle(&server, &call_gate(1)).await.unwrap();
+    assert_eq!(duplicate["id"], 1);
+    assert_eq!(duplicate["error"]["code"], -32600);
+
+    // The same number as a string is a different id
+    let ping = r#"{"jsonrpc": "2.0", "id": "1", "method": "ping"}"#;
+    assert!(handle(&server, ping).await.unwrap().get("result").is_some());
+
+    gate.notify_one();
+    let response = first.await.unwrap().unwrap();
+    assert_eq!(response["result"]["content"][0]["text"], "released");
+    assert!(server.in_flight().is_empty());
+
+    // Once answered, the id can be used again
+    let again = start(&server, call_gate(1)).await;
+    gate.notify_one();
+    assert!(again.await.unwrap().unwrap().get("result").is_some());
+}
+
+#[tokio::test]
+async fn cancelled_requests_get_no_response() {
+    let (server, _gate) = server().await;
+    let request = start(&server, call_gate(5)).await;
+
+    let cancel = json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/cancelled",
+        "params": { "requestId": 5, "reason": "user aborted" }
+    });
+    assert!(handle(&server, &cancel.to_string()).await.is_none());
+
+    assert!(request.await.unwrap().is_none());
+    assert!(server.in_flight().is_empty());
+    assert!(!server.cancel_request(None, &json!(5)));
+}
+
+#[tokio::test]
+async fn ids_are_scoped_to_their_session() {
+    let (server, gate) = server().await;
+    let in_a = {
+        let server = server.clone();
+        tokio::spawn(async move {
+            session::with_session(Some("a".to_string()), handle(&server, &call_gate(1))).await
+        })
+    };
+    while server.in_flight().is_empty() {
+        tokio::task::yield_now().await;
+    }
+
+    let in_b = {
+        let server = server.clone();
+        tokio::spawn(async move {
+            session::with_session(Some("b".to_string()), handle(&server, &call_gate(1))).await
+        })
+    };
+    while server.in_flight().len() < 2 {
+        tokio::task::yield_now().await;
+    }
+    let sessions: Vec<_> = server.in_flight().into_iter().map(|r| r.session).collect();
+    assert!(sessions.contains(&Some("a".to_string())));
+    assert!(sessions.contains(&Some("b".to_string())));
+
+    assert!(server.cancel_request(Some("b"), &json!(1)));
+    assert!(in_b.await.unwrap().is_none());
+    gate.notify_one();
+    assert!(in_a.await.unwrap().unwrap().get("result").is_some());
+}
+
+#[tokio::test]
+async fn ending_a_session_cancels_its_requests() {
+    let (server, gate) = server().await;
+    let in_session = |id: &str| {
+        let (server, id) = (server.clone(), id.to_string());
+        tokio::spawn(async move { session::with_session(Some(id), handle(&server, &call_gate(1))).await })
+    };
+    let in_a = in_session("a");
+    let in_b = in_session("b");
+    while server.in_flight().len() < 2 {
+        tokio::task::yield_now().await;
+    }
+
+    server.end_session("a").await.unwrap();
+    assert!(in_a.await.unwrap().is_none());
+    let left: Vec<_> = server.in_flight().into_iter().map(|r| r.session).collect();
+    assert_eq!(left, [Some("b".to_string())]);
+
+    gate.notify_one();
+    assert!(in_b.await.unwrap().unwrap().get("result").is_some());
+}
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts how often it actually runs
+struct Resize {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl ToolHandler for Resize {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(CallToolResult::success(vec![TextContent::new("resized")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "resize".to_string(),
+            description: "Resizes an image".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "width": { "type": "integer", "minimum": 1 },
+                    "height": { "type": "integer", "minimum": 1 }
+                },
+                "required": ["width", "height"]
+            }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn arguments_are_checked_against_the_input_schema() {
+    let tool = Arc::new(Resize { calls: AtomicUsize::new(0) });
+    let server = McpServer::new(ServerConfig::new("Inputs", "0.1.0"));
+    server.register_tool("resize".to_string(), tool.clone()).await.unwrap();
+    let client = McpTestClient::new(server);
+
+    let result = client.call_tool("resize", json!({ "width": 0, "height": "tall" })).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    let violations = result.structured_content.unwrap()["violations"].as_array().unwrap().clone();
+    assert_eq!(violations.len(), 2);
+    assert!(violations.iter().any(|v| v.as_str().unwrap().starts_with("/width")));
+    assert!(violations.iter().any(|v| v.as_str().unwrap().starts_with("/height")));
+    assert!(result.content[0].as_text().unwrap().starts_with("Invalid arguments for tool resize"));
+
+    let result = client.call_tool("resize", json!({ "width": 2 })).await.unwrap();
+    assert!(result.content[0].as_text().unwrap().contains("\"height\" is a required property"));
+    // Rejected calls never reach the handler
+    assert_eq!(tool.calls.load(Ordering::SeqCst), 0);
+
+    let result = client.call_tool("resize", json!({ "width": 2, "height": 3 })).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(tool.calls.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,73 @@
+use mcp_server_rust::lifecycle::Phase;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{Error, McpServer, ServerConfig};
+use serde_json::json;
+
+fn client(strict: bool) -> McpTestClient {
+    let config = ServerConfig::new("Lifecycle", "0.1.0").with_strict_lifecycle(strict);
+    McpTestClient::new(McpServer::new(config))
+}
+
+fn code(error: Error) -> i64 {
+    match error {
+        Error::Rpc { code, .. } => code,
+        other => panic!("expected a JSON-RPC error, got {}", other),
+    }
+}
+
+async fn start_handshake(client: &McpTestClient) {
+    let params = json!({
+        "protocolVersion": "2025-06-18",
+        "capabilities": {},
+        "clientInfo": { "name": "lifecycle-test", "version": "0.1.0" }
+    });
+    client.request("initialize", params).await.unwrap();
+}
+
+#[tokio::test]
+async fn strict_servers_wait_for_the_handshake() {
+    let client = client(true);
+    let server = client.server().clone();
+    assert_eq!(server.lifecycle_phase(), Phase::AwaitingInitialize);
+    assert_eq!(code(client.request("tools/list", json!({})).await.unwrap_err()), -32004);
+    client.ping().await.unwrap();
+
+    start_handshake(&client).await;
+    assert_eq!(server.lifecycle_phase(), Phase::Initializing);
+    assert_eq!(code(client.request("tools/list", json!({})).await.unwrap_err()), -32004);
+
+    client.notify("notifications/initialized", json!({})).await.unwrap();
+    assert_eq!(server.lifecycle_phase(), Phase::Operating);
+    assert!(!client.list_tools().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn lenient_servers_track_the_phase_without_enforcing_it() {
+    let client = client(false);
+    assert!(!client.list_tools().await.unwrap().is_empty());
+    assert_eq!(client.server().lifecycle_phase(), Phase::AwaitingInitialize);
+    client.initialize().await.unwrap();
+    assert_eq!(client.server().lifecycle_phase(), Phase::Operating);
+}
+
+#[tokio::test]
+async fn sessions_go_through_the_lifecycle_separately() {
+    let server = client(true).server().clone();
+    let ready = McpTestClient::from_arc(server.clone()).with_session("ready");
+    ready.initialize().await.unwrap();
+
+    assert!(ready.request("tools/list", json!({})).await.is_ok());
+    let other = McpTestClient::from_arc(server).with_session("new");
+    assert_eq!(code(other.request("tools/list", json!({})).await.unwrap_err()), -32004);
+}
+
+#[tokio::test]
+async fn requests_are_refused_once_shutdown_begins() {
+    let client = client(false);
+    client.initialize().await.unwrap();
+    client.server().begin_shutdown();
+
+    assert_eq!(client.server().lifecycle_phase(), Phase::ShuttingDown);
+    assert_eq!(code(client.request("tools/list", json!({})).await.unwrap_err()), -32005);
+    client.ping().await.unwrap();
+}
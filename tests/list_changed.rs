@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::prompts::{GetPromptResult, Prompt, PromptHandler};
+use mcp_server_rust::resources::config_resource::ConfigResource;
+use mcp_server_rust::tools::ToolHandler;
+use mcp_server_rust::tools::greeting_tool::GreetingTool;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::TryRecvError;
+
+struct Empty;
+
+#[async_trait]
+impl PromptHandler for Empty {
+    async fn get(&self, _context: &RequestContext, _arguments: Option<Value>) -> Result<GetPromptResult> {
+        Ok(GetPromptResult {
+            description: None,
+            messages: Vec::new(),
+            meta: None,
+        })
+    }
+
+    fn definition(&self) -> Option<Prompt> {
+        Some(Prompt {
+            name: "empty".to_string(),
+            description: "Nothing".to_string(),
+            arguments: None,
+        })
+    }
+}
+
+fn greeting() -> Arc<dyn ToolHandler> {
+    Arc::new(GreetingTool::new())
+}
+
+#[tokio::test]
+async fn list_changed_is_advertised_only_when_enabled() {
+    let client = McpTestClient::new(McpServer::new(ServerConfig::new("Quiet", "0.1.0")));
+    let capabilities = client.initialize().await.unwrap()["capabilities"].clone();
+    assert!(capabilities["tools"].get("listChanged").is_none());
+    assert!(capabilities["prompts"].get("listChanged").is_none());
+
+    let config = ServerConfig::new("Chatty", "0.1.0").with_list_changed(true);
+    let client = McpTestClient::new(McpServer::new(config));
+    let capabilities = client.initialize().await.unwrap()["capabilities"].clone();
+    for list in ["tools", "resources", "prompts"] {
+        assert_eq!(capabilities[list]["listChanged"], true, "{}", list);
+    }
+}
+
+#[tokio::test]
+async fn registry_changes_after_initialize_are_announced() {
+    let config = ServerConfig::new("Chatty", "0.1.0").with_list_changed(true);
+    let server = Arc::new(McpServer::new(config));
+    let mut notifications = server.subscribe_notifications();
+
+    // Nobody to tell yet
+    server.register_tool("early".to_string(), greeting()).await.unwrap();
+    assert!(matches!(notifications.try_recv(), Err(TryRecvError::Empty)));
+
+    McpTestClient::from_arc(server.clone()).initialize().await.unwrap();
+    server.register_tool("late".to_string(), greeting()).await.unwrap();
+    assert_eq!(notifications.try_recv().unwrap()["method"], "notifications/tools/list_changed");
+    server.unregister_tool("late").await;
+    assert_eq!(notifications.try_recv().unwrap()["method"], "notifications/tools/list_changed");
+    server.register_prompt("empty".to_string(), Arc::new(Empty)).await.unwrap();
+    assert_eq!(notifications.try_recv().unwrap()["method"], "notifications/prompts/list_changed");
+    server.register_resource("config://".to_string(), Arc::new(ConfigResource::new())).await.unwrap();
+    assert_eq!(notifications.try_recv().unwrap()["method"], "notifications/resources/list_changed");
+
+    // Unregistering something that isn't there changes nothing
+    server.unregister_tool("missing").await;
+    assert!(matches!(notifications.try_recv(), Err(TryRecvError::Empty)));
+}
+
+#[tokio::test]
+async fn nothing_is_announced_without_the_capability() {
+    let server = Arc::new(McpServer::new(ServerConfig::new("Quiet", "0.1.0")));
+    McpTestClient::from_arc(server.clone()).initialize().await.unwrap();
+    let mut notifications = server.subscribe_notifications();
+    server.register_tool("late".to_string(), greeting()).await.unwrap();
+    assert!(matches!(notifications.try_recv(), Err(TryRecvError::Empty)));
+}
+
+#[tokio::test]
+async fn session_registrations_notify_that_session() {
+    let config = ServerConfig::new("Chatty", "0.1.0").with_list_changed(true);
+    let server = Arc::new(McpServer::new(config));
+    McpTestClient::from_arc(server.clone()).initialize().await.unwrap();
+    let mut broadcast = server.subscribe_notifications();
+    let mut channel = server.open_session_channel("s1");
+
+    server.register_session_tool("s1", "mine".to_string(), greeting()).await.unwrap();
+    assert_eq!(channel.recv().await.unwrap()["method"], "notifications/tools/list_changed");
+    assert!(matches!(broadcast.try_recv(), Err(TryRecvError::Empty)));
+}
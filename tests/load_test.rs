@@ -0,0 +1,69 @@
+use mcp_server_rust::load_test::{LoadTest, MixEntry, Target};
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::json;
+use std::sync::Arc;
+
+fn server() -> Target {
+    Target::InProcess(Arc::new(McpServer::new(ServerConfig::new("Load", "0.1.0"))))
+}
+
+#[tokio::test]
+async fn requests_follow_the_weighted_mix() {
+    let mix = vec![
+        MixEntry::new(
+            "tools/call",
+            json!({ "name": "greet", "arguments": { "name": "Ada" } }),
+        )
+        .with_weight(3),
+        MixEntry::new("no/such/method", json!({})),
+    ];
+    let report = LoadTest::new(server())
+        .with_sessions(4)
+        .with_requests_per_session(20)
+        .with_mix(mix)
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(report.overall.count, 80);
+    assert_eq!(report.by_method["tools/call"].count, 60);
+    assert_eq!(report.by_method["tools/call"].errors, 0);
+    assert_eq!(report.by_method["no/such/method"].count, 20);
+    assert_eq!(report.overall.errors, 20);
+    assert!(report.overall.p50 <= report.overall.p99);
+    assert!(report.throughput() > 0.0);
+    assert!(report.to_string().contains("tools/call"));
+}
+
+#[tokio::test]
+async fn child_processes_can_be_the_target() {
+    let target = Target::Command {
+        command: env!("CARGO_BIN_EXE_mcp-server-rust").to_string(),
+        args: Vec::new(),
+    };
+    let report = LoadTest::new(target)
+        .with_sessions(2)
+        .with_requests_per_session(10)
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(report.overall.count, 20);
+    assert_eq!(report.overall.errors, 0);
+    assert_eq!(report.by_method.len(), 2);
+}
+
+#[tokio::test]
+async fn mixes_load_from_json_and_must_not_be_empty() {
+    let path = std::env::temp_dir().join(format!("mcp-mix-{}.json", uuid::Uuid::new_v4()));
+    std::fs::write(&path, r#"[{ "method": "ping", "weight": 2 }, { "method": "prompts/list" }]"#)
+        .unwrap();
+    let mix = MixEntry::load(&path).unwrap();
+    assert_eq!(mix.len(), 2);
+    assert_eq!(mix[0].weight, 2);
+    assert_eq!(mix[1].weight, 1);
+    std::fs::remove_file(&path).unwrap();
+
+    let result = LoadTest::new(server()).with_mix(Vec::new()).run().await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,94 @@
+use mcp_server_rust::session;
+use mcp_server_rust::utils::i18n::{self, MessageKey};
+use mcp_server_rust::{Error, McpServer, ServerConfig};
+use serde_json::{Value, json};
+
+/// Sends `method` as part of `session`; returns the response
+async fn send(server: &McpServer, session: Option<&str>, method: &str, params: Value) -> Value {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    session::with_session(session.map(str::to_string), server.handle_request(request))
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn locale_tags_map_onto_the_catalogs() {
+    for (requested, negotiated) in [
+        ("es", "es"),
+        ("es-MX", "es"),
+        ("FR_ca", "fr"),
+        ("en-GB", "en"),
+        ("de", "en"),
+        ("", "en"),
+    ] {
+        assert_eq!(i18n::negotiate(requested), negotiated, "{:?}", requested);
+    }
+}
+
+#[test]
+fn messages_are_filled_in_the_requested_language() {
+    let args = ["greet".to_string()];
+    assert_eq!(i18n::translate("fr", MessageKey::ToolNotFound, &args), "Outil introuvable : greet");
+    assert_eq!(i18n::translate("es-AR", MessageKey::ToolNotFound, &args), "Herramienta no encontrada: greet");
+    assert_eq!(i18n::translate("ja", MessageKey::ToolNotFound, &args), "Tool not found: greet");
+}
+
+#[test]
+fn localized_errors_keep_their_key_and_english_text() {
+    let error = Error::invalid_params(MessageKey::MissingArgument, &["name"]);
+    assert_eq!(error.code(), -32602);
+    assert_eq!(error.to_string(), "Invalid params: Missing 'name' argument");
+
+    let (message, key) = i18n::localize_error("es", &error);
+    assert_eq!(message, "Parámetros no válidos: Falta el argumento 'name'");
+    assert_eq!(key, Some("argument.missing"));
+
+    // Errors without a key are sent as they are
+    let plain = Error::InternalError("boom".to_string());
+    assert_eq!(i18n::localize_error("fr", &plain), ("Internal error: boom".to_string(), None));
+}
+
+#[tokio::test]
+async fn handlers_translate_into_the_locale_of_their_request() {
+    assert_eq!(i18n::t(MessageKey::MissingParams, &[]), "Missing params");
+    let translated = i18n::with_locale("fr".to_string(), async { i18n::t(MessageKey::MissingParams, &[]) }).await;
+    assert_eq!(translated, "Paramètres manquants");
+    assert_eq!(i18n::current_locale(), i18n::DEFAULT_LOCALE);
+}
+
+async fn initialize(server: &McpServer, session: Option<&str>, locale: Option<&str>) {
+    let mut params = json!({
+        "protocolVersion": "2025-06-18",
+        "capabilities": {},
+        "clientInfo": { "name": "t", "version": "1" }
+    });
+    if let Some(locale) = locale {
+        params["locale"] = json!(locale);
+    }
+    send(server, session, "initialize", params).await;
+}
+
+async fn missing_tool_name(server: &McpServer, session: Option<&str>) -> String {
+    let response = send(server, session, "tools/call", json!({ "arguments": {} })).await;
+    assert_eq!(response["error"]["data"]["messageKey"], "tool.name_missing");
+    response["error"]["message"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn errors_follow_the_locale_of_their_session() {
+    let server = McpServer::new(ServerConfig::new("Locales", "0.1.0"));
+    initialize(&server, Some("spanish"), Some("es-MX")).await;
+    initialize(&server, Some("french"), Some("fr")).await;
+    initialize(&server, Some("default"), None).await;
+
+    assert!(missing_tool_name(&server, Some("spanish")).await.contains("Falta el nombre"));
+    assert!(missing_tool_name(&server, Some("french")).await.contains("Nom de l'outil"));
+    assert!(missing_tool_name(&server, Some("default")).await.contains("Missing tool name"));
+    // Requests outside any initialized session get the default
+    assert!(missing_tool_name(&server, None).await.contains("Missing tool name"));
+    assert!(missing_tool_name(&server, Some("unknown")).await.contains("Missing tool name"));
+
+    server.end_session("spanish").await.unwrap();
+    assert!(missing_tool_name(&server, Some("spanish")).await.contains("Missing tool name"));
+}
@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::logging::{ClientLogger, LogLevel};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Logs one message at each of a few levels
+struct Chatty;
+
+#[async_trait]
+impl ToolHandler for Chatty {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let logger = ClientLogger::current("chatty").expect("called while handling a request");
+        logger.debug("looking around");
+        logger.info("found 3 files");
+        logger.warning("one file is empty");
+        logger.log(LogLevel::Critical, json!({ "disk": "full" }));
+        Ok(CallToolResult::success(vec![TextContent::new("done")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "chatty".to_string(),
+            description: "Logs as it works".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+async fn client() -> McpTestClient {
+    let server = McpServer::new(ServerConfig::new("Logging", "0.1.0"));
+    server.register_tool("chatty".to_string(), Arc::new(Chatty)).await.unwrap();
+    McpTestClient::new(server)
+}
+
+fn levels(notifications: &mut tokio::sync::broadcast::Receiver<Value>) -> Vec<String> {
+    let mut levels = Vec::new();
+    while let Ok(notification) = notifications.try_recv() {
+        assert_eq!(notification["method"], "notifications/message");
+        assert_eq!(notification["params"]["logger"], "chatty");
+        levels.push(notification["params"]["level"].as_str().unwrap().to_string());
+    }
+    levels
+}
+
+#[tokio::test]
+async fn the_logging_capability_is_advertised() {
+    let client = client().await;
+    let result = client.initialize().await.unwrap();
+    assert_eq!(result["capabilities"]["logging"], json!({}));
+}
+
+#[tokio::test]
+async fn messages_below_the_client_level_are_dropped() {
+    let client = client().await;
+    let mut notifications = client.server().subscribe_notifications();
+
+    client.call_tool("chatty", json!({})).await.unwrap();
+    assert_eq!(levels(&mut notifications), vec!["info", "warning", "critical"]);
+
+    client.request("logging/setLevel", json!({ "level": "debug" })).await.unwrap();
+    client.call_tool("chatty", json!({})).await.unwrap();
+    assert_eq!(levels(&mut notifications), vec!["debug", "info", "warning", "critical"]);
+
+    client.request("logging/setLevel", json!({ "level": "error" })).await.unwrap();
+    client.call_tool("chatty", json!({})).await.unwrap();
+    assert_eq!(levels(&mut notifications), vec!["critical"]);
+}
+
+#[tokio::test]
+async fn levels_are_kept_per_session() {
+    let client = client().await;
+    let first = McpTestClient::from_arc(client.server().clone()).with_session("first");
+    let second = McpTestClient::from_arc(client.server().clone()).with_session("second");
+
+    first.request("logging/setLevel", json!({ "level": "emergency" })).await.unwrap();
+    first.call_tool("chatty", json!({})).await.unwrap();
+    second.call_tool("chatty", json!({})).await.unwrap();
+
+    assert_eq!(second.next_notification().await["params"]["level"], "info");
+    assert!(first.take_notifications().is_empty());
+}
+
+#[tokio::test]
+async fn unknown_levels_are_invalid_params() {
+    let client = client().await;
+    let error = client.request("logging/setLevel", json!({ "level": "loud" })).await.unwrap_err();
+    assert_eq!(error.code(), -32602);
+    let error = client.request("logging/setLevel", json!({})).await.unwrap_err();
+    assert_eq!(error.code(), -32602);
+}
+
+#[test]
+fn tracing_levels_map_onto_syslog_severities() {
+    assert_eq!(LogLevel::from(tracing::Level::TRACE), LogLevel::Debug);
+    assert_eq!(LogLevel::from(tracing::Level::WARN), LogLevel::Warning);
+    assert!(LogLevel::Emergency > LogLevel::Error);
+}
@@ -0,0 +1,160 @@
+use mcp_server_rust::manifest::Manifest;
+use mcp_server_rust::testing::McpTestClient;
+use serde_json::json;
+use std::path::PathBuf;
+
+fn manifest_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mcp-manifest-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(dir.join("docs")).unwrap();
+    std::fs::write(dir.join("docs/intro.txt"), "Hello from the docs").unwrap();
+    std::fs::write(dir.join("NOTES.md"), "# Notes").unwrap();
+    dir
+}
+
+#[tokio::test]
+async fn yaml_manifest_builds_a_complete_server() {
+    let dir = manifest_dir();
+    let path = dir.join("server.yaml");
+    std::fs::write(
+        &path,
+        r#"
+server:
+  name: docs-server
+  version: 2.0.0
+roots:
+  - uriPrefix: file:///docs/
+    path: ./docs
+tools:
+  - name: echo-json
+    description: Echo the arguments
+    command: cat
+prompts:
+  - name: summarize
+    description: Summarize a topic
+    arguments:
+      - name: topic
+        description: What to summarize
+        required: true
+    template: Summarize what the docs say about {{topic}}.
+resources:
+  - uri: docs://notes
+    path: ./NOTES.md
+    mimeType: text/markdown
+  - uri: docs://motd
+    text: Welcome
+"#,
+    )
+    .unwrap();
+
+    let manifest = Manifest::from_file(&path).unwrap();
+    let client = McpTestClient::new(manifest.build().await.unwrap());
+
+    let init = client.initialize().await.unwrap();
+    assert_eq!(init["serverInfo"]["name"], "docs-server");
+    assert_eq!(init["serverInfo"]["version"], "2.0.0");
+
+    let result = client.call_tool("echo-json", json!({ "x": 1 })).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), r#"{"x":1}"#);
+
+    let read = client.read_resource("file:///docs/intro.txt").await.unwrap();
+    assert_eq!(read.contents[0].text.as_deref(), Some("Hello from the docs"));
+    let read = client.read_resource("docs://notes").await.unwrap();
+    assert_eq!(read.contents[0].text.as_deref(), Some("# Notes"));
+    assert_eq!(read.contents[0].mime_type, "text/markdown");
+    let read = client.read_resource("docs://motd").await.unwrap();
+    assert_eq!(read.contents[0].text.as_deref(), Some("Welcome"));
+
+    let prompt = client
+        .get_prompt("summarize", json!({ "topic": "installation" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        prompt.messages[0].content.as_text().unwrap(),
+        "Summarize what the docs say about installation."
+    );
+    assert!(client.get_prompt("summarize", json!({})).await.is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn toml_manifests_parse() {
+    let manifest = Manifest::from_toml(
+        r#"
+[server]
+name = "toml-server"
+version = "0.1.0"
+
+[transports]
+stdio = false
+http = "127.0.0.1:8080"
+
+[[tools]]
+name = "date"
+command = "date"
+"#,
+    )
+    .unwrap();
+    assert!(!manifest.transports.stdio);
+    assert_eq!(manifest.transports.http, Some("127.0.0.1:8080".parse().unwrap()));
+    assert_eq!(manifest.tools[0].name, "date");
+}
+
+#[test]
+fn unknown_manifest_fields_are_rejected() {
+    let error = Manifest::from_yaml("server: { name: a, version: b }\ntoolz: []\n")
+        .err()
+        .unwrap();
+    assert!(error.to_string().contains("toolz"));
+}
+
+#[test]
+fn validation_reports_every_problem() {
+    let dir = manifest_dir();
+    let mut manifest = Manifest::from_yaml(
+        r#"
+server:
+  name: broken
+  version: 0.1.0
+transports:
+  stdio: false
+roots:
+  - uriPrefix: file:///missing/
+    path: ./missing
+tools:
+  - name: twice
+    command: cat
+  - name: twice
+    command: ./bin/nowhere.sh
+prompts:
+  - name: summarize
+    arguments: [{ name: topic, description: The topic }]
+    template: Summarize {{topic}} for {{audience}}.
+resources:
+  - uri: docs://notes
+    path: ./NOTES.md
+  - uri: docs://gone
+    path: ./GONE.md
+"#,
+    )
+    .unwrap();
+    manifest.base_dir = dir.clone();
+
+    let problems = manifest.validate();
+    assert_eq!(problems.len(), 6, "{:#?}", problems);
+    assert!(problems.contains(&"No transport is enabled".to_string()));
+    assert!(problems.iter().any(|p| p.starts_with("Root file:///missing/")));
+    assert!(problems.iter().any(|p| p.starts_with("Command of tool twice")));
+    assert!(problems.contains(&"Duplicate tool name: twice".to_string()));
+    assert!(problems.contains(&"Prompt summarize uses undeclared argument audience".to_string()));
+    assert!(problems.iter().any(|p| p.starts_with("File of resource docs://gone")));
+
+    manifest.transports.stdio = true;
+    manifest.roots[0].path = "./docs".into();
+    manifest.tools.truncate(1);
+    manifest.prompts[0].template = "Summarize {{topic}}.".to_string();
+    manifest.resources.truncate(1);
+    assert!(manifest.validate().is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
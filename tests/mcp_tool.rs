@@ -0,0 +1,81 @@
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, ToolHandler};
+use mcp_server_rust::{Error, McpServer, Result, ServerConfig, mcp_tool};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Counts the words in a text
+#[mcp_tool]
+async fn word_count(
+    #[schemars(description = "The text to count")] text: String,
+    #[serde(default)] distinct: bool,
+) -> Result<String> {
+    let mut words: Vec<&str> = text.split_whitespace().collect();
+    if distinct {
+        words.sort();
+        words.dedup();
+    }
+    Ok(words.len().to_string())
+}
+
+#[mcp_tool(name = "divide", description = "Divides a by b")]
+fn quotient(a: f64, b: f64) -> Result<Value> {
+    if b == 0.0 {
+        return Err(Error::InvalidParams("b must not be zero".to_string()));
+    }
+    Ok(json!({ "quotient": a / b }))
+}
+
+/// Reports that the server is up
+#[mcp_tool]
+async fn health() -> CallToolResult {
+    CallToolResult::error("degraded")
+}
+
+#[test]
+fn definitions_come_from_the_signature() {
+    let definition = WordCountTool.definition().unwrap();
+    assert_eq!(definition.name, "word-count");
+    assert_eq!(definition.description, "Counts the words in a text");
+    let schema = definition.input_schema;
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["properties"]["text"]["type"], "string");
+    assert_eq!(schema["properties"]["text"]["description"], "The text to count");
+    assert_eq!(schema["properties"]["distinct"]["type"], "boolean");
+    assert_eq!(schema["required"], json!(["text"]));
+    assert!(schema.get("title").is_none());
+
+    let definition = QuotientTool.definition().unwrap();
+    assert_eq!(definition.name, "divide");
+    assert_eq!(definition.description, "Divides a by b");
+    assert_eq!(HealthTool.definition().unwrap().input_schema["type"], "object");
+}
+
+#[tokio::test]
+async fn calls_are_deserialized_into_the_parameters() {
+    let server = McpServer::new(ServerConfig::new("Typed", "0.1.0"));
+    server.register_tool("word-count".to_string(), Arc::new(WordCountTool)).await.unwrap();
+    server.register_tool("divide".to_string(), Arc::new(QuotientTool)).await.unwrap();
+    server.register_tool("health".to_string(), Arc::new(HealthTool)).await.unwrap();
+    let client = McpTestClient::new(server);
+
+    let listed = client.list_tools().await.unwrap();
+    let listed = listed.iter().find(|t| t.name == "word-count").unwrap();
+    assert_eq!(listed.input_schema["properties"]["text"]["type"], "string");
+
+    let result = client.call_tool("word-count", json!({ "text": "a b a" })).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "3");
+    let result = client.call_tool("word-count", json!({ "text": "a b a", "distinct": true })).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "2");
+
+    let result = client.call_tool("divide", json!({ "a": 1.0, "b": 4.0 })).await.unwrap();
+    assert_eq!(result.structured_content, Some(json!({ "quotient": 0.25 })));
+    assert!(client.call_tool("divide", json!({ "a": 1.0, "b": 0.0 })).await.is_err());
+
+    let result = client.call_tool("health", json!({})).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+
+    // Rejected against the generated schema before the call
+    let result = client.call_tool("word-count", json!({ "text": 7 })).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+}
@@ -0,0 +1,91 @@
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::utils::memory::approx_size;
+use mcp_server_rust::utils::{Error, MemoryGuard, RequestLimits};
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::json;
+use std::sync::Arc;
+
+#[test]
+fn reservations_are_released_when_dropped() {
+    let guard = Arc::new(MemoryGuard::new(1000));
+    let first = guard.reserve(300);
+    let second = guard.try_reserve(200).unwrap();
+    assert_eq!((first.bytes(), second.bytes()), (300, 200));
+    assert_eq!(guard.used(), 500);
+
+    drop(first);
+    assert_eq!(guard.used(), 200);
+    drop(second);
+    assert_eq!(guard.used(), 0);
+}
+
+#[test]
+fn the_ceiling_refuses_only_what_would_cross_it() {
+    let guard = Arc::new(MemoryGuard::new(1000));
+    let held = guard.try_reserve(1000).unwrap();
+    assert!(guard.under_pressure());
+
+    match guard.try_reserve(1).unwrap_err() {
+        Error::Application { code, data, .. } => {
+            assert_eq!(code, -32001);
+            assert_eq!(data, Some(json!({ "usedBytes": 1000, "requestedBytes": 1, "ceilingBytes": 1000 })));
+        }
+        other => panic!("expected a memory pressure error, got {}", other),
+    }
+    // A refused reservation holds nothing
+    assert_eq!(guard.used(), 1000);
+
+    // Plain reservations are counted past the ceiling
+    let over = guard.reserve(500);
+    assert_eq!(guard.used(), 1500);
+    drop((held, over));
+    assert!(!guard.under_pressure());
+    assert!(guard.try_reserve(1000).is_ok());
+}
+
+#[test]
+fn without_a_ceiling_nothing_is_refused() {
+    let guard = Arc::new(MemoryGuard::new(0));
+    let _held = guard.try_reserve(u64::MAX / 2).unwrap();
+    assert!(guard.try_reserve(u64::MAX / 4).is_ok());
+    assert!(!guard.under_pressure());
+}
+
+#[test]
+fn sizes_are_estimated_close_to_the_serialized_length() {
+    assert_eq!(approx_size(&json!("abc")), 5);
+    assert_eq!(approx_size(&json!([])), 2);
+    let value = json!({
+        "name": "report",
+        "tags": ["a", "bb", "ccc"],
+        "nested": { "count": 12, "ok": true, "none": null },
+        "body": "x".repeat(1000)
+    });
+    let estimate = approx_size(&value) as f64;
+    let actual = serde_json::to_string(&value).unwrap().len() as f64;
+    assert!((estimate / actual - 1.0).abs() < 0.1, "{} vs {}", estimate, actual);
+}
+
+#[tokio::test]
+async fn large_work_is_shed_under_pressure() {
+    let limits = RequestLimits {
+        max_memory_bytes: 100_000,
+        ..RequestLimits::default()
+    };
+    let server = McpServer::new(ServerConfig::new("Memory", "0.1.0").with_limits(limits));
+    let guard = server.memory_guard();
+    let client = McpTestClient::new(server);
+    client.initialize().await.unwrap();
+
+    let held = guard.reserve(100_000);
+    let shed = client.call_tool("greet", json!({ "name": "Ada" })).await.unwrap_err();
+    assert!(matches!(shed, Error::Rpc { code: -32001, .. }), "{}", shed);
+    // Cheap requests still get through
+    client.ping().await.unwrap();
+    assert!(!client.list_tools().await.unwrap().is_empty());
+
+    drop(held);
+    client.call_tool("greet", json!({ "name": "Ada" })).await.unwrap();
+    // Requests give back what they held once answered
+    assert_eq!(guard.used(), 0);
+}
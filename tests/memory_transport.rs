@@ -0,0 +1,49 @@
+use mcp_server_rust::transport::{MemoryTransport, Transport};
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::json;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn a_pair_drives_the_server_end_to_end() {
+    let server = Arc::new(McpServer::new(ServerConfig::new("Memory", "0.1.0")));
+    let (mut client, mut server_end) = MemoryTransport::pair();
+    let serving = tokio::spawn({
+        let server = server.clone();
+        async move { server_end.serve(&server).await }
+    });
+
+    client
+        .send(json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {
+            "protocolVersion": "2025-06-18", "capabilities": {}, "clientInfo": { "name": "t", "version": "1" }
+        } }))
+        .await
+        .unwrap();
+    let initialized = client.receive().await.unwrap().unwrap();
+    assert_eq!(initialized["result"]["serverInfo"]["name"], "Memory");
+    client.send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).await.unwrap();
+
+    client
+        .send(json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {
+            "name": "greet", "arguments": { "name": "Ada" }
+        } }))
+        .await
+        .unwrap();
+    let called = client.receive().await.unwrap().unwrap();
+    assert_eq!(called["id"], 2);
+    assert_eq!(called["result"]["content"][0]["text"], "Hello, Ada! Welcome to MCP.");
+
+    server.notify("notifications/message", json!({ "level": "info", "data": "hi" }));
+    let notification = client.receive().await.unwrap().unwrap();
+    assert_eq!(notification["method"], "notifications/message");
+
+    client.close().await.unwrap();
+    serving.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn closing_one_end_ends_the_other() {
+    let (mut client, mut server) = MemoryTransport::pair();
+    server.close().await.unwrap();
+    assert!(client.receive().await.unwrap().is_none());
+    assert!(client.send(json!({})).await.is_err());
+}
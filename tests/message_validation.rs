@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::spec;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+fn validating_client() -> McpTestClient {
+    McpTestClient::new(McpServer::new(
+        ServerConfig::new("Validated", "0.1.0").with_message_validation(true),
+    ))
+}
+
+#[tokio::test]
+async fn builtin_responses_conform() {
+    let client = validating_client();
+    client.initialize().await.unwrap();
+    client.ping().await.unwrap();
+    client.list_tools().await.unwrap();
+    client.call_tool("greet", json!({ "name": "Ada" })).await.unwrap();
+    client.call_tool("calculate-bmi", json!({ "weightKg": 70, "heightM": 1.75 })).await.unwrap();
+    client.list_resources().await.unwrap();
+    client.read_resource("config://app").await.unwrap();
+    client.list_prompts().await.unwrap();
+    client
+        .get_prompt("review-code", json!({ "code": "fn main() {}" }))
+        .await
+        .unwrap();
+    // Error responses are checked too
+    client.call_tool("no-such-tool", json!({})).await.unwrap_err();
+}
+
+/// Advertises an input schema that isn't an object schema
+struct Misdeclared;
+
+#[async_trait]
+impl ToolHandler for Misdeclared {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![TextContent::new("ok")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "misdeclared".to_string(),
+            description: "Takes a bare string".to_string(),
+            input_schema: json!({ "type": "string" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+#[tokio::test]
+#[should_panic(expected = "tools/list response violates the MCP schema")]
+async fn violations_fail_loudly_in_debug_builds() {
+    let server = McpServer::new(ServerConfig::new("Validated", "0.1.0").with_message_validation(true));
+    server.register_tool("misdeclared".to_string(), Arc::new(Misdeclared)).await.unwrap();
+    McpTestClient::new(server).list_tools().await.unwrap();
+}
+
+#[test]
+fn checks_follow_the_negotiated_revision() {
+    let response = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": { "content": [{ "type": "audio", "data": "AAAA", "mimeType": "audio/wav" }] }
+    });
+    assert!(spec::check_response("2025-03-26", "tools/call", &response).is_empty());
+    assert!(!spec::check_response("2024-11-05", "tools/call", &response).is_empty());
+}
+
+#[test]
+fn notifications_are_checked_by_method() {
+    let progress = json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/progress",
+        "params": { "progressToken": "t", "progress": 1, "total": 2 }
+    });
+    assert!(spec::check_notification("2025-06-18", &progress).is_empty());
+
+    let missing_progress = json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/progress",
+        "params": { "progressToken": "t" }
+    });
+    let violations = spec::check_notification("2025-06-18", &missing_progress);
+    assert!(violations[0].contains("ProgressNotificationParams"), "{:?}", violations);
+}
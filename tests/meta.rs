@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::pool::ToolPoolConfig;
+use mcp_server_rust::tools::{CallToolResult, TextContent, ToolHandler};
+use mcp_server_rust::utils::meta;
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Reports the progress token it was called with and echoes a vendor key
+/// back in the result's `_meta`
+struct Traced;
+
+#[async_trait]
+impl ToolHandler for Traced {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let Some(request_meta) = meta::current() else {
+            return Ok(CallToolResult::success(vec![TextContent::new("no meta")]));
+        };
+        let token = request_meta.progress_token.clone().unwrap_or_default();
+        let trace = request_meta.get("com.example/trace").cloned().unwrap_or_default();
+        Ok(CallToolResult::success(vec![TextContent::new(token.to_string())])
+            .with_meta(json!({ "com.example/trace": trace })))
+    }
+}
+
+async fn client(config: ServerConfig) -> McpTestClient {
+    let server = McpServer::new(config);
+    server.register_tool("traced".to_string(), Arc::new(Traced)).await.unwrap();
+    McpTestClient::new(server)
+}
+
+fn traced_call() -> Value {
+    json!({
+        "name": "traced",
+        "arguments": {},
+        "_meta": { "progressToken": "tok-1", "com.example/trace": "abc" }
+    })
+}
+
+#[tokio::test]
+async fn request_meta_reaches_handlers_and_result_meta_reaches_clients() {
+    let client = client(ServerConfig::new("Meta", "0.1.0")).await;
+    let result = client.request("tools/call", traced_call()).await.unwrap();
+    assert_eq!(result["content"][0]["text"], "\"tok-1\"");
+    assert_eq!(result["_meta"], json!({ "com.example/trace": "abc" }));
+
+    let plain = client.call_tool("traced", json!({})).await.unwrap();
+    assert_eq!(plain.content[0].as_text().unwrap(), "no meta");
+    assert!(plain.meta.is_none());
+}
+
+#[tokio::test]
+async fn request_meta_reaches_pooled_tool_calls() {
+    let config = ServerConfig::new("Meta", "0.1.0").with_tool_pool(ToolPoolConfig::new(2));
+    let client = client(config).await;
+    let result = client.request("tools/call", traced_call()).await.unwrap();
+    assert_eq!(result["_meta"]["com.example/trace"], "abc");
+}
+
+#[test]
+fn meta_that_is_not_an_object_is_ignored() {
+    use mcp_server_rust::utils::meta::RequestMeta;
+    assert!(RequestMeta::from_params(Some(&json!({ "_meta": 5 }))).is_none());
+    let params = json!({ "_meta": { "progressToken": 7, "x": 1 } });
+    let parsed = RequestMeta::from_params(Some(&params)).unwrap();
+    assert_eq!(parsed.progress_token, Some(json!(7)));
+    assert_eq!(parsed.get("x"), Some(&json!(1)));
+}
@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use mcp_server_rust::builder::McpServerBuilder;
+use mcp_server_rust::middleware::{LoggingMiddleware, Middleware, RequestInfo, TimingMiddleware};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{Error, McpServer, Result, ServerConfig};
+use serde_json::value::RawValue;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+
+type Events = Arc<Mutex<Vec<String>>>;
+
+/// Records the hooks it runs, and can refuse one method
+struct Recorder {
+    name: &'static str,
+    events: Events,
+    refuse: Option<&'static str>,
+}
+
+impl Recorder {
+    fn new(name: &'static str, events: &Events) -> Arc<Self> {
+        Arc::new(Self { name, events: events.clone(), refuse: None })
+    }
+
+    fn refusing(name: &'static str, events: &Events, method: &'static str) -> Arc<Self> {
+        Arc::new(Self { name, events: events.clone(), refuse: Some(method) })
+    }
+
+    fn record(&self, hook: &str, request: &RequestInfo) {
+        self.events.lock().unwrap().push(format!("{} {} {}", self.name, hook, request.method));
+    }
+}
+
+#[async_trait]
+impl Middleware for Recorder {
+    async fn before_request(&self, request: &RequestInfo, _params: &mut Option<Value>) -> Result<()> {
+        self.record("before", request);
+        if self.refuse == Some(request.method.as_str()) {
+            return Err(Error::InvalidRequest(format!("{} refused {}", self.name, request.method)));
+        }
+        Ok(())
+    }
+
+    async fn after_response(&self, request: &RequestInfo, _result: &RawValue) -> Result<()> {
+        self.record("after", request);
+        Ok(())
+    }
+
+    async fn on_error(&self, request: &RequestInfo, _error: &Error) {
+        self.record("error", request);
+    }
+}
+
+/// Greets everyone as Ada, whatever name was passed
+struct ForceName;
+
+#[async_trait]
+impl Middleware for ForceName {
+    async fn before_request(&self, request: &RequestInfo, params: &mut Option<Value>) -> Result<()> {
+        if request.method == "tools/call"
+            && let Some(params) = params
+        {
+            params["arguments"]["name"] = json!("Ada");
+        }
+        Ok(())
+    }
+}
+
+/// Withholds every prompt listing
+struct HidePrompts;
+
+#[async_trait]
+impl Middleware for HidePrompts {
+    async fn after_response(&self, request: &RequestInfo, _result: &RawValue) -> Result<()> {
+        match request.method.as_str() {
+            "prompts/list" => Err(Error::MethodNotFound(request.method.clone())),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn events(recorded: &Events) -> Vec<String> {
+    std::mem::take(&mut *recorded.lock().unwrap())
+}
+
+#[tokio::test]
+async fn middleware_wraps_every_method_in_order() {
+    let recorded = Events::default();
+    let server = McpServer::new(ServerConfig::new("Middleware", "0.1.0"))
+        .with_middleware(Recorder::new("outer", &recorded))
+        .with_middleware(Recorder::new("inner", &recorded));
+    let client = McpTestClient::new(server);
+
+    client.ping().await.unwrap();
+    assert_eq!(
+        events(&recorded),
+        ["outer before ping", "inner before ping", "inner after ping", "outer after ping"]
+    );
+
+    client.notify("notifications/initialized", json!({})).await.unwrap();
+    assert_eq!(events(&recorded).len(), 4);
+
+    assert!(client.request("no/such/method", json!({})).await.is_err());
+    assert_eq!(
+        events(&recorded),
+        [
+            "outer before no/such/method",
+            "inner before no/such/method",
+            "inner error no/such/method",
+            "outer error no/such/method"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn rejections_stop_the_request_on_the_way_in() {
+    let recorded = Events::default();
+    let server = McpServer::new(ServerConfig::new("Middleware", "0.1.0"))
+        .with_middleware(Recorder::new("outer", &recorded))
+        .with_middleware(Recorder::refusing("guard", &recorded, "tools/list"))
+        .with_middleware(Recorder::new("inner", &recorded));
+    let client = McpTestClient::new(server);
+
+    let error = client.list_tools().await.unwrap_err();
+    assert!(error.to_string().contains("guard refused tools/list"), "{}", error);
+    assert_eq!(
+        events(&recorded),
+        ["outer before tools/list", "guard before tools/list", "guard error tools/list", "outer error tools/list"]
+    );
+}
+
+#[tokio::test]
+async fn middleware_can_rewrite_params_and_replace_results() {
+    let server = McpServer::new(ServerConfig::new("Middleware", "0.1.0"))
+        .with_middleware(Arc::new(ForceName))
+        .with_middleware(Arc::new(HidePrompts));
+    let client = McpTestClient::new(server);
+
+    let greeting = client.call_tool("greet", json!({ "name": "Bob" })).await.unwrap();
+    assert_eq!(greeting.content[0].as_text().unwrap(), "Hello, Ada! Welcome to MCP.");
+    assert!(client.list_prompts().await.is_err());
+}
+
+#[tokio::test]
+async fn timing_counts_calls_and_errors_per_method() {
+    let timing = Arc::new(TimingMiddleware::new());
+    let server = McpServerBuilder::new("Middleware", "0.1.0")
+        .middleware(Arc::new(LoggingMiddleware::new()))
+        .middleware(timing.clone())
+        .build()
+        .await
+        .unwrap();
+    let client = McpTestClient::new(server);
+
+    client.ping().await.unwrap();
+    client.ping().await.unwrap();
+    assert!(client.call_tool("no-such-tool", json!({})).await.is_err());
+
+    let stats = timing.stats();
+    assert_eq!((stats["ping"].calls, stats["ping"].errors), (2, 0));
+    assert_eq!((stats["tools/call"].calls, stats["tools/call"].errors), (1, 1));
+    assert!(stats["ping"].max >= stats["ping"].mean());
+}
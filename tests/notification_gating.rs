@@ -0,0 +1,61 @@
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::TryRecvError;
+
+fn server() -> Arc<McpServer> {
+    Arc::new(McpServer::new(ServerConfig::new("Gating", "0.1.0")))
+}
+
+async fn initialize(server: &Arc<McpServer>, capabilities: serde_json::Value) {
+    let client = McpTestClient::from_arc(server.clone());
+    client
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": capabilities,
+                "clientInfo": { "name": "gating-test", "version": "0.1.0" }
+            }),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn list_changed_needs_the_advertised_capability() {
+    let server = server();
+    let mut notifications = server.subscribe_notifications();
+
+    // Nobody has initialized yet
+    server.enable_tools_list_changed();
+    server.notify("notifications/tools/list_changed", json!({}));
+    assert!(matches!(notifications.try_recv(), Err(TryRecvError::Empty)));
+
+    initialize(&server, json!({})).await;
+    server.notify("notifications/tools/list_changed", json!({}));
+    assert_eq!(
+        notifications.try_recv().unwrap()["method"],
+        "notifications/tools/list_changed"
+    );
+
+    // Prompts and resources don't advertise listChanged or subscribe
+    server.notify("notifications/prompts/list_changed", json!({}));
+    server.notify("notifications/resources/updated", json!({ "uri": "config://app" }));
+    assert!(matches!(notifications.try_recv(), Err(TryRecvError::Empty)));
+}
+
+#[tokio::test]
+async fn client_features_need_the_declared_capability() {
+    let server = server();
+    initialize(&server, json!({ "roots": { "listChanged": true } })).await;
+    assert!(server.may_send("roots/list"));
+    assert!(!server.may_send("sampling/createMessage"));
+    assert!(!server.may_send("elicitation/create"));
+    assert_eq!(server.client_capabilities(), Some(json!({ "roots": { "listChanged": true } })));
+
+    // Ungated notifications always go out
+    assert!(server.may_send("notifications/progress"));
+    assert!(server.may_send("notifications/message"));
+}
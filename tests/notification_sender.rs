@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::notifications::{self, NotificationSender};
+use mcp_server_rust::session;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Hands out its notification sender so the test can use it after the call
+struct Watch(Arc<Mutex<Option<NotificationSender>>>);
+
+#[async_trait]
+impl ToolHandler for Watch {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        *self.0.lock().unwrap() = notifications::current();
+        Ok(CallToolResult::success(vec![TextContent::new("watching")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "watch".to_string(),
+            description: "Watches a file".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+async fn server(capabilities: Value) -> (Arc<McpServer>, Arc<Mutex<Option<NotificationSender>>>) {
+    let config = ServerConfig::new("Notify", "0.1.0").with_capabilities(capabilities);
+    let server = Arc::new(McpServer::new(config));
+    let slot = Arc::new(Mutex::new(None));
+    server.register_tool("watch".to_string(), Arc::new(Watch(slot.clone()))).await.unwrap();
+    (server, slot)
+}
+
+async fn initialize(server: &Arc<McpServer>) {
+    McpTestClient::from_arc(server.clone())
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": "2025-06-18",
+                "capabilities": {},
+                "clientInfo": { "name": "notify-test", "version": "0.1.0" }
+            }),
+        )
+        .await
+        .unwrap();
+}
+
+async fn call_watch(server: &Arc<McpServer>) {
+    McpTestClient::from_arc(server.clone())
+        .request("tools/call", json!({ "name": "watch", "arguments": {} }))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn handlers_can_notify_after_the_request_completed() {
+    let (server, slot) = server(json!({ "resources": { "subscribe": true } })).await;
+    initialize(&server).await;
+    let mut outgoing = server.subscribe_notifications();
+    call_watch(&server).await;
+
+    let sender = slot.lock().unwrap().take().expect("the handler had a sender");
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(sender.resource_updated("file:///notes.txt"));
+    });
+    let notification = tokio::time::timeout(Duration::from_secs(1), outgoing.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(notification["method"], "notifications/resources/updated");
+    assert_eq!(notification["params"]["uri"], "file:///notes.txt");
+}
+
+#[tokio::test]
+async fn notifications_the_client_was_not_told_to_expect_are_dropped() {
+    let (server, slot) = server(json!({})).await;
+    initialize(&server).await;
+    let _outgoing = server.subscribe_notifications();
+    call_watch(&server).await;
+
+    let sender = slot.lock().unwrap().take().unwrap();
+    assert!(!sender.resource_updated("file:///notes.txt"));
+    assert!(sender.notify("notifications/message", json!({ "level": "info", "data": "hi" })));
+}
+
+#[tokio::test]
+async fn session_senders_use_the_session_channel() {
+    let (server, slot) = server(json!({})).await;
+    initialize(&server).await;
+    let mut channel = server.open_session_channel("s1");
+    // The test holds the session's channel, so the call goes to the server
+    // directly rather than through a client connected to the session
+    let call = json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": { "name": "watch", "arguments": {} } });
+    session::with_session(Some("s1".to_string()), server.handle_request(call)).await.unwrap();
+
+    let sender = slot.lock().unwrap().take().unwrap();
+    assert_eq!(sender.session(), Some("s1"));
+    assert!(sender.notify("notifications/message", json!({ "level": "info", "data": "hi" })));
+    assert_eq!(channel.recv().await.unwrap()["method"], "notifications/message");
+
+    server.end_session("s1").await.unwrap();
+    assert!(!sender.notify("notifications/message", json!({ "level": "info", "data": "bye" })));
+}
+
+#[tokio::test]
+async fn there_is_no_sender_outside_requests() {
+    assert!(notifications::current().is_none());
+}
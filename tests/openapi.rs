@@ -0,0 +1,80 @@
+use axum::extract::{Path, Query};
+use axum::http::HeaderMap;
+use axum::routing::get;
+use axum::{Json, Router};
+use mcp_server_rust::openapi::{AuthConfig, OpenApiSpec, Secret};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const SPEC: &str = r##"
+openapi: 3.0.0
+info: { title: Pets, version: "1.0" }
+paths:
+  /pets/{petId}:
+    parameters:
+      - $ref: "#/components/parameters/PetId"
+    get:
+      operationId: getPet
+      summary: Fetch a pet
+      parameters:
+        - { name: verbose, in: query, schema: { type: boolean } }
+components:
+  parameters:
+    PetId: { name: petId, in: path, required: true, schema: { type: string } }
+"##;
+
+async fn get_pet(
+    Path(id): Path<String>,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Json<Value> {
+    Json(json!({
+        "id": id,
+        "verbose": query.get("verbose"),
+        "auth": headers.get("authorization").and_then(|v| v.to_str().ok())
+    }))
+}
+
+#[tokio::test]
+async fn operations_become_callable_tools() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let api = Router::new().route("/pets/{id}", get(get_pet));
+    tokio::spawn(async move { axum::serve(listener, api).await });
+
+    let spec = OpenApiSpec::parse(SPEC).unwrap();
+    let auth = AuthConfig::Bearer {
+        token: Secret::Literal("s3cret".to_string()),
+    };
+    let server = McpServer::new(ServerConfig::new("Bridge", "0.1.0"));
+    for tool in spec.tools(&base_url, auth).unwrap() {
+        server
+            .register_tool(tool.name().to_string(), Arc::new(tool))
+            .await
+            .unwrap();
+    }
+
+    let client = McpTestClient::new(server);
+    client.initialize().await.unwrap();
+    let tool = client
+        .list_tools()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|t| t.name == "getPet")
+        .unwrap();
+    assert_eq!(tool.input_schema["required"], json!(["petId"]));
+
+    let result = client
+        .call_tool("getPet", json!({ "petId": "a b", "verbose": true }))
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_str(result.content[0].as_text().unwrap()).unwrap();
+    assert_eq!(
+        body,
+        json!({ "id": "a b", "verbose": "true", "auth": "Bearer s3cret" })
+    );
+}
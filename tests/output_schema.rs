@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::schema::OutputValidation;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Returns whatever structured content it is asked to
+struct Echo;
+
+#[async_trait]
+impl ToolHandler for Echo {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        let result = CallToolResult::success(vec![TextContent::new("echo")]);
+        Ok(match arguments.get("structured") {
+            Some(structured) => result.with_structured_content(structured.clone()),
+            None => result,
+        })
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "echo".to_string(),
+            description: "Echoes structured content".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: Some(json!({
+                "type": "object",
+                "properties": { "temperature": { "type": "number" } },
+                "required": ["temperature"]
+            })),
+        })
+    }
+}
+
+async fn client(validation: OutputValidation) -> McpTestClient {
+    let config = ServerConfig::new("Output", "0.1.0").with_output_validation(validation);
+    let server = McpServer::new(config);
+    server.register_tool("echo".to_string(), Arc::new(Echo)).await.unwrap();
+    McpTestClient::new(server)
+}
+
+#[tokio::test]
+async fn conforming_results_pass_through() {
+    let client = client(OutputValidation::Enforce).await;
+    let result = client
+        .call_tool("echo", json!({ "structured": { "temperature": 21.5 } }))
+        .await
+        .unwrap();
+    assert_eq!(result.structured_content, Some(json!({ "temperature": 21.5 })));
+
+    let tools = client.list_tools().await.unwrap();
+    let echo = tools.iter().find(|t| t.name == "echo").unwrap();
+    assert!(echo.output_schema.is_some());
+}
+
+#[tokio::test]
+async fn mismatches_become_internal_errors() {
+    let client = client(OutputValidation::Enforce).await;
+    let wrong = json!({ "structured": { "temperature": "warm" } });
+    let error = client.call_tool("echo", wrong).await.unwrap_err().to_string();
+    assert!(error.contains("outputSchema"), "{}", error);
+    assert!(error.contains("/temperature"), "{}", error);
+
+    let missing = client.call_tool("echo", json!({})).await.unwrap_err().to_string();
+    assert!(missing.contains("structuredContent is missing"), "{}", missing);
+}
+
+#[tokio::test]
+async fn warn_only_sends_the_result_anyway() {
+    let client = client(OutputValidation::Warn).await;
+    let result = client
+        .call_tool("echo", json!({ "structured": { "temperature": "warm" } }))
+        .await
+        .unwrap();
+    assert_eq!(result.structured_content, Some(json!({ "temperature": "warm" })));
+}
+
+#[tokio::test]
+async fn weather_is_returned_as_structured_content() {
+    let client = client(OutputValidation::Enforce).await;
+    let tools = client.list_tools().await.unwrap();
+    let weather = tools.iter().find(|t| t.name == "fetch-weather").unwrap();
+    assert_eq!(weather.output_schema.as_ref().unwrap()["required"][0], "city");
+
+    let result = client.call_tool("fetch-weather", json!({ "city": "Oslo" })).await.unwrap();
+    let structured = result.structured_content.unwrap();
+    assert_eq!(structured["city"], "Oslo");
+    assert!(structured["temperature"].is_string());
+    assert!(result.content[0].as_text().unwrap().starts_with("Weather for Oslo"));
+}
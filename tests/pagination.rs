@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::utils::pagination::{decode_cursor, encode_cursor};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+struct Numbered(usize);
+
+#[async_trait]
+impl ToolHandler for Numbered {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![TextContent::new("ok")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: format!("tool-{:02}", self.0),
+            description: "A numbered tool".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+async fn client(config: ServerConfig) -> McpTestClient {
+    let server = McpServer::new(config);
+    for n in 0..10 {
+        server.register_tool(format!("tool-{:02}", n), Arc::new(Numbered(n))).await.unwrap();
+    }
+    McpTestClient::from_arc(Arc::new(server))
+}
+
+fn names(result: &Value) -> Vec<String> {
+    result["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn pages_follow_next_cursor_to_the_end() {
+    let client = client(ServerConfig::new("Paged", "0.1.0").with_page_size(5)).await;
+
+    let mut seen = Vec::new();
+    let mut params = json!({});
+    let mut pages = 0;
+    loop {
+        let result = client.request("tools/list", params).await.unwrap();
+        pages += 1;
+        assert!(names(&result).len() <= 5);
+        seen.extend(names(&result));
+        match result.get("nextCursor").and_then(Value::as_str) {
+            Some(cursor) => params = json!({ "cursor": cursor }),
+            None => break,
+        }
+    }
+
+    // The three built-in tools plus the ten numbered ones
+    assert_eq!(pages, 3);
+    assert_eq!(seen.len(), 13);
+    let mut sorted = seen.clone();
+    sorted.sort();
+    assert_eq!(seen, sorted);
+}
+
+#[tokio::test]
+async fn without_a_page_size_everything_is_on_one_page() {
+    let client = client(ServerConfig::new("Unpaged", "0.1.0")).await;
+    let result = client.request("tools/list", json!({})).await.unwrap();
+    assert_eq!(names(&result).len(), 13);
+    assert!(result.get("nextCursor").is_none());
+}
+
+#[tokio::test]
+async fn invalid_cursors_are_rejected() {
+    let client = client(ServerConfig::new("Paged", "0.1.0").with_page_size(5)).await;
+    for method in ["tools/list", "resources/list", "prompts/list"] {
+        let error = client
+            .request(method, json!({ "cursor": "not-a-valid-cursor" }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), -32602, "{}", method);
+    }
+}
+
+#[tokio::test]
+async fn a_cursor_past_the_end_gives_an_empty_page() {
+    let client = client(ServerConfig::new("Paged", "0.1.0").with_page_size(5)).await;
+    let result = client
+        .request("prompts/list", json!({ "cursor": encode_cursor(100) }))
+        .await
+        .unwrap();
+    assert_eq!(result["prompts"], json!([]));
+    assert!(result.get("nextCursor").is_none());
+}
+
+#[test]
+fn cursors_round_trip() {
+    assert_eq!(decode_cursor(&encode_cursor(42)).unwrap(), 42);
+    assert!(decode_cursor("42").is_err());
+}
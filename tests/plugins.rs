@@ -0,0 +1,100 @@
+#![cfg(unix)]
+
+use mcp_server_rust::plugins::PluginWatcher;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::json;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+fn plugin_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mcp-plugins-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_plugin(dir: &Path, name: &str, description: &str, script: &str) {
+    let command = dir.join(format!("{}.sh", name));
+    std::fs::write(&command, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    std::fs::set_permissions(&command, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(
+        dir.join(format!("{}.json", name)),
+        json!({
+            "name": name,
+            "description": description,
+            "command": format!("./{}.sh", name)
+        })
+        .to_string(),
+    )
+    .unwrap();
+}
+
+async fn tool_description(client: &McpTestClient, name: &str) -> Option<String> {
+    client
+        .list_tools()
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|t| t.name == name)
+        .map(|t| t.description)
+}
+
+#[tokio::test]
+async fn plugins_are_loaded_reloaded_and_removed() {
+    let dir = plugin_dir();
+    write_plugin(&dir, "echo-args", "Echo the arguments", "cat");
+
+    let server = Arc::new(McpServer::new(ServerConfig::new("Plugins", "0.1.0")));
+    server.enable_tools_list_changed();
+    let client = McpTestClient::from_arc(server.clone());
+    client.initialize().await.unwrap();
+    let mut notifications = server.subscribe_notifications();
+    let mut watcher = PluginWatcher::new(&dir);
+    assert!(watcher.reload(&server).await.unwrap());
+    assert_eq!(
+        notifications.recv().await.unwrap()["method"],
+        "notifications/tools/list_changed"
+    );
+
+    assert_eq!(
+        tool_description(&client, "echo-args").await.as_deref(),
+        Some("Echo the arguments")
+    );
+    let result = client.call_tool("echo-args", json!({ "a": 1 })).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), r#"{"a":1}"#);
+
+    // Nothing changed on disk
+    assert!(!watcher.reload(&server).await.unwrap());
+
+    write_plugin(&dir, "echo-args", "Echo the arguments back verbatim", "echo changed");
+    assert!(watcher.reload(&server).await.unwrap());
+    assert_eq!(
+        tool_description(&client, "echo-args").await.as_deref(),
+        Some("Echo the arguments back verbatim")
+    );
+    let result = client.call_tool("echo-args", json!({})).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "changed\n");
+
+    std::fs::remove_file(dir.join("echo-args.json")).unwrap();
+    assert!(watcher.reload(&server).await.unwrap());
+    assert_eq!(tool_description(&client, "echo-args").await, None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn failing_scripts_return_error_results() {
+    let dir = plugin_dir();
+    write_plugin(&dir, "broken", "Always fails", "echo boom >&2; exit 3");
+
+    let server = Arc::new(McpServer::new(ServerConfig::new("Plugins", "0.1.0")));
+    PluginWatcher::new(&dir).reload(&server).await.unwrap();
+
+    let client = McpTestClient::from_arc(server);
+    let result = client.call_tool("broken", json!({})).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert!(result.content[0].as_text().unwrap().contains("boom"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
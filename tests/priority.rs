@@ -0,0 +1,136 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::utils::{Priority, RequestLimits};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+struct Slow;
+
+/// Never finishes unless cancelled
+struct Stuck;
+
+#[async_trait]
+impl ToolHandler for Stuck {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        futures::future::pending().await
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "stuck".to_string(),
+            description: "Never finishes".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+#[async_trait]
+impl ToolHandler for Slow {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        Ok(CallToolResult::success(vec![TextContent::new("done")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "slow".to_string(),
+            description: "Takes a while".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+#[test]
+fn control_plane_methods_have_priority() {
+    for method in ["ping", "initialize", "notifications/cancelled", "tools/list", "prompts/list"] {
+        assert_eq!(Priority::of(method), Priority::Control, "{}", method);
+    }
+    for method in ["tools/call", "resources/read", "prompts/get"] {
+        assert_eq!(Priority::of(method), Priority::Normal, "{}", method);
+    }
+}
+
+#[tokio::test]
+async fn listings_overtake_queued_tool_calls() {
+    let limits = RequestLimits {
+        max_in_flight: 1,
+        ..RequestLimits::default()
+    };
+    let server = Arc::new(McpServer::new(
+        ServerConfig::new("Priority", "0.1.0").with_limits(limits),
+    ));
+    server.register_tool("slow".to_string(), Arc::new(Slow)).await.unwrap();
+
+    let finished = Arc::new(Mutex::new(Vec::new()));
+    let mut tasks = Vec::new();
+    for (id, method, params) in [
+        (1, "tools/call", json!({ "name": "slow" })),
+        (2, "tools/call", json!({ "name": "slow" })),
+        (3, "tools/call", json!({ "name": "slow" })),
+        (4, "tools/list", json!({})),
+    ] {
+        let server = server.clone();
+        let finished = finished.clone();
+        tasks.push(tokio::spawn(async move {
+            server
+                .handle_request(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+                .await
+                .unwrap();
+            finished.lock().unwrap().push(id);
+        }));
+        // Let each request reach the limiter before the next one
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert_eq!(*finished.lock().unwrap(), vec![1, 4, 2, 3]);
+}
+
+#[tokio::test]
+async fn pings_and_cancellations_need_no_free_slot() {
+    let limits = RequestLimits {
+        max_in_flight: 1,
+        ..RequestLimits::default()
+    };
+    let server = Arc::new(McpServer::new(
+        ServerConfig::new("Priority", "0.1.0").with_limits(limits),
+    ));
+    server.register_tool("stuck".to_string(), Arc::new(Stuck)).await.unwrap();
+    let stuck = tokio::spawn({
+        let server = server.clone();
+        async move {
+            let call = json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": { "name": "stuck" } });
+            server.handle_request(call).await.unwrap()
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    // The only slot is held, yet both are handled at once
+    let within = |request: Value| {
+        let server = server.clone();
+        async move {
+            tokio::time::timeout(Duration::from_secs(2), server.handle_request(request))
+                .await
+                .expect("waited for a slot")
+                .unwrap()
+        }
+    };
+    let pong = within(json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" })).await.unwrap();
+    assert!(pong["result"].is_object());
+    let cancel = json!({ "jsonrpc": "2.0", "method": "notifications/cancelled", "params": { "requestId": 1 } });
+    assert!(within(cancel).await.is_none());
+
+    // Cancelling the stuck call gave its slot back
+    assert!(stuck.await.unwrap().is_none());
+    let listed = within(json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/list" })).await.unwrap();
+    assert!(listed["result"]["tools"].is_array());
+}
@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::progress::ProgressReporter;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Indexes three files, reporting after each
+struct Index;
+
+#[async_trait]
+impl ToolHandler for Index {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let reported = match ProgressReporter::current() {
+            Some(progress) => {
+                for done in 1..=3 {
+                    progress.report(done as f64, Some(3.0), Some(&format!("Indexed {} of 3", done)));
+                }
+                // Progress never goes backwards
+                !progress.report(2.0, Some(3.0), None)
+            }
+            None => false,
+        };
+        Ok(CallToolResult::success(vec![TextContent::new(reported.to_string())]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "index".to_string(),
+            description: "Indexes files".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+async fn client() -> McpTestClient {
+    let server = McpServer::new(ServerConfig::new("Progress", "0.1.0"));
+    server.register_tool("index".to_string(), Arc::new(Index)).await.unwrap();
+    McpTestClient::new(server)
+}
+
+#[tokio::test]
+async fn requests_with_a_progress_token_get_progress_notifications() {
+    let client = client().await;
+    let mut notifications = client.server().subscribe_notifications();
+    let result = client
+        .request(
+            "tools/call",
+            json!({ "name": "index", "arguments": {}, "_meta": { "progressToken": "idx-1" } }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result["content"][0]["text"], "true");
+
+    for done in 1..=3 {
+        let notification = notifications.try_recv().unwrap();
+        assert_eq!(notification["method"], "notifications/progress");
+        assert_eq!(notification["params"]["progressToken"], "idx-1");
+        assert_eq!(notification["params"]["progress"], done as f64);
+        assert_eq!(notification["params"]["total"], 3.0);
+        assert_eq!(notification["params"]["message"], format!("Indexed {} of 3", done));
+    }
+    assert!(notifications.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn requests_without_a_token_get_no_reporter() {
+    let client = client().await;
+    let mut notifications = client.server().subscribe_notifications();
+    let result = client.call_tool("index", json!({})).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "false");
+    assert!(notifications.try_recv().is_err());
+    assert!(ProgressReporter::current().is_none());
+}
@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::prompts::{Content, GetPromptResult, Message, Prompt, PromptHandler};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{Error, McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+struct Standup {
+    listed: bool,
+}
+
+#[async_trait]
+impl PromptHandler for Standup {
+    async fn get(&self, _context: &RequestContext, _arguments: Option<Value>) -> Result<GetPromptResult> {
+        Ok(GetPromptResult {
+            description: None,
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: Content::text("What did you do yesterday?"),
+            }],
+            meta: None,
+        })
+    }
+
+    fn definition(&self) -> Option<Prompt> {
+        self.listed.then(|| Prompt {
+            name: "standup".to_string(),
+            description: "Daily standup questions".to_string(),
+            arguments: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn prompts_are_listed_and_resolved_from_the_registry() {
+    let server = McpServer::new(ServerConfig::new("Prompts", "0.1.0"));
+    server.register_prompt("standup".to_string(), Arc::new(Standup { listed: true })).await.unwrap();
+    server.register_prompt("hidden".to_string(), Arc::new(Standup { listed: false })).await.unwrap();
+    let client = McpTestClient::new(server);
+
+    let names: Vec<String> = client.list_prompts().await.unwrap().into_iter().map(|p| p.name).collect();
+    assert_eq!(names, vec!["review-code", "standup"]);
+
+    let standup = client.get_prompt("standup", json!({})).await.unwrap();
+    assert_eq!(standup.messages[0].content.as_text().unwrap(), "What did you do yesterday?");
+    // Unlisted prompts still resolve by name
+    assert!(client.get_prompt("hidden", json!({})).await.is_ok());
+    assert!(client.get_prompt("review-code", json!({ "code": "x" })).await.is_ok());
+
+    match client.get_prompt("retro", json!({})).await.unwrap_err() {
+        Error::Rpc { code, .. } => assert_eq!(code, -32601),
+        other => panic!("expected a JSON-RPC error, got {}", other),
+    }
+}
+
+#[tokio::test]
+async fn prompts_can_be_replaced_and_unregistered() {
+    let server = McpServer::new(ServerConfig::new("Prompts", "0.1.0"));
+    server.register_prompt("standup".to_string(), Arc::new(Standup { listed: false })).await.unwrap();
+    let client = McpTestClient::new(server);
+
+    let previous = client.server().replace_prompt("standup", Arc::new(Standup { listed: true })).await.unwrap();
+    assert!(previous.definition().is_none());
+    let names: Vec<String> = client.list_prompts().await.unwrap().into_iter().map(|p| p.name).collect();
+    assert_eq!(names, vec!["review-code", "standup"]);
+
+    assert!(client.server().unregister_prompt("standup").await);
+    assert!(!client.server().unregister_prompt("standup").await);
+    assert!(client.get_prompt("standup", json!({})).await.is_err());
+    assert!(client.server().replace_prompt("standup", Arc::new(Standup { listed: true })).await.is_err());
+}
@@ -0,0 +1,110 @@
+use mcp_server_rust::logging::LogLevel;
+use mcp_server_rust::protocol::{
+    CallToolParams, FromParams, Implementation, InitializeParams, InitializeResult, ReadResourceParams,
+    SetLevelParams,
+};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{Error, McpServer, ServerConfig};
+use serde_json::json;
+
+fn client() -> McpTestClient {
+    McpTestClient::new(McpServer::new(ServerConfig::new("Messages", "0.1.0")))
+}
+
+fn rpc_error(error: Error) -> (i64, String) {
+    match error {
+        Error::Rpc { code, message, .. } => (code, message),
+        other => panic!("expected a JSON-RPC error, got {}", other),
+    }
+}
+
+#[test]
+fn params_parse_from_the_wire_format() {
+    let params = CallToolParams::from_params(Some(json!({ "name": "greet" }))).unwrap();
+    assert_eq!(params.name, "greet");
+    assert_eq!(params.arguments, json!({}));
+
+    let params = ReadResourceParams::from_params(Some(json!({ "uri": "config://app", "range": { "offset": 2, "length": 8 } })))
+        .unwrap();
+    assert_eq!(params.uri, "config://app");
+    assert_eq!(params.range.unwrap().offset, 2);
+
+    let params = SetLevelParams::from_params(Some(json!({ "level": "warning" }))).unwrap();
+    assert_eq!(params.level, LogLevel::Warning);
+}
+
+#[test]
+fn missing_and_mistyped_fields_are_invalid_params() {
+    let error = CallToolParams::from_params(None).unwrap_err();
+    assert_eq!(error.code(), -32602);
+
+    let error = CallToolParams::from_params(Some(json!({ "arguments": {} }))).unwrap_err();
+    assert_eq!(error.to_string(), Error::InvalidParams("Missing tool name".to_string()).to_string());
+
+    let error = CallToolParams::from_params(Some(json!({ "name": 7 }))).unwrap_err();
+    assert_eq!(error.code(), -32602);
+    assert!(error.to_string().contains("invalid type"), "{}", error);
+}
+
+#[test]
+fn initialize_round_trips() {
+    let params = InitializeParams {
+        protocol_version: Some(mcp_server_rust::PROTOCOL_VERSION.to_string()),
+        client_info: Some(Implementation::new("t", "1")),
+        ..InitializeParams::default()
+    };
+    let wire = serde_json::to_value(&params).unwrap();
+    assert_eq!(
+        wire,
+        json!({
+            "protocolVersion": mcp_server_rust::PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "t", "version": "1" }
+        })
+    );
+    let parsed = InitializeParams::from_params(Some(wire)).unwrap();
+    assert_eq!(parsed.client_info, params.client_info);
+}
+
+#[tokio::test]
+async fn the_server_answers_with_typed_results() {
+    let client = client();
+    let result: InitializeResult = serde_json::from_value(client.initialize().await.unwrap()).unwrap();
+    assert_eq!(result.server_info.name, "Messages");
+    assert_eq!(result.protocol_version, mcp_server_rust::PROTOCOL_VERSION);
+}
+
+#[tokio::test]
+async fn clients_that_leave_out_their_version_still_initialize() {
+    let client = client();
+    let params = json!({
+        "protocolVersion": mcp_server_rust::PROTOCOL_VERSION,
+        "capabilities": {},
+        "clientInfo": { "name": "versionless" }
+    });
+    let result: InitializeResult = serde_json::from_value(client.request("initialize", params).await.unwrap()).unwrap();
+    assert_eq!(result.server_info.name, "Messages");
+
+    let parsed = InitializeParams::from_params(Some(json!({ "clientInfo": { "name": "versionless" } }))).unwrap();
+    assert_eq!(parsed.client_info, Some(Implementation::new("versionless", "")));
+}
+
+#[tokio::test]
+async fn the_server_refuses_malformed_params() {
+    let client = client();
+    client.initialize().await.unwrap();
+
+    let (code, message) = rpc_error(client.request("tools/call", json!({ "name": ["greet"] })).await.unwrap_err());
+    assert_eq!(code, -32602);
+    assert!(message.contains("invalid type"), "{}", message);
+
+    let (code, message) = rpc_error(client.request("tools/call", json!({ "arguments": {} })).await.unwrap_err());
+    assert_eq!(code, -32602);
+    assert!(message.ends_with("Missing tool name"), "{}", message);
+
+    let (code, _) = rpc_error(client.request("resources/read", json!({ "uri": "config://app", "range": "all" })).await.unwrap_err());
+    assert_eq!(code, -32602);
+
+    let (code, _) = rpc_error(client.request("tools/list", json!({ "cursor": 3 })).await.unwrap_err());
+    assert_eq!(code, -32602);
+}
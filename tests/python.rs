@@ -0,0 +1,72 @@
+#![cfg(feature = "python")]
+
+use mcp_server_rust::python::load_script;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::json;
+
+const SCRIPT: &str = r#"
+import mcp
+
+@mcp.tool("word-count", "Count the words in a text",
+          {"type": "object", "properties": {"text": {"type": "string"}}})
+def word_count(args):
+    return str(len(args["text"].split()))
+
+@mcp.tool("fail", "Always raises")
+def fail(args):
+    raise ValueError("boom")
+
+@mcp.prompt("haiku", "Ask for a haiku",
+            [{"name": "topic", "description": "What to write about", "required": True}])
+def haiku(args):
+    return f"Write a haiku about {args['topic']}."
+"#;
+
+#[tokio::test]
+async fn python_tools_and_prompts_are_served() {
+    let path = std::env::temp_dir().join(format!("mcp-python-{}.py", uuid::Uuid::new_v4()));
+    std::fs::write(&path, SCRIPT).unwrap();
+
+    let server = McpServer::new(ServerConfig::new("Python", "0.1.0"));
+    assert_eq!(load_script(&server, &path).await.unwrap(), 3);
+    let client = McpTestClient::new(server);
+
+    let tools = client.list_tools().await.unwrap();
+    let word_count = tools.iter().find(|t| t.name == "word-count").unwrap();
+    assert_eq!(word_count.description, "Count the words in a text");
+
+    let result = client
+        .call_tool("word-count", json!({ "text": "one two three" }))
+        .await
+        .unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "3");
+
+    let result = client.call_tool("fail", json!({})).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert!(result.content[0].as_text().unwrap().contains("boom"));
+
+    let prompt = client
+        .get_prompt("haiku", json!({ "topic": "rust" }))
+        .await
+        .unwrap();
+    assert_eq!(
+        prompt.messages[0].content.as_text().unwrap(),
+        "Write a haiku about rust."
+    );
+    assert!(client.get_prompt("haiku", json!({})).await.is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn script_errors_are_reported() {
+    let path = std::env::temp_dir().join(format!("mcp-python-{}.py", uuid::Uuid::new_v4()));
+    std::fs::write(&path, "raise RuntimeError('broken script')\n").unwrap();
+
+    let server = McpServer::new(ServerConfig::new("Python", "0.1.0"));
+    let error = load_script(&server, &path).await.unwrap_err();
+    assert!(error.to_string().contains("broken script"));
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,63 @@
+use mcp_server_rust::repl::{Repl, parse_arguments};
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::json;
+use std::sync::Arc;
+
+fn repl() -> Repl {
+    Repl::new(Arc::new(McpServer::new(ServerConfig::new("Repl", "0.1.0"))))
+}
+
+#[test]
+fn arguments_are_json_or_key_value_pairs() {
+    assert_eq!(
+        parse_arguments(r#"{"name": "Ada"}"#).unwrap(),
+        json!({ "name": "Ada" })
+    );
+    assert_eq!(
+        parse_arguments("name=Ada weight=70 active=true").unwrap(),
+        json!({ "name": "Ada", "weight": 70, "active": true })
+    );
+    assert_eq!(parse_arguments("").unwrap(), json!({}));
+    assert!(parse_arguments("name").is_err());
+}
+
+#[tokio::test]
+async fn commands_list_call_read_and_render() {
+    let repl = repl();
+
+    let tools = repl.execute("tools").await.unwrap().unwrap();
+    assert!(tools.lines().any(|line| line.starts_with("greet - ")));
+
+    let output = repl.execute("call greet name=Ada").await.unwrap().unwrap();
+    assert_eq!(output, "Hello, Ada! Welcome to MCP.");
+
+    let resources = repl.execute("resources").await.unwrap().unwrap();
+    assert!(resources.contains("config://app - Application Configuration"));
+    let config = repl.execute("read config://app").await.unwrap().unwrap();
+    assert!(config.contains("\"appName\": \"Rust MCP Server\""));
+
+    let prompt = repl
+        .execute(r#"prompt review-code {"code": "fn main() {}", "language": "rust"}"#)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(prompt.starts_with("[user]\n"));
+    assert!(prompt.contains("fn main() {}"));
+
+    assert!(repl.execute("frobnicate").await.is_err());
+    assert!(repl.execute("call").await.is_err());
+    assert_eq!(repl.execute("quit").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn sessions_run_until_quit() {
+    let input: &[u8] = b"help\ncall greet name=Ada\nbogus\nquit\ntools\n";
+    let mut output = Vec::new();
+    repl().run(input, &mut output).await.unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("call <tool> [arguments]"));
+    assert!(output.contains("mcp> Hello, Ada! Welcome to MCP."));
+    assert!(output.contains("error: "));
+    assert!(!output.contains("greet - "));
+}
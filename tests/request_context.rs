@@ -0,0 +1,194 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, ToolHandler};
+use mcp_server_rust::utils::MockClock;
+use mcp_server_rust::{McpServer, McpServerBuilder, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::sync::{Notify, oneshot};
+
+/// Reports what its context says about the request
+struct Whoami;
+
+#[async_trait]
+impl ToolHandler for Whoami {
+    async fn call(&self, context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let report = json!({
+            "id": context.request_id(),
+            "client": context.client_name(),
+            "progressToken": context.progress_token(),
+            "cancelled": context.is_cancelled(),
+        });
+        Ok(CallToolResult::success(vec![TextContent::new("")]).with_structured_content(report))
+    }
+}
+
+/// Hands its context's cancellation to a background task, then never
+/// finishes
+struct Watcher {
+    started: Arc<Notify>,
+    cancelled: std::sync::Mutex<Option<oneshot::Sender<()>>>,
+}
+
+#[async_trait]
+impl ToolHandler for Watcher {
+    async fn call(&self, context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let context = context.clone();
+        let cancelled = self.cancelled.lock().unwrap().take();
+        tokio::spawn(async move {
+            context.cancelled().await;
+            if let Some(cancelled) = cancelled {
+                let _ = cancelled.send(());
+            }
+        });
+        self.started.notify_one();
+        std::future::pending().await
+    }
+}
+
+/// Gives up on work that never finishes after five seconds on the
+/// server's clock
+struct TimesOut {
+    started: Arc<Notify>,
+}
+
+#[async_trait]
+impl ToolHandler for TimesOut {
+    async fn call(&self, context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        self.started.notify_one();
+        tokio::select! {
+            _ = std::future::pending::<()>() => unreachable!(),
+            _ = context.clock().sleep(Duration::from_secs(5)) => Ok(CallToolResult::error("Timed out")),
+        }
+    }
+}
+
+/// Shared among calls, like a connection pool
+#[derive(Default)]
+struct Counter(AtomicUsize);
+
+/// Counts its calls in the server's shared `Counter`
+struct Count;
+
+#[async_trait]
+impl ToolHandler for Count {
+    async fn call(&self, context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let Some(counter) = context.state::<Counter>() else {
+            return Ok(CallToolResult::error("No counter"));
+        };
+        let calls = counter.0.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(CallToolResult::success(vec![TextContent::new(calls.to_string())]))
+    }
+}
+
+#[tokio::test]
+async fn handlers_see_the_request_and_its_client() {
+    let server = McpServer::new(ServerConfig::new("Context", "0.1.0"));
+    server.register_tool("whoami".to_string(), Arc::new(Whoami)).await.unwrap();
+    let client = McpTestClient::new(server);
+    client.initialize().await.unwrap();
+
+    let result = client
+        .request(
+            "tools/call",
+            json!({ "name": "whoami", "arguments": {}, "_meta": { "progressToken": "tok-1" } }),
+        )
+        .await
+        .unwrap();
+    let report = &result["structuredContent"];
+    assert!(report["id"].is_number(), "{}", report);
+    assert_eq!(report["client"], "McpTestClient");
+    assert_eq!(report["progressToken"], "tok-1");
+    assert_eq!(report["cancelled"], false);
+}
+
+#[tokio::test]
+async fn cancelling_a_request_cancels_its_context() {
+    let started = Arc::new(Notify::new());
+    let (cancelled, on_cancel) = oneshot::channel();
+    let watcher = Watcher {
+        started: started.clone(),
+        cancelled: std::sync::Mutex::new(Some(cancelled)),
+    };
+    let server = Arc::new(McpServer::new(ServerConfig::new("Context", "0.1.0")));
+    server.register_tool("watch".to_string(), Arc::new(watcher)).await.unwrap();
+
+    let call = json!({
+        "jsonrpc": "2.0",
+        "id": 9,
+        "method": "tools/call",
+        "params": { "name": "watch", "arguments": {} }
+    });
+    let request = {
+        let server = server.clone();
+        tokio::spawn(async move { server.handle_frame(&call.to_string()).await.unwrap() })
+    };
+    started.notified().await;
+
+    assert!(server.cancel_request(None, &json!(9)));
+    on_cancel.await.unwrap();
+    assert!(request.await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn handlers_share_the_servers_state() {
+    let counter = Arc::new(Counter::default());
+    let server = McpServer::new(ServerConfig::new("Context", "0.1.0")).with_state(counter.clone());
+    server.register_tool("count".to_string(), Arc::new(Count)).await.unwrap();
+    let client = McpTestClient::new(server);
+
+    client.call_tool("count", json!({})).await.unwrap();
+    let second = client.call_tool("count", json!({})).await.unwrap();
+    assert_eq!(second.content[0].as_text().unwrap(), "2");
+    assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+    assert!(Arc::ptr_eq(&client.server().state::<Counter>().unwrap(), &counter));
+    assert!(client.server().state::<String>().is_none());
+}
+
+#[tokio::test]
+async fn state_can_be_added_through_the_builder() {
+    let counter = Arc::new(Counter::default());
+    let server = McpServerBuilder::new("Context", "0.1.0")
+        .state(counter.clone())
+        .build()
+        .await
+        .unwrap();
+    server.register_tool("count".to_string(), Arc::new(Count)).await.unwrap();
+    let client = McpTestClient::new(server);
+
+    let first = client.call_tool("count", json!({})).await.unwrap();
+    assert_eq!(first.content[0].as_text().unwrap(), "1");
+
+    let bare = McpTestClient::new(McpServer::new(ServerConfig::new("Context", "0.1.0")));
+    bare.server().register_tool("count".to_string(), Arc::new(Count)).await.unwrap();
+    assert_eq!(bare.call_tool("count", json!({})).await.unwrap().is_error, Some(true));
+}
+
+#[tokio::test]
+async fn handlers_time_out_on_the_servers_clock() {
+    let clock = MockClock::new();
+    let server = McpServer::new(ServerConfig::new("Context", "0.1.0")).with_clock(Arc::new(clock.clone()));
+    let started = Arc::new(Notify::new());
+    let tool = TimesOut { started: started.clone() };
+    server.register_tool("slow".to_string(), Arc::new(tool)).await.unwrap();
+    let client = Arc::new(McpTestClient::new(server));
+
+    let call = {
+        let client = client.clone();
+        tokio::spawn(async move { client.call_tool("slow", json!({})).await })
+    };
+    // Once the call is waiting, move time to just short of the timeout: it
+    // must still be running
+    started.notified().await;
+    clock.advance(Duration::from_millis(4_999));
+    tokio::task::yield_now().await;
+    assert!(!call.is_finished());
+
+    clock.advance(Duration::from_millis(1));
+    let result = call.await.unwrap().unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(result.content[0].as_text().unwrap(), "Timed out");
+}
@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::utils::{Error, RequestLimiter, RequestLimits};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+fn limiter(max_in_flight: usize, max_queue_per_method: usize) -> Arc<RequestLimiter> {
+    Arc::new(RequestLimiter::new(RequestLimits {
+        max_in_flight,
+        max_queue_per_method,
+        retry_after_ms: 250,
+        ..RequestLimits::default()
+    }))
+}
+
+/// Starts acquiring a slot for `method` in the background and lets it queue
+async fn queue(limiter: &Arc<RequestLimiter>, method: &str) -> tokio::task::JoinHandle<Result<()>> {
+    let (limiter, method) = (limiter.clone(), method.to_string());
+    let waiting = tokio::spawn(async move { limiter.acquire(&method).await.map(drop) });
+    tokio::task::yield_now().await;
+    waiting
+}
+
+fn assert_overloaded(error: Error) {
+    match error {
+        Error::Application { code, data, .. } => {
+            assert_eq!(code, -32000);
+            assert_eq!(data.unwrap()["retryAfterMs"], 250);
+        }
+        other => panic!("expected an overload error, got {}", other),
+    }
+}
+
+#[tokio::test]
+async fn no_limit_admits_everything() {
+    let limiter = limiter(0, 0);
+    let permits: Vec<_> = futures::future::join_all((0..10).map(|_| limiter.acquire("tools/call"))).await;
+    assert!(permits.iter().all(|permit| matches!(permit, Ok(None))));
+    assert_eq!(limiter.in_flight(), 0);
+}
+
+#[tokio::test]
+async fn requests_beyond_a_full_queue_are_rejected() {
+    let limiter = limiter(1, 1);
+    let running = limiter.acquire("tools/call").await.unwrap();
+    assert_eq!(limiter.in_flight(), 1);
+    let queued = queue(&limiter, "tools/call").await;
+
+    assert_overloaded(limiter.acquire("tools/call").await.err().unwrap());
+    // Queues are per method
+    let other = queue(&limiter, "resources/read").await;
+
+    drop(running);
+    queued.await.unwrap().unwrap();
+    other.await.unwrap().unwrap();
+    assert_eq!(limiter.in_flight(), 0);
+}
+
+#[tokio::test]
+async fn abandoned_waiters_give_up_their_place() {
+    let limiter = limiter(1, 1);
+    let running = limiter.acquire("tools/call").await.unwrap();
+    let queued = queue(&limiter, "tools/call").await;
+    queued.abort();
+    let _ = queued.await;
+
+    let next = queue(&limiter, "tools/call").await;
+    drop(running);
+    next.await.unwrap().unwrap();
+    assert_eq!(limiter.in_flight(), 0);
+}
+
+/// Holds every call until released
+struct Gate(Arc<Semaphore>);
+
+#[async_trait]
+impl ToolHandler for Gate {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        self.0.acquire().await.unwrap().forget();
+        Ok(CallToolResult::success(vec![TextContent::new("done")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "gate".to_string(),
+            description: "Waits to be released".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn overloaded_servers_answer_with_a_retry_hint() {
+    let limits = RequestLimits {
+        max_in_flight: 1,
+        max_queue_per_method: 1,
+        retry_after_ms: 250,
+        ..RequestLimits::default()
+    };
+    let server = Arc::new(McpServer::new(ServerConfig::new("Limits", "0.1.0").with_limits(limits)));
+    let release = Arc::new(Semaphore::new(0));
+    server.register_tool("gate".to_string(), Arc::new(Gate(release.clone()))).await.unwrap();
+    let call = |id: u64| {
+        let server = server.clone();
+        async move {
+            let request = json!({ "jsonrpc": "2.0", "id": id, "method": "tools/call", "params": { "name": "gate" } });
+            server.handle_request(request).await.unwrap().unwrap()
+        }
+    };
+
+    let running = tokio::spawn(call(1));
+    tokio::task::yield_now().await;
+    let queued = tokio::spawn(call(2));
+    tokio::task::yield_now().await;
+
+    let rejected = call(3).await;
+    assert_eq!(rejected["error"]["code"], -32000);
+    assert_eq!(rejected["error"]["data"]["retryAfterMs"], 250);
+
+    release.add_permits(2);
+    assert!(running.await.unwrap()["result"].is_object());
+    assert!(queued.await.unwrap()["result"].is_object());
+}
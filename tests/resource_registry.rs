@@ -0,0 +1,99 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::resources::file_resource::FileResource;
+use mcp_server_rust::resources::{Resource, ResourceHandler, ResourceReadResult};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::{Error, FeatureFlags, McpServer, Result, ServerConfig};
+use std::sync::Arc;
+
+/// Serves `echo://<anything>` without listing anything
+struct Echo;
+
+#[async_trait]
+impl ResourceHandler for Echo {
+    async fn read(&self, _context: &RequestContext, uri: &str) -> Result<ResourceReadResult> {
+        Ok(ResourceReadResult {
+            contents: vec![Resource {
+                uri: uri.to_string(),
+                mime_type: "text/plain".to_string(),
+                text: uri.strip_prefix("echo://").map(str::to_string),
+                blob: None,
+                size: None,
+            }],
+            meta: None,
+        })
+    }
+}
+
+fn uris(resources: Vec<serde_json::Value>) -> Vec<String> {
+    resources.iter().map(|r| r["uri"].as_str().unwrap().to_string()).collect()
+}
+
+#[tokio::test]
+async fn listings_and_reads_come_from_registered_handlers() {
+    let dir = std::env::temp_dir().join(format!("mcp-resource-registry-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    std::fs::write(dir.join("notes.txt"), "remember").unwrap();
+    std::fs::write(dir.join("nested/deep.json"), "{}").unwrap();
+
+    let server = McpServer::new(ServerConfig::new("Resources", "0.1.0"));
+    server
+        .register_resource("file:///docs/".to_string(), Arc::new(FileResource::new(dir.clone()).with_uri_prefix("file:///docs/")))
+        .await
+        .unwrap();
+    server.register_resource("echo://".to_string(), Arc::new(Echo)).await.unwrap();
+    let client = McpTestClient::new(server);
+
+    let listed = client.list_resources().await.unwrap();
+    assert_eq!(uris(listed.clone()), vec!["config://app", "file:///docs/notes.txt"]);
+    assert_eq!(listed[1]["name"], "notes.txt");
+    assert_eq!(listed[1]["mimeType"], "text/plain");
+
+    let notes = client.read_resource("file:///docs/notes.txt").await.unwrap();
+    assert_eq!(notes.contents[0].text.as_deref(), Some("remember"));
+    let deep = client.read_resource("file:///docs/nested/deep.json").await.unwrap();
+    assert_eq!(deep.contents[0].text.as_deref(), Some("{}"));
+    let echoed = client.read_resource("echo://hello").await.unwrap();
+    assert_eq!(echoed.contents[0].text.as_deref(), Some("hello"));
+
+    match client.read_resource("nowhere://x").await.unwrap_err() {
+        Error::Rpc { message, .. } => assert!(message.contains("Resource not found"), "{}", message),
+        other => panic!("expected a JSON-RPC error, got {}", other),
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn disabled_resources_have_no_handlers() {
+    let mut features = FeatureFlags::default();
+    features.disable("resources");
+    let server = McpServer::new(ServerConfig::new("Resources", "0.1.0").with_features(features));
+    server.register_resource("echo://".to_string(), Arc::new(Echo)).await.unwrap();
+    let client = McpTestClient::new(server);
+
+    assert!(client.list_resources().await.is_err());
+    assert!(client.read_resource("config://app").await.is_err());
+}
+
+#[tokio::test]
+async fn resource_handlers_can_be_replaced_and_unregistered() {
+    let server = McpServer::new(ServerConfig::new("Resources", "0.1.0"));
+    server.register_resource("echo://".to_string(), Arc::new(Echo)).await.unwrap();
+    let client = McpTestClient::new(server);
+
+    let dir = std::env::temp_dir().join(format!("mcp-resource-swap-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("hello.txt"), "from disk").unwrap();
+    client
+        .server()
+        .replace_resource("echo://", Arc::new(FileResource::new(dir.clone()).with_uri_prefix("echo://")))
+        .await
+        .unwrap();
+    let read = client.read_resource("echo://hello.txt").await.unwrap();
+    assert_eq!(read.contents[0].text.as_deref(), Some("from disk"));
+
+    assert!(client.server().unregister_resource("echo://").await);
+    assert!(client.read_resource("echo://hello.txt").await.is_err());
+    assert!(client.server().replace_resource("echo://", Arc::new(Echo)).await.is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::transport::rest;
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Barrier;
+
+/// Returns only once as many calls as the barrier expects are running
+struct Meet(Arc<Barrier>);
+
+#[async_trait]
+impl ToolHandler for Meet {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        self.0.wait().await;
+        Ok(CallToolResult::success(vec![TextContent::new("met")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "meet".to_string(),
+            description: "Waits for the other calls".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+async fn gateway(server: McpServer) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, rest::router(Arc::new(server))).await });
+    base
+}
+
+#[tokio::test]
+async fn tools_and_resources_are_reachable_over_plain_http() {
+    let base = gateway(McpServer::new(ServerConfig::new("RestTest", "0.1.0"))).await;
+    let http = reqwest::Client::new();
+
+    let response = http
+        .post(format!("{}/tools/greet", base))
+        .json(&json!({ "name": "Ada" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["content"][0]["text"], "Hello, Ada! Welcome to MCP.");
+
+    let response = http
+        .post(format!("{}/tools/missing", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    let response = http
+        .get(format!("{}/resources", base))
+        .query(&[("uri", "config://app")])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn overlapping_calls_are_all_answered() {
+    let server = McpServer::new(ServerConfig::new("RestTest", "0.1.0"));
+    server.register_tool("meet".to_string(), Arc::new(Meet(Arc::new(Barrier::new(3))))).await.unwrap();
+    let base = gateway(server).await;
+    let http = reqwest::Client::new();
+
+    let calls = (0..3).map(|_| http.post(format!("{}/tools/meet", base)).send());
+    let responses = tokio::time::timeout(Duration::from_secs(10), futures::future::join_all(calls))
+        .await
+        .unwrap();
+    for response in responses {
+        let response = response.unwrap();
+        assert_eq!(response.status(), 200);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["content"][0]["text"], "met");
+    }
+}
@@ -0,0 +1,126 @@
+use mcp_server_rust::utils::{Error, MockClock, RetryPolicy};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+fn refused() -> Error {
+    Error::Io(std::io::ErrorKind::ConnectionRefused.into())
+}
+
+/// Lets spawned tasks run until they wait on something
+async fn settle() {
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[test]
+fn backoff_grows_exponentially_up_to_the_cap() {
+    let policy = RetryPolicy::new(10)
+        .with_initial_delay(Duration::from_millis(100))
+        .with_max_delay(Duration::from_secs(1))
+        .with_multiplier(3.0)
+        .with_jitter(0.0);
+
+    let delays: Vec<_> = (1..=5).map(|retry| policy.base_delay(retry)).collect();
+    assert_eq!(
+        delays,
+        [100, 300, 900, 1000, 1000].map(Duration::from_millis)
+    );
+    assert_eq!(policy.delay(3), policy.base_delay(3));
+    assert_eq!(policy.base_delay(u32::MAX), Duration::from_secs(1));
+}
+
+#[test]
+fn jitter_only_shortens_the_delay() {
+    let policy = RetryPolicy::new(5)
+        .with_initial_delay(Duration::from_millis(200))
+        .with_jitter(0.25);
+    let base = policy.base_delay(2);
+    for _ in 0..200 {
+        let delay = policy.delay(2);
+        assert!(delay <= base, "{:?} > {:?}", delay, base);
+        assert!(delay >= base.mul_f64(0.75), "{:?} < 75% of {:?}", delay, base);
+    }
+}
+
+#[test]
+fn settings_are_kept_in_range() {
+    let policy = RetryPolicy::new(0).with_multiplier(0.5).with_jitter(3.0);
+    assert_eq!(policy.max_attempts, 1);
+    assert_eq!(policy.multiplier, 1.0);
+    assert_eq!(policy.jitter, 1.0);
+    assert_eq!(RetryPolicy::new(4).with_jitter(-1.0).jitter, 0.0);
+}
+
+#[tokio::test]
+async fn retries_wait_out_the_backoff_on_the_policys_clock() {
+    let clock = MockClock::new();
+    let policy = RetryPolicy::new(3)
+        .with_initial_delay(Duration::from_millis(100))
+        .with_jitter(0.0)
+        .with_clock(Arc::new(clock.clone()));
+    let attempts = Arc::new(AtomicU32::new(0));
+    let retried = tokio::spawn({
+        let attempts = attempts.clone();
+        async move {
+            policy
+                .retry(|| {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    async { Err::<(), _>(refused()) }
+                })
+                .await
+        }
+    });
+
+    settle().await;
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    clock.advance(Duration::from_millis(99));
+    settle().await;
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    clock.advance(Duration::from_millis(1));
+    settle().await;
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    // The second retry waits twice as long
+    clock.advance(Duration::from_millis(199));
+    settle().await;
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    clock.advance(Duration::from_millis(1));
+
+    let error = retried.await.unwrap().unwrap_err();
+    assert!(error.is_retryable());
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn errors_that_are_not_retryable_fail_at_once() {
+    let policy = RetryPolicy::new(5).with_clock(Arc::new(MockClock::new()));
+    let attempts = AtomicU32::new(0);
+    let result = policy
+        .retry(|| {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Err::<(), _>(Error::InvalidParams("bad".to_string())) }
+        })
+        .await;
+    assert!(matches!(result, Err(Error::InvalidParams(_))));
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn a_success_ends_the_retries() {
+    let clock = MockClock::new();
+    let policy = RetryPolicy::new(5)
+        .with_initial_delay(Duration::ZERO)
+        .with_clock(Arc::new(clock));
+    let attempts = AtomicU32::new(0);
+    let result = policy
+        .retry_if(
+            |e: &String| e == "again",
+            || {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                async move { if attempt < 3 { Err("again".to_string()) } else { Ok(attempt) } }
+            },
+        )
+        .await;
+    assert_eq!(result, Ok(3));
+}
@@ -0,0 +1,201 @@
+use mcp_server_rust::resources::file_resource::FileResource;
+use mcp_server_rust::roots::{Root, RootsChanged};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::transport::StdioTransport;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, duplex};
+
+fn server() -> Arc<McpServer> {
+    Arc::new(McpServer::new(ServerConfig::new("Roots", "0.1.0")))
+}
+
+async fn initialize(client: &McpTestClient, capabilities: Value) {
+    client
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": "2025-06-18",
+                "capabilities": capabilities,
+                "clientInfo": { "name": "roots-test", "version": "0.1.0" }
+            }),
+        )
+        .await
+        .unwrap();
+}
+
+fn root(uri: &str) -> Root {
+    Root { uri: uri.to_string(), name: None }
+}
+
+#[tokio::test]
+async fn roots_are_read_once_and_refreshed_on_change() {
+    let server = server();
+    let client = McpTestClient::from_arc(server.clone());
+    initialize(&client, json!({ "roots": { "listChanged": true } })).await;
+    let mut outgoing = server.subscribe_notifications();
+    let mut changes = server.subscribe_roots();
+
+    // Play the client: answer the server's roots/list requests
+    let answering = server.clone();
+    let answers = tokio::spawn(async move {
+        for workspace in ["file:///work/a", "file:///work/b"] {
+            let request = outgoing.recv().await.unwrap();
+            assert_eq!(request["method"], "roots/list");
+            let reply = json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": { "roots": [{ "uri": workspace }] }
+            });
+            assert_eq!(answering.handle_request(reply).await.unwrap(), None);
+        }
+    });
+
+    assert_eq!(server.roots().await.unwrap(), vec![root("file:///work/a")]);
+    // Cached: no second request goes out
+    assert_eq!(server.roots().await.unwrap(), vec![root("file:///work/a")]);
+
+    client.notify("notifications/roots/list_changed", json!({})).await.unwrap();
+    assert_eq!(
+        changes.recv().await.unwrap(),
+        RootsChanged { session: None, roots: vec![root("file:///work/b")] }
+    );
+    assert_eq!(server.roots().await.unwrap(), vec![root("file:///work/b")]);
+    answers.await.unwrap();
+}
+
+#[tokio::test]
+async fn clients_without_roots_are_not_asked() {
+    let server = server();
+    let client = McpTestClient::from_arc(server.clone());
+    initialize(&client, json!({})).await;
+    let mut outgoing = server.subscribe_notifications();
+
+    let error = server.roots().await.unwrap_err().to_string();
+    assert!(error.contains("roots/list"), "{}", error);
+    client.notify("notifications/roots/list_changed", json!({})).await.unwrap();
+    tokio::task::yield_now().await;
+    assert!(outgoing.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn stdio_clients_are_asked_again_after_a_change() {
+    let server = server();
+    let mut changes = server.subscribe_roots();
+    let (client_io, server_io) = duplex(64 * 1024);
+    let (server_read, server_write) = tokio::io::split(server_io);
+    let (client_read, mut client_write) = tokio::io::split(client_io);
+    let serving = server.clone();
+    let served = tokio::spawn(async move {
+        StdioTransport::from_io(BufReader::new(server_read), server_write)
+            .serve(&serving)
+            .await
+    });
+
+    let mut lines = BufReader::new(client_read).lines();
+    let mut send = async |message: Value| {
+        client_write.write_all(format!("{}\n", message).as_bytes()).await.unwrap();
+    };
+    send(json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": { "roots": { "listChanged": true } },
+            "clientInfo": { "name": "roots-test", "version": "0.1.0" }
+        }
+    }))
+    .await;
+    let initialized: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(initialized["id"], 1);
+
+    send(json!({ "jsonrpc": "2.0", "method": "notifications/roots/list_changed" })).await;
+    let request: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(request["method"], "roots/list");
+    send(json!({
+        "jsonrpc": "2.0",
+        "id": request["id"],
+        "result": { "roots": [{ "uri": "file:///home/me/project", "name": "project" }] }
+    }))
+    .await;
+
+    let changed = changes.recv().await.unwrap();
+    assert_eq!(changed.roots[0].name.as_deref(), Some("project"));
+
+    client_write.shutdown().await.unwrap();
+    served.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn roots_are_read_once_the_client_is_initialized() {
+    let server = server();
+    let client = McpTestClient::from_arc(server.clone());
+    initialize(&client, json!({ "roots": {} })).await;
+    let mut outgoing = server.subscribe_notifications();
+    let mut changes = server.subscribe_roots();
+
+    client.notify("notifications/initialized", json!({})).await.unwrap();
+    let request = outgoing.recv().await.unwrap();
+    assert_eq!(request["method"], "roots/list");
+    let reply = json!({
+        "jsonrpc": "2.0",
+        "id": request["id"],
+        "result": { "roots": [{ "uri": "file:///work/a" }] }
+    });
+    server.handle_request(reply).await.unwrap();
+
+    assert_eq!(changes.recv().await.unwrap().roots, vec![root("file:///work/a")]);
+    assert_eq!(server.roots().await.unwrap(), vec![root("file:///work/a")]);
+}
+
+#[test]
+fn file_roots_map_to_local_paths() {
+    assert_eq!(root("file:///home/me/my%20project").path(), Some("/home/me/my project".into()));
+    assert_eq!(root("file://localhost/srv").path(), Some("/srv".into()));
+    assert_eq!(root("https://example.com/").path(), None);
+    assert!(root("file:///srv/app").contains("/srv/app/src/main.rs".as_ref()));
+    assert!(!root("file:///srv/app").contains("/srv/application".as_ref()));
+}
+
+#[tokio::test]
+async fn file_resources_stay_inside_the_client_roots() {
+    let dir = std::env::temp_dir().join(format!("mcp-roots-{}", uuid::Uuid::new_v4()));
+    for (sub, file) in [("granted", "in.txt"), ("private", "out.txt")] {
+        std::fs::create_dir_all(dir.join(sub)).unwrap();
+        std::fs::write(dir.join(sub).join(file), "text").unwrap();
+    }
+    let granted = std::fs::canonicalize(dir.join("granted")).unwrap();
+
+    let server = server();
+    let files = FileResource::new(dir.clone()).with_client_roots(true);
+    server.register_resource("file:///data/".to_string(), Arc::new(files)).await.unwrap();
+    let client = McpTestClient::from_arc(server.clone());
+    initialize(&client, json!({ "roots": {} })).await;
+    let mut outgoing = server.subscribe_notifications();
+    let mut changes = server.subscribe_roots();
+
+    client.notify("notifications/initialized", json!({})).await.unwrap();
+    let request = outgoing.recv().await.unwrap();
+    let reply = json!({
+        "jsonrpc": "2.0",
+        "id": request["id"],
+        "result": { "roots": [{ "uri": format!("file://{}", granted.display()) }] }
+    });
+    server.handle_request(reply).await.unwrap();
+    changes.recv().await.unwrap();
+
+    let read = client
+        .request("resources/read", json!({ "uri": "file:///data/granted/in.txt" }))
+        .await
+        .unwrap();
+    assert_eq!(read["contents"][0]["text"], "text");
+    let error = client
+        .request("resources/read", json!({ "uri": "file:///data/private/out.txt" }))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("outside the client's roots"), "{}", error);
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,27 @@
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::{Value, json};
+
+// Dispatch only needs an executor, so any runtime (or none) can drive it
+#[test]
+fn dispatch_runs_outside_a_tokio_runtime() {
+    let server = McpServer::new(ServerConfig::new("Agnostic", "0.1.0"));
+    let request = |id: u64, method: &str, params: Value| {
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let server = &server;
+        async move { server.handle_request(request).await.unwrap().unwrap() }
+    };
+
+    futures::executor::block_on(async {
+        let initialized = request(1, "initialize", json!({
+            "protocolVersion": mcp_server_rust::PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "t", "version": "1" }
+        }))
+        .await;
+        assert_eq!(initialized["result"]["serverInfo"]["name"], "Agnostic");
+        let called = request(2, "tools/call", json!({ "name": "greet", "arguments": { "name": "Ada" } })).await;
+        assert_eq!(called["result"]["content"][0]["text"], "Hello, Ada! Welcome to MCP.");
+        let listed = request(3, "tools/list", json!({})).await;
+        assert!(!listed["result"]["tools"].as_array().unwrap().is_empty());
+    });
+}
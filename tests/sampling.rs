@@ -0,0 +1,179 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::sampling::{
+    self, CreateMessageRequest, IncludeContext, ModelPreferences, SamplingContent, SamplingMessage,
+};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+/// Has the client's model summarize the text it is given
+struct Summarize;
+
+#[async_trait]
+impl ToolHandler for Summarize {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        let text = arguments["text"].as_str().unwrap_or_default();
+        let request = CreateMessageRequest::new(50).with_user_message(format!("Summarize: {}", text));
+        let result = sampling::create_message(&request).await?;
+        Ok(CallToolResult::success(vec![TextContent::new(format!(
+            "{} (by {})",
+            result.text().unwrap_or_default(),
+            result.model
+        ))]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "summarize".to_string(),
+            description: "Summarizes text".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+async fn server(client_capabilities: Value) -> Arc<McpServer> {
+    let server = Arc::new(McpServer::new(ServerConfig::new("Sampling", "0.1.0")));
+    server.register_tool("summarize".to_string(), Arc::new(Summarize)).await.unwrap();
+    McpTestClient::from_arc(server.clone())
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": "2025-06-18",
+                "capabilities": client_capabilities,
+                "clientInfo": { "name": "sampling-test", "version": "0.1.0" }
+            }),
+        )
+        .await
+        .unwrap();
+    server
+}
+
+#[test]
+fn requests_serialize_to_the_spec_shape() {
+    let request = CreateMessageRequest::new(100)
+        .with_user_message("What is the capital of France?")
+        .with_message(SamplingMessage::assistant("Paris."))
+        .with_message(SamplingMessage {
+            role: mcp_server_rust::sampling::Role::User,
+            content: SamplingContent::Image {
+                data: "iVBORw0KGgo=".to_string(),
+                mime_type: "image/png".to_string(),
+            },
+        })
+        .with_model_preferences(
+            ModelPreferences::new()
+                .with_hint("claude-3-sonnet")
+                .with_hint("claude")
+                .with_intelligence_priority(0.8)
+                .with_speed_priority(0.5),
+        )
+        .with_system_prompt("You are a helpful assistant.")
+        .with_include_context(IncludeContext::ThisServer)
+        .with_temperature(0.2)
+        .with_stop_sequence("\n\n")
+        .with_metadata(json!({ "provider": "any" }));
+
+    assert_eq!(
+        request.params().unwrap(),
+        json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "What is the capital of France?" } },
+                { "role": "assistant", "content": { "type": "text", "text": "Paris." } },
+                { "role": "user", "content": { "type": "image", "data": "iVBORw0KGgo=", "mimeType": "image/png" } }
+            ],
+            "modelPreferences": {
+                "hints": [{ "name": "claude-3-sonnet" }, { "name": "claude" }],
+                "intelligencePriority": 0.8,
+                "speedPriority": 0.5
+            },
+            "systemPrompt": "You are a helpful assistant.",
+            "includeContext": "thisServer",
+            "temperature": 0.2,
+            "maxTokens": 100,
+            "stopSequences": ["\n\n"],
+            "metadata": { "provider": "any" }
+        })
+    );
+
+    let parsed: CreateMessageRequest = serde_json::from_value(request.params().unwrap()).unwrap();
+    assert_eq!(parsed, request);
+}
+
+#[test]
+fn minimal_requests_omit_unset_fields() {
+    let params = CreateMessageRequest::new(10).with_user_message("hi").params().unwrap();
+    assert_eq!(
+        params,
+        json!({
+            "messages": [{ "role": "user", "content": { "type": "text", "text": "hi" } }],
+            "maxTokens": 10
+        })
+    );
+}
+
+#[test]
+fn out_of_spec_requests_are_rejected() {
+    let no_messages = CreateMessageRequest::new(10).params().unwrap_err();
+    assert!(no_messages.to_string().contains("at least one message"));
+
+    let priority = CreateMessageRequest::new(10)
+        .with_user_message("hi")
+        .with_model_preferences(ModelPreferences::new().with_cost_priority(1.5))
+        .params()
+        .unwrap_err();
+    assert!(priority.to_string().contains("costPriority"), "{}", priority);
+
+    let tokens = CreateMessageRequest::new(0).with_user_message("hi").params().unwrap_err();
+    assert!(tokens.to_string().contains("maxTokens"));
+}
+
+#[tokio::test]
+async fn tools_get_completions_from_the_client() {
+    let server = server(json!({ "sampling": {} })).await;
+    let mut outgoing = server.subscribe_notifications();
+    // Plays the client's LLM
+    let responder = tokio::spawn({
+        let server = server.clone();
+        async move {
+            let request = outgoing.recv().await.unwrap();
+            assert_eq!(request["method"], "sampling/createMessage");
+            assert_eq!(request["params"]["maxTokens"], 50);
+            assert_eq!(request["params"]["messages"][0]["content"]["text"], "Summarize: a long story");
+            let reply = json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "role": "assistant",
+                    "content": { "type": "text", "text": "A story." },
+                    "model": "test-model",
+                    "stopReason": "endTurn"
+                }
+            });
+            server.handle_request(reply).await.unwrap();
+        }
+    });
+
+    let client = McpTestClient::from_arc(server);
+    let result = client.call_tool("summarize", json!({ "text": "a long story" })).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "A story. (by test-model)");
+    responder.await.unwrap();
+}
+
+#[tokio::test]
+async fn sampling_needs_the_client_capability() {
+    let server = server(json!({})).await;
+    let client = McpTestClient::from_arc(server);
+    let error = client.call_tool("summarize", json!({ "text": "x" })).await.unwrap_err();
+    assert!(error.to_string().contains("sampling/createMessage"), "{}", error);
+}
+
+#[tokio::test]
+async fn sampling_outside_a_request_fails() {
+    let request = CreateMessageRequest::new(10).with_user_message("hi");
+    assert!(sampling::create_message(&request).await.is_err());
+}
@@ -0,0 +1,41 @@
+use mcp_server_rust::scaffold::{Scaffold, ScaffoldKind};
+use std::path::Path;
+
+#[test]
+fn names_follow_the_crate_layout() {
+    let scaffold = Scaffold::new(ScaffoldKind::Tool, "word-count").unwrap();
+    assert_eq!(scaffold.module_name(), "word_count_tool");
+    assert_eq!(scaffold.type_name(), "WordCountTool");
+
+    let files = scaffold.files();
+    assert_eq!(files[0].path, Path::new("src/tools/word_count_tool.rs"));
+    assert_eq!(files[1].path, Path::new("tests/word_count_tool.rs"));
+    assert!(files[0].contents.contains("pub struct WordCountTool"));
+    assert!(files[0].contents.contains(r#"name: "word-count".to_string()"#));
+    assert!(scaffold.registration_snippet().contains("pub mod word_count_tool;"));
+
+    let resource = Scaffold::new(ScaffoldKind::Resource, "notes").unwrap();
+    assert!(resource.registration_snippet().contains(r#"register_resource("notes://""#));
+}
+
+#[test]
+fn invalid_names_are_rejected() {
+    for name in ["", "Word", "1st", "word count", "../escape"] {
+        assert!(Scaffold::new(ScaffoldKind::Prompt, name).is_err(), "{}", name);
+    }
+}
+
+#[test]
+fn existing_files_are_not_overwritten_without_force() {
+    let root = std::env::temp_dir().join(format!("mcp-scaffold-{}", uuid::Uuid::new_v4()));
+    let scaffold = Scaffold::new(ScaffoldKind::Prompt, "daily-summary").unwrap();
+
+    let written = scaffold.write(&root, false).unwrap();
+    assert_eq!(written.len(), 2);
+    assert!(root.join("src/prompts/daily_summary_prompt.rs").exists());
+
+    assert!(scaffold.write(&root, false).is_err());
+    assert!(scaffold.write(&root, true).is_ok());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
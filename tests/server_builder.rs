@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::prompts::{Content, GetPromptResult, Message, Prompt, PromptHandler};
+use mcp_server_rust::resources::{Resource, ResourceHandler, ResourceReadResult};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::server::Icon;
+use mcp_server_rust::{Error, McpServer, Result};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+struct Echo {
+    listed: bool,
+}
+
+#[async_trait]
+impl ToolHandler for Echo {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![TextContent::new(arguments["text"].to_string())]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        self.listed.then(|| Tool {
+            name: "echo".to_string(),
+            description: "Repeats the text".to_string(),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+struct Motd;
+
+#[async_trait]
+impl ResourceHandler for Motd {
+    async fn read(&self, _context: &RequestContext, uri: &str) -> Result<ResourceReadResult> {
+        Ok(ResourceReadResult {
+            contents: vec![Resource {
+                uri: uri.to_string(),
+                mime_type: "text/plain".to_string(),
+                text: Some("Welcome".to_string()),
+                blob: None,
+                size: None,
+            }],
+            meta: None,
+        })
+    }
+}
+
+struct Standup;
+
+#[async_trait]
+impl PromptHandler for Standup {
+    async fn get(&self, _context: &RequestContext, _arguments: Option<Value>) -> Result<GetPromptResult> {
+        Ok(GetPromptResult {
+            description: None,
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: Content::text("What did you do yesterday?"),
+            }],
+            meta: None,
+        })
+    }
+
+    fn definition(&self) -> Option<Prompt> {
+        Some(Prompt {
+            name: "standup".to_string(),
+            description: "Daily standup questions".to_string(),
+            arguments: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn builder_registers_handlers_and_configures_initialize() {
+    let server = McpServer::builder("Built", "2.0.0")
+        .instructions("Call echo first")
+        .capabilities(json!({ "experimental": { "x-echo": {} }, "logging": {} }))
+        .tool(Arc::new(Echo { listed: true }))
+        .resource("motd://", Arc::new(Motd))
+        .prompt(Arc::new(Standup))
+        .build()
+        .await
+        .unwrap();
+    let client = McpTestClient::new(server);
+
+    let result = client.initialize().await.unwrap();
+    assert_eq!(result["serverInfo"]["name"], "Built");
+    assert_eq!(result["instructions"], "Call echo first");
+    assert!(result["capabilities"]["logging"].is_object());
+    // Merged with, not replacing, what the server advertises itself
+    assert!(result["capabilities"]["experimental"]["x-echo"].is_object());
+    assert!(result["capabilities"]["experimental"]["x-tools/search"].is_object());
+    assert!(result["capabilities"]["tools"].is_object());
+
+    let echoed = client.call_tool("echo", json!({ "text": "hi" })).await.unwrap();
+    assert_eq!(echoed.content[0].as_text().unwrap(), "\"hi\"");
+    let motd = client.read_resource("motd://today").await.unwrap();
+    assert_eq!(motd.contents[0].text.as_deref(), Some("Welcome"));
+    let standup = client.get_prompt("standup", json!({})).await.unwrap();
+    assert_eq!(standup.messages[0].content.as_text().unwrap(), "What did you do yesterday?");
+}
+
+#[tokio::test]
+async fn instructions_are_omitted_unless_set() {
+    let client = McpTestClient::new(McpServer::builder("Plain", "0.1.0").build().await.unwrap());
+    let result = client.initialize().await.unwrap();
+    assert!(result.get("instructions").is_none());
+    assert_eq!(result["serverInfo"], json!({ "name": "Plain", "version": "0.1.0" }));
+}
+
+#[tokio::test]
+async fn server_info_carries_title_website_and_icons() {
+    let server = McpServer::builder("notes", "1.0.0")
+        .title("Team Notes")
+        .website_url("https://notes.example.com")
+        .icon(Icon::new("https://notes.example.com/icon.png").with_mime_type("image/png").with_size("48x48"))
+        .build()
+        .await
+        .unwrap();
+    let result = McpTestClient::new(server).initialize().await.unwrap();
+    assert_eq!(
+        result["serverInfo"],
+        json!({
+            "name": "notes",
+            "version": "1.0.0",
+            "title": "Team Notes",
+            "websiteUrl": "https://notes.example.com",
+            "icons": [{ "src": "https://notes.example.com/icon.png", "mimeType": "image/png", "sizes": ["48x48"] }]
+        })
+    );
+}
+
+#[tokio::test]
+async fn handlers_without_a_definition_are_rejected() {
+    let error = McpServer::builder("Nameless", "0.1.0")
+        .tool(Arc::new(Echo { listed: false }))
+        .build()
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(error, Error::ValidationError(_)));
+}
@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+struct TenantTool(&'static str);
+
+#[async_trait]
+impl ToolHandler for TenantTool {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        Ok(CallToolResult::success(vec![TextContent::new(self.0)]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "query-db".to_string(),
+            description: format!("Query the {} database", self.0),
+            input_schema: json!({ "type": "object" }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn session_tools_are_isolated_per_tenant() {
+    let server = McpServer::new(ServerConfig::new("Tenants", "0.1.0"));
+    server
+        .register_session_tool(
+            "a",
+            "query-db".to_string(),
+            Arc::new(TenantTool("tenant-a")),
+        )
+        .await
+        .unwrap();
+    server
+        .register_session_tool(
+            "b",
+            "query-db".to_string(),
+            Arc::new(TenantTool("tenant-b")),
+        )
+        .await
+        .unwrap();
+    let client = McpTestClient::new(server);
+    let tenant = |id: &str| McpTestClient::from_arc(client.server().clone()).with_session(id);
+
+    let result = tenant("a").call_tool("query-db", json!({})).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "tenant-a");
+
+    let result = tenant("b").call_tool("query-db", json!({})).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "tenant-b");
+
+    let tools = tenant("c").list_tools().await.unwrap();
+    assert!(tools.iter().all(|t| t.name != "query-db"));
+    assert!(client.call_tool("query-db", json!({})).await.is_err());
+}
@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::tools::{CallToolResult, TextContent, ToolHandler};
+use mcp_server_rust::transport::{StdioTransport, TcpTransport};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, duplex};
+use tokio::net::TcpStream;
+use tokio::sync::Notify;
+
+/// Blocks until released
+struct Gate(Arc<Notify>);
+
+#[async_trait]
+impl ToolHandler for Gate {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        self.0.notified().await;
+        Ok(CallToolResult::success(vec![TextContent::new("released")]))
+    }
+}
+
+async fn server() -> (Arc<McpServer>, Arc<Notify>) {
+    let gate = Arc::new(Notify::new());
+    let server = McpServer::new(ServerConfig::new("Shutdown", "0.1.0"));
+    server.register_tool("gate".to_string(), Arc::new(Gate(gate.clone()))).await.unwrap();
+    (Arc::new(server), gate)
+}
+
+fn call_gate(id: u64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": "gate", "arguments": {} }
+    })
+}
+
+/// Starts a gate call in the background and waits until it is in flight
+async fn start_call(server: &Arc<McpServer>, id: u64) -> tokio::task::JoinHandle<Option<Value>> {
+    let task = {
+        let server = server.clone();
+        tokio::spawn(async move { server.handle_request(call_gate(id)).await.unwrap() })
+    };
+    while server.in_flight().is_empty() {
+        tokio::task::yield_now().await;
+    }
+    task
+}
+
+#[tokio::test]
+async fn shutdown_waits_for_requests_in_flight() {
+    let (server, gate) = server().await;
+    let call = start_call(&server, 1).await;
+    let shutdown = {
+        let server = server.clone();
+        tokio::spawn(async move { server.shutdown(Duration::from_secs(5)).await })
+    };
+    server.shutdown_requested().await;
+
+    // New requests are refused meanwhile
+    let refused = server.handle_request(call_gate(2)).await.unwrap().unwrap();
+    assert_eq!(refused["error"]["code"], -32005);
+
+    gate.notify_one();
+    let answered = call.await.unwrap().unwrap();
+    assert_eq!(answered["result"]["content"][0]["text"], "released");
+    assert!(shutdown.await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn requests_still_running_after_the_grace_period_are_cancelled() {
+    let (server, _gate) = server().await;
+    let call = start_call(&server, 7).await;
+
+    let abandoned = server.shutdown(Duration::from_millis(20)).await;
+    assert_eq!(abandoned.len(), 1);
+    assert_eq!(abandoned[0].id, json!(7));
+    assert_eq!(call.await.unwrap(), None);
+    assert!(server.in_flight().is_empty());
+}
+
+#[tokio::test]
+async fn stdio_answers_what_it_read_and_stops_serving() {
+    let (server, gate) = server().await;
+    let (client_io, server_io) = duplex(64 * 1024);
+    let (server_read, server_write) = tokio::io::split(server_io);
+    let (client_read, mut client_write) = tokio::io::split(client_io);
+    let serving = server.clone();
+    let served = tokio::spawn(async move {
+        StdioTransport::from_io(BufReader::new(server_read), server_write)
+            .serve(&serving)
+            .await
+    });
+
+    client_write.write_all(format!("{}\n", call_gate(1)).as_bytes()).await.unwrap();
+    while server.in_flight().is_empty() {
+        tokio::task::yield_now().await;
+    }
+    let shutdown = {
+        let server = server.clone();
+        tokio::spawn(async move { server.shutdown(Duration::from_secs(5)).await })
+    };
+    server.shutdown_requested().await;
+    gate.notify_one();
+
+    // Serving ends although stdin is still open
+    served.await.unwrap().unwrap();
+    assert!(shutdown.await.unwrap().is_empty());
+    let mut lines = BufReader::new(client_read).lines();
+    let response: Value = serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+    assert_eq!(response["result"]["content"][0]["text"], "released");
+}
+
+#[tokio::test]
+async fn tcp_stops_accepting_and_closes_idle_connections() {
+    let (server, _gate) = server().await;
+    let transport = TcpTransport::bind("127.0.0.1:0").await.unwrap();
+    let addr = transport.local_addr().unwrap();
+    let serving = server.clone();
+    let served = tokio::spawn(async move { transport.serve(serving).await });
+    let mut connection = TcpStream::connect(addr).await.unwrap();
+    connection.write_all(b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ping\"}\n").await.unwrap();
+    let mut lines = BufReader::new(&mut connection).lines();
+    assert!(lines.next_line().await.unwrap().unwrap().contains("\"result\""));
+
+    assert!(server.shutdown(Duration::from_secs(5)).await.is_empty());
+    served.await.unwrap().unwrap();
+    // The server side of the connection is gone
+    assert_eq!(lines.next_line().await.unwrap(), None);
+}
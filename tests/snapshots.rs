@@ -0,0 +1,14 @@
+use mcp_server_rust::testing::fixtures;
+use mcp_server_rust::{McpServer, ServerConfig};
+
+#[tokio::test]
+async fn responses_match_their_snapshots() {
+    let server = McpServer::new(ServerConfig::new("SnapshotServer", "1.0.0"));
+    let initialized = server.handle_request(fixtures::initialize_request()).await.unwrap();
+    insta::assert_json_snapshot!("initialize", initialized);
+
+    for case in fixtures::cases() {
+        let response = server.handle_request(case.request).await.unwrap();
+        insta::assert_json_snapshot!(case.name, response);
+    }
+}
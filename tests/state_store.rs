@@ -0,0 +1,79 @@
+use mcp_server_rust::state::{MemoryStateStore, SledStateStore, StateStore};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("mcp-state-{}", uuid::Uuid::new_v4()))
+}
+
+/// Opens the sled store at `dir` again, once the previous one's background
+/// flusher has let go of the file lock
+async fn reopen(dir: &Path) -> SledStateStore {
+    for _ in 0..100 {
+        match SledStateStore::open(dir) {
+            Ok(store) => return store,
+            Err(e) if e.to_string().contains("could not acquire lock") => {
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+            Err(e) => panic!("{}", e),
+        }
+    }
+    panic!("{} stayed locked", dir.display());
+}
+
+/// What every backend must do the same way
+async fn behaves_like_a_store(store: &dyn StateStore) {
+    assert_eq!(store.get("kv", "missing").await.unwrap(), None);
+
+    store.put("kv", "b", b"2".to_vec()).await.unwrap();
+    store.put("kv", "a", b"1".to_vec()).await.unwrap();
+    store.put("jobs", "a", b"job".to_vec()).await.unwrap();
+    assert_eq!(store.get("kv", "a").await.unwrap().unwrap(), b"1");
+    // Namespaces don't share keys
+    assert_eq!(store.get("jobs", "a").await.unwrap().unwrap(), b"job");
+
+    store.put("kv", "a", b"one".to_vec()).await.unwrap();
+    assert_eq!(
+        store.list("kv").await.unwrap(),
+        vec![("a".to_string(), b"one".to_vec()), ("b".to_string(), b"2".to_vec())]
+    );
+    assert!(store.list("empty").await.unwrap().is_empty());
+
+    assert!(store.delete("kv", "a").await.unwrap());
+    assert!(!store.delete("kv", "a").await.unwrap());
+    assert!(!store.delete("empty", "a").await.unwrap());
+    assert_eq!(store.get("kv", "a").await.unwrap(), None);
+
+    store.put_json("kv", "doc", &json!({ "n": 1 })).await.unwrap();
+    assert_eq!(store.get_json("kv", "doc").await.unwrap(), Some(json!({ "n": 1 })));
+    assert_eq!(store.get_json("kv", "missing").await.unwrap(), None);
+    store.put("kv", "broken", b"{".to_vec()).await.unwrap();
+    assert!(store.get_json("kv", "broken").await.is_err());
+}
+
+#[tokio::test]
+async fn memory_store_behaves_like_a_store() {
+    behaves_like_a_store(&MemoryStateStore::new()).await;
+}
+
+#[tokio::test]
+async fn sled_store_behaves_like_a_store() {
+    let dir = temp_dir();
+    behaves_like_a_store(&SledStateStore::open(&dir).unwrap()).await;
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn sled_state_survives_reopening() {
+    let dir = temp_dir();
+    {
+        let store = SledStateStore::open(&dir).unwrap();
+        store.put("kv", "kept", b"yes".to_vec()).await.unwrap();
+        store.put("kv", "dropped", b"no".to_vec()).await.unwrap();
+        store.delete("kv", "dropped").await.unwrap();
+    }
+
+    let store = reopen(&dir).await;
+    assert_eq!(store.list("kv").await.unwrap(), vec![("kept".to_string(), b"yes".to_vec())]);
+    let _ = std::fs::remove_dir_all(dir);
+}
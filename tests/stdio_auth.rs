@@ -0,0 +1,55 @@
+use mcp_server_rust::client::McpClient;
+use mcp_server_rust::transport::StdioTransport;
+use mcp_server_rust::{Error, McpServer, ServerConfig};
+use tokio::io::{BufReader, duplex};
+use tokio::task::JoinHandle;
+
+const SECRET: &str = "s3cr3t-from-the-host";
+
+/// A server requiring `SECRET`, and a client connected to it
+fn connect(token: Option<&str>) -> (McpClient<StdioTransport>, JoinHandle<mcp_server_rust::Result<()>>) {
+    let (client_io, server_io) = duplex(64 * 1024);
+    let (server_read, server_write) = tokio::io::split(server_io);
+    let (client_read, client_write) = tokio::io::split(client_io);
+
+    let server = tokio::spawn(async move {
+        let server = McpServer::new(ServerConfig::new("StdioAuth", "0.1.0"));
+        StdioTransport::from_io(BufReader::new(server_read), server_write)
+            .with_shared_secret(SECRET)
+            .serve(&server)
+            .await
+    });
+
+    let mut client = McpClient::new(StdioTransport::from_io(BufReader::new(client_read), client_write));
+    if let Some(token) = token {
+        client = client.with_auth_token(token);
+    }
+    (client, server)
+}
+
+#[tokio::test]
+async fn clients_presenting_the_secret_are_served() {
+    let (mut client, server) = connect(Some(SECRET));
+    client.initialize().await.unwrap();
+    assert!(!client.list_tools().await.unwrap().is_empty());
+
+    client.close().await.unwrap();
+    server.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn other_clients_are_refused_and_disconnected() {
+    for token in [None, Some("guess")] {
+        let (mut client, server) = connect(token);
+        match client.initialize().await.unwrap_err() {
+            Error::Rpc { code, message, .. } => {
+                assert_eq!(code, -32003);
+                assert!(message.contains("Authentication required"), "{}", message);
+            }
+            other => panic!("expected a JSON-RPC error, got {}", other),
+        }
+        // The server stopped reading after the first message
+        server.await.unwrap().unwrap();
+        assert!(client.ping().await.is_err());
+    }
+}
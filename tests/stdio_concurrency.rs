@@ -0,0 +1,232 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::roots;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::transport::StdioTransport;
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::{Arc, Barrier};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, Lines, ReadHalf, WriteHalf, duplex};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// Finishes only once the test lets it
+struct Gate(Arc<Notify>);
+
+#[async_trait]
+impl ToolHandler for Gate {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        self.0.notified().await;
+        Ok(CallToolResult::success(vec![TextContent::new("opened")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(tool("gate", "Waits until the test opens it"))
+    }
+}
+
+/// Blocks its thread until the other party to the rendezvous arrives
+struct Rendezvous(Arc<Barrier>);
+
+#[async_trait]
+impl ToolHandler for Rendezvous {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        self.0.wait();
+        Ok(CallToolResult::success(vec![TextContent::new("met")]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(tool("rendezvous", "Waits on a barrier, blocking the thread"))
+    }
+}
+
+/// Lists the client's roots, asking the client within the call
+struct CountRoots;
+
+#[async_trait]
+impl ToolHandler for CountRoots {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let roots = roots::list_roots().await?;
+        Ok(CallToolResult::success(vec![TextContent::new(format!("{} roots", roots.len()))]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(tool("count-roots", "Counts the client's roots"))
+    }
+}
+
+fn tool(name: &str, description: &str) -> Tool {
+    Tool {
+        name: name.to_string(),
+        description: description.to_string(),
+        input_schema: json!({ "type": "object" }),
+        annotations: None,
+        output_schema: None,
+    }
+}
+
+struct Client {
+    lines: Lines<BufReader<ReadHalf<DuplexStream>>>,
+    write: WriteHalf<DuplexStream>,
+    served: JoinHandle<Result<()>>,
+}
+
+impl Client {
+    async fn serve(server: McpServer, transport: impl FnOnce(StdioTransport) -> StdioTransport) -> Self {
+        Self::start(transport, |mut transport| async move { transport.serve(&server).await })
+    }
+
+    async fn serve_shared(server: Arc<McpServer>, transport: impl FnOnce(StdioTransport) -> StdioTransport) -> Self {
+        Self::start(transport, |mut transport| async move { transport.serve_shared(server).await })
+    }
+
+    fn start<F>(
+        transport: impl FnOnce(StdioTransport) -> StdioTransport,
+        serve: impl FnOnce(StdioTransport) -> F,
+    ) -> Self
+    where
+        F: Future<Output = Result<()>> + Send + 'static,
+    {
+        let (client_io, server_io) = duplex(64 * 1024);
+        let (server_read, server_write) = tokio::io::split(server_io);
+        let (client_read, write) = tokio::io::split(client_io);
+        let transport = transport(StdioTransport::from_io(BufReader::new(server_read), server_write));
+        let served = tokio::spawn(serve(transport));
+        Self {
+            lines: BufReader::new(client_read).lines(),
+            write,
+            served,
+        }
+    }
+
+    async fn send(&mut self, message: Value) {
+        self.write.write_all(format!("{}\n", message).as_bytes()).await.unwrap();
+    }
+
+    async fn call(&mut self, id: u64, name: &str) {
+        let params = json!({ "name": name, "arguments": {} });
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": "tools/call", "params": params })).await;
+    }
+
+    async fn receive(&mut self) -> Value {
+        serde_json::from_str(&self.lines.next_line().await.unwrap().unwrap()).unwrap()
+    }
+
+    async fn close(mut self) {
+        self.write.shutdown().await.unwrap();
+        self.served.await.unwrap().unwrap();
+    }
+}
+
+async fn gated_server() -> (McpServer, Arc<Notify>) {
+    let server = McpServer::new(ServerConfig::new("StdioConcurrency", "0.1.0"));
+    let gate = Arc::new(Notify::new());
+    server.register_tool("gate".to_string(), Arc::new(Gate(gate.clone()))).await.unwrap();
+    (server, gate)
+}
+
+#[tokio::test]
+async fn slow_calls_do_not_hold_up_other_requests() {
+    let (server, gate) = gated_server().await;
+    let mut client = Client::serve(server, |transport| transport).await;
+
+    client.call(1, "gate").await;
+    client.send(json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" })).await;
+    assert_eq!(client.receive().await["id"], 2);
+
+    gate.notify_one();
+    let opened = client.receive().await;
+    assert_eq!(opened["id"], 1);
+    assert_eq!(opened["result"]["content"][0]["text"], "opened");
+    client.close().await;
+}
+
+#[tokio::test]
+async fn requests_read_before_stdin_closes_are_still_answered() {
+    let (server, gate) = gated_server().await;
+    let mut client = Client::serve(server, |transport| transport).await;
+
+    client.call(1, "gate").await;
+    client.write.shutdown().await.unwrap();
+    gate.notify_one();
+    assert_eq!(client.receive().await["id"], 1);
+    client.served.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn the_cap_holds_back_further_frames() {
+    let (server, gate) = gated_server().await;
+    let mut client = Client::serve(server, |transport| transport.with_max_concurrency(1)).await;
+
+    client.call(1, "gate").await;
+    client.send(json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" })).await;
+    // The ping is not read while the call holds the only slot
+    tokio::task::yield_now().await;
+    gate.notify_one();
+    assert_eq!(client.receive().await["id"], 1);
+    assert_eq!(client.receive().await["id"], 2);
+    client.close().await;
+}
+
+#[tokio::test]
+async fn handlers_can_await_the_client_mid_call() {
+    let server = McpServer::new(ServerConfig::new("StdioConcurrency", "0.1.0"));
+    server.register_tool("count-roots".to_string(), Arc::new(CountRoots)).await.unwrap();
+    let mut client = Client::serve(server, |transport| transport).await;
+    let params = json!({
+        "protocolVersion": "2025-06-18",
+        "capabilities": { "roots": {} },
+        "clientInfo": { "name": "concurrency-test", "version": "0.1.0" }
+    });
+    client.send(json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": params })).await;
+    assert_eq!(client.receive().await["id"], 1);
+
+    client.call(2, "count-roots").await;
+    let request = client.receive().await;
+    assert_eq!(request["method"], "roots/list");
+    let roots = json!({ "roots": [{ "uri": "file:///a" }, { "uri": "file:///b" }] });
+    client.send(json!({ "jsonrpc": "2.0", "id": request["id"], "result": roots })).await;
+
+    let counted = client.receive().await;
+    assert_eq!(counted["id"], 2);
+    assert_eq!(counted["result"]["content"][0]["text"], "2 roots");
+    client.close().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn shared_servers_run_requests_in_parallel() {
+    let server = McpServer::new(ServerConfig::new("StdioConcurrency", "0.1.0"));
+    let rendezvous = Arc::new(Rendezvous(Arc::new(Barrier::new(2))));
+    server.register_tool("rendezvous".to_string(), rendezvous).await.unwrap();
+    let mut client = Client::serve_shared(Arc::new(server), |transport| transport).await;
+
+    // Neither call returns until both are running on threads of their own
+    client.call(1, "rendezvous").await;
+    client.call(2, "rendezvous").await;
+    let mut ids = Vec::new();
+    for _ in 0..2 {
+        let met = tokio::time::timeout(Duration::from_secs(10), client.receive()).await.unwrap();
+        assert_eq!(met["result"]["content"][0]["text"], "met");
+        ids.push(met["id"].as_u64().unwrap());
+    }
+    ids.sort();
+    assert_eq!(ids, [1, 2]);
+    client.close().await;
+}
+
+#[tokio::test]
+async fn shared_servers_keep_the_cap() {
+    let (server, gate) = gated_server().await;
+    let mut client = Client::serve_shared(Arc::new(server), |transport| transport.with_max_concurrency(1)).await;
+
+    client.call(1, "gate").await;
+    client.send(json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" })).await;
+    // The ping waits for the gated call to give up its place
+    assert!(tokio::time::timeout(Duration::from_millis(100), client.receive()).await.is_err());
+
+    gate.notify_one();
+    assert_eq!(client.receive().await["id"], 1);
+    assert_eq!(client.receive().await["id"], 2);
+    client.close().await;
+}
@@ -0,0 +1,67 @@
+use mcp_server_rust::transport::StdioTransport;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, duplex};
+
+/// Feeds `input` to a stdio server and collects every line it writes
+async fn serve(input: impl AsRef<[u8]>) -> Vec<Value> {
+    let (mut client, server_io) = duplex(64 * 1024);
+    let (server_read, server_write) = tokio::io::split(server_io);
+    let server = tokio::spawn(async move {
+        let server = McpServer::new(ServerConfig::new("StdioErrors", "0.1.0"));
+        StdioTransport::from_io(BufReader::new(server_read), server_write)
+            .serve(&server)
+            .await
+    });
+
+    client.write_all(input.as_ref()).await.unwrap();
+    client.shutdown().await.unwrap();
+    server.await.unwrap().unwrap();
+
+    let mut lines = BufReader::new(client).lines();
+    let mut output = Vec::new();
+    while let Some(line) = lines.next_line().await.unwrap() {
+        output.push(serde_json::from_str(&line).unwrap());
+    }
+    output
+}
+
+#[tokio::test]
+async fn invalid_json_gets_a_parse_error_and_serving_continues() {
+    let output = serve(concat!(
+        "{\"jsonrpc\": \"2.0\", \"id\": 1, \"method\": \"ping\"\n",
+        "not json at all\n",
+        "{\"jsonrpc\": \"2.0\", \"id\": 2, \"method\": \"ping\"}\n",
+    ))
+    .await;
+
+    assert_eq!(output.len(), 3);
+    for parse_error in &output[..2] {
+        assert_eq!(parse_error["id"], Value::Null);
+        assert_eq!(parse_error["error"]["code"], -32700);
+    }
+    assert_eq!(output[2]["id"], 2);
+    assert!(output[2].get("result").is_some());
+}
+
+#[tokio::test]
+async fn invalid_utf8_is_answered_without_ending_the_session() {
+    let mut input = b"\xff\xfe garbage\n".to_vec();
+    input.extend_from_slice(b"{\"jsonrpc\": \"2.0\", \"id\": 7, \"method\": \"ping\"}\n");
+    let output = serve(input).await;
+
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0]["error"]["code"], -32700);
+    assert_eq!(output[1]["id"], 7);
+}
+
+#[test]
+fn only_stream_failures_are_fatal() {
+    use mcp_server_rust::Error;
+    use std::io::{self, ErrorKind};
+
+    assert!(Error::Io(io::Error::from(ErrorKind::BrokenPipe)).is_fatal());
+    assert!(!Error::Io(io::Error::from(ErrorKind::InvalidData)).is_fatal());
+    assert!(!Error::InvalidRequest("bad".to_string()).is_fatal());
+    assert!(!Error::InternalError("oops".to_string()).is_fatal());
+}
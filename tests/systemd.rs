@@ -0,0 +1,56 @@
+#![cfg(target_os = "linux")]
+
+use serde_json::{Value, json};
+use std::os::fd::AsRawFd;
+use std::os::unix::net::UnixDatagram;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+#[tokio::test]
+async fn serves_on_an_activated_socket_and_reports_readiness() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let notify_path = std::env::temp_dir().join(format!("mcp-notify-{}", uuid::Uuid::new_v4()));
+    let notify = UnixDatagram::bind(&notify_path).unwrap();
+    notify.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+
+    // Hand the socket over on fd 3 the way systemd does; `exec` keeps the
+    // shell's pid, which is what LISTEN_PID has to name
+    let listener_fd = listener.as_raw_fd();
+    let mut command = Command::new("sh");
+    command
+        .args(["-c", "LISTEN_PID=$$ LISTEN_FDS=1 exec \"$0\""])
+        .arg(env!("CARGO_BIN_EXE_mcp-server-rust"))
+        .env("NOTIFY_SOCKET", &notify_path)
+        .stdin(Stdio::null());
+    unsafe {
+        command.pre_exec(move || {
+            if libc::dup2(listener_fd, 3) < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut child = command.spawn().unwrap();
+    drop(listener);
+
+    let mut buf = [0u8; 64];
+    let len = notify.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"READY=1");
+
+    let response: Value = reqwest::Client::new()
+        .post(format!("http://{}/mcp", addr))
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(response["result"], json!({}));
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    std::fs::remove_file(&notify_path).unwrap();
+}
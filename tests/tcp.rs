@@ -0,0 +1,103 @@
+use mcp_server_rust::transport::TcpTransport;
+use mcp_server_rust::{McpServer, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+
+struct Connection {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl Connection {
+    async fn open(addr: std::net::SocketAddr) -> Self {
+        let (reader, writer) = TcpStream::connect(addr).await.unwrap().into_split();
+        Self { reader: BufReader::new(reader), writer }
+    }
+
+    async fn request(&mut self, message: Value) -> Value {
+        self.writer.write_all(format!("{}\n", message).as_bytes()).await.unwrap();
+        let mut line = String::new();
+        self.reader.read_line(&mut line).await.unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    async fn initialize(&mut self, version: &str) -> Value {
+        let params = json!({ "protocolVersion": version, "capabilities": {}, "clientInfo": { "name": "t", "version": "1" } });
+        let response = self.request(json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": params })).await;
+        let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        self.writer.write_all(format!("{}\n", initialized).as_bytes()).await.unwrap();
+        response
+    }
+
+    async fn weather_tool(&mut self) -> Value {
+        let response = self.request(json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" })).await;
+        response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["name"] == "fetch-weather")
+            .unwrap()
+            .clone()
+    }
+}
+
+#[tokio::test]
+async fn each_connection_is_its_own_session() {
+    let server = Arc::new(McpServer::new(ServerConfig::new("Tcp", "0.1.0")));
+    let transport = TcpTransport::bind("127.0.0.1:0").await.unwrap();
+    let addr = transport.local_addr().unwrap();
+    let serving = tokio::spawn({
+        let server = server.clone();
+        async move { transport.serve(server).await }
+    });
+
+    let mut old = Connection::open(addr).await;
+    let mut new = Connection::open(addr).await;
+    assert_eq!(old.initialize("2024-11-05").await["result"]["protocolVersion"], "2024-11-05");
+    assert_eq!(new.initialize("2025-06-18").await["result"]["protocolVersion"], "2025-06-18");
+
+    // Each connection is answered in the revision it negotiated
+    assert!(old.weather_tool().await.get("outputSchema").is_none());
+    assert!(new.weather_tool().await.get("outputSchema").is_some());
+
+    let now = server.clock().now();
+    assert_eq!(server.sessions().idle_times(now).len(), 2);
+    drop(old);
+    for _ in 0..100 {
+        if server.sessions().idle_times(server.clock().now()).len() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(server.sessions().idle_times(server.clock().now()).len(), 1);
+    // The remaining connection is unaffected
+    assert!(new.weather_tool().await.get("outputSchema").is_some());
+    serving.abort();
+}
+
+#[tokio::test]
+async fn notifications_reach_every_connection() {
+    let server = Arc::new(McpServer::new(ServerConfig::new("Tcp", "0.1.0")));
+    let transport = TcpTransport::bind("127.0.0.1:0").await.unwrap();
+    let addr = transport.local_addr().unwrap();
+    let serving = tokio::spawn({
+        let server = server.clone();
+        async move { transport.serve(server).await }
+    });
+
+    let mut connection = Connection::open(addr).await;
+    connection.initialize("2025-06-18").await;
+    // A round trip makes sure the connection is being served
+    connection.request(json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" })).await;
+    server.notify("notifications/message", json!({ "level": "info", "data": "hello" }));
+
+    let mut line = String::new();
+    connection.reader.read_line(&mut line).await.unwrap();
+    let notification: Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(notification["method"], "notifications/message");
+    serving.abort();
+}
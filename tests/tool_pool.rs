@@ -0,0 +1,85 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::pool::ToolPoolConfig;
+use mcp_server_rust::tools::{CallToolResult, TextContent, ToolHandler};
+use mcp_server_rust::{McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Records how many of its calls ran at the same time
+#[derive(Default)]
+struct Tracked {
+    running: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+#[async_trait]
+impl ToolHandler for Tracked {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        Ok(CallToolResult::success(vec![TextContent::new("done")]))
+    }
+}
+
+struct Panics;
+
+#[async_trait]
+impl ToolHandler for Panics {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        panic!("tool bug");
+    }
+}
+
+async fn client(pool: ToolPoolConfig) -> (McpTestClient, Arc<Tracked>) {
+    let tracked = Arc::new(Tracked::default());
+    let server = McpServer::new(ServerConfig::new("Pool", "0.1.0").with_tool_pool(pool));
+    server.register_tool("tracked".to_string(), tracked.clone()).await.unwrap();
+    server.register_tool("panics".to_string(), Arc::new(Panics)).await.unwrap();
+    (McpTestClient::new(server), tracked)
+}
+
+async fn call_four(client: &McpTestClient) {
+    let (a, b, c, d) = tokio::join!(
+        client.call_tool("tracked", json!({})),
+        client.call_tool("tracked", json!({})),
+        client.call_tool("tracked", json!({})),
+        client.call_tool("tracked", json!({})),
+    );
+    for result in [a, b, c, d] {
+        assert_eq!(result.unwrap().content[0].as_text().unwrap(), "done");
+    }
+}
+
+#[tokio::test]
+async fn workers_bound_parallelism() {
+    let (client, tracked) = client(ToolPoolConfig::new(2)).await;
+    call_four(&client).await;
+    assert_eq!(tracked.peak.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn pinned_tools_run_on_one_worker() {
+    let (client, tracked) = client(ToolPoolConfig::new(4).with_affinity("tracked", 1)).await;
+    call_four(&client).await;
+    assert_eq!(tracked.peak.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn builtin_tools_run_on_the_pool() {
+    let (client, _) = client(ToolPoolConfig::new(1)).await;
+    let result = client.call_tool("greet", json!({ "name": "Ada" })).await.unwrap();
+    assert!(result.content[0].as_text().unwrap().contains("Ada"));
+}
+
+#[tokio::test]
+async fn a_panicking_call_does_not_take_down_its_worker() {
+    let (client, _) = client(ToolPoolConfig::new(1)).await;
+    assert!(client.call_tool("panics", json!({})).await.is_err());
+    client.call_tool("tracked", json!({})).await.unwrap();
+}
@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+use mcp_server_rust::context::RequestContext;
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::pool::ToolPoolConfig;
+use mcp_server_rust::tools::{CallToolResult, TextContent, Tool, ToolHandler};
+use mcp_server_rust::utils::MockClock;
+use mcp_server_rust::{Error, FeatureFlags, McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct Shout;
+
+#[async_trait]
+impl ToolHandler for Shout {
+    async fn call(&self, _context: &RequestContext, arguments: Value) -> Result<CallToolResult> {
+        let text = arguments["text"].as_str().unwrap_or_default().to_uppercase();
+        Ok(CallToolResult::success(vec![TextContent::new(text)]))
+    }
+
+    fn definition(&self) -> Option<Tool> {
+        Some(Tool {
+            name: "shout".to_string(),
+            description: "Repeats the text, louder".to_string(),
+            input_schema: json!({ "type": "object", "properties": { "text": { "type": "string" } } }),
+            annotations: None,
+            output_schema: None,
+        })
+    }
+}
+
+/// Numbers its calls, so every call must reach the same instance
+#[derive(Default)]
+struct Tally(AtomicUsize);
+
+#[async_trait]
+impl ToolHandler for Tally {
+    async fn call(&self, _context: &RequestContext, _arguments: Value) -> Result<CallToolResult> {
+        let call = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+        Ok(CallToolResult::success(vec![TextContent::new(call.to_string())]))
+    }
+}
+
+fn names(tools: Vec<Tool>) -> Vec<String> {
+    tools.into_iter().map(|t| t.name).collect()
+}
+
+fn not_found(error: Error) -> bool {
+    matches!(error, Error::Rpc { code: -32601, .. })
+}
+
+#[tokio::test]
+async fn calls_and_listings_come_from_the_registry() {
+    let client = McpTestClient::new(McpServer::new(ServerConfig::new("Registry", "0.1.0")));
+    assert_eq!(names(client.list_tools().await.unwrap()), vec!["calculate-bmi", "fetch-weather", "greet"]);
+
+    client.server().register_tool("shout".to_string(), Arc::new(Shout)).await.unwrap();
+    let result = client.call_tool("shout", json!({ "text": "hi" })).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "HI");
+    assert!(names(client.list_tools().await.unwrap()).contains(&"shout".to_string()));
+
+    // Built-ins are ordinary registrations
+    assert!(client.server().unregister_tool("greet").await);
+    assert!(!names(client.list_tools().await.unwrap()).contains(&"greet".to_string()));
+    assert!(not_found(client.call_tool("greet", json!({ "name": "Ada" })).await.unwrap_err()));
+}
+
+#[tokio::test]
+async fn disabled_tools_leave_the_registry_empty() {
+    let mut features = FeatureFlags::default();
+    features.disable("tools");
+    let server = McpServer::new(ServerConfig::new("Registry", "0.1.0").with_features(features));
+    let client = McpTestClient::new(server);
+
+    let error = client.list_tools().await.unwrap_err();
+    assert!(not_found(error));
+    assert!(not_found(client.call_tool("greet", json!({ "name": "Ada" })).await.unwrap_err()));
+}
+
+#[tokio::test]
+async fn tools_can_be_replaced_in_place() {
+    let client = McpTestClient::new(McpServer::new(ServerConfig::new("Registry", "0.1.0")));
+    let previous = client.server().replace_tool("greet", Arc::new(Shout)).await.unwrap();
+    assert_eq!(previous.definition().unwrap().name, "greet");
+
+    let result = client.call_tool("greet", json!({ "text": "hi" })).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "HI");
+
+    // Replacing needs something to replace
+    let error = client.server().replace_tool("missing", Arc::new(Shout)).await.err().unwrap();
+    assert_eq!(error.code(), -32601);
+    assert!(client.call_tool("missing", json!({})).await.is_err());
+}
+
+#[tokio::test]
+async fn changing_the_clock_keeps_every_registration() {
+    let server = McpServer::new(ServerConfig::new("Registry", "0.1.0"));
+    server.replace_tool("fetch-weather", Arc::new(Shout)).await.unwrap();
+    let client = McpTestClient::new(server.with_clock(Arc::new(MockClock::new())));
+
+    let result = client.call_tool("fetch-weather", json!({ "text": "hi" })).await.unwrap();
+    assert_eq!(result.content[0].as_text().unwrap(), "HI");
+}
+
+#[tokio::test]
+async fn registered_handlers_serve_every_call() {
+    for config in [
+        ServerConfig::new("Registry", "0.1.0"),
+        ServerConfig::new("Registry", "0.1.0").with_tool_pool(ToolPoolConfig::new(2)),
+    ] {
+        let tally = Arc::new(Tally::default());
+        let client = McpTestClient::new(McpServer::new(config));
+        client.server().register_tool("tally".to_string(), tally.clone()).await.unwrap();
+
+        for expected in ["1", "2", "3"] {
+            let result = client.call_tool("tally", json!({})).await.unwrap();
+            assert_eq!(result.content[0].as_text().unwrap(), expected);
+        }
+        assert_eq!(tally.0.load(Ordering::SeqCst), 3);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn calls_and_registrations_run_concurrently() {
+    let client = Arc::new(McpTestClient::new(McpServer::new(ServerConfig::new("Registry", "0.1.0"))));
+    client.server().register_tool("shout".to_string(), Arc::new(Shout)).await.unwrap();
+
+    let writer = {
+        let server = client.server().clone();
+        tokio::spawn(async move {
+            for i in 0..200 {
+                server.register_tool(format!("extra-{}", i), Arc::new(Shout)).await.unwrap();
+            }
+            for i in 0..200 {
+                assert!(server.unregister_tool(&format!("extra-{}", i)).await);
+            }
+        })
+    };
+    let callers: Vec<_> = (0..4)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                for _ in 0..50 {
+                    let result = client.call_tool("shout", json!({ "text": "hi" })).await.unwrap();
+                    assert_eq!(result.content[0].as_text().unwrap(), "HI");
+                    client.list_tools().await.unwrap();
+                }
+            })
+        })
+        .collect();
+
+    writer.await.unwrap();
+    for caller in callers {
+        caller.await.unwrap();
+    }
+    assert_eq!(names(client.list_tools().await.unwrap()), vec!["calculate-bmi", "fetch-weather", "greet", "shout"]);
+}
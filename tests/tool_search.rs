@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use mcp_server_rust::access::{self, AccessPolicy, Role};
+use mcp_server_rust::testing::McpTestClient;
+use mcp_server_rust::tools::search::EmbeddingBackend;
+use mcp_server_rust::{Error, McpServer, Result, ServerConfig};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn names(result: &Value) -> Vec<&str> {
+    result["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn keywords_rank_names_above_descriptions() {
+    let client = McpTestClient::new(McpServer::new(ServerConfig::new("Search", "0.1.0")));
+
+    let result = client.request("x-tools/search", json!({ "query": "weather in Paris" })).await.unwrap();
+    assert_eq!(names(&result), vec!["fetch-weather"]);
+    assert!(result["tools"][0]["score"].as_f64().unwrap() > 0.0);
+    assert!(result["tools"][0]["inputSchema"].is_object());
+
+    let result = client.request("x-tools/search", json!({ "query": "calc body mass" })).await.unwrap();
+    assert_eq!(names(&result), vec!["calculate-bmi"]);
+
+    let result = client
+        .request("x-tools/search", json!({ "query": "greet a person, check the weather", "limit": 1 }))
+        .await
+        .unwrap();
+    assert_eq!(names(&result).len(), 1);
+
+    let result = client.request("x-tools/search", json!({ "query": "quantum" })).await.unwrap();
+    assert!(names(&result).is_empty());
+
+    match client.request("x-tools/search", json!({})).await.unwrap_err() {
+        Error::Rpc { code, .. } => assert_eq!(code, -32602),
+        other => panic!("expected a JSON-RPC error, got {}", other),
+    }
+}
+
+#[tokio::test]
+async fn results_respect_the_access_policy() {
+    let policy = AccessPolicy::new()
+        .with_role("greeter", Role::new().with_tools(&["greet"]))
+        .with_anonymous(&["greeter"]);
+    let client = McpTestClient::new(McpServer::new(
+        ServerConfig::new("Search", "0.1.0").with_access_policy(policy),
+    ));
+    access::with_identity(None, async {
+        let result = client.request("x-tools/search", json!({ "query": "weather" })).await.unwrap();
+        assert!(names(&result).is_empty());
+    })
+    .await;
+}
+
+/// Embeds texts as counts of a few topic words
+struct TopicEmbeddings {
+    calls: AtomicUsize,
+}
+
+#[async_trait]
+impl EmbeddingBackend for TopicEmbeddings {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let topics = [["rain", "weather", "forecast"], ["hello", "greets", "welcome"]];
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let text = text.to_lowercase();
+                topics
+                    .iter()
+                    .map(|words| words.iter().filter(|w| text.contains(*w)).count() as f32)
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn embeddings_rank_by_meaning() {
+    let backend = Arc::new(TopicEmbeddings { calls: AtomicUsize::new(0) });
+    let server = McpServer::new(ServerConfig::new("Search", "0.1.0")).with_embedding_backend(backend.clone());
+    let client = McpTestClient::new(server);
+
+    // No keyword in common with the tool, but the same topic
+    let result = client.request("x-tools/search", json!({ "query": "will it rain?" })).await.unwrap();
+    assert_eq!(names(&result), vec!["fetch-weather"]);
+    let result = client.request("x-tools/search", json!({ "query": "say hello" })).await.unwrap();
+    assert_eq!(names(&result), vec!["greet"]);
+    assert_eq!(backend.calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn search_is_advertised() {
+    let client = McpTestClient::new(McpServer::new(ServerConfig::new("Search", "0.1.0")));
+    let result = client.initialize().await.unwrap();
+    assert!(result["capabilities"]["experimental"]["x-tools/search"].is_object());
+}
@@ -0,0 +1,69 @@
+use mcp_server_rust::transport::mock::FixtureStep;
+use mcp_server_rust::transport::{MemoryTransport, MockTransport, Transport};
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn a_sender_writes_while_the_transport_is_receiving() {
+    let (mut client, mut server_end) = MemoryTransport::pair();
+    let sender = server_end.sender();
+
+    let receiving = tokio::spawn(async move { server_end.receive().await });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    sender
+        .send(json!({ "jsonrpc": "2.0", "method": "notifications/message" }))
+        .await
+        .unwrap();
+    let pushed = tokio::time::timeout(Duration::from_secs(1), client.receive())
+        .await
+        .expect("the sender must not wait for receive to return")
+        .unwrap()
+        .unwrap();
+    assert_eq!(pushed["method"], "notifications/message");
+
+    client.send(json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" })).await.unwrap();
+    let received = receiving.await.unwrap().unwrap().unwrap();
+    assert_eq!(received["method"], "ping");
+}
+
+#[tokio::test]
+async fn senders_are_cloneable_across_tasks() {
+    let (mut client, server_end) = MemoryTransport::pair();
+    let sender = server_end.sender();
+    let tasks: Vec<_> = (0..4)
+        .map(|i| {
+            let sender = sender.clone();
+            tokio::spawn(async move { sender.send(json!({ "n": i })).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+    let mut seen = Vec::new();
+    for _ in 0..4 {
+        seen.push(client.receive().await.unwrap().unwrap()["n"].as_u64().unwrap());
+    }
+    seen.sort();
+    assert_eq!(seen, vec![0, 1, 2, 3]);
+}
+
+#[tokio::test]
+async fn a_closed_memory_transport_rejects_its_senders() {
+    let (client, server_end) = MemoryTransport::pair();
+    let sender = server_end.sender();
+    drop(client);
+    assert!(sender.send(json!({})).await.is_err());
+}
+
+#[tokio::test]
+async fn mock_senders_are_checked_against_the_script() {
+    let mut transport = MockTransport::new(vec![FixtureStep {
+        client: json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }),
+        expect: Some(json!({ "id": 1, "result": {} })),
+    }]);
+    let sender = transport.sender();
+    transport.receive().await.unwrap();
+    sender.send(json!({ "jsonrpc": "2.0", "id": 2, "result": {} })).await.unwrap();
+    transport.close().await.unwrap();
+    assert!(transport.verify().is_err());
+}